@@ -0,0 +1,342 @@
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::compress::GZ_EXTENSION;
+use crate::{entry_day, is_entry_file};
+
+/// Location of the zip archive for a given year (`<journal>/YYYY.zip`)
+pub fn zip_path(journal_path: &Path, year: i32) -> PathBuf {
+    journal_path.join(format!("{}.zip", year))
+}
+
+/// Location of the tarball for a given year (`<journal>/YYYY.tar.gz`)
+pub fn tar_path(journal_path: &Path, year: i32) -> PathBuf {
+    journal_path.join(format!("{}.tar.gz", year))
+}
+
+/// The year's tarball, if the year only exists as one (`get` can't read inside tarballs)
+pub fn tar_only(journal_path: &Path, year: i32) -> Option<PathBuf> {
+    let tar = tar_path(journal_path, year);
+    (!journal_path.join(year.to_string()).exists() && tar.is_file()).then_some(tar)
+}
+
+/// Years between `from` and `to` (either end open) that only exist as a tarball, oldest first
+pub fn tar_only_years(journal_path: &Path, from: Option<i32>, to: Option<i32>) -> Vec<(i32, PathBuf)> {
+    let Ok(items) = fs::read_dir(journal_path) else {
+        return Vec::new();
+    };
+    let mut years: Vec<(i32, PathBuf)> = items
+        .flatten()
+        .filter_map(|item| item.file_name().to_str()?.strip_suffix(".tar.gz")?.parse::<i32>().ok())
+        .filter(|year| from.is_none_or(|from| *year >= from) && to.is_none_or(|to| *year <= to))
+        .filter_map(|year| Some((year, tar_only(journal_path, year)?)))
+        .collect();
+    years.sort();
+    years
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    TarGz,
+    Zip,
+}
+
+/// Every file under `dir`, keyed by its `/`-separated name relative to `base`
+fn collect_files(base: &Path, dir: &Path, files: &mut BTreeMap<String, PathBuf>) -> Result<(), String> {
+    let items = fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for item in items.flatten() {
+        let path = item.path();
+        if path.is_dir() {
+            collect_files(base, &path, files)?;
+        } else {
+            let relative = path.strip_prefix(base).unwrap_or(&path);
+            let name = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+            files.insert(name, path);
+        }
+    }
+    Ok(())
+}
+
+fn digest(bytes: &[u8]) -> [u8; 32] {
+    Sha256::digest(bytes).into()
+}
+
+/// Bundle the year folder into `YYYY.tar.gz` or `YYYY.zip` (laid out as `YYYY/MM/<file>`),
+/// then read the archive back and check every file against the original. Returns the
+/// archive and how many files it holds; the folder itself is left alone.
+pub fn create(journal_path: &Path, year: i32, format: Format) -> Result<(PathBuf, usize), String> {
+    let year_dir = journal_path.join(year.to_string());
+    if !year_dir.is_dir() {
+        return Err(format!("No folder for {} at {}", year, year_dir.display()));
+    }
+    let target = match format {
+        Format::TarGz => tar_path(journal_path, year),
+        Format::Zip => zip_path(journal_path, year),
+    };
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+
+    let mut files = BTreeMap::new();
+    collect_files(journal_path, &year_dir, &mut files)?;
+    let mut expected = BTreeMap::new();
+    for (name, path) in &files {
+        let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        expected.insert(name.clone(), digest(&bytes));
+    }
+
+    // Written under a temporary name so an interrupted run never leaves a partial archive
+    let tmp = target.with_file_name(format!(".{}.tmp", target.file_name().unwrap_or_default().to_string_lossy()));
+    let written = match format {
+        Format::TarGz => write_tar(&tmp, &files),
+        Format::Zip => write_zip(&tmp, &files),
+    };
+    let verified = written.and_then(|_| match format {
+        Format::TarGz => read_tar_digests(&tmp),
+        Format::Zip => read_zip_digests(&tmp),
+    });
+    let result = verified.and_then(|found| {
+        if found == expected {
+            Ok(())
+        } else {
+            Err(format!("Verifying {} failed: its contents don't match {}", target.display(), year_dir.display()))
+        }
+    });
+    if let Err(e) = result.and_then(|_| fs::rename(&tmp, &target).map_err(|e| e.to_string())) {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+    Ok((target, files.len()))
+}
+
+fn write_tar(path: &Path, files: &BTreeMap<String, PathBuf>) -> Result<(), String> {
+    let file = fs::File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::best()));
+    for (name, source) in files {
+        builder
+            .append_path_with_name(source, name)
+            .map_err(|e| format!("Failed to add {}: {}", source.display(), e))?;
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .and_then(|file| file.sync_all())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn write_zip(path: &Path, files: &BTreeMap<String, PathBuf>) -> Result<(), String> {
+    let file = fs::File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut writer = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, source) in files {
+        let bytes = fs::read(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        writer
+            .start_file(name.as_str(), options)
+            .and_then(|_| writer.write_all(&bytes).map_err(Into::into))
+            .map_err(|e| format!("Failed to add {}: {}", source.display(), e))?;
+    }
+    writer
+        .finish()
+        .map_err(|e| e.to_string())
+        .and_then(|file| file.sync_all().map_err(|e| e.to_string()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn read_tar_digests(path: &Path) -> Result<BTreeMap<String, [u8; 32]>, String> {
+    let failed = |e: std::io::Error| format!("Failed to read back {}: {}", path.display(), e);
+    let file = fs::File::open(path).map_err(failed)?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut digests = BTreeMap::new();
+    for entry in archive.entries().map_err(failed)? {
+        let mut entry = entry.map_err(failed)?;
+        let name = entry.path().map_err(failed)?.to_string_lossy().to_string();
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).map_err(failed)?;
+        digests.insert(name, digest(&bytes));
+    }
+    Ok(digests)
+}
+
+fn read_zip_digests(path: &Path) -> Result<BTreeMap<String, [u8; 32]>, String> {
+    let failed = |e: String| format!("Failed to read back {}: {}", path.display(), e);
+    let file = fs::File::open(path).map_err(|e| failed(e.to_string()))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| failed(e.to_string()))?;
+    let mut digests = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut zip_file = archive.by_index(i).map_err(|e| failed(e.to_string()))?;
+        let mut bytes = Vec::new();
+        zip_file.read_to_end(&mut bytes).map_err(|e| failed(e.to_string()))?;
+        digests.insert(zip_file.name().to_string(), digest(&bytes));
+    }
+    Ok(digests)
+}
+
+/// List entries stored inside a year archive.
+///
+/// Archived entries are addressed as `<journal>/YYYY.zip/YYYY/MM/<file>` so the
+/// rest of the tool can treat them like regular paths; `read_zip_entry` resolves
+/// them back into the archive.
+pub fn list_zip_entries(
+    zip_path: &Path,
+    month: Option<u32>,
+    day: Option<u32>,
+) -> Result<Vec<PathBuf>, String> {
+    let file = fs::File::open(zip_path)
+        .map_err(|e| format!("Failed to open {}: {}", zip_path.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read archive {}: {}", zip_path.display(), e))?;
+
+    let month_dir = month.map(|m| format!("{:02}", m));
+
+    let mut entries = Vec::new();
+    for i in 0..archive.len() {
+        let zip_file = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read archive {}: {}", zip_path.display(), e))?;
+        if zip_file.is_dir() {
+            continue;
+        }
+
+        // Expected layout inside the archive: YYYY/MM/<file>
+        let name = zip_file.name().to_string();
+        let parts: Vec<&str> = name.split('/').collect();
+        let [_, month_part, filename] = parts.as_slice() else {
+            continue;
+        };
+        if !is_entry_file(filename) {
+            continue;
+        }
+        if month_dir.as_deref().is_some_and(|m| m != *month_part) {
+            continue;
+        }
+        if day.is_some() && entry_day(filename) != day {
+            continue;
+        }
+
+        entries.push(zip_path.join(&name));
+    }
+
+    Ok(entries)
+}
+
+/// Split a virtual archive path into the archive file and the name inside it
+pub fn split_zip_path(path: &Path) -> Option<(PathBuf, String)> {
+    let archive = path.ancestors().find(|ancestor| {
+        ancestor
+            .extension()
+            .is_some_and(|ext| ext == "zip")
+            && ancestor.is_file()
+    })?;
+
+    let inner = path.strip_prefix(archive).ok()?;
+    let name = inner
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/");
+    Some((archive.to_path_buf(), name))
+}
+
+/// Read an entry from inside a zip archive, decompressing one that was gzipped before archiving
+pub fn read_zip_entry(zip_path: &Path, name: &str) -> std::io::Result<String> {
+    let file = fs::File::open(zip_path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    let mut zip_file = archive.by_name(name)?;
+    let mut content = String::new();
+    if name.ends_with(GZ_EXTENSION) {
+        GzDecoder::new(zip_file).read_to_string(&mut content)?;
+    } else {
+        zip_file.read_to_string(&mut content)?;
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_test_zip(journal_path: &Path) -> PathBuf {
+        let path = zip_path(journal_path, 2021);
+        let file = fs::File::create(&path).unwrap();
+        let mut writer = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("2021/05/03-080000-first.md", options).unwrap();
+        writer.write_all(b"# first\n\nDate: 03-05-2021\n").unwrap();
+        writer.start_file("2021/05/04-090000-second.md", options).unwrap();
+        writer.write_all(b"# second\n").unwrap();
+        writer.start_file("2021/06/01-100000-third.md", options).unwrap();
+        writer.write_all(b"# third\n").unwrap();
+        writer.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_list_zip_entries_filters_by_month_and_day() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let zip = write_test_zip(temp_dir.path());
+
+        assert_eq!(list_zip_entries(&zip, None, None).unwrap().len(), 3);
+        assert_eq!(list_zip_entries(&zip, Some(5), None).unwrap().len(), 2);
+
+        let day = list_zip_entries(&zip, Some(5), Some(3)).unwrap();
+        assert_eq!(day, vec![zip.join("2021/05/03-080000-first.md")]);
+    }
+
+    #[test]
+    fn test_read_through_virtual_path() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let zip = write_test_zip(temp_dir.path());
+
+        let (archive, name) = split_zip_path(&zip.join("2021/05/03-080000-first.md")).unwrap();
+        assert_eq!(archive, zip);
+        assert_eq!(name, "2021/05/03-080000-first.md");
+        assert!(read_zip_entry(&archive, &name).unwrap().contains("Date: 03-05-2021"));
+    }
+
+    #[test]
+    fn test_create_and_verify() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let month = temp_dir.path().join("2021").join("05");
+        fs::create_dir_all(&month).unwrap();
+        fs::write(month.join("03-080000-first.md"), "# first\n").unwrap();
+        fs::write(month.join("04-090000-second.md"), "# second\n").unwrap();
+
+        let (tar, count) = create(temp_dir.path(), 2021, Format::TarGz).unwrap();
+        assert_eq!((tar.clone(), count), (tar_path(temp_dir.path(), 2021), 2));
+        assert_eq!(read_tar_digests(&tar).unwrap().len(), 2);
+        assert!(create(temp_dir.path(), 2021, Format::TarGz).unwrap_err().contains("already exists"));
+
+        let (zip, _) = create(temp_dir.path(), 2021, Format::Zip).unwrap();
+        assert_eq!(list_zip_entries(&zip, Some(5), Some(4)).unwrap(), vec![zip.join("2021/05/04-090000-second.md")]);
+
+        // Still there until removed, so not yet "only a tarball"
+        assert_eq!(tar_only(temp_dir.path(), 2021), None);
+        fs::remove_dir_all(temp_dir.path().join("2021")).unwrap();
+        assert_eq!(tar_only(temp_dir.path(), 2021), Some(tar.clone()));
+        assert_eq!(tar_only_years(temp_dir.path(), Some(2020), None), vec![(2021, tar)]);
+        assert!(tar_only_years(temp_dir.path(), None, Some(2020)).is_empty());
+        assert!(create(temp_dir.path(), 2022, Format::Zip).unwrap_err().contains("No folder"));
+    }
+
+    #[test]
+    fn test_read_compressed_entry_from_zip() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let month = temp_dir.path().join("2021").join("05");
+        fs::create_dir_all(&month).unwrap();
+        fs::write(month.join("03-080000-first.md"), "# first\n").unwrap();
+        crate::compress::compress_before(temp_dir.path(), 2022).unwrap();
+
+        let (zip, _) = create(temp_dir.path(), 2021, Format::Zip).unwrap();
+        fs::remove_dir_all(temp_dir.path().join("2021")).unwrap();
+        let entries = list_zip_entries(&zip, Some(5), None).unwrap();
+        assert_eq!(entries, vec![zip.join("2021/05/03-080000-first.md.gz")]);
+        let (archive, name) = split_zip_path(&entries[0]).unwrap();
+        assert_eq!(read_zip_entry(&archive, &name).unwrap(), "# first\n");
+    }
+}
@@ -0,0 +1,120 @@
+//! `ask`: answer a question from the most relevant entries (requires the `network` feature).
+
+use std::path::{Path, PathBuf};
+
+use crate::{frontmatter, read_entry};
+
+/// Entries handed to the model
+pub const CONTEXT_ENTRIES: usize = 8;
+
+/// Longest excerpt of a single entry put into the prompt, in characters
+const EXCERPT_LIMIT: usize = 4000;
+
+const PROMPT: &str = "Answer the question using only the journal entries below. Each entry starts with its path \
+in square brackets. Cite the entries you used by putting their paths in square brackets after the sentences that \
+rely on them. If the entries don't contain the answer, say so.\n\nQuestion: {question}\n\n{entries}";
+
+/// Words too common to say anything about relevance
+const STOPWORDS: &[&str] = &[
+    "a", "about", "an", "and", "are", "at", "did", "do", "does", "for", "from", "had", "has", "have", "how", "i", "in",
+    "is", "it", "last", "me", "my", "of", "on", "or", "the", "to", "was", "were", "what", "when", "where", "which",
+    "who", "why", "with",
+];
+
+/// How many distinct meaningful words of a natural-language `query` appear in `text`
+fn score(text: &str, query: &str) -> usize {
+    let text = text.to_lowercase();
+    let mut words: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|w| !w.is_empty() && !STOPWORDS.contains(&w.as_str()))
+        .collect();
+    words.sort();
+    words.dedup();
+    words.iter().filter(|w| text.contains(w.as_str())).count()
+}
+
+/// An entry retrieved for the question, with its path relative to the journal
+pub struct Excerpt {
+    pub path: String,
+    pub text: String,
+}
+
+impl Excerpt {
+    pub fn load(journal_path: &Path, path: &Path) -> Option<Excerpt> {
+        let content = read_entry(path).ok()?;
+        let body = frontmatter::parse(&content).1;
+        Some(Excerpt {
+            path: path.strip_prefix(journal_path).unwrap_or(path).to_string_lossy().to_string(),
+            text: body.chars().take(EXCERPT_LIMIT).collect(),
+        })
+    }
+}
+
+/// The `limit` entries sharing the most words with the question, newest first among equals
+pub fn keyword_matches(journal_path: &Path, entries: &[PathBuf], question: &str, limit: usize) -> Vec<Excerpt> {
+    let mut scored: Vec<(usize, Excerpt)> = entries
+        .iter()
+        .rev()
+        .filter_map(|path| Excerpt::load(journal_path, path))
+        .map(|excerpt| (score(&excerpt.text, question), excerpt))
+        .filter(|(score, _)| *score > 0)
+        .collect();
+    // Stable, so newer entries stay ahead of older ones with the same score
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(limit).map(|(_, excerpt)| excerpt).collect()
+}
+
+pub fn build_prompt(question: &str, excerpts: &[Excerpt]) -> String {
+    let entries: Vec<String> = excerpts.iter().map(|e| format!("[{}]\n{}", e.path, e.text.trim())).collect();
+    PROMPT.replace("{question}", question).replace("{entries}", &entries.join("\n\n"))
+}
+
+/// Retrieved entries the answer cites, or all of them if it cites none
+pub fn sources<'a>(answer: &str, excerpts: &'a [Excerpt]) -> Vec<&'a str> {
+    let cited: Vec<&str> = excerpts.iter().map(|e| e.path.as_str()).filter(|p| answer.contains(p)).collect();
+    if cited.is_empty() { excerpts.iter().map(|e| e.path.as_str()).collect() } else { cited }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_score_ignores_stopwords() {
+        let query = "When did I last service the car?";
+        assert_eq!(score("Took the car in for its service", query), 2);
+        assert_eq!(score("When I was at the office", query), 0);
+    }
+
+    #[test]
+    fn test_keyword_matches_and_sources() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        let paths: Vec<PathBuf> = [
+            ("10-080000-garage.md", "# Garage\n\nCar service at the garage, oil changed\n"),
+            ("12-080000-work.md", "# Work\n\nLong meeting\n"),
+            ("14-080000-tyres.md", "# Tyres\n\nNew tyres on the car\n"),
+        ]
+        .iter()
+        .map(|(name, content)| {
+            fs::write(month.join(name), content).unwrap();
+            month.join(name)
+        })
+        .collect();
+
+        let found = keyword_matches(dir.path(), &paths, "When did I last service the car?", 5);
+        let found_paths: Vec<&str> = found.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(found_paths, vec!["2026/02/10-080000-garage.md", "2026/02/14-080000-tyres.md"]);
+
+        let prompt = build_prompt("When?", &found);
+        assert!(prompt.contains("Question: When?\n\n[2026/02/10-080000-garage.md]\n# Garage"));
+
+        let answer = "On February 10th [2026/02/10-080000-garage.md].";
+        assert_eq!(sources(answer, &found), vec!["2026/02/10-080000-garage.md"]);
+        assert_eq!(sources("I don't know.", &found).len(), 2);
+    }
+}
@@ -0,0 +1,169 @@
+//! Non-Gregorian calendars (requires the `calendars` feature): a secondary date shown next to
+//! the Gregorian one, and optionally the calendar that names the `YYYY/MM/dd-...` folders and files.
+
+use chrono::{Datelike, NaiveDate};
+use icu_calendar::cal::{Hebrew, Hijri, Persian};
+use icu_calendar::{AsCalendar, Date, Iso};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Calendar {
+    Hebrew,
+    /// Umm al-Qura, the Saudi civil Hijri calendar
+    Hijri,
+    /// Solar Hijri, as used in Iran and Afghanistan
+    Persian,
+}
+
+const HEBREW_MONTHS: [&str; 12] =
+    ["Tishrei", "Cheshvan", "Kislev", "Tevet", "Shevat", "Adar", "Nisan", "Iyar", "Sivan", "Tammuz", "Av", "Elul"];
+
+const HIJRI_MONTHS: [&str; 12] = [
+    "Muharram",
+    "Safar",
+    "Rabi' al-Awwal",
+    "Rabi' al-Thani",
+    "Jumada al-Awwal",
+    "Jumada al-Thani",
+    "Rajab",
+    "Sha'ban",
+    "Ramadan",
+    "Shawwal",
+    "Dhu al-Qa'dah",
+    "Dhu al-Hijjah",
+];
+
+const PERSIAN_MONTHS: [&str; 12] = [
+    "Farvardin",
+    "Ordibehesht",
+    "Khordad",
+    "Tir",
+    "Mordad",
+    "Shahrivar",
+    "Mehr",
+    "Aban",
+    "Azar",
+    "Dey",
+    "Bahman",
+    "Esfand",
+];
+
+/// `date` in another calendar system
+fn convert<C: AsCalendar>(date: NaiveDate, calendar: C) -> Option<Date<C>> {
+    let iso = Date::try_new_iso(date.year(), date.month() as u8, date.day() as u8).ok()?;
+    Some(iso.to_calendar(calendar))
+}
+
+fn to_gregorian<C: AsCalendar>(date: Date<C>) -> Option<NaiveDate> {
+    let iso = date.to_calendar(Iso);
+    NaiveDate::from_ymd_opt(iso.year().extended_year(), iso.month().number() as u32, iso.day_of_month().0 as u32)
+}
+
+impl Calendar {
+    /// Calendar named in the config: "hebrew", "hijri" or "persian"
+    pub fn from_name(name: &str) -> Result<Calendar, String> {
+        match name.to_lowercase().as_str() {
+            "hebrew" => Ok(Calendar::Hebrew),
+            "hijri" | "islamic" => Ok(Calendar::Hijri),
+            "persian" | "jalali" => Ok(Calendar::Persian),
+            _ => Err(format!("Unknown calendar '{}' (expected hebrew, hijri or persian)", name)),
+        }
+    }
+
+    /// Calendar named by `layout_calendar`; it must have twelve months every year to fit the folders
+    pub fn for_layout(name: &str) -> Result<Calendar, String> {
+        match Calendar::from_name(name)? {
+            Calendar::Hebrew => Err("The Hebrew calendar's leap months don't fit the YYYY/MM layout (use persian or hijri)".to_string()),
+            calendar => Ok(calendar),
+        }
+    }
+
+    /// Year, month and day of `date` in this calendar, as used for folder and file names
+    /// (not available for the Hebrew calendar)
+    pub fn ymd(self, date: NaiveDate) -> Option<(i32, u32, u32)> {
+        let (year, month, day) = match self {
+            Calendar::Hebrew => return None,
+            Calendar::Hijri => {
+                let date = convert(date, Hijri::new_umm_al_qura())?;
+                (date.year().extended_year(), date.month().ordinal, date.day_of_month().0)
+            }
+            Calendar::Persian => {
+                let date = convert(date, Persian::new())?;
+                (date.year().extended_year(), date.month().ordinal, date.day_of_month().0)
+            }
+        };
+        Some((year, month as u32, day as u32))
+    }
+
+    /// Gregorian date of a year, month and day in this calendar (not available for the Hebrew calendar)
+    pub fn to_gregorian(self, year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        let (month, day) = (u8::try_from(month).ok()?, u8::try_from(day).ok()?);
+        match self {
+            Calendar::Hebrew => None,
+            Calendar::Hijri => to_gregorian(Date::try_new_hijri_with_calendar(year, month, day, Hijri::new_umm_al_qura()).ok()?),
+            Calendar::Persian => to_gregorian(Date::try_new_persian(year, month, day).ok()?),
+        }
+    }
+
+    /// `date` in this calendar, e.g. "29 Shevat 5786", "28 Sha'ban 1447 AH" or "27 Bahman 1404 AP"
+    pub fn format(self, date: NaiveDate) -> Option<String> {
+        Some(match self {
+            Calendar::Hebrew => {
+                let date = convert(date, Hebrew::new())?;
+                let month = date.month();
+                let name = match month.number() {
+                    5 if month.to_input().is_leap() => "Adar I",
+                    // Adar is Adar II in years that have a leap month
+                    6 if date.months_in_year() == 13 => "Adar II",
+                    number => HEBREW_MONTHS[number as usize - 1],
+                };
+                format!("{} {} {}", date.day_of_month().0, name, date.year().extended_year())
+            }
+            Calendar::Hijri => {
+                let date = convert(date, Hijri::new_umm_al_qura())?;
+                let month = HIJRI_MONTHS[date.month().number() as usize - 1];
+                format!("{} {} {} AH", date.day_of_month().0, month, date.year().extended_year())
+            }
+            Calendar::Persian => {
+                let date = convert(date, Persian::new())?;
+                let month = PERSIAN_MONTHS[date.month().number() as usize - 1];
+                format!("{} {} {} AP", date.day_of_month().0, month, date.year().extended_year())
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(Calendar::Persian.format(date(2026, 3, 21)).unwrap(), "1 Farvardin 1405 AP");
+        assert_eq!(Calendar::Persian.format(date(2026, 2, 16)).unwrap(), "27 Bahman 1404 AP");
+        assert_eq!(Calendar::Hebrew.format(date(2025, 9, 23)).unwrap(), "1 Tishrei 5786");
+        assert_eq!(Calendar::Hebrew.format(date(2024, 3, 1)).unwrap(), "21 Adar I 5784");
+        assert_eq!(Calendar::Hebrew.format(date(2024, 3, 24)).unwrap(), "14 Adar II 5784");
+        assert_eq!(Calendar::Hijri.format(date(2026, 2, 18)).unwrap(), "1 Ramadan 1447 AH");
+    }
+
+    #[test]
+    fn test_layout_round_trip() {
+        assert_eq!(Calendar::Persian.ymd(date(2026, 2, 16)), Some((1404, 11, 27)));
+        assert_eq!(Calendar::Persian.to_gregorian(1404, 11, 27), Some(date(2026, 2, 16)));
+        assert_eq!(Calendar::Persian.to_gregorian(1404, 12, 31), None);
+        let day = date(2026, 2, 18);
+        let (y, m, d) = Calendar::Hijri.ymd(day).unwrap();
+        assert_eq!(Calendar::Hijri.to_gregorian(y, m, d), Some(day));
+        assert!(Calendar::for_layout("hebrew").is_err());
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Calendar::from_name("Jalali").unwrap(), Calendar::Persian);
+        assert!(Calendar::from_name("mayan").is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! Change feed: every create, edit, move and delete of an entry made through file-journal,
+//! appended as a JSON line to `changes.log` at the journal root. `changes --since` reads it
+//! back, for sync and incremental consumers.
+//!
+//! Hashes are SHA-256 of the entry's content (decompressed for `.md.gz`), so a change can be
+//! matched against what's on disk now.
+
+use chrono::{DateTime, FixedOffset, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use crate::read_entry;
+
+pub const CHANGES_FILE: &str = "changes.log";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Create,
+    Edit,
+    Move,
+    Delete,
+}
+
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Create => "create",
+            Action::Edit => "edit",
+            Action::Move => "move",
+            Action::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub time: String,
+    pub action: Action,
+    /// Entry path relative to the journal; where it ended up for a move
+    pub path: String,
+    /// Where a moved entry was before
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// Content hash before the change (absent for a create)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    /// Content hash after the change (absent for a delete)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+impl Change {
+    pub fn at(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(&self.time).ok()
+    }
+}
+
+/// Hash of an entry's content, `None` if it can't be read; take it before changing the entry
+pub fn hash(entry: &Path) -> Option<String> {
+    let content = read_entry(entry).ok()?;
+    Some(Sha256::digest(content.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn relative(journal_path: &Path, path: &Path) -> String {
+    path.strip_prefix(journal_path).unwrap_or(path).to_string_lossy().replace('\\', "/")
+}
+
+/// Append a change to `path` (moved from `from`), whose content hashed to `before` beforehand.
+/// An edit that left the content as it was isn't recorded.
+pub fn record(journal_path: &Path, action: Action, path: &Path, from: Option<&Path>, before: Option<String>) -> Result<(), String> {
+    let after = if action == Action::Delete { None } else { hash(path) };
+    if action == Action::Edit && after == before {
+        return Ok(());
+    }
+    let change = Change {
+        time: chrono::Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+        action,
+        path: relative(journal_path, path),
+        from: from.map(|from| relative(journal_path, from)),
+        before,
+        after,
+    };
+    let mut line = serde_json::to_string(&change).map_err(|e| format!("Failed to encode change: {}", e))?;
+    line.push('\n');
+
+    let log_path = journal_path.join(CHANGES_FILE);
+    let mut log = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| format!("Failed to open {}: {}", log_path.display(), e))?;
+    // One write per line, so appends from concurrent processes don't interleave
+    log.write_all(line.as_bytes()).map_err(|e| format!("Failed to write {}: {}", log_path.display(), e))
+}
+
+/// Changes made at or after `since`, oldest first. Lines that don't parse (such as one cut
+/// short by a crash) are skipped.
+pub fn since(journal_path: &Path, since: Option<DateTime<FixedOffset>>) -> Result<Vec<Change>, String> {
+    let log_path = journal_path.join(CHANGES_FILE);
+    let log = match fs::read_to_string(&log_path) {
+        Ok(log) => log,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", log_path.display(), e)),
+    };
+    Ok(log
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Change>(line).ok())
+        .filter(|change| match (since, change.at()) {
+            (Some(since), Some(at)) => at >= since,
+            (Some(_), None) => false,
+            (None, _) => true,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_query() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        let entry = month.join("17-081503-note.md");
+        fs::write(&entry, "# note\n").unwrap();
+        record(dir.path(), Action::Create, &entry, None, None).unwrap();
+
+        let before = hash(&entry);
+        record(dir.path(), Action::Edit, &entry, None, before.clone()).unwrap();
+        fs::write(&entry, "# note\n\nmore\n").unwrap();
+        record(dir.path(), Action::Edit, &entry, None, before).unwrap();
+
+        let moved = month.join("18-081503-note.md");
+        let before = hash(&entry);
+        fs::rename(&entry, &moved).unwrap();
+        record(dir.path(), Action::Move, &moved, Some(&entry), before.clone()).unwrap();
+        fs::remove_file(&moved).unwrap();
+        record(dir.path(), Action::Delete, &moved, None, hash(&moved).or(before)).unwrap();
+
+        let changes = since(dir.path(), None).unwrap();
+        let actions: Vec<Action> = changes.iter().map(|c| c.action).collect();
+        // The edit that changed nothing isn't logged
+        assert_eq!(actions, [Action::Create, Action::Edit, Action::Move, Action::Delete]);
+        assert_eq!(changes[0].path, "2026/02/17-081503-note.md");
+        assert_eq!(changes[1].before, changes[0].after);
+        assert_eq!(changes[2].from.as_deref(), Some("2026/02/17-081503-note.md"));
+        assert_eq!((changes[3].before.clone(), changes[3].after.clone()), (changes[2].after.clone(), None));
+
+        let later = chrono::Local::now().fixed_offset() + chrono::Duration::seconds(1);
+        assert!(since(dir.path(), Some(later)).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,113 @@
+//! Resumable bulk imports: which entries of an import source are already written, kept in
+//! `<journal>/.import-checkpoints/<source hash>.log` until the import completes, so running
+//! the same import again after a crash or Ctrl-C continues instead of duplicating entries.
+
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::lock;
+
+pub const CHECKPOINT_DIR: &str = ".import-checkpoints";
+
+pub struct Checkpoint {
+    path: PathBuf,
+    done: BTreeSet<usize>,
+    /// Keeps a second run of the same import from working through it at the same time
+    _lock: lock::Lock,
+}
+
+impl Checkpoint {
+    /// Checkpoint for importing `source` (everything that determines the entries, such as
+    /// the plugin name and the input bytes), picking up any earlier unfinished run
+    pub fn open(journal_path: &Path, source: &[&[u8]]) -> Result<Checkpoint, String> {
+        let mut hasher = Sha256::new();
+        for part in source {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(part);
+        }
+        let dir = journal_path.join(CHECKPOINT_DIR);
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let path = dir.join(format!("{:x}.log", hasher.finalize()));
+        let lock = lock::acquire(&path)?;
+        let done = match fs::read_to_string(&path) {
+            // A line cut short by a crash has no tab yet and is ignored
+            Ok(log) => log.lines().filter_map(|line| line.split_once('\t')?.0.parse().ok()).collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeSet::new(),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        Ok(Checkpoint { path, done, _lock: lock })
+    }
+
+    /// How many entries an earlier run already imported
+    pub fn resumed(&self) -> usize {
+        self.done.len()
+    }
+
+    pub fn is_done(&self, index: usize) -> bool {
+        self.done.contains(&index)
+    }
+
+    /// Record that entry `index` was written to `entry`, synced to disk before returning
+    pub fn mark_done(&mut self, index: usize, entry: &Path) -> Result<(), String> {
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("Failed to open {}: {}", self.path.display(), e))?;
+        writeln!(log, "{}\t{}", index, entry.display())
+            .and_then(|_| log.sync_data())
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))?;
+        self.done.insert(index);
+        Ok(())
+    }
+
+    /// The import completed: forget it, so importing the same source again starts over
+    pub fn finish(self) -> Result<(), String> {
+        for path in [self.path.clone(), self.path.with_extension("log.lock")] {
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(format!("Failed to remove {}: {}", path.display(), e)),
+            }
+        }
+        if let Some(dir) = self.path.parent() {
+            let _ = fs::remove_dir(dir);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resume_and_finish() {
+        let dir = TempDir::new().unwrap();
+        let source: [&[u8]; 2] = [b"org", b"* one\n* two\n* three\n"];
+
+        let mut checkpoint = Checkpoint::open(dir.path(), &source).unwrap();
+        assert_eq!(checkpoint.resumed(), 0);
+        checkpoint.mark_done(0, Path::new("2026/02/17-080000-one.md")).unwrap();
+        checkpoint.mark_done(1, Path::new("2026/02/17-080001-two.md")).unwrap();
+        drop(checkpoint);
+
+        // A later run of the same import picks up where this one stopped
+        let log = dir.path().join(CHECKPOINT_DIR);
+        let file = fs::read_dir(&log).unwrap().flatten().map(|f| f.path()).find(|f| f.extension() == Some("log".as_ref())).unwrap();
+        fs::OpenOptions::new().append(true).open(&file).unwrap().write_all(b"2").unwrap();
+        let checkpoint = Checkpoint::open(dir.path(), &source).unwrap();
+        assert_eq!((checkpoint.resumed(), checkpoint.is_done(1), checkpoint.is_done(2)), (2, true, false));
+
+        // Other input is a different import
+        assert_eq!(Checkpoint::open(dir.path(), &[b"org", b"* other\n"]).unwrap().resumed(), 0);
+
+        checkpoint.finish().unwrap();
+        assert!(!file.exists());
+        assert_eq!(Checkpoint::open(dir.path(), &source).unwrap().resumed(), 0);
+    }
+}
@@ -0,0 +1,156 @@
+use chrono::NaiveTime;
+use std::collections::BTreeMap;
+
+/// A time span logged in an entry as `clock: 09:00-11:30 project [label]`
+#[derive(Debug, PartialEq)]
+pub struct ClockLine {
+    pub minutes: i64,
+    pub project: String,
+    pub label: Option<String>,
+}
+
+/// Parse a clock line, optionally written as a list item (`- clock: ...`)
+pub fn parse_clock_line(line: &str) -> Option<ClockLine> {
+    let line = line.trim();
+    let line = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")).unwrap_or(line);
+    let prefix = line.get(..6)?;
+    if !prefix.eq_ignore_ascii_case("clock:") {
+        return None;
+    }
+
+    let mut parts = line[6..].split_whitespace();
+    let (start, end) = parts.next()?.split_once('-')?;
+    let start = NaiveTime::parse_from_str(start, "%H:%M").ok()?;
+    let end = NaiveTime::parse_from_str(end, "%H:%M").ok()?;
+    let project = parts.next()?.to_string();
+    let label: Vec<&str> = parts.collect();
+
+    let mut minutes = (end - start).num_minutes();
+    if minutes < 0 {
+        // Spans past midnight
+        minutes += 24 * 60;
+    }
+
+    Some(ClockLine {
+        minutes,
+        project,
+        label: if label.is_empty() { None } else { Some(label.join(" ")) },
+    })
+}
+
+/// Billable time per project/label
+#[derive(Debug, PartialEq)]
+pub struct InvoiceLine {
+    pub project: String,
+    pub label: String,
+    pub hours: f64,
+}
+
+/// Sum clock lines into hours per (project, label), sorted
+pub fn summarize(lines: &[ClockLine]) -> Vec<InvoiceLine> {
+    let mut totals: BTreeMap<(String, String), i64> = BTreeMap::new();
+    for line in lines {
+        let key = (line.project.clone(), line.label.clone().unwrap_or_default());
+        *totals.entry(key).or_default() += line.minutes;
+    }
+
+    totals
+        .into_iter()
+        .map(|((project, label), minutes)| InvoiceLine {
+            project,
+            label,
+            hours: minutes as f64 / 60.0,
+        })
+        .collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render the invoice summary as CSV or a Markdown table
+pub fn render_invoice(lines: &[InvoiceLine], rate: Option<f64>, format: &str) -> Result<String, String> {
+    let total_hours: f64 = lines.iter().map(|l| l.hours).sum();
+    let amount = |hours: f64| rate.map(|r| format!("{:.2}", hours * r)).unwrap_or_default();
+    let rate_text = rate.map(|r| format!("{:.2}", r)).unwrap_or_default();
+
+    let mut out = String::new();
+    match format {
+        "csv" => {
+            out.push_str("project,label,hours,rate,amount\n");
+            for line in lines {
+                out.push_str(&format!(
+                    "{},{},{:.2},{},{}\n",
+                    csv_field(&line.project),
+                    csv_field(&line.label),
+                    line.hours,
+                    rate_text,
+                    amount(line.hours)
+                ));
+            }
+            out.push_str(&format!("total,,{:.2},{},{}\n", total_hours, rate_text, amount(total_hours)));
+        }
+        "md" => {
+            out.push_str("| Project | Label | Hours | Rate | Amount |\n");
+            out.push_str("|---|---|---:|---:|---:|\n");
+            for line in lines {
+                out.push_str(&format!(
+                    "| {} | {} | {:.2} | {} | {} |\n",
+                    line.project,
+                    line.label,
+                    line.hours,
+                    rate_text,
+                    amount(line.hours)
+                ));
+            }
+            let total_amount = match rate {
+                Some(_) => format!("**{}**", amount(total_hours)),
+                None => String::new(),
+            };
+            out.push_str(&format!("| **Total** | | **{:.2}** | | {} |\n", total_hours, total_amount));
+        }
+        other => return Err(format!("Unsupported invoice format '{}' (expected csv or md)", other)),
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_clock_line() {
+        assert_eq!(
+            parse_clock_line("- clock: 09:00-11:30 atlas API design"),
+            Some(ClockLine { minutes: 150, project: "atlas".into(), label: Some("API design".into()) })
+        );
+        assert_eq!(parse_clock_line("CLOCK: 23:30-00:15 ops").map(|c| c.minutes), Some(45));
+        assert_eq!(parse_clock_line("clock: 9-11 atlas"), None);
+        assert_eq!(parse_clock_line("clock: 09:00-10:00"), None);
+        assert_eq!(parse_clock_line("the clock: broke"), None);
+    }
+
+    #[test]
+    fn test_invoice_csv_totals() {
+        let lines = vec![
+            parse_clock_line("clock: 09:00-10:30 atlas").unwrap(),
+            parse_clock_line("clock: 13:00-14:00 atlas").unwrap(),
+            parse_clock_line("clock: 15:00-15:45 zeus review, QA").unwrap(),
+        ];
+        let summary = summarize(&lines);
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].hours, 2.5);
+
+        let csv = render_invoice(&summary, Some(90.0), "csv").unwrap();
+        assert_eq!(
+            csv,
+            "project,label,hours,rate,amount\natlas,,2.50,90.00,225.00\nzeus,\"review, QA\",0.75,90.00,67.50\ntotal,,3.25,90.00,292.50\n"
+        );
+        assert!(render_invoice(&summary, None, "md").unwrap().ends_with("| **Total** | | **3.25** | |  |\n"));
+        assert!(render_invoice(&summary, None, "pdf").is_err());
+    }
+}
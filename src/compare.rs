@@ -0,0 +1,355 @@
+//! `compare`: two days' entries side by side in the terminal (requires the `tui` feature).
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{Datelike, NaiveDate};
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+
+use crate::i18n::{Language, Message};
+use crate::keys::{Action, Keymap};
+use crate::theme::{self, Role, Theme};
+use crate::{find_entries, focus, frontmatter, read_entry, secondary_date};
+
+/// Text of every entry written on `date`, oldest first, without frontmatter
+pub fn day_text(journal_path: &Path, date: NaiveDate, language: Language) -> String {
+    let entries = find_entries(journal_path, Some(date.day()), Some(date.month()), Some(date.year())).unwrap_or_default();
+    let bodies: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| read_entry(entry).ok())
+        .map(|content| frontmatter::parse(&content).1.trim().to_string())
+        .collect();
+    if bodies.is_empty() { language.text(Message::NoEntries).to_string() } else { bodies.join("\n\n---\n\n") }
+}
+
+struct Pane {
+    date: NaiveDate,
+    text: String,
+    /// `text` wrapped to the width it was last drawn at
+    rows: Vec<String>,
+    /// Role of the line each row belongs to, for colouring
+    roles: Vec<Option<Role>>,
+    width: usize,
+    scroll: usize,
+}
+
+impl Pane {
+    fn load(journal_path: &Path, date: NaiveDate, language: Language) -> Self {
+        let text = day_text(journal_path, date, language);
+        let rows = text.lines().map(str::to_string).collect();
+        let roles = text.lines().map(theme::line_role).collect();
+        Pane { date, text, rows, roles, width: 0, scroll: 0 }
+    }
+
+    fn wrap_to(&mut self, width: usize) {
+        if width == self.width {
+            return;
+        }
+        self.rows.clear();
+        self.roles.clear();
+        for line in self.text.lines() {
+            let chars: Vec<char> = line.chars().collect();
+            let starts = focus::wrap(&chars, width);
+            let ends: Vec<usize> = starts.iter().skip(1).copied().chain([chars.len()]).collect();
+            for (start, end) in starts.into_iter().zip(ends) {
+                self.rows.push(chars[start..end].iter().collect());
+                self.roles.push(theme::line_role(line));
+            }
+        }
+        self.width = width;
+        self.scroll = self.scroll.min(self.last_row());
+    }
+
+    fn last_row(&self) -> usize {
+        self.rows.len().saturating_sub(1)
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self.scroll.saturating_add_signed(delta).min(self.last_row());
+    }
+
+    /// Scroll to the next (or previous) row containing `query`, ignoring case
+    fn find(&mut self, query: &str, forward: bool) -> bool {
+        let query = query.to_lowercase();
+        let hit = |i: &usize| self.rows[*i].to_lowercase().contains(&query);
+        let found = if forward {
+            (self.scroll + 1..self.rows.len()).chain(0..=self.scroll.min(self.last_row())).find(hit)
+        } else {
+            (0..self.scroll).rev().chain((self.scroll..self.rows.len()).rev()).find(hit)
+        };
+        if let Some(row) = found {
+            self.scroll = row;
+        }
+        found.is_some()
+    }
+}
+
+pub struct Comparison {
+    journal_path: PathBuf,
+    keymap: Keymap,
+    theme: Theme,
+    language: Language,
+    panes: [Pane; 2],
+    /// Index of the pane that scrolls
+    active: usize,
+    /// Scroll both panes together
+    linked: bool,
+    /// Search being typed after `/`
+    input: Option<String>,
+    /// Last search, repeated by next/previous match
+    query: Option<String>,
+    message: Option<String>,
+}
+
+impl Comparison {
+    pub fn new(journal_path: &Path, keymap: Keymap, theme: Theme, language: Language, left: NaiveDate, right: NaiveDate) -> Self {
+        Comparison {
+            journal_path: journal_path.to_path_buf(),
+            keymap,
+            theme,
+            language,
+            panes: [Pane::load(journal_path, left, language), Pane::load(journal_path, right, language)],
+            active: 1,
+            linked: false,
+            input: None,
+            query: None,
+            message: None,
+        }
+    }
+
+    fn scrolled(&mut self, f: impl Fn(&mut Pane)) {
+        if self.linked {
+            self.panes.iter_mut().for_each(f);
+        } else {
+            f(&mut self.panes[self.active]);
+        }
+    }
+
+    /// Move both days by `days`, keeping the distance between them
+    fn shift(&mut self, days: i64) {
+        for pane in self.panes.iter_mut() {
+            if let Some(date) = pane.date.checked_add_signed(chrono::Duration::days(days)) {
+                *pane = Pane::load(&self.journal_path, date, self.language);
+            }
+        }
+    }
+
+    fn find(&mut self, forward: bool) {
+        let Some(query) = self.query.clone() else {
+            return;
+        };
+        if !self.panes[self.active].find(&query, forward) {
+            self.message = Some(format!("{}: {}", self.language.text(Message::NotFound), query));
+        }
+    }
+
+    /// Typing a search after `/`; Enter runs it, Esc cancels
+    fn handle_input(&mut self, key: KeyEvent) {
+        let Some(input) = self.input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                self.query = self.input.take().filter(|q| !q.is_empty());
+                self.find(true);
+            }
+            KeyCode::Esc => self.input = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+    }
+
+    /// Apply a key press; returns false once the view should close
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.kind != KeyEventKind::Press {
+            return true;
+        }
+        if self.input.is_some() {
+            self.handle_input(key);
+            return true;
+        }
+        self.message = None;
+        let page = 10;
+        match self.keymap.feed(&key) {
+            Some(Action::Quit) => return false,
+            Some(Action::ScrollDown) => self.scrolled(|p| p.scroll_by(1)),
+            Some(Action::ScrollUp) => self.scrolled(|p| p.scroll_by(-1)),
+            Some(Action::PageDown) => self.scrolled(|p| p.scroll_by(page)),
+            Some(Action::PageUp) => self.scrolled(|p| p.scroll_by(-page)),
+            Some(Action::Top) => self.scrolled(|p| p.scroll = 0),
+            Some(Action::Bottom) => self.scrolled(|p| p.scroll = p.last_row()),
+            Some(Action::Search) => self.input = Some(String::new()),
+            Some(Action::NextMatch) => self.find(true),
+            Some(Action::PreviousMatch) => self.find(false),
+            Some(Action::SwitchPane) => self.active = 1 - self.active,
+            Some(Action::LeftPane) => self.active = 0,
+            Some(Action::RightPane) => self.active = 1,
+            Some(Action::LinkScroll) => self.linked = !self.linked,
+            Some(Action::PreviousDay) => self.shift(-1),
+            Some(Action::NextDay) => self.shift(1),
+            Some(Action::Save | Action::ToggleTypewriter) | None => {}
+        }
+        true
+    }
+
+    fn draw(&mut self, frame: &mut ratatui::Frame) {
+        let [body, help] = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        let columns = Layout::horizontal([Constraint::Percentage(50); 2]).split(body);
+        for (i, (pane, area)) in self.panes.iter_mut().zip(columns.iter()).enumerate() {
+            let border = if i == self.active || self.linked {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().add_modifier(Modifier::DIM)
+            };
+            let date = match secondary_date(pane.date) {
+                Some(secondary) => format!(" {} · {} ", self.language.long_date(pane.date), secondary),
+                None => format!(" {} ", self.language.long_date(pane.date)),
+            };
+            let date = Span::styled(date, self.theme.style(Role::Date));
+            let block = Block::bordered().title(date).border_style(border);
+            pane.wrap_to(block.inner(*area).width.max(1) as usize);
+            let rows: Vec<Line> = pane
+                .rows
+                .iter()
+                .zip(&pane.roles)
+                .skip(pane.scroll)
+                .map(|(row, role)| match role {
+                    Some(role) => Line::styled(row.as_str(), self.theme.style(*role)),
+                    None => Line::raw(row.as_str()),
+                })
+                .collect();
+            frame.render_widget(Paragraph::new(rows).block(block), *area);
+        }
+
+        let status = match (&self.input, &self.message) {
+            (Some(input), _) => format!("/{}", input),
+            (None, Some(message)) => message.clone(),
+            (None, None) => {
+                let (keys, text) = (&self.keymap, |message| self.language.text(message));
+                format!(
+                    "{} {}   {}/{} {}   {} {}   {} {} ({})   {} {} {}   {} {}",
+                    keys.label(Action::SwitchPane),
+                    text(Message::SwitchPane),
+                    keys.label(Action::ScrollDown),
+                    keys.label(Action::ScrollUp),
+                    text(Message::Scroll),
+                    keys.label(Action::Search),
+                    text(Message::Search),
+                    keys.label(Action::LinkScroll),
+                    text(Message::LinkedScrolling),
+                    text(if self.linked { Message::On } else { Message::Off }),
+                    keys.label(Action::PreviousDay),
+                    keys.label(Action::NextDay),
+                    text(Message::PreviousNextDay),
+                    keys.label(Action::Quit),
+                    text(Message::Quit),
+                )
+            }
+        };
+        let style = if self.input.is_some() { Style::default() } else { Style::default().add_modifier(Modifier::DIM) };
+        frame.render_widget(Paragraph::new(status).style(style).centered(), help);
+    }
+
+    fn run_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<(), String> {
+        loop {
+            terminal.draw(|frame| self.draw(frame)).map_err(|e| e.to_string())?;
+            if event::poll(Duration::from_millis(250)).map_err(|e| e.to_string())?
+                && let Event::Key(key) = event::read().map_err(|e| e.to_string())?
+                && !self.handle_key(key)
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Take over the terminal until the user quits
+    pub fn run(mut self) -> Result<(), String> {
+        let mut terminal = ratatui::try_init().map_err(|e| format!("Failed to set up the terminal: {}", e))?;
+        let result = self.run_loop(&mut terminal);
+        ratatui::restore();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn journal() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        for (month, name, content) in [
+            ("2025/02", "16-080000-ski.md", "---\ntags: [trip]\n---\n\n# Ski\n\nFirst time skiing\n"),
+            ("2026/02", "16-080000-work.md", "# Work\n\nRelease day\n"),
+            ("2026/02", "16-200000-evening.md", "# Evening\n\nQuiet\n"),
+            ("2026/02", "17-080000-next.md", "# Next\n"),
+        ] {
+            fs::create_dir_all(dir.path().join(month)).unwrap();
+            fs::write(dir.path().join(month).join(name), content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_day_text() {
+        let dir = journal();
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(day_text(dir.path(), date(2025, 2, 16), Language::English), "# Ski\n\nFirst time skiing");
+        assert_eq!(day_text(dir.path(), date(2026, 2, 16), Language::English), "# Work\n\nRelease day\n\n---\n\n# Evening\n\nQuiet");
+        assert_eq!(day_text(dir.path(), date(2024, 2, 16), Language::English), "No entries.");
+        assert_eq!(day_text(dir.path(), date(2024, 2, 16), Language::German), "Keine Einträge.");
+    }
+
+    #[test]
+    fn test_keys_scroll_and_shift_days() {
+        let dir = journal();
+        let left = NaiveDate::from_ymd_opt(2025, 2, 16).unwrap();
+        let keymap = Keymap::new(None).unwrap();
+        let mut view = Comparison::new(dir.path(), keymap, Theme::from_config(None).unwrap(), Language::English, left, NaiveDate::from_ymd_opt(2026, 2, 16).unwrap());
+        let press = |view: &mut Comparison, c: char| view.handle_key(KeyEvent::from(KeyCode::Char(c)));
+
+        press(&mut view, 'j');
+        assert_eq!((view.panes[0].scroll, view.panes[1].scroll), (0, 1));
+        press(&mut view, 's');
+        press(&mut view, 'G');
+        // Each pane stops at its last row
+        assert_eq!((view.panes[0].scroll, view.panes[1].scroll), (2, 8));
+        press(&mut view, 'g');
+        press(&mut view, 'g');
+        assert_eq!((view.panes[0].scroll, view.panes[1].scroll), (0, 0));
+
+        press(&mut view, ']');
+        assert_eq!(view.panes[0].text, "No entries.");
+        assert_eq!(view.panes[1].text, "# Next");
+        assert!(!press(&mut view, 'q'));
+    }
+
+    #[test]
+    fn test_search_and_wrapping() {
+        let dir = journal();
+        let date = NaiveDate::from_ymd_opt(2026, 2, 16).unwrap();
+        let mut view = Comparison::new(dir.path(), Keymap::new(None).unwrap(), Theme::from_config(None).unwrap(), Language::English, date, date);
+        for c in "/quiet".chars() {
+            view.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        view.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(view.panes[1].scroll, 8);
+        view.handle_key(KeyEvent::from(KeyCode::Char('n')));
+        assert_eq!(view.panes[1].scroll, 8);
+        assert!(view.message.is_none());
+
+        let pane = &mut view.panes[0];
+        pane.wrap_to(4);
+        assert_eq!(&pane.rows[..4], ["# ", "Work", "", "Rele"]);
+        assert_eq!(&pane.roles[..3], [Some(Role::Title), Some(Role::Title), None]);
+    }
+}
@@ -0,0 +1,155 @@
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{changes, interrupt, is_valid_month, is_valid_year, progress, record_change};
+
+/// Extension used for compressed journal entries
+pub const GZ_EXTENSION: &str = ".md.gz";
+
+/// Read a gzipped entry and return its decompressed content
+pub fn read_gz(path: &Path) -> std::io::Result<String> {
+    let file = fs::File::open(path)?;
+    let mut decoder = GzDecoder::new(file);
+    let mut content = String::new();
+    decoder.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// Gzip a single entry next to the original and remove the plain file
+fn compress_file(path: &Path) -> Result<PathBuf, String> {
+    let content = fs::read(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let filename = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or("Invalid entry filename")?;
+    let target = path.with_file_name(format!("{}.gz", filename));
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+
+    // Write to a temporary file first so an interrupted run never leaves a truncated .gz
+    let tmp = path.with_file_name(format!("{}.gz.tmp", filename));
+    let file = fs::File::create(&tmp)
+        .map_err(|e| format!("Failed to create {}: {}", tmp.display(), e))?;
+    let mut encoder = GzEncoder::new(file, Compression::best());
+    let written = encoder
+        .write_all(&content)
+        .and_then(|_| encoder.finish().map(|_| ()))
+        .map_err(|e| format!("Failed to compress {}: {}", path.display(), e))
+        .and_then(|_| {
+            fs::rename(&tmp, &target)
+                .map_err(|e| format!("Failed to move {} into place: {}", target.display(), e))
+        });
+    if written.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    written?;
+    fs::remove_file(path)
+        .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+
+    Ok(target)
+}
+
+/// Compress every plain `.md` entry in year folders older than `before_year`, stopping
+/// early (with what's done so far) on Ctrl-C
+pub fn compress_before(journal_path: &Path, before_year: i32) -> Result<Vec<PathBuf>, String> {
+    let mut pending = Vec::new();
+
+    let years = fs::read_dir(journal_path)
+        .map_err(|e| format!("Failed to read {}: {}", journal_path.display(), e))?;
+
+    let mut year_dirs: Vec<PathBuf> = years
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .filter(|name| is_valid_year(name))
+                .and_then(|name| name.parse::<i32>().ok())
+                .is_some_and(|year| year < before_year)
+        })
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    year_dirs.sort();
+
+    for year_dir in year_dirs {
+        let Ok(months) = fs::read_dir(&year_dir) else {
+            continue;
+        };
+        let mut month_dirs: Vec<PathBuf> = months
+            .flatten()
+            .filter(|entry| entry.file_name().to_str().is_some_and(is_valid_month))
+            .map(|entry| entry.path())
+            .collect();
+        month_dirs.sort();
+
+        for month_dir in month_dirs {
+            let Ok(files) = fs::read_dir(&month_dir) else {
+                continue;
+            };
+            let mut plain: Vec<PathBuf> = files
+                .flatten()
+                .filter(|file| file.file_name().to_str().is_some_and(|name| name.ends_with(".md")))
+                .map(|file| file.path())
+                .collect();
+            plain.sort();
+            pending.extend(plain);
+        }
+    }
+
+    let bar = progress::bar(pending.len(), "Compressing");
+    let mut compressed = Vec::new();
+    for file in pending {
+        if interrupt::requested() {
+            break;
+        }
+        let result = compress_file(&file);
+        bar.inc(1);
+        match result {
+            Ok(target) => {
+                // Same content, so the hash carries over unchanged
+                record_change(journal_path, changes::Action::Move, &target, Some(&file), changes::hash(&target));
+                compressed.push(target);
+            }
+            Err(e) => {
+                bar.finish_and_clear();
+                return Err(e);
+            }
+        }
+    }
+    bar.finish_and_clear();
+    Ok(compressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compress_before_only_touches_older_years() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let old_month = temp_dir.path().join("2021").join("05");
+        let new_month = temp_dir.path().join("2023").join("01");
+        fs::create_dir_all(&old_month).unwrap();
+        fs::create_dir_all(&new_month).unwrap();
+        fs::write(old_month.join("03-080000-old.md"), "# old\n\nDate: 03-05-2021\n\nhello\n").unwrap();
+        fs::write(new_month.join("03-080000-new.md"), "# new\n").unwrap();
+
+        let compressed = compress_before(temp_dir.path(), 2023).expect("compress failed");
+
+        assert_eq!(compressed, vec![old_month.join("03-080000-old.md.gz")]);
+        assert!(!old_month.join("03-080000-old.md").exists());
+        assert!(new_month.join("03-080000-new.md").exists());
+        assert_eq!(
+            read_gz(&compressed[0]).unwrap(),
+            "# old\n\nDate: 03-05-2021\n\nhello\n"
+        );
+    }
+}
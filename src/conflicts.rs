@@ -0,0 +1,152 @@
+//! Conflict copies: the second version of an entry that a sync tool kept when both sides
+//! changed it. Recognizes file-journal's own `<name>-conflict.md`, Dropbox's
+//! `<name> (… conflicted copy …).md` and Google Drive's duplicate `<name> (1).md`, so they
+//! can be cleaned up the same way: copies identical to their entry are dropped, and the
+//! others renamed to `<name>-conflict.md` for a person to merge.
+
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::compress::GZ_EXTENSION;
+use crate::{find_all_entries, read_entry};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Provider {
+    FileJournal,
+    Dropbox,
+    GoogleDrive,
+}
+
+impl Provider {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Provider::FileJournal => "file-journal",
+            Provider::Dropbox => "Dropbox",
+            Provider::GoogleDrive => "Google Drive",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub copy: PathBuf,
+    /// The entry it's a copy of
+    pub original: PathBuf,
+    pub provider: Provider,
+}
+
+static PATTERNS: LazyLock<[(Provider, Regex); 3]> = LazyLock::new(|| {
+    [
+        (Provider::FileJournal, Regex::new(r"^(?P<base>.+)-conflict(?:-\d+)?$").expect("valid regex")),
+        (Provider::Dropbox, Regex::new(r"^(?P<base>.+) \([^()]*conflicted copy[^()]*\)$").expect("valid regex")),
+        (Provider::GoogleDrive, Regex::new(r"^(?P<base>.+?) ?\(\d+\)$").expect("valid regex")),
+    ]
+});
+
+fn split_extension(filename: &str) -> Option<(&str, &str)> {
+    for extension in [GZ_EXTENSION, ".md"] {
+        if let Some(stem) = filename.strip_suffix(extension) {
+            return Some((stem, extension));
+        }
+    }
+    None
+}
+
+/// The entry filename a conflict copy's name points back to, and who made the copy
+pub fn detect(filename: &str) -> Option<(String, Provider)> {
+    let (stem, extension) = split_extension(filename)?;
+    PATTERNS.iter().find_map(|(provider, pattern)| {
+        let base = pattern.captures(stem)?.name("base")?.as_str();
+        Some((format!("{}{}", base, extension), *provider))
+    })
+}
+
+/// Every conflict copy in the journal whose entry still exists
+pub fn find(journal_path: &Path) -> Result<Vec<Conflict>, String> {
+    let mut conflicts = Vec::new();
+    for copy in find_all_entries(journal_path)?.into_iter().filter(|path| path.is_file()) {
+        let Some(filename) = copy.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if let Some((original, provider)) = detect(filename) {
+            let original = copy.with_file_name(original);
+            if original.is_file() {
+                conflicts.push(Conflict { copy, original, provider });
+            }
+        }
+    }
+    Ok(conflicts)
+}
+
+impl Conflict {
+    /// Whether the copy says the same as the entry, so nothing is lost by dropping it
+    pub fn identical(&self) -> bool {
+        match (read_entry(&self.copy), read_entry(&self.original)) {
+            (Ok(copy), Ok(original)) => copy == original,
+            _ => false,
+        }
+    }
+
+    /// `<name>-conflict.md` next to the entry (numbered if taken), or `None` if the copy
+    /// already has that form
+    pub fn normalized(&self) -> Option<PathBuf> {
+        if self.provider == Provider::FileJournal {
+            return None;
+        }
+        let filename = self.original.file_name()?.to_str()?;
+        let (stem, extension) = split_extension(filename)?;
+        let mut target = self.original.with_file_name(format!("{}-conflict{}", stem, extension));
+        let mut n = 1;
+        while target.exists() {
+            n += 1;
+            target = self.original.with_file_name(format!("{}-conflict-{}{}", stem, n, extension));
+        }
+        Some(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect() {
+        let cases = [
+            ("17-081503-notes-conflict.md", Some(("17-081503-notes.md", Provider::FileJournal))),
+            ("17-081503-notes-conflict-2.md.gz", Some(("17-081503-notes.md.gz", Provider::FileJournal))),
+            ("17-081503-notes (Ana's conflicted copy 2026-02-17).md", Some(("17-081503-notes.md", Provider::Dropbox))),
+            ("17-081503-notes (conflicted copy 2026-02-17 081503).md", Some(("17-081503-notes.md", Provider::Dropbox))),
+            ("17-081503-notes (1).md", Some(("17-081503-notes.md", Provider::GoogleDrive))),
+            ("17-081503-notes(2).md", Some(("17-081503-notes.md", Provider::GoogleDrive))),
+            ("17-081503-notes.md", None),
+            ("17-081503-notes (draft).md", None),
+        ];
+        for (filename, expected) in cases {
+            let detected = detect(filename);
+            assert_eq!(detected.as_ref().map(|(name, provider)| (name.as_str(), *provider)), expected, "{}", filename);
+        }
+    }
+
+    #[test]
+    fn test_find_and_normalize() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        fs::write(month.join("17-081503-notes.md"), "# Notes\n").unwrap();
+        fs::write(month.join("17-081503-notes (1).md"), "# Notes\n").unwrap();
+        fs::write(month.join("17-081503-notes (Ana's conflicted copy 2026-02-17).md"), "# Notes\n\nAna's\n").unwrap();
+        fs::write(month.join("17-081503-notes-conflict.md"), "# Notes\n\nother\n").unwrap();
+        // No entry left to conflict with
+        fs::write(month.join("18-090000-gone (1).md"), "# Gone\n").unwrap();
+
+        let conflicts = find(dir.path()).unwrap();
+        let providers: Vec<Provider> = conflicts.iter().map(|c| c.provider).collect();
+        assert_eq!(providers, [Provider::GoogleDrive, Provider::Dropbox, Provider::FileJournal]);
+        assert_eq!(conflicts.iter().map(Conflict::identical).collect::<Vec<_>>(), [true, false, false]);
+        assert_eq!(conflicts[1].normalized(), Some(month.join("17-081503-notes-conflict-2.md")));
+        assert_eq!(conflicts[2].normalized(), None);
+    }
+}
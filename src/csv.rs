@@ -0,0 +1,290 @@
+//! CSV import: reading the file (RFC 4180 quoting, so fields may hold separators, quotes and
+//! line breaks), mapping columns to entry fields with `--map field=Column`, and telling which
+//! date format the date column is written in.
+
+use std::collections::BTreeMap;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+/// Entry fields a column can be mapped to; `date` is required
+pub const FIELDS: [&str; 5] = ["date", "time", "title", "body", "tags"];
+
+/// Date formats tried on the date column, most specific first (`%B` reads `Feb` as well as
+/// `February`)
+const DATE_FORMATS: [&str; 15] = [
+    "%Y-%m-%dT%H:%M:%S%.f%:z",
+    "%Y-%m-%dT%H:%M:%S%.fZ",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%d/%m/%Y %H:%M",
+    "%m/%d/%Y %H:%M",
+    "%d/%m/%Y",
+    "%m/%d/%Y",
+    "%d.%m.%Y %H:%M",
+    "%d.%m.%Y",
+    "%B %d, %Y",
+    "%d %B %Y",
+];
+
+/// Formats tried on a separate time column
+const TIME_FORMATS: [&str; 4] = ["%H:%M:%S", "%H:%M", "%I:%M %p", "%I:%M:%S %p"];
+
+/// Records of `text`, the header row first. Blank lines are skipped.
+pub fn parse(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                line += 1;
+                record.push(std::mem::take(&mut field));
+                if record.len() > 1 || !record[0].is_empty() {
+                    records.push(std::mem::take(&mut record));
+                } else {
+                    record.clear();
+                }
+            }
+            c if c == delimiter => record.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("Unclosed quote on line {}", line));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Which column each mapped field comes from
+#[derive(Debug)]
+pub struct Mapping {
+    columns: BTreeMap<&'static str, usize>,
+}
+
+impl Mapping {
+    /// Mapping from `field=Column` pairs, with columns looked up in `header` (ignoring case
+    /// when there's no exact match)
+    pub fn new(maps: &[String], header: &[String]) -> Result<Mapping, String> {
+        let mut columns = BTreeMap::new();
+        for map in maps {
+            let Some((field, column)) = map.split_once('=') else {
+                return Err(format!("Invalid --map '{}': expected field=Column, e.g. date=Date", map));
+            };
+            let field = field.trim().to_lowercase();
+            let Some(field) = FIELDS.iter().copied().find(|f| *f == field) else {
+                return Err(format!("Unknown field '{}' in --map: expected one of {}", field, FIELDS.join(", ")));
+            };
+            let column = column.trim();
+            let index = header
+                .iter()
+                .position(|name| name.trim() == column)
+                .or_else(|| header.iter().position(|name| name.trim().eq_ignore_ascii_case(column)))
+                .ok_or_else(|| format!("No column '{}' in the CSV (it has: {})", column, header.join(", ")))?;
+            if columns.insert(field, index).is_some() {
+                return Err(format!("{} is mapped more than once", field));
+            }
+        }
+        if !columns.contains_key("date") {
+            return Err("Map a column to the entry date, e.g. --map date=Date".to_string());
+        }
+        Ok(Mapping { columns })
+    }
+
+    /// Value of `field` in `record`, empty when the field isn't mapped or the row is short
+    fn get<'a>(&self, field: &str, record: &'a [String]) -> &'a str {
+        self.columns.get(field).and_then(|&index| record.get(index)).map_or("", |value| value.trim())
+    }
+
+    /// Values of the date column, skipping the header
+    pub fn dates<'a>(&self, records: &'a [Vec<String>]) -> Vec<&'a str> {
+        records.iter().skip(1).map(|record| self.get("date", record)).collect()
+    }
+}
+
+/// Day and, if the format has one, time of `value`
+fn parse_date(value: &str, format: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    match NaiveDateTime::parse_from_str(value, format) {
+        Ok(written) => Some((written.date(), Some(written.time()))),
+        Err(_) => NaiveDate::parse_from_str(value, format).ok().map(|date| (date, None)),
+    }
+}
+
+/// The one date format every non-empty value is written in. An error if none fits, or if
+/// formats that fit read the same value as different days (such as 03/04/2026, day-first or
+/// month-first) so it takes `--date-format` to settle.
+pub fn detect_date_format(values: &[&str]) -> Result<&'static str, String> {
+    let values: Vec<&str> = values.iter().copied().filter(|value| !value.is_empty()).collect();
+    let Some(first) = values.first() else {
+        return Err("The date column is empty".to_string());
+    };
+    let fits = |format: &str| values.iter().all(|value| parse_date(value, format).is_some());
+    let fitting: Vec<&str> = DATE_FORMATS.iter().copied().filter(|format| fits(format)).collect();
+    match fitting.as_slice() {
+        [] => Err(format!("Can't tell the date format of values like '{}'; pass --date-format, e.g. %d/%m/%Y", first)),
+        [format, others @ ..] => {
+            let read = |format: &str| values.iter().map(|value| parse_date(value, format)).collect::<Vec<_>>();
+            match others.iter().find(|other| read(other) != read(format)) {
+                Some(other) => {
+                    Err(format!("The dates fit both {} and {}; pass --date-format to pick one", format, other))
+                }
+                None => Ok(format),
+            }
+        }
+    }
+}
+
+/// An entry to create from one CSV row
+#[derive(Debug, PartialEq)]
+pub struct Row {
+    /// 1-based row in the file, counting the header
+    pub number: usize,
+    pub written: NaiveDateTime,
+    pub title: String,
+    pub body: String,
+    pub tags: Vec<String>,
+}
+
+/// Entries for every row after the header. Rows with no time (in the date or a time column)
+/// are written at midnight, a second apart in file order, so they keep their order.
+pub fn rows(records: &[Vec<String>], mapping: &Mapping, date_format: &str) -> Result<Vec<Row>, String> {
+    let mut untimed: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    let mut rows = Vec::new();
+    for (i, record) in records.iter().enumerate().skip(1) {
+        let number = i + 1;
+        let value = mapping.get("date", record);
+        let Some((date, time)) = parse_date(value, date_format) else {
+            return Err(format!("Row {}: date '{}' doesn't match {}", number, value, date_format));
+        };
+        let time = match (time, mapping.get("time", record)) {
+            (_, "") | (Some(_), _) => time,
+            (None, value) => Some(
+                TIME_FORMATS
+                    .iter()
+                    .find_map(|format| NaiveTime::parse_from_str(value, format).ok())
+                    .ok_or_else(|| format!("Row {}: can't read time '{}'", number, value))?,
+            ),
+        };
+        let time = time.unwrap_or_else(|| {
+            let seconds = untimed.entry(date).or_default();
+            *seconds += 1;
+            NaiveTime::from_num_seconds_from_midnight_opt(*seconds - 1, 0).unwrap_or_default()
+        });
+        let title = match mapping.get("title", record) {
+            "" => "journal".to_string(),
+            title => title.to_string(),
+        };
+        let tags = mapping
+            .get("tags", record)
+            .split([',', ';'])
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect();
+        rows.push(Row {
+            number,
+            written: date.and_time(time),
+            title,
+            body: mapping.get("body", record).to_string(),
+            tags,
+        });
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = concat!(
+            "\u{feff}Date,Subject,Notes\r\n",
+            "2026-02-17,\"Sync, weekly\",\"Said \"\"hi\"\"\nthen left\"\n",
+            "\n",
+            "2026-02-18,x,\n"
+        );
+        assert_eq!(
+            parse(text, ',').unwrap(),
+            [
+                vec!["Date", "Subject", "Notes"],
+                vec!["2026-02-17", "Sync, weekly", "Said \"hi\"\nthen left"],
+                vec!["2026-02-18", "x", ""],
+            ]
+        );
+        assert_eq!(parse("a;b\n1;2", ';').unwrap(), [vec!["a", "b"], vec!["1", "2"]]);
+        assert!(parse("a,b\n\"1,2\n", ',').unwrap_err().contains("line 3"));
+    }
+
+    #[test]
+    fn test_detect_date_format() {
+        assert_eq!(detect_date_format(&["2026-02-17", "", "2026-03-01"]), Ok("%Y-%m-%d"));
+        assert_eq!(detect_date_format(&["17/02/2026", "03/04/2026"]), Ok("%d/%m/%Y"));
+        assert_eq!(detect_date_format(&["Feb 17, 2026"]), Ok("%B %d, %Y"));
+        assert_eq!(detect_date_format(&["2026-02-17T09:30:00Z"]), Ok("%Y-%m-%dT%H:%M:%S%.fZ"));
+        assert!(detect_date_format(&["03/04/2026"]).unwrap_err().contains("--date-format"));
+        assert!(detect_date_format(&["someday"]).is_err());
+    }
+
+    #[test]
+    fn test_rows() {
+        let text = concat!(
+            "Date,At,Subject,Notes,Labels\n",
+            "17/02/2026,,Standup,Done,work; team\n",
+            "17/02/2026,,,,\n",
+            "18/02/2026,9:05,Plan,,\n"
+        );
+        let records = parse(text, ',').unwrap();
+        let maps = ["date=Date", "time=at", "title=Subject", "body=Notes", "tags=Labels"].map(String::from);
+        let mapping = Mapping::new(&maps, &records[0]).unwrap();
+        let format = detect_date_format(&mapping.dates(&records)).unwrap();
+        let rows = rows(&records, &mapping, format).unwrap();
+        let at = |d, h, m, s| NaiveDate::from_ymd_opt(2026, 2, d).unwrap().and_hms_opt(h, m, s).unwrap();
+        let row = |number, written, title: &str, body: &str, tags: &[&str]| Row {
+            number,
+            written,
+            title: title.to_string(),
+            body: body.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        };
+        assert_eq!(
+            rows,
+            [
+                row(2, at(17, 0, 0, 0), "Standup", "Done", &["work", "team"]),
+                row(3, at(17, 0, 0, 1), "journal", "", &[]),
+                row(4, at(18, 9, 5, 0), "Plan", "", &[]),
+            ]
+        );
+
+        assert!(Mapping::new(&["title=Subject".to_string()], &records[0]).unwrap_err().contains("date"));
+        assert!(Mapping::new(&["date=When".to_string()], &records[0]).unwrap_err().contains("No column 'When'"));
+        assert!(Mapping::new(&["mood=Notes".to_string()], &records[0]).is_err());
+    }
+}
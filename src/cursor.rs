@@ -0,0 +1,98 @@
+//! `get --since-last-run`: a high-water mark per named consumer (a backup or sync script),
+//! kept in `.cursors.json` at the journal root, so each run only sees entries created or
+//! modified since that consumer's previous run.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{entry_modified, lock};
+
+pub const CURSORS_FILE: &str = ".cursors.json";
+
+pub struct Cursor {
+    path: PathBuf,
+    name: String,
+    marks: BTreeMap<String, String>,
+    since: Option<DateTime<Utc>>,
+    /// Becomes the new mark, so entries changed while this run reads them are seen again
+    started: DateTime<Utc>,
+    /// Keeps two runs of the same consumer from moving the mark past each other
+    _lock: lock::Lock,
+}
+
+impl Cursor {
+    /// The consumer's cursor; one that never ran before sees every entry
+    pub fn open(journal_path: &Path, name: &str) -> Result<Cursor, String> {
+        if name.trim().is_empty() {
+            return Err("Cursor name can't be empty".to_string());
+        }
+        let path = journal_path.join(CURSORS_FILE);
+        let lock = lock::acquire(&path)?;
+        let marks: BTreeMap<String, String> = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        let since = match marks.get(name) {
+            Some(mark) => Some(
+                DateTime::parse_from_rfc3339(mark)
+                    .map_err(|e| format!("Invalid mark for cursor '{}' in {}: {}", name, path.display(), e))?
+                    .with_timezone(&Utc),
+            ),
+            None => None,
+        };
+        Ok(Cursor { path, name: name.to_string(), marks, since, started: Utc::now(), _lock: lock })
+    }
+
+    /// The entries created or modified since the consumer's last run
+    pub fn changed(&self, entries: Vec<PathBuf>) -> Vec<PathBuf> {
+        let Some(since) = self.since else {
+            return entries;
+        };
+        entries.into_iter().filter(|entry| entry_modified(entry).is_none_or(|time| time >= since)).collect()
+    }
+
+    /// Record this run, once its output is written
+    pub fn advance(mut self) -> Result<(), String> {
+        self.marks.insert(self.name.clone(), self.started.to_rfc3339_opts(SecondsFormat::Nanos, true));
+        let json = serde_json::to_string_pretty(&self.marks).map_err(|e| format!("Failed to encode cursors: {}", e))?;
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, json).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+        fs::rename(&tmp, &self.path).map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_since_last_run() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let old = dir.path().join("old.md");
+        let new = dir.path().join("new.md");
+        fs::write(&old, "# Old\n").unwrap();
+        fs::write(&new, "# New\n").unwrap();
+        let earlier = SystemTime::now() - Duration::from_secs(3600);
+        fs::File::options().write(true).open(&old).unwrap().set_modified(earlier).unwrap();
+        let entries = || vec![old.clone(), new.clone()];
+
+        // A consumer's first run sees everything
+        let cursor = Cursor::open(dir.path(), "backup").unwrap();
+        assert_eq!(cursor.changed(entries()).len(), 2);
+        cursor.advance().unwrap();
+
+        // Then only what changed since
+        fs::File::options().write(true).open(&new).unwrap().set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+        let cursor = Cursor::open(dir.path(), "backup").unwrap();
+        assert_eq!(cursor.changed(entries()), vec![new.clone()]);
+        drop(cursor);
+
+        // Consumers are independent
+        assert_eq!(Cursor::open(dir.path(), "sync").unwrap().changed(entries()).len(), 2);
+        assert!(Cursor::open(dir.path(), " ").is_err());
+    }
+}
@@ -0,0 +1,381 @@
+//! The journal API as JSON-RPC 2.0, served newline-delimited over a unix socket by
+//! `file-journal daemon` and over HTTP by `file-journal serve`.
+//!
+//! Methods: `ping`, `list {selector?}` (each entry's `path`, `date`, `time` and `title`), `read {path}`, `create {title, note?}`, `append {text}`.
+
+use serde_json::{Value, json};
+use std::collections::{BTreeSet, HashMap};
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Component, Path, PathBuf};
+#[cfg(unix)]
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use sha2::{Digest, Sha256};
+use std::fs;
+
+use crate::archive;
+use crate::entry::Entry;
+#[cfg(feature = "network")]
+use crate::html;
+use crate::metrics::{self, Metrics};
+
+use crate::{
+    append_to_day, changes, entry_date, find_all_entries, frontmatter, read_entry, record_change,
+    resolve_journal_selector, write_entry,
+};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Application error, e.g. an entry that cannot be read
+const SERVER_ERROR: i64 = -32000;
+
+/// Whether a method changes the journal (as opposed to only reading it)
+pub fn is_write_method(method: &str) -> bool {
+    matches!(method, "create" | "append")
+}
+
+/// Default socket: `$XDG_RUNTIME_DIR/file-journal.sock`, or the temp dir
+#[cfg(unix)]
+pub fn default_socket() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("file-journal.sock")
+}
+
+pub struct Daemon {
+    journal_path: PathBuf,
+    author: Option<String>,
+    /// Entry contents by path, reused until the file's mtime changes
+    cache: HashMap<PathBuf, (Option<SystemTime>, String)>,
+    pub metrics: Metrics,
+}
+
+impl Daemon {
+    pub fn new(journal_path: PathBuf, author: Option<String>) -> Self {
+        Daemon { journal_path, author, cache: HashMap::new(), metrics: Metrics::default() }
+    }
+
+    pub fn journal_path(&self) -> &Path {
+        &self.journal_path
+    }
+
+    /// Prometheus metrics, with the entry count and streak computed from the journal now
+    pub fn render_metrics(&self) -> String {
+        let entries = find_all_entries(&self.journal_path).unwrap_or_default();
+        let dates: BTreeSet<_> = entries.iter().filter_map(|e| entry_date(e)).collect();
+        let streak = metrics::streak(&dates, chrono::Local::now().date_naive());
+        metrics::render(&self.metrics, entries.len(), streak)
+    }
+
+    fn read_cached(&mut self, path: &Path) -> Result<String, String> {
+        let modified = path.metadata().and_then(|m| m.modified()).ok();
+        if let Some((cached_at, content)) = self.cache.get(path)
+            && modified.is_some()
+            && *cached_at == modified
+        {
+            return Ok(content.clone());
+        }
+        let content = read_entry(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        self.cache.insert(path.to_path_buf(), (modified, content.clone()));
+        Ok(content)
+    }
+
+    /// Resolve a client-supplied entry path, refusing anything outside the journal
+    fn entry_path(&self, path: &str) -> Result<PathBuf, String> {
+        let path = Path::new(path);
+        if path.components().any(|c| c == Component::ParentDir) {
+            return Err("Entry paths may not contain '..'".to_string());
+        }
+        let path = if path.is_absolute() { path.to_path_buf() } else { self.journal_path.join(path) };
+        if !path.starts_with(&self.journal_path) {
+            return Err(format!("{} is outside the journal", path.display()));
+        }
+        Ok(path)
+    }
+
+    /// Entry content and its ETag, for `GET /entries/{id}`
+    pub fn get_entry(&mut self, id: &str) -> Result<(String, String), (u16, String)> {
+        let path = self.entry_path(id).map_err(|e| (400, e))?;
+        if !path.is_file() && archive::split_zip_path(&path).is_none() {
+            return Err((404, format!("No entry {}", id)));
+        }
+        self.metrics.queries += 1;
+        // Read fresh rather than from the cache so the ETag can't be stale
+        let content = read_entry(&path).map_err(|e| (404, format!("Failed to read {}: {}", id, e)))?;
+        let etag = etag(&content);
+        Ok((content, etag))
+    }
+
+    /// Replace an entry's content if `if_match` matches its current ETag, returning the new ETag
+    pub fn put_entry(&mut self, id: &str, content: &str, if_match: Option<&str>) -> Result<String, (u16, String)> {
+        let path = self.entry_path(id).map_err(|e| (400, e))?;
+        if !path.is_file() {
+            return Err((404, format!("No entry {}", id)));
+        }
+        if !path.to_string_lossy().ends_with(".md") {
+            return Err((409, "Compressed and archived entries are read-only".to_string()));
+        }
+        let Some(if_match) = if_match else {
+            return Err((428, "PUT requires If-Match with the entry's ETag".to_string()));
+        };
+
+        let current = fs::read_to_string(&path).map_err(|e| (500, format!("Failed to read {}: {}", id, e)))?;
+        let current_etag = etag(&current);
+        let matches = if_match.split(',').map(str::trim).any(|tag| tag == "*" || tag == current_etag);
+        if !matches {
+            return Err((412, format!("{} changed since it was read (current ETag {})", id, current_etag)));
+        }
+
+        let before = changes::hash(&path);
+        let tmp = path.with_extension("md.tmp");
+        fs::write(&tmp, content)
+            .and_then(|_| fs::rename(&tmp, &path))
+            .map_err(|e| (500, format!("Failed to write {}: {}", id, e)))?;
+        record_change(&self.journal_path, changes::Action::Edit, &path, None, before);
+        self.metrics.entries_updated += 1;
+        Ok(etag(content))
+    }
+
+    /// Add `text` to today's entry, creating it if needed
+    pub fn append(&mut self, text: &str) -> Result<PathBuf, String> {
+        let now = chrono::Local::now().naive_local();
+        let path = append_to_day(&self.journal_path, now, text, self.author.clone())?;
+        self.metrics.entries_appended += 1;
+        Ok(path)
+    }
+
+    /// Save a clipped page selection as a bookmark entry: its HTML as Markdown, then a link
+    /// back to `url`. The title defaults to the page's host.
+    #[cfg(feature = "network")]
+    pub fn clip(&mut self, url: &str, title: Option<&str>, html: &str) -> Result<PathBuf, String> {
+        let host = url.split_once("://").and_then(|(_, rest)| rest.split(['/', '?', '#']).next());
+        let title = title.map(str::trim).filter(|title| !title.is_empty()).or(host.filter(|host| !host.is_empty()));
+        let mut entry_frontmatter = frontmatter::Frontmatter::default();
+        entry_frontmatter.set("source", frontmatter::Value::Scalar(url.to_string()));
+        entry_frontmatter.set("tags", frontmatter::Value::List(vec!["bookmark".to_string()]));
+        let markdown = html::to_markdown(html);
+        let source = format!("[Source]({})", html::link_target(url));
+        let note = if markdown.is_empty() { source } else { format!("{}\n\n{}", markdown, source) };
+        let path = write_entry(
+            self.journal_path.clone(),
+            title.unwrap_or("Clipping"),
+            &note,
+            entry_frontmatter,
+            self.author.clone(),
+        )?;
+        self.metrics.entries_created += 1;
+        Ok(path)
+    }
+
+    fn call(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        let param = |name: &str| params[name].as_str().map(str::to_string);
+        let missing = |name: &str| (INVALID_PARAMS, format!("Missing string param '{}'", name));
+
+        match method {
+            "ping" => Ok(json!("pong")),
+            "list" => {
+                self.metrics.queries += 1;
+                let entries = match param("selector") {
+                    Some(selector) => resolve_journal_selector(&self.journal_path, &selector),
+                    None => find_all_entries(&self.journal_path),
+                }
+                .map_err(|e| (SERVER_ERROR, e))?;
+
+                let mut listed = Vec::new();
+                for path in entries {
+                    let content = self.read_cached(&path).unwrap_or_default();
+                    let entry = Entry::parse(&path, &content);
+                    listed.push(json!({
+                        "path": path,
+                        "date": entry.date.map(|d| d.to_string()),
+                        "time": entry.time.map(|t| t.format("%H:%M:%S").to_string()),
+                        "title": entry.title,
+                    }));
+                }
+                Ok(Value::Array(listed))
+            }
+            "read" => {
+                self.metrics.queries += 1;
+                let path = param("path").ok_or_else(|| missing("path"))?;
+                let path = self.entry_path(&path).map_err(|e| (INVALID_PARAMS, e))?;
+                let content = self.read_cached(&path).map_err(|e| (SERVER_ERROR, e))?;
+                Ok(json!({ "path": path, "content": content }))
+            }
+            "create" => {
+                let title = param("title").ok_or_else(|| missing("title"))?;
+                let note = param("note").unwrap_or_default();
+                let path = write_entry(
+                    self.journal_path.clone(),
+                    &title,
+                    &note,
+                    frontmatter::Frontmatter::default(),
+                    self.author.clone(),
+                )
+                .map_err(|e| (SERVER_ERROR, e))?;
+                self.metrics.entries_created += 1;
+                Ok(json!({ "path": path }))
+            }
+            "append" => {
+                let text = param("text").ok_or_else(|| missing("text"))?;
+                let path = self.append(&text).map_err(|e| (SERVER_ERROR, e))?;
+                Ok(json!({ "path": path }))
+            }
+            other => Err((METHOD_NOT_FOUND, format!("Unknown method '{}'", other))),
+        }
+    }
+
+    /// Handle one request line, returning the response line
+    pub fn handle(&mut self, line: &str) -> Value {
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => return error_response(Value::Null, PARSE_ERROR, &format!("Invalid JSON: {}", e)),
+        };
+        let id = request["id"].clone();
+        let Some(method) = request["method"].as_str() else {
+            return error_response(id, INVALID_REQUEST, "Request has no method");
+        };
+
+        match self.call(method, &request["params"]) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => {
+                self.metrics.errors += 1;
+                error_response(id, code, &message)
+            }
+        }
+    }
+}
+
+/// Strong ETag of an entry's content
+pub fn etag(content: &str) -> String {
+    format!("\"{:x}\"", Sha256::digest(content.as_bytes()))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+#[cfg(unix)]
+fn serve_client(daemon: &Mutex<Daemon>, stream: UnixStream) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = daemon.lock().unwrap_or_else(|p| p.into_inner()).handle(&line);
+        writeln!(writer, "{}", response)?;
+    }
+    Ok(())
+}
+
+/// Listen on `socket` until the process is stopped, one thread per client
+#[cfg(unix)]
+pub fn serve(daemon: Arc<Mutex<Daemon>>, socket: &Path) -> Result<(), String> {
+    if socket.exists() {
+        if UnixStream::connect(socket).is_ok() {
+            return Err(format!("A daemon is already listening on {}", socket.display()));
+        }
+        // Left behind by a daemon that didn't shut down cleanly
+        std::fs::remove_file(socket).map_err(|e| format!("Failed to remove stale {}: {}", socket.display(), e))?;
+    }
+    let listener = UnixListener::bind(socket).map_err(|e| format!("Failed to bind {}: {}", socket.display(), e))?;
+    // Only the owner may talk to the journal
+    std::fs::set_permissions(socket, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to restrict {}: {}", socket.display(), e))?;
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let daemon = Arc::clone(&daemon);
+                std::thread::spawn(move || {
+                    if let Err(e) = serve_client(&daemon, stream) {
+                        eprintln!("Warning: client connection failed: {}", e);
+                    }
+                });
+            }
+            Err(e) => eprintln!("Warning: failed to accept connection: {}", e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_handle_create_list_read() {
+        let dir = TempDir::new().unwrap();
+        let mut daemon = Daemon::new(dir.path().to_path_buf(), None);
+
+        let created = daemon
+            .handle(r#"{"jsonrpc": "2.0", "id": 1, "method": "create", "params": {"title": "Idea", "note": "hi"}}"#);
+        let path = created["result"]["path"].as_str().unwrap().to_string();
+
+        let listed = daemon.handle(r#"{"jsonrpc": "2.0", "id": 2, "method": "list", "params": {"selector": "today"}}"#);
+        assert_eq!(listed["id"], 2);
+        assert_eq!(listed["result"][0]["title"], "Idea");
+
+        let read = json!({"jsonrpc": "2.0", "id": 3, "method": "read", "params": {"path": path}});
+        let read = daemon.handle(&read.to_string());
+        assert!(read["result"]["content"].as_str().unwrap().contains("hi"));
+        assert_eq!((daemon.metrics.entries_created, daemon.metrics.queries), (1, 2));
+        assert!(daemon.render_metrics().contains("file_journal_entries 1\n"));
+    }
+
+    #[test]
+    fn test_put_entry_checks_etag() {
+        let dir = TempDir::new().unwrap();
+        let mut daemon = Daemon::new(dir.path().to_path_buf(), None);
+        let created = daemon.handle(r#"{"id": 1, "method": "create", "params": {"title": "Draft"}}"#);
+        let path = PathBuf::from(created["result"]["path"].as_str().unwrap());
+        let id = path.strip_prefix(dir.path()).unwrap().to_string_lossy().to_string();
+
+        let (_, tag) = daemon.get_entry(&id).unwrap();
+        assert_eq!(daemon.put_entry(&id, "# Draft\n", None).unwrap_err().0, 428);
+        let new_tag = daemon.put_entry(&id, "# Draft\n\nv2\n", Some(&tag)).unwrap();
+        assert_eq!(etag("# Draft\n\nv2\n"), new_tag);
+        let logged = changes::since(dir.path(), None).unwrap();
+        let actions: Vec<&str> = logged.iter().map(|change| change.action.as_str()).collect();
+        assert_eq!(actions, ["create", "edit"]);
+        assert_eq!(logged[1].path, id);
+
+        // A second editor still holding the old ETag is refused
+        assert_eq!(daemon.put_entry(&id, "# Draft\n\nother\n", Some(&tag)).unwrap_err().0, 412);
+        assert_eq!(daemon.get_entry(&id).unwrap().0, "# Draft\n\nv2\n");
+        assert_eq!(daemon.get_entry("2026/01/nope.md").unwrap_err().0, 404);
+    }
+
+    #[test]
+    fn test_handle_errors() {
+        let dir = TempDir::new().unwrap();
+        let mut daemon = Daemon::new(dir.path().to_path_buf(), None);
+
+        assert_eq!(daemon.handle("not json")["error"]["code"], PARSE_ERROR);
+        assert_eq!(daemon.handle(r#"{"id": 1, "method": "nope"}"#)["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(
+            daemon.handle(r#"{"id": 2, "method": "read", "params": {"path": "../etc/passwd"}}"#)["error"]["code"],
+            INVALID_PARAMS
+        );
+        assert_eq!(
+            daemon.handle(r#"{"id": 3, "method": "read", "params": {"path": "/etc/passwd"}}"#)["error"]["code"],
+            INVALID_PARAMS
+        );
+        let outside = TempDir::new().unwrap();
+        let secret = outside.path().join("secret.md");
+        fs::write(&secret, "# secret\n").unwrap();
+        let request = json!({"id": 4, "method": "list", "params": {"selector": secret}});
+        let listed = daemon.handle(&request.to_string());
+        assert_eq!(listed["error"]["code"], SERVER_ERROR);
+        assert!(listed["error"]["message"].as_str().unwrap().contains("outside the journal"));
+    }
+}
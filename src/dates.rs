@@ -0,0 +1,131 @@
+//! Day expressions, shared by every option that takes a single day: `today`, `yesterday`,
+//! `tomorrow`, `YYYY-MM-DD`, `3 days ago` (or weeks, months, years), and weekdays, where
+//! `monday` and `last monday` are the most recent Monday before today and `next monday` the
+//! first one after it. Also ISO weeks (`2026-W07`) and months by name.
+
+use chrono::{Datelike, Months, NaiveDate, Weekday};
+
+/// The day `expression` stands for, counting from `today`
+pub fn parse(expression: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let expression = expression.trim().to_lowercase();
+    let words: Vec<&str> = expression.split_whitespace().collect();
+    match words.as_slice() {
+        ["today"] => Some(today),
+        ["yesterday"] => today.pred_opt(),
+        ["tomorrow"] => today.succ_opt(),
+        [date] if date.contains('-') => NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+        [count, unit, "ago"] => {
+            let count: u32 = match *count {
+                "a" | "an" | "one" => 1,
+                count => count.parse().ok()?,
+            };
+            match unit.strip_suffix('s').unwrap_or(unit) {
+                "day" => today.checked_sub_days(chrono::Days::new(count.into())),
+                "week" => today.checked_sub_days(chrono::Days::new(u64::from(count) * 7)),
+                "month" => today.checked_sub_months(Months::new(count)),
+                "year" => today.checked_sub_months(Months::new(count.checked_mul(12)?)),
+                _ => None,
+            }
+        }
+        [weekday] | ["last", weekday] => {
+            let weekday: Weekday = weekday.parse().ok()?;
+            let back = (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday() - 1) % 7 + 1;
+            today.checked_sub_days(chrono::Days::new(back.into()))
+        }
+        ["next", weekday] => {
+            let weekday: Weekday = weekday.parse().ok()?;
+            let ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday() - 1) % 7 + 1;
+            today.checked_add_days(chrono::Days::new(ahead.into()))
+        }
+        _ => None,
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november",
+    "december",
+];
+
+/// Month number of `2`, `feb`, `Sept` or `February`; names need at least three letters
+pub fn month(value: &str) -> Option<u32> {
+    let value = value.trim().to_lowercase();
+    if let Ok(number) = value.parse::<u32>() {
+        return (1..=12).contains(&number).then_some(number);
+    }
+    if value.len() < 3 {
+        return None;
+    }
+    let index = MONTHS.iter().position(|name| name.starts_with(&value))?;
+    Some(index as u32 + 1)
+}
+
+/// Monday of an ISO week written `2026-W07` (or `2026W07`)
+pub fn iso_week(spec: &str) -> Option<NaiveDate> {
+    let spec = spec.trim().to_uppercase();
+    let (year, week) = spec.split_once('W')?;
+    let year = year.strip_suffix('-').unwrap_or(year).parse().ok()?;
+    NaiveDate::from_isoywd_opt(year, week.parse().ok()?, Weekday::Mon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        // A Tuesday
+        let today = NaiveDate::from_ymd_opt(2026, 3, 3).unwrap();
+        let day = |m, d| NaiveDate::from_ymd_opt(2026, m, d);
+        let cases = [
+            ("today", day(3, 3)),
+            ("Yesterday", day(3, 2)),
+            ("tomorrow", day(3, 4)),
+            ("2026-02-17", day(2, 17)),
+            ("3 days ago", day(2, 28)),
+            ("1 day ago", day(3, 2)),
+            ("a week ago", day(2, 24)),
+            ("2 months ago", day(1, 3)),
+            ("1 year ago", NaiveDate::from_ymd_opt(2025, 3, 3)),
+            ("last monday", day(3, 2)),
+            ("last tuesday", day(2, 24)),
+            ("wed", day(2, 25)),
+            ("next tuesday", day(3, 10)),
+            ("next  friday", day(3, 6)),
+            ("2026-02-30", None),
+            ("3 fortnights ago", None),
+            ("last", None),
+            ("someday", None),
+        ];
+        for (expression, expected) in cases {
+            assert_eq!(parse(expression, today), expected, "{}", expression);
+        }
+    }
+
+    #[test]
+    fn test_month() {
+        let cases = [
+            ("2", Some(2)),
+            ("12", Some(12)),
+            ("feb", Some(2)),
+            ("February", Some(2)),
+            ("Sept", Some(9)),
+            ("MAY", Some(5)),
+            ("13", None),
+            ("0", None),
+            ("ju", None),
+            ("febuary", None),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(month(value), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_iso_week() {
+        assert_eq!(iso_week("2026-W07"), NaiveDate::from_ymd_opt(2026, 2, 9));
+        assert_eq!(iso_week("2026w1"), NaiveDate::from_ymd_opt(2025, 12, 29));
+        assert_eq!(iso_week("2020-W53"), NaiveDate::from_ymd_opt(2020, 12, 28));
+        assert_eq!(iso_week("2025-W53"), None);
+        assert_eq!(iso_week("2026-07"), None);
+    }
+}
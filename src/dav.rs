@@ -0,0 +1,274 @@
+//! WebDAV storage backend (requires the `network` feature).
+
+use base64::Engine;
+use ureq::http;
+
+use crate::is_entry_file;
+use crate::storage::Storage;
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:getetag/><d:resourcetype/></d:prop></d:propfind>"#;
+
+pub struct DavStorage {
+    agent: ureq::Agent,
+    /// Collection URL without a trailing slash
+    base: String,
+    auth: Option<String>,
+}
+
+/// Turn `dav://user@host/path` (https) or `http(s)://...` into a base URL and optional user
+pub fn parse_url(url: &str) -> Result<(String, Option<String>), String> {
+    let (scheme, rest) = if let Some(rest) = url.strip_prefix("dav://").or_else(|| url.strip_prefix("davs://")) {
+        ("https", rest)
+    } else if let Some(rest) = url.strip_prefix("https://") {
+        ("https", rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        ("http", rest)
+    } else {
+        return Err(format!("Unsupported journal_url '{}' (expected dav://, http:// or https://)", url));
+    };
+
+    let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let (user, host) = match authority.rsplit_once('@') {
+        Some((user, host)) => (Some(user.to_string()), host),
+        None => (None, authority),
+    };
+    if host.is_empty() {
+        return Err(format!("journal_url '{}' has no host", url));
+    }
+
+    let base = format!("{}://{}/{}", scheme, host, path.trim_end_matches('/'));
+    Ok((base.trim_end_matches('/').to_string(), user))
+}
+
+impl DavStorage {
+    pub fn new(base: String, user: Option<String>, password: Option<String>) -> Self {
+        let auth = user.map(|user| {
+            let credentials = format!("{}:{}", user, password.unwrap_or_default());
+            format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(credentials))
+        });
+        DavStorage { agent: ureq::Agent::new_with_defaults(), base, auth }
+    }
+
+    fn url(&self, path: &str) -> String {
+        let encoded: Vec<String> = path.split('/').map(percent_encode).collect();
+        format!("{}/{}", self.base, encoded.join("/"))
+    }
+
+    fn request(&self, method: &str, url: &str) -> http::request::Builder {
+        let mut builder = http::Request::builder().method(method).uri(url);
+        if let Some(auth) = &self.auth {
+            builder = builder.header("Authorization", auth);
+        }
+        builder
+    }
+
+    /// PROPFIND with Depth: 1, returning (href, etag, is_collection) per child
+    fn propfind(&self, url: &str) -> Result<Vec<(String, String, bool)>, String> {
+        let request = self
+            .request("PROPFIND", url)
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml")
+            .body(PROPFIND_BODY)
+            .map_err(|e| format!("Invalid WebDAV request: {}", e))?;
+        let xml = self
+            .agent
+            .run(request)
+            .map_err(|e| format!("WebDAV PROPFIND {} failed: {}", url, e))?
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| format!("Invalid WebDAV response from {}: {}", url, e))?;
+        Ok(parse_multistatus(&xml))
+    }
+
+    /// Path of `href` relative to the journal root
+    fn relative(&self, href: &str) -> String {
+        let base_path = self.base.split_once("://").and_then(|(_, r)| r.find('/').map(|i| &r[i..])).unwrap_or("");
+        let href = percent_decode(href);
+        let path = href.split_once("://").and_then(|(_, r)| r.find('/').map(|i| r[i..].to_string())).unwrap_or(href);
+        path.strip_prefix(&percent_decode(base_path))
+            .unwrap_or(&path)
+            .trim_matches('/')
+            .to_string()
+    }
+}
+
+impl Storage for DavStorage {
+    fn list(&self) -> Result<Vec<(String, String)>, String> {
+        let mut entries = Vec::new();
+        // The journal layout is two levels deep, so walk YYYY/MM explicitly
+        // rather than relying on Depth: infinity, which many servers disable.
+        let mut dirs = vec![String::new()];
+        while let Some(dir) = dirs.pop() {
+            let depth = dir.split('/').filter(|s| !s.is_empty()).count();
+            let url = if dir.is_empty() { format!("{}/", self.base) } else { format!("{}/", self.url(&dir)) };
+            for (href, etag, is_collection) in self.propfind(&url)? {
+                let path = self.relative(&href);
+                let name = path.rsplit('/').next().unwrap_or_default();
+                if path == dir || name.starts_with('.') {
+                    continue;
+                }
+                let numeric = |len: usize| name.len() == len && name.chars().all(|c| c.is_ascii_digit());
+                if is_collection && ((depth == 0 && numeric(4)) || (depth == 1 && numeric(2))) {
+                    dirs.push(path);
+                } else if !is_collection && depth == 2 && is_entry_file(name) {
+                    entries.push((path, etag));
+                }
+            }
+        }
+        entries.sort();
+        Ok(entries)
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, String> {
+        let url = self.url(path);
+        let request = self
+            .request("GET", &url)
+            .body(())
+            .map_err(|e| format!("Invalid WebDAV request: {}", e))?;
+        self.agent
+            .run(request)
+            .map_err(|e| format!("WebDAV GET {} failed: {}", url, e))?
+            .body_mut()
+            .with_config()
+            .limit(64 * 1024 * 1024)
+            .read_to_vec()
+            .map_err(|e| format!("WebDAV GET {} failed: {}", url, e))
+    }
+
+    fn write(&self, path: &str, content: &[u8]) -> Result<(), String> {
+        // Create YYYY/ and YYYY/MM/ first; MKCOL on an existing collection fails harmlessly
+        let parts: Vec<&str> = path.split('/').collect();
+        for i in 1..parts.len() {
+            let url = format!("{}/", self.url(&parts[..i].join("/")));
+            if let Ok(request) = self.request("MKCOL", &url).body(()) {
+                let _ = self.agent.run(request);
+            }
+        }
+
+        let url = self.url(path);
+        let request = self
+            .request("PUT", &url)
+            .body(content)
+            .map_err(|e| format!("Invalid WebDAV request: {}", e))?;
+        self.agent
+            .run(request)
+            .map(|_| ())
+            .map_err(|e| format!("WebDAV PUT {} failed: {}", url, e))
+    }
+
+    fn delete(&self, path: &str) -> Result<(), String> {
+        let url = self.url(path);
+        let request = self.request("DELETE", &url).body(()).map_err(|e| format!("Invalid WebDAV request: {}", e))?;
+        self.agent.run(request).map(|_| ()).map_err(|e| format!("WebDAV DELETE {} failed: {}", url, e))
+    }
+}
+
+/// Inner text of every element with the given local name, ignoring namespace prefixes
+fn elements<'a>(xml: &'a str, local_name: &str) -> Vec<&'a str> {
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let end = rest.find(['>', ' ', '/', '\n', '\t', '\r']).unwrap_or(rest.len());
+        let name = &rest[..end];
+        if name.starts_with('/') || name.rsplit(':').next() != Some(local_name) {
+            continue;
+        }
+        let Some(open_end) = rest.find('>') else { break };
+        if rest[..open_end].ends_with('/') {
+            found.push("");
+            continue;
+        }
+        let close = format!("</{}>", name);
+        let Some(close_at) = rest.find(&close) else { break };
+        found.push(&rest[open_end + 1..close_at]);
+        rest = &rest[close_at + close.len()..];
+    }
+    found
+}
+
+/// (href, etag, is_collection) for each `<response>` in a PROPFIND multistatus body
+pub fn parse_multistatus(xml: &str) -> Vec<(String, String, bool)> {
+    elements(xml, "response")
+        .into_iter()
+        .filter_map(|response| {
+            let href = elements(response, "href").first()?.trim().to_string();
+            let etag = elements(response, "getetag")
+                .first()
+                .map(|e| e.trim().replace("&quot;", "\"").trim_matches('"').to_string())
+                .unwrap_or_default();
+            let is_collection = elements(response, "resourcetype")
+                .first()
+                .is_some_and(|r| !elements(r, "collection").is_empty());
+            Some((href, etag, is_collection))
+        })
+        .collect()
+}
+
+fn percent_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && let Some(b) = std::str::from_utf8(&bytes[i + 1..i + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(b);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        assert_eq!(
+            parse_url("dav://t@cloud.example.org/remote.php/dav/files/t/journal/").unwrap(),
+            ("https://cloud.example.org/remote.php/dav/files/t/journal".to_string(), Some("t".to_string()))
+        );
+        assert_eq!(parse_url("http://localhost:8080").unwrap(), ("http://localhost:8080".to_string(), None));
+        assert!(parse_url("ftp://x").is_err());
+    }
+
+    #[test]
+    fn test_parse_multistatus_and_relative_paths() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+ <d:response><d:href>/dav/journal/2026/02/</d:href>
+  <d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat></d:response>
+ <d:response><d:href>/dav/journal/2026/02/17-081503-caf%C3%A9.md</d:href>
+  <d:propstat><d:prop><d:getetag>&quot;abc&quot;</d:getetag><d:resourcetype/></d:prop></d:propstat></d:response>
+</d:multistatus>"#;
+
+        let parsed = parse_multistatus(xml);
+        assert_eq!(parsed.len(), 2);
+        assert!(parsed[0].2);
+        assert_eq!(parsed[1].1, "abc");
+        assert!(!parsed[1].2);
+
+        let storage = DavStorage::new("https://x.test/dav/journal".to_string(), None, None);
+        assert_eq!(storage.relative(&parsed[1].0), "2026/02/17-081503-café.md");
+        assert_eq!(storage.url("2026/02/a b.md"), "https://x.test/dav/journal/2026/02/a%20b.md");
+    }
+}
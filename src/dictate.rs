@@ -0,0 +1,159 @@
+//! `dictate`: record from the microphone, transcribe with an external command, and
+//! save the transcript as an entry (requires the `dictation` feature).
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+/// `[dictation]` section of the config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictationConfig {
+    /// Command printing the transcript on stdout; `{audio}` is replaced by the WAV file
+    /// (appended if absent), e.g. "whisper-cli -nt -m /models/ggml-base.en.bin -f {audio}".
+    /// Arguments are split on whitespace, without shell quoting.
+    pub transcribe_command: String,
+}
+
+/// Mono or multi-channel 16-bit PCM as captured from the input device
+pub struct Recording {
+    pub samples: Vec<i16>,
+    pub channels: u16,
+    pub sample_rate: u32,
+}
+
+impl Recording {
+    pub fn seconds(&self) -> f32 {
+        self.samples.len() as f32 / self.channels.max(1) as f32 / self.sample_rate.max(1) as f32
+    }
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    samples: Arc<Mutex<Vec<i16>>>,
+    convert: fn(T) -> i16,
+) -> Result<cpal::Stream, String>
+where
+    T: cpal::SizedSample + Send + 'static,
+{
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                samples.lock().unwrap_or_else(|p| p.into_inner()).extend(data.iter().map(|s| convert(*s)));
+            },
+            |e| eprintln!("Warning: audio input error: {}", e),
+            None,
+        )
+        .map_err(|e| format!("Failed to open the microphone: {}", e))
+}
+
+/// Record from the default input device until Enter is pressed
+pub fn record_until_enter() -> Result<Recording, String> {
+    let device = cpal::default_host().default_input_device().ok_or("No microphone found")?;
+    let supported = device
+        .default_input_config()
+        .map_err(|e| format!("Failed to query the microphone: {}", e))?;
+    let config: cpal::StreamConfig = supported.config();
+    let samples = Arc::new(Mutex::new(Vec::new()));
+
+    let shared = Arc::clone(&samples);
+    let stream = match supported.sample_format() {
+        cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config, shared, |s| s)?,
+        cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config, shared, |s| (s as i32 - 32768) as i16)?,
+        cpal::SampleFormat::F32 => {
+            build_stream::<f32>(&device, &config, shared, |s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?
+        }
+        other => return Err(format!("Unsupported microphone sample format {:?}", other)),
+    };
+    stream.play().map_err(|e| format!("Failed to start recording: {}", e))?;
+
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line).map_err(|e| format!("Failed to read input: {}", e))?;
+    drop(stream);
+
+    let samples = std::mem::take(&mut *samples.lock().unwrap_or_else(|p| p.into_inner()));
+    Ok(Recording { samples, channels: config.channels, sample_rate: config.sample_rate.0 })
+}
+
+pub fn save_wav(recording: &Recording, path: &Path) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let spec = hound::WavSpec {
+        channels: recording.channels,
+        sample_rate: recording.sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let failed = |e: hound::Error| format!("Failed to write {}: {}", path.display(), e);
+    let mut writer = hound::WavWriter::create(path, spec).map_err(failed)?;
+    for sample in &recording.samples {
+        writer.write_sample(*sample).map_err(failed)?;
+    }
+    writer.finalize().map_err(failed)
+}
+
+/// Program and arguments of the transcription command for `audio`
+pub fn transcribe_args(command: &str, audio: &Path) -> Result<(String, Vec<String>), String> {
+    let audio = audio.to_string_lossy();
+    let mut parts = command.split_whitespace().map(str::to_string);
+    let program = parts.next().ok_or("transcribe_command is empty")?;
+    let mut args: Vec<String> = parts.collect();
+    if args.iter().any(|a| a.contains("{audio}")) {
+        args = args.into_iter().map(|a| a.replace("{audio}", &audio)).collect();
+    } else {
+        args.push(audio.to_string());
+    }
+    Ok((program, args))
+}
+
+/// Run the transcription command and return the transcript it prints
+pub fn transcribe(command: &str, audio: &Path) -> Result<String, String> {
+    let (program, args) = transcribe_args(command, audio)?;
+    let output = Command::new(&program)
+        .args(&args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let transcript = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if transcript.is_empty() {
+        return Err(format!("{} returned an empty transcript", program));
+    }
+    Ok(transcript)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcribe_args() {
+        let audio = Path::new("/j/assets/2026/02/16-081503-dictation.wav");
+        let (program, args) = transcribe_args("whisper-cli -nt -f {audio}", audio).unwrap();
+        assert_eq!(program, "whisper-cli");
+        assert_eq!(args, vec!["-nt", "-f", "/j/assets/2026/02/16-081503-dictation.wav"]);
+
+        let (_, args) = transcribe_args("transcribe --lang en", audio).unwrap();
+        assert_eq!(args.last().unwrap(), "/j/assets/2026/02/16-081503-dictation.wav");
+        assert!(transcribe_args("  ", audio).is_err());
+    }
+
+    #[test]
+    fn test_save_wav() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("assets").join("clip.wav");
+        let recording = Recording { samples: vec![0, 1000, -1000, 0], channels: 2, sample_rate: 16000 };
+        save_wav(&recording, &path).unwrap();
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.len(), 4);
+        assert!((recording.seconds() - 2.0 / 16000.0).abs() < 1e-9);
+    }
+}
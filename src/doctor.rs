@@ -0,0 +1,177 @@
+//! `doctor`: problems in the journal's layout that make entries hard to find, and the
+//! fixes that are safe to make without asking.
+
+use chrono::NaiveDate;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{entry_date, filename_format, is_entry_file, is_valid_month, is_valid_year, read_entry, set_date_line};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// An entry file whose name doesn't follow `filename_format`
+    BadFilename(PathBuf),
+    /// A folder inside a year folder that isn't a month from 01 to 12
+    BadMonthFolder(PathBuf),
+    /// The `Date:` line says one day, the entry's folder and filename another
+    DateMismatch { path: PathBuf, written: NaiveDate, filed: NaiveDate },
+    /// A year or month folder with no files in it
+    EmptyFolder(PathBuf),
+}
+
+impl Problem {
+    pub fn path(&self) -> &Path {
+        match self {
+            Problem::BadFilename(path) | Problem::BadMonthFolder(path) | Problem::EmptyFolder(path) => path,
+            Problem::DateMismatch { path, .. } => path,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Problem::BadFilename(_) => "filename doesn't match the entry filename format".to_string(),
+            Problem::BadMonthFolder(_) => "not a month folder (01-12)".to_string(),
+            Problem::DateMismatch { written, filed, .. } => {
+                format!("Date: line says {} but the entry is filed under {}", written, filed)
+            }
+            Problem::EmptyFolder(_) => "empty folder".to_string(),
+        }
+    }
+
+    /// Whether `fix` can correct it: empty folders are removed, and the `Date:` line of a
+    /// plain entry is rewritten to match where it's filed. Names are left to the user.
+    pub fn fixable(&self) -> bool {
+        match self {
+            Problem::EmptyFolder(_) => true,
+            Problem::DateMismatch { path, .. } => path.extension().is_some_and(|ext| ext == "md"),
+            Problem::BadFilename(_) | Problem::BadMonthFolder(_) => false,
+        }
+    }
+
+    pub fn fix(&self) -> Result<(), String> {
+        match self {
+            Problem::EmptyFolder(path) => {
+                fs::remove_dir_all(path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+            }
+            Problem::DateMismatch { path, filed, .. } if self.fixable() => {
+                let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                fs::write(path, set_date_line(&content, *filed))
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+            }
+            _ => Err("can't be fixed automatically".to_string()),
+        }
+    }
+}
+
+fn sorted_children(dir: &Path) -> Vec<PathBuf> {
+    let mut children: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(items) => items.flatten().map(|item| item.path()).collect(),
+        Err(_) => Vec::new(),
+    };
+    children.sort();
+    children
+}
+
+fn name(path: &Path) -> &str {
+    path.file_name().and_then(|name| name.to_str()).unwrap_or_default()
+}
+
+/// Whether a folder holds no files, counting nested folders that hold none either
+fn is_empty_tree(dir: &Path) -> bool {
+    sorted_children(dir).iter().all(|child| child.is_dir() && is_empty_tree(child))
+}
+
+/// The date on an entry's first `Date: DD-MM-YYYY` line
+fn written_date(content: &str) -> Option<NaiveDate> {
+    let value = content.lines().find_map(|line| line.strip_prefix("Date: "))?;
+    NaiveDate::parse_from_str(value.get(..10)?, "%d-%m-%Y").ok()
+}
+
+/// Every problem in the journal, in path order
+pub fn check(journal_path: &Path) -> Result<Vec<Problem>, String> {
+    if !journal_path.is_dir() {
+        return Err(format!("{} is not a folder", journal_path.display()));
+    }
+    let mut problems = Vec::new();
+    for year_dir in sorted_children(journal_path) {
+        if !year_dir.is_dir() || !is_valid_year(name(&year_dir)) {
+            continue;
+        }
+        if is_empty_tree(&year_dir) {
+            problems.push(Problem::EmptyFolder(year_dir));
+            continue;
+        }
+        for month_dir in sorted_children(&year_dir) {
+            if !month_dir.is_dir() {
+                continue;
+            }
+            if !is_valid_month(name(&month_dir)) {
+                problems.push(Problem::BadMonthFolder(month_dir));
+                continue;
+            }
+            if is_empty_tree(&month_dir) {
+                problems.push(Problem::EmptyFolder(month_dir));
+                continue;
+            }
+            for file in sorted_children(&month_dir) {
+                let filename = name(&file);
+                if !file.is_file() || filename.starts_with('.') || !is_entry_file(filename) {
+                    continue;
+                }
+                if filename_format().parse(filename).is_none() {
+                    problems.push(Problem::BadFilename(file));
+                    continue;
+                }
+                let written = read_entry(&file).ok().and_then(|content| written_date(&content));
+                if let (Some(written), Some(filed)) = (written, entry_date(&file))
+                    && written != filed
+                {
+                    problems.push(Problem::DateMismatch { path: file, written, filed });
+                }
+            }
+        }
+    }
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_and_fix() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        fs::create_dir_all(dir.path().join("2026").join("13")).unwrap();
+        fs::create_dir_all(dir.path().join("2025").join("07")).unwrap();
+        fs::write(month.join("17-081503-fine.md"), "# Fine\n\nDate: 17-02-2026\n").unwrap();
+        fs::write(month.join("notes.md"), "# Loose\n").unwrap();
+        fs::write(month.join("18-090000-moved.md"), "# Moved\n\nDate: 03-01-2026 \n\nText\n").unwrap();
+        fs::write(month.join("photo.jpg"), "").unwrap();
+
+        let problems = check(dir.path()).unwrap();
+        let moved = month.join("18-090000-moved.md");
+        assert_eq!(
+            problems,
+            [
+                Problem::EmptyFolder(dir.path().join("2025")),
+                Problem::DateMismatch {
+                    path: moved.clone(),
+                    written: NaiveDate::from_ymd_opt(2026, 1, 3).unwrap(),
+                    filed: NaiveDate::from_ymd_opt(2026, 2, 18).unwrap(),
+                },
+                Problem::BadFilename(month.join("notes.md")),
+                Problem::BadMonthFolder(dir.path().join("2026").join("13")),
+            ]
+        );
+
+        for problem in problems.iter().filter(|p| p.fixable()) {
+            problem.fix().unwrap();
+        }
+        assert_eq!(fs::read_to_string(&moved).unwrap(), "# Moved\n\nDate: 18-02-2026\n\nText\n");
+        assert!(!dir.path().join("2025").exists());
+        assert_eq!(check(dir.path()).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,115 @@
+//! `edit`, `open`, `show` and `get --pick`: hand entries and folders to the user's editor, file
+//! manager or pager, or let them pick one.
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Editor to run: the configured one, then $VISUAL, then $EDITOR, then vi
+pub fn command(configured: Option<&str>) -> String {
+    configured
+        .map(str::to_string)
+        .or_else(|| std::env::var("VISUAL").ok())
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|c| !c.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Pager to run: $PAGER, then `less -R` (which keeps colours)
+pub fn pager() -> String {
+    std::env::var("PAGER").ok().filter(|c| !c.trim().is_empty()).unwrap_or_else(|| "less -R".to_string())
+}
+
+/// Program that shows a folder in the system file manager
+pub fn file_manager() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    }
+}
+
+/// Index picked from a numbered list of `count` entries; `None` cancels
+pub fn parse_choice(answer: &str, count: usize) -> Result<Option<usize>, String> {
+    let answer = answer.trim();
+    if answer.is_empty() || answer.eq_ignore_ascii_case("q") {
+        return Ok(None);
+    }
+    match answer.parse::<usize>() {
+        Ok(n) if (1..=count).contains(&n) => Ok(Some(n - 1)),
+        _ => Err(format!("Expected a number from 1 to {}", count)),
+    }
+}
+
+/// Fuzzy-searchable list of `labels` to choose from; `None` if cancelled with Esc or q
+pub fn fuzzy_pick(prompt: &str, labels: &[String]) -> Result<Option<usize>, String> {
+    if !std::io::IsTerminal::is_terminal(&std::io::stderr()) {
+        return Err("Picking an entry needs a terminal".to_string());
+    }
+    dialoguer::FuzzySelect::new()
+        .with_prompt(prompt)
+        .items(labels)
+        .default(0)
+        .interact_opt()
+        .map_err(|e| format!("Picker failed: {}", e))
+}
+
+/// Run the editor (or file manager) on `path` and wait for it to exit. Arguments in the
+/// command ("code --wait") are split on whitespace.
+pub fn open(command: &str, path: &Path) -> Result<(), String> {
+    let mut parts = command.split_whitespace();
+    let program = parts.next().ok_or("Editor command is empty")?;
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if status.success() { Ok(()) } else { Err(format!("{} exited with {}", program, status)) }
+}
+
+/// Show `text` through the pager `command`, split on whitespace like editor commands
+pub fn page(command: &str, text: &str) -> Result<(), String> {
+    let mut parts = command.split_whitespace();
+    let program = parts.next().ok_or("Pager command is empty")?;
+    let mut child = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closing early (quitting before the end) isn't an error
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait().map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_choice() {
+        assert_eq!(parse_choice("2\n", 3).unwrap(), Some(1));
+        assert_eq!(parse_choice("", 3).unwrap(), None);
+        assert_eq!(parse_choice("q", 3).unwrap(), None);
+        assert!(parse_choice("4", 3).is_err());
+        assert!(parse_choice("0", 3).is_err());
+        assert!(parse_choice("two", 3).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_page_tolerates_early_exit() {
+        // `true` exits without reading, as when the pager is quit before the end
+        page("true", &"line\n".repeat(100_000)).unwrap();
+        assert!(page("  ", "text").is_err());
+    }
+
+    #[test]
+    fn test_configured_editor_wins() {
+        assert_eq!(command(Some("hx")), "hx");
+    }
+}
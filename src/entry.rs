@@ -0,0 +1,192 @@
+//! An entry read into its parts, for commands that need more than the raw text.
+//!
+//! Date and time come from `date` in the frontmatter when an entry has one (as imported
+//! notes often do), else from the entry's folders and filename. Only a file with no date
+//! at all falls back to its modification time for both, so a filename without a time
+//! never gets one from whenever the file was last touched.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::frontmatter::{self, Frontmatter};
+use crate::{entry_date, entry_title, filename_format, read_entry};
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub date: Option<NaiveDate>,
+    pub time: Option<NaiveTime>,
+    /// From the first `# ` heading
+    pub title: Option<String>,
+    pub frontmatter: Frontmatter,
+    /// Everything after the frontmatter
+    pub body: String,
+}
+
+/// A frontmatter `date`: `YYYY-MM-DD`, optionally with a time, or an RFC 3339 timestamp
+fn parse_frontmatter_date(value: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(value) {
+        let local = timestamp.naive_local();
+        return Some((local.date(), Some(local.time())));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(value, format) {
+            return Some((datetime.date(), Some(datetime.time())));
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok().map(|date| (date, None))
+}
+
+/// Time of day from the filename's `{time}` part
+fn filename_time(path: &Path) -> Option<NaiveTime> {
+    let filename = path.file_name()?.to_str()?;
+    NaiveTime::parse_from_str(filename_format().parse(filename)?.time?, "%H%M%S").ok()
+}
+
+/// When the file was last modified, in local time
+fn modified(path: &Path) -> Option<NaiveDateTime> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(chrono::DateTime::<chrono::Local>::from(modified).naive_local())
+}
+
+impl Entry {
+    /// Read and parse an entry, including compressed and archived ones
+    pub fn read(path: &Path) -> std::io::Result<Entry> {
+        Ok(Entry::parse(path, &read_entry(path)?))
+    }
+
+    /// Parse an entry's already-read content
+    pub fn parse(path: &Path, content: &str) -> Entry {
+        let (frontmatter, body) = frontmatter::parse(content);
+        let declared = frontmatter.get("date").and_then(parse_frontmatter_date);
+        let mut date = declared.map(|(date, _)| date).or_else(|| entry_date(path));
+        let mut time = declared.and_then(|(_, time)| time).or_else(|| filename_time(path));
+        if date.is_none() {
+            let modified = modified(path);
+            date = modified.map(|m| m.date());
+            time = time.or(modified.map(|m| m.time()));
+        }
+        Entry {
+            path: path.to_path_buf(),
+            date,
+            time,
+            title: entry_title(body),
+            frontmatter,
+            body: body.to_string(),
+        }
+    }
+
+    pub fn words(&self) -> usize {
+        word_count(&self.body)
+    }
+}
+
+/// What `--format json-full` prints for an entry
+#[derive(Debug, Serialize, PartialEq)]
+pub struct EntryMeta {
+    pub path: String,
+    pub title: Option<String>,
+    /// `YYYY-MM-DD`
+    pub date: Option<String>,
+    /// `HH:MM:SS`
+    pub time: Option<String>,
+    pub words: usize,
+    pub tags: Vec<String>,
+    /// Bytes on disk (compressed size for `.md.gz`, text size for entries inside archives)
+    pub size: u64,
+}
+
+impl EntryMeta {
+    pub fn read(path: &Path) -> std::io::Result<EntryMeta> {
+        let content = read_entry(path)?;
+        let entry = Entry::parse(path, &content);
+        let size = fs::metadata(path).map_or(content.len() as u64, |metadata| metadata.len());
+        Ok(EntryMeta {
+            path: path.to_string_lossy().to_string(),
+            title: entry.title.clone(),
+            date: entry.date.map(|date| date.to_string()),
+            time: entry.time.map(|time| time.format("%H:%M:%S").to_string()),
+            words: entry.words(),
+            tags: entry.frontmatter.list("tags"),
+            size,
+        })
+    }
+}
+
+/// Words in an entry body: runs of non-whitespace, the way `wc -w` counts them
+pub fn word_count(body: &str) -> usize {
+    body.split_whitespace().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        let path = month.join("16-080000-plan.md");
+        fs::write(&path, "---\ntags: [work]\n---\n# Plan\n\nDate: 16-02-2026\n\nShip the thing\n").unwrap();
+
+        let entry = Entry::read(&path).unwrap();
+        assert_eq!(entry.date, NaiveDate::from_ymd_opt(2026, 2, 16));
+        assert_eq!(entry.time, NaiveTime::from_hms_opt(8, 0, 0));
+        assert_eq!(entry.title.as_deref(), Some("Plan"));
+        assert_eq!(entry.frontmatter.list("tags"), ["work"]);
+        assert_eq!(entry.words(), 7);
+
+        let meta = EntryMeta::read(&path).unwrap();
+        assert_eq!(meta.title.as_deref(), Some("Plan"));
+        assert_eq!(meta.date.as_deref(), Some("2026-02-16"));
+        assert_eq!(meta.time.as_deref(), Some("08:00:00"));
+        assert_eq!((meta.words, meta.tags, meta.size), (7, vec!["work".to_string()], 62));
+    }
+
+    #[test]
+    fn test_meta_time_from_filename() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        let noon = chrono::NaiveDate::from_ymd_opt(2026, 2, 16).unwrap().and_hms_opt(12, 34, 56).unwrap();
+        let noon: std::time::SystemTime = noon.and_local_timezone(chrono::Local).unwrap().into();
+        for (name, time) in [("16-080000-plan.md", Some("08:00:00")), ("16-plan.md", None)] {
+            let path = month.join(name);
+            fs::write(&path, "# Plan\n").unwrap();
+            fs::File::options().write(true).open(&path).unwrap().set_modified(noon).unwrap();
+            assert_eq!(EntryMeta::read(&path).unwrap().time.as_deref(), time, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_metadata_fallbacks() {
+        let dir = TempDir::new().unwrap();
+        // Imported notes: a frontmatter date wins over the path
+        let imported = Entry::parse(Path::new("/inbox/2026/03/01-090000-x.md"), "---\ndate: 2024-05-06 07:30\n---\nBody\n");
+        assert_eq!(imported.date, NaiveDate::from_ymd_opt(2024, 5, 6));
+        assert_eq!(imported.time, NaiveTime::from_hms_opt(7, 30, 0));
+
+        // A dated path with no time in the filename has no time, not the modification time's
+        let month = dir.path().join("2026").join("03");
+        fs::create_dir_all(&month).unwrap();
+        let untimed = month.join("02-notes.md");
+        fs::write(&untimed, "Body\n").unwrap();
+        let entry = Entry::read(&untimed).unwrap();
+        assert_eq!((entry.date, entry.time), (NaiveDate::from_ymd_opt(2026, 3, 2), None));
+        let dated = Entry::parse(&untimed, "---\ndate: 2024-05-06\n---\nBody\n");
+        assert_eq!((dated.date, dated.time), (NaiveDate::from_ymd_opt(2024, 5, 6), None));
+
+        // A loose file with neither: the modification time
+        let loose = dir.path().join("notes.md");
+        fs::write(&loose, "Just text\n").unwrap();
+        let entry = Entry::read(&loose).unwrap();
+        assert_eq!(entry.date, Some(chrono::Local::now().date_naive()));
+        assert!(entry.time.is_some());
+        assert_eq!(entry.title, None);
+    }
+}
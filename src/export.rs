@@ -0,0 +1,218 @@
+use chrono::NaiveDate;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+use crate::entry::Entry;
+use crate::frontmatter;
+use crate::i18n::{Language, Message};
+
+/// Versions of the JSON export format this build can write, oldest first; a version's
+/// shape never changes once released, new fields mean a new version
+pub const JSON_SCHEMA_VERSIONS: [u32; 1] = [1];
+
+/// Version written unless `--schema-version` asks for another
+pub const LATEST_JSON_SCHEMA: u32 = JSON_SCHEMA_VERSIONS[JSON_SCHEMA_VERSIONS.len() - 1];
+
+/// One entry as it appears in a worklog
+#[derive(Debug, Clone)]
+pub struct WorklogItem {
+    pub date: NaiveDate,
+    pub project: Option<String>,
+    pub title: String,
+    pub bullets: Vec<String>,
+}
+
+/// Summarize an entry body as bullets: its top-level list items, or the first
+/// line of prose when the entry has no list
+pub fn summarize(body: &str) -> Vec<String> {
+    let prose = body
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.starts_with("Date: "));
+
+    let items: Vec<String> = prose
+        .clone()
+        .filter_map(|line| line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")))
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect();
+    if !items.is_empty() {
+        return items;
+    }
+
+    prose
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(|line| vec![line.to_string()])
+        .unwrap_or_default()
+}
+
+/// Render a Markdown worklog grouped by project, then by date, with dates in `language`
+pub fn render_worklog(heading: &str, items: &[WorklogItem], language: Language) -> String {
+    let mut by_project: BTreeMap<&str, Vec<&WorklogItem>> = BTreeMap::new();
+    for item in items {
+        by_project
+            .entry(item.project.as_deref().unwrap_or(language.text(Message::NoProject)))
+            .or_default()
+            .push(item);
+    }
+
+    let mut out = format!("# {}\n", heading);
+    for (project, mut project_items) in by_project {
+        project_items.sort_by_key(|item| item.date);
+        out.push_str(&format!("\n## {}\n", project));
+
+        let mut current_date = None;
+        for item in project_items {
+            if current_date != Some(item.date) {
+                out.push_str(&format!("\n### {}\n\n", language.short_date(item.date)));
+                current_date = Some(item.date);
+            }
+            match item.bullets.as_slice() {
+                [] => out.push_str(&format!("- {}\n", item.title)),
+                [single] => out.push_str(&format!("- {}: {}\n", item.title, single)),
+                bullets => {
+                    out.push_str(&format!("- {}\n", item.title));
+                    for bullet in bullets {
+                        out.push_str(&format!("  - {}\n", bullet));
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+/// The JSON Schema (draft 2020-12) the JSON export of `version` conforms to
+pub fn json_schema(version: u32) -> Option<Value> {
+    if version != 1 {
+        return None;
+    }
+    let string_or_null = json!({ "type": ["string", "null"] });
+    Some(json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": "urn:file-journal:export:v1",
+        "title": "file-journal export, version 1",
+        "type": "object",
+        "required": ["schema_version", "exported_at", "entries"],
+        "additionalProperties": false,
+        "properties": {
+            "schema_version": { "const": 1 },
+            "exported_at": { "type": "string", "format": "date-time" },
+            "entries": { "type": "array", "items": { "$ref": "#/$defs/entry" } }
+        },
+        "$defs": {
+            "entry": {
+                "type": "object",
+                "required": ["path", "date", "time", "title", "tags", "frontmatter", "body"],
+                "additionalProperties": false,
+                "properties": {
+                    "path": { "type": "string", "description": "Relative to the journal, with / separators" },
+                    "date": { "type": "string", "format": "date" },
+                    "time": { "type": ["string", "null"], "pattern": "^\\d{2}:\\d{2}:\\d{2}$" },
+                    "title": string_or_null,
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "frontmatter": {
+                        "type": "object",
+                        "additionalProperties": {
+                            "oneOf": [{ "type": "string" }, { "type": "array", "items": { "type": "string" } }]
+                        }
+                    },
+                    "body": { "type": "string", "description": "Markdown after the frontmatter" }
+                }
+            }
+        }
+    }))
+}
+
+/// One entry of the JSON export; `path` is relative to the journal
+pub fn json_entry(entry: &Entry, date: NaiveDate, path: &str) -> Value {
+    let fields: serde_json::Map<String, Value> = entry
+        .frontmatter
+        .fields()
+        .map(|(key, value)| {
+            let value = match value {
+                frontmatter::Value::Scalar(s) => json!(s),
+                frontmatter::Value::List(items) => json!(items),
+            };
+            (key.to_string(), value)
+        })
+        .collect();
+    json!({
+        "path": path,
+        "date": date.to_string(),
+        "time": entry.time.map(|t| t.format("%H:%M:%S").to_string()),
+        "title": entry.title,
+        "tags": entry.frontmatter.list("tags"),
+        "frontmatter": fields,
+        "body": entry.body,
+    })
+}
+
+/// The whole JSON export document
+pub fn render_json(version: u32, exported_at: &str, entries: Vec<Value>) -> String {
+    let document = json!({ "schema_version": version, "exported_at": exported_at, "entries": entries });
+    format!("{:#}\n", document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_entry_matches_schema() {
+        let content = "---\ntags: [work]\nproject: atlas\n---\n\n# Planning\n\nDate: 17-02-2026\n";
+        let entry = Entry::parse(std::path::Path::new("2026/02/17-081503-planning.md"), content);
+        let date = NaiveDate::from_ymd_opt(2026, 2, 17).unwrap();
+        let exported = json_entry(&entry, date, "2026/02/17-081503-planning.md");
+        assert_eq!(exported["time"], "08:15:03");
+        assert_eq!(exported["tags"], json!(["work"]));
+        assert_eq!(exported["frontmatter"]["project"], "atlas");
+
+        // Every field the schema describes is written, and nothing else
+        let schema = json_schema(1).unwrap();
+        let described = &schema["$defs"]["entry"]["properties"];
+        let mut keys: Vec<&String> = exported.as_object().unwrap().keys().collect();
+        let mut properties: Vec<&String> = described.as_object().unwrap().keys().collect();
+        keys.sort();
+        properties.sort();
+        assert_eq!(keys, properties);
+        assert_eq!(schema["$defs"]["entry"]["required"].as_array().unwrap().len(), keys.len());
+        assert!(json_schema(2).is_none());
+    }
+
+    #[test]
+    fn test_summarize_prefers_list_items() {
+        let body = "# Planning\n\nDate: 17-02-2026\n\nSome context.\n\n- scoped Q1\n- estimated API work\n";
+        assert_eq!(summarize(body), vec!["scoped Q1", "estimated API work"]);
+        assert_eq!(summarize("# Call\n\nDate: 17-02-2026\n\nTalked to client\n"), vec!["Talked to client"]);
+        assert!(summarize("# Empty\n\nDate: 17-02-2026\n").is_empty());
+    }
+
+    #[test]
+    fn test_render_worklog_groups_by_project_and_date() {
+        let date = |d| NaiveDate::from_ymd_opt(2026, 2, d).unwrap();
+        let items = vec![
+            WorklogItem {
+                date: date(18),
+                project: Some("atlas".into()),
+                title: "Review".into(),
+                bullets: vec!["merged API".into()],
+            },
+            WorklogItem {
+                date: date(17),
+                project: Some("atlas".into()),
+                title: "Planning".into(),
+                bullets: vec!["scoped Q1".into(), "estimates".into()],
+            },
+            WorklogItem { date: date(17), project: None, title: "Admin".into(), bullets: vec![] },
+        ];
+
+        let worklog = render_worklog("Worklog: February 2026", &items, Language::English);
+        assert_eq!(
+            worklog,
+            "# Worklog: February 2026\n\n## (no project)\n\n### Tue 17 Feb 2026\n\n- Admin\n\n## atlas\n\n### Tue 17 Feb 2026\n\n- Planning\n  - scoped Q1\n  - estimates\n\n### Wed 18 Feb 2026\n\n- Review: merged API\n"
+        );
+        let arbeit = render_worklog("Arbeitsprotokoll: Februar 2026", &items[2..], Language::German);
+        assert_eq!(arbeit, "# Arbeitsprotokoll: Februar 2026\n\n## (kein Projekt)\n\n### Di 17. Feb 2026\n\n- Admin\n");
+    }
+}
@@ -0,0 +1,153 @@
+//! Entry filenames: built from `filename_format` (default `{day}-{time}-{title}`) and parsed back,
+//! so finding a day's entries doesn't depend on filenames happening to start with the day.
+
+use regex::Regex;
+
+use crate::compress::GZ_EXTENSION;
+
+pub const DEFAULT_FORMAT: &str = "{day}-{time}-{title}";
+
+#[derive(Debug, Clone)]
+pub struct FilenameFormat {
+    pattern: String,
+    regex: Regex,
+}
+
+/// The parts of a filename that matched the format; `time` and `title` are `None` when the
+/// format doesn't have them
+#[derive(Debug, PartialEq)]
+pub struct Parsed<'a> {
+    pub day: u32,
+    pub time: Option<&'a str>,
+    pub title: Option<&'a str>,
+}
+
+/// Filename without its `.md` or `.md.gz` extension
+fn stem(filename: &str) -> Option<&str> {
+    filename.strip_suffix(GZ_EXTENSION).or_else(|| filename.strip_suffix(".md"))
+}
+
+fn valid_day(day: &str) -> Option<u32> {
+    day.parse().ok().filter(|day| (1..=31).contains(day))
+}
+
+/// Day of files that don't follow the format, such as imported ones: a leading
+/// `YYYY-MM-DD` date or a leading one- or two-digit day followed by a separator
+fn fallback_day(stem: &str) -> Option<u32> {
+    static FALLBACK: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+        Regex::new(r"^(?:\d{4}-\d{2}-(?P<iso>\d{2})|(?P<day>\d{1,2}))(?:[^0-9]|$)").expect("valid regex")
+    });
+    let captures = FALLBACK.captures(stem)?;
+    valid_day(captures.name("iso").or(captures.name("day"))?.as_str())
+}
+
+impl FilenameFormat {
+    /// Format from a pattern using `{day}` (required, two digits), `{time}` (HHMMSS) and `{title}`
+    pub fn new(pattern: &str) -> Result<FilenameFormat, String> {
+        let placeholders = Regex::new(r"\{([^}]*)\}").expect("valid regex");
+        let mut regex = String::from("^");
+        let mut last = 0;
+        let mut seen = Vec::new();
+        for placeholder in placeholders.captures_iter(pattern) {
+            let whole = placeholder.get(0).expect("match");
+            let name = placeholder.get(1).expect("group").as_str();
+            let group = match name {
+                "day" => r"(?P<day>\d{2})",
+                "time" => r"(?P<time>\d{6})",
+                "title" => r"(?P<title>.*)",
+                _ => {
+                    return Err(format!(
+                        "filename_format '{}' has unknown placeholder {} (expected {{day}}, {{time}} or {{title}})",
+                        pattern,
+                        whole.as_str()
+                    ));
+                }
+            };
+            if seen.contains(&name) {
+                return Err(format!("filename_format '{}' repeats {}", pattern, whole.as_str()));
+            }
+            seen.push(name);
+            regex.push_str(&regex::escape(&pattern[last..whole.start()]));
+            regex.push_str(group);
+            last = whole.end();
+        }
+        regex.push_str(&regex::escape(&pattern[last..]));
+        regex.push('$');
+        if !seen.contains(&"day") {
+            return Err(format!("filename_format '{}' needs a {{day}}", pattern));
+        }
+        let regex = Regex::new(&regex).map_err(|e| format!("filename_format '{}': {}", pattern, e))?;
+        Ok(FilenameFormat { pattern: pattern.to_string(), regex })
+    }
+
+    /// Filename (with `.md`) for an entry on `day`, written at `time` (HHMMSS), titled `title`
+    pub fn render(&self, day: u32, time: &str, title: &str) -> String {
+        let name = self.pattern.replace("{day}", &format!("{:02}", day)).replace("{time}", time).replace("{title}", title);
+        format!("{}.md", name)
+    }
+
+    /// The parts of a filename written in this format
+    pub fn parse<'a>(&self, filename: &'a str) -> Option<Parsed<'a>> {
+        let captures = self.regex.captures(stem(filename)?)?;
+        Some(Parsed {
+            day: valid_day(captures.name("day")?.as_str())?,
+            time: captures.name("time").map(|m| m.as_str()),
+            title: captures.name("title").map(|m| m.as_str()),
+        })
+    }
+
+    /// Day of month an entry file was written on, from the format or else the fallback patterns
+    pub fn day(&self, filename: &str) -> Option<u32> {
+        self.parse(filename).map(|parsed| parsed.day).or_else(|| fallback_day(stem(filename)?))
+    }
+}
+
+impl Default for FilenameFormat {
+    fn default() -> Self {
+        FilenameFormat::new(DEFAULT_FORMAT).expect("default format is valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_format() {
+        let format = FilenameFormat::default();
+        assert_eq!(format.render(7, "081503", "Standup"), "07-081503-Standup.md");
+        assert_eq!(
+            format.parse("17-081503-niet-lekker-geslapen.md"),
+            Some(Parsed { day: 17, time: Some("081503"), title: Some("niet-lekker-geslapen") })
+        );
+        assert_eq!(format.day("17-081503-note.md.gz"), Some(17));
+        assert_eq!(format.parse("17-notes.md"), None);
+        assert_eq!(format.day("notes.txt"), None);
+    }
+
+    #[test]
+    fn test_custom_format() {
+        let format = FilenameFormat::new("{title}_{day}T{time}").unwrap();
+        assert_eq!(format.render(3, "120000", "Plan"), "Plan_03T120000.md");
+        // A title starting with digits no longer looks like a day
+        assert_eq!(format.day("17-people_03T120000.md"), Some(3));
+        assert_eq!(format.parse("Plan_03T120000.md").unwrap().title, Some("Plan"));
+    }
+
+    #[test]
+    fn test_fallback_day() {
+        let format = FilenameFormat::default();
+        assert_eq!(format.day("2026-02-17 imported.md"), Some(17));
+        assert_eq!(format.day("7-quick.md"), Some(7));
+        assert_eq!(format.day("17.md"), Some(17));
+        assert_eq!(format.day("170-notes.md"), None);
+        assert_eq!(format.day("45-notes.md"), None);
+    }
+
+    #[test]
+    fn test_invalid_formats() {
+        assert!(FilenameFormat::new("{time}-{title}").unwrap_err().contains("needs a {day}"));
+        assert!(FilenameFormat::new("{day}-{slug}").unwrap_err().contains("unknown placeholder {slug}"));
+        assert!(FilenameFormat::new("{day}-{day}").unwrap_err().contains("repeats {day}"));
+    }
+}
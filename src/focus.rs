@@ -0,0 +1,445 @@
+//! `focus`: a distraction-free, full-screen writing mode (requires the `tui` feature).
+//!
+//! Only the entry body is shown; its frontmatter is written back unchanged. The
+//! entry is saved every few seconds while there are changes, and again on exit.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+
+use crate::frontmatter;
+use crate::keys::{Action, Keymap};
+use crate::i18n::{Language, Message};
+use crate::theme::{self, Theme};
+
+/// Widest the text column gets, in characters
+const TEXT_WIDTH: u16 = 72;
+
+/// Text being written, one `Vec<char>` per line, with a cursor
+#[derive(Debug)]
+pub struct Buffer {
+    lines: Vec<Vec<char>>,
+    row: usize,
+    col: usize,
+}
+
+impl Buffer {
+    /// Buffer holding `text` with the cursor at its end, where writing usually resumes
+    pub fn new(text: &str) -> Self {
+        let lines: Vec<Vec<char>> = text.split('\n').map(|line| line.chars().collect()).collect();
+        let row = lines.len() - 1;
+        let col = lines[row].len();
+        Buffer { lines, row, col }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.iter().map(|line| line.iter().collect::<String>()).collect::<Vec<_>>().join("\n")
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.lines.iter().map(|line| line.iter().collect::<String>().split_whitespace().count()).sum()
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.lines[self.row].insert(self.col, c);
+        self.col += 1;
+    }
+
+    pub fn newline(&mut self) {
+        let rest = self.lines[self.row].split_off(self.col);
+        self.row += 1;
+        self.col = 0;
+        self.lines.insert(self.row, rest);
+    }
+
+    pub fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            self.lines[self.row].remove(self.col);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.lines[self.row].len();
+            self.lines[self.row].extend(line);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.col < self.lines[self.row].len() {
+            self.lines[self.row].remove(self.col);
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].extend(next);
+        }
+    }
+
+    pub fn left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.lines[self.row].len();
+        }
+    }
+
+    pub fn right(&mut self) {
+        if self.col < self.lines[self.row].len() {
+            self.col += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    pub fn up(&mut self) {
+        if self.row > 0 {
+            self.row -= 1;
+            self.col = self.col.min(self.lines[self.row].len());
+        }
+    }
+
+    pub fn down(&mut self) {
+        if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = self.col.min(self.lines[self.row].len());
+        }
+    }
+
+    pub fn home(&mut self) {
+        self.col = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.col = self.lines[self.row].len();
+    }
+
+    /// The text soft-wrapped to `width` columns, and the cursor's row and column in it
+    pub fn layout(&self, width: usize) -> (Vec<String>, (usize, usize)) {
+        let mut rows = Vec::new();
+        let mut cursor = (0, 0);
+        for (i, line) in self.lines.iter().enumerate() {
+            let starts = wrap(line, width);
+            for (j, start) in starts.iter().enumerate() {
+                let end = starts.get(j + 1).copied().unwrap_or(line.len());
+                if i == self.row && *start <= self.col && (self.col < end || j + 1 == starts.len()) {
+                    cursor = (rows.len(), self.col - start);
+                }
+                rows.push(line[*start..end].iter().collect());
+            }
+        }
+        (rows, cursor)
+    }
+}
+
+/// Where each row of `line` starts when word-wrapped to `width` columns
+pub fn wrap(line: &[char], width: usize) -> Vec<usize> {
+    let width = width.max(1);
+    let mut starts = vec![0];
+    let mut start = 0;
+    while line.len() - start > width {
+        // Break after the last space that still fits, or mid-word if there is none
+        let end = match line[start + 1..=start + width].iter().rposition(|c| *c == ' ') {
+            Some(space) => start + 1 + space + 1,
+            None => start + width,
+        };
+        starts.push(end);
+        start = end;
+    }
+    starts
+}
+
+/// First wrapped row to show in a view `height` rows tall. Typewriter mode keeps
+/// the cursor row in the middle, showing blank space above the first line if needed;
+/// otherwise the view only scrolls when the cursor would leave it.
+pub fn viewport_top(cursor_row: usize, height: usize, typewriter: bool, scroll: usize) -> isize {
+    let height = height.max(1);
+    if typewriter {
+        cursor_row as isize - (height / 2) as isize
+    } else if cursor_row < scroll {
+        cursor_row as isize
+    } else if cursor_row >= scroll + height {
+        (cursor_row + 1 - height) as isize
+    } else {
+        scroll as isize
+    }
+}
+
+/// `mm:ss`, or `h:mm:ss` after an hour
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    } else {
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+enum SaveState {
+    Saved,
+    Unsaved,
+    Failed(String),
+}
+
+/// What the session did, printed after leaving focus mode
+pub struct Summary {
+    pub words_added: isize,
+    pub elapsed: Duration,
+    pub language: Language,
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:+} {} {}", self.words_added, self.language.text(Message::WordsIn), format_elapsed(self.elapsed))
+    }
+}
+
+pub struct Session {
+    path: PathBuf,
+    /// Everything before the body (the frontmatter block as written, comments and all), written
+    /// back in front of it on every save
+    frontmatter: String,
+    buffer: Buffer,
+    keymap: Keymap,
+    theme: Theme,
+    language: Language,
+    typewriter: bool,
+    autosave: Duration,
+    scroll: usize,
+    started: Instant,
+    start_words: usize,
+    last_save: Instant,
+    state: SaveState,
+}
+
+impl Session {
+    pub fn open(path: &Path, keymap: Keymap, theme: Theme, language: Language, typewriter: bool, autosave: Duration) -> Result<Self, String> {
+        if !path.to_string_lossy().ends_with(".md") {
+            return Err(format!("{} is compressed or archived and can't be edited", path.display()));
+        }
+        let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let body = frontmatter::parse(&content).1;
+        let buffer = Buffer::new(body.trim_end_matches('\n'));
+        let start_words = buffer.word_count();
+        Ok(Session {
+            path: path.to_path_buf(),
+            frontmatter: content[..content.len() - body.len()].to_string(),
+            buffer,
+            keymap,
+            theme,
+            language,
+            typewriter,
+            autosave,
+            scroll: 0,
+            started: Instant::now(),
+            start_words,
+            last_save: Instant::now(),
+            state: SaveState::Saved,
+        })
+    }
+
+    fn save(&mut self) {
+        let content = format!("{}{}\n", self.frontmatter, self.buffer.text());
+        self.state = match fs::write(&self.path, content) {
+            Ok(()) => SaveState::Saved,
+            Err(e) => SaveState::Failed(e.to_string()),
+        };
+        self.last_save = Instant::now();
+    }
+
+    /// Apply a key press; returns false once the session should end
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.kind != KeyEventKind::Press {
+            return true;
+        }
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        // Typed characters are always text; other keys may be bound to commands
+        if control || !matches!(key.code, KeyCode::Char(_)) {
+            match self.keymap.lookup(&key) {
+                Some(Action::Quit) => return false,
+                Some(Action::Save) => {
+                    self.save();
+                    return true;
+                }
+                Some(Action::ToggleTypewriter) => {
+                    self.typewriter = !self.typewriter;
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        match key.code {
+            KeyCode::Char(_) if control => return true,
+            KeyCode::Char(c) => self.buffer.insert(c),
+            KeyCode::Enter => self.buffer.newline(),
+            KeyCode::Backspace => self.buffer.backspace(),
+            KeyCode::Delete => self.buffer.delete(),
+            KeyCode::Left => self.buffer.left(),
+            KeyCode::Right => self.buffer.right(),
+            KeyCode::Up => self.buffer.up(),
+            KeyCode::Down => self.buffer.down(),
+            KeyCode::Home => self.buffer.home(),
+            KeyCode::End => self.buffer.end(),
+            _ => {}
+        }
+        if matches!(key.code, KeyCode::Char(_) | KeyCode::Enter | KeyCode::Backspace | KeyCode::Delete) {
+            self.state = SaveState::Unsaved;
+        }
+        true
+    }
+
+    fn draw(&mut self, frame: &mut ratatui::Frame) {
+        let [text_area, status_area] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let width = text_area.width.min(TEXT_WIDTH);
+        let column = Rect { x: text_area.x + (text_area.width - width) / 2, width, ..text_area };
+
+        // One column is kept free so the cursor fits after a full row
+        let (rows, (cursor_row, cursor_col)) = self.buffer.layout(width.saturating_sub(1) as usize);
+        let height = column.height as usize;
+        let top = viewport_top(cursor_row, height, self.typewriter, self.scroll);
+        self.scroll = top.max(0) as usize;
+        let visible: Vec<Line> = (top..top + height as isize)
+            .map(|i| {
+                let row = usize::try_from(i).ok().and_then(|i| rows.get(i));
+                match row.map(|r| (r, theme::line_role(r))) {
+                    Some((row, Some(role))) => Line::styled(row.as_str(), self.theme.style(role)),
+                    Some((row, None)) => Line::raw(row.as_str()),
+                    None => Line::default(),
+                }
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(visible), column);
+        frame.set_cursor_position((column.x + cursor_col as u16, column.y + (cursor_row as isize - top) as u16));
+
+        let words = self.buffer.word_count();
+        let text = |message| self.language.text(message);
+        let saved = match &self.state {
+            SaveState::Saved => text(Message::Saved).to_string(),
+            SaveState::Unsaved => text(Message::Unsaved).to_string(),
+            SaveState::Failed(e) => format!("{}: {}", text(Message::SaveFailed), e),
+        };
+        let status = format!(
+            "{} {} ({:+})   {}   {}{}   {} {}  {} {}  {} {}",
+            words,
+            text(Message::Words),
+            words as isize - self.start_words as isize,
+            format_elapsed(self.started.elapsed()),
+            saved,
+            if self.typewriter { format!("   {}", text(Message::Typewriter)) } else { String::new() },
+            self.keymap.label(Action::Save),
+            text(Message::Save),
+            self.keymap.label(Action::ToggleTypewriter),
+            text(Message::Typewriter),
+            self.keymap.label(Action::Quit),
+            text(Message::Quit),
+        );
+        let style = Style::default().add_modifier(Modifier::DIM);
+        frame.render_widget(Paragraph::new(status).style(style).centered(), status_area);
+    }
+
+    fn run_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<(), String> {
+        loop {
+            terminal.draw(|frame| self.draw(frame)).map_err(|e| e.to_string())?;
+            // Wake up regularly so the timer ticks and autosave runs while idle
+            if event::poll(Duration::from_millis(250)).map_err(|e| e.to_string())?
+                && let Event::Key(key) = event::read().map_err(|e| e.to_string())?
+                && !self.handle_key(key)
+            {
+                return Ok(());
+            }
+            if matches!(self.state, SaveState::Unsaved) && self.last_save.elapsed() >= self.autosave {
+                self.save();
+            }
+        }
+    }
+
+    /// Take over the terminal until the user quits, then save
+    pub fn run(mut self) -> Result<Summary, String> {
+        let mut terminal = ratatui::try_init().map_err(|e| format!("Failed to set up the terminal: {}", e))?;
+        let result = self.run_loop(&mut terminal);
+        ratatui::restore();
+        result?;
+
+        if !matches!(self.state, SaveState::Saved) {
+            self.save();
+        }
+        if let SaveState::Failed(e) = &self.state {
+            return Err(format!("Failed to write {}: {}", self.path.display(), e));
+        }
+        Ok(Summary {
+            words_added: self.buffer.word_count() as isize - self.start_words as isize,
+            elapsed: self.started.elapsed(),
+            language: self.language,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_buffer_editing() {
+        let mut buffer = Buffer::new("# Title\n\nHello");
+        buffer.insert('!');
+        buffer.newline();
+        buffer.insert('x');
+        buffer.backspace();
+        buffer.backspace();
+        assert_eq!(buffer.text(), "# Title\n\nHello!");
+        buffer.up();
+        buffer.up();
+        buffer.end();
+        buffer.delete();
+        assert_eq!(buffer.text(), "# Title\nHello!");
+        assert_eq!(buffer.word_count(), 3);
+    }
+
+    #[test]
+    fn test_wrap_breaks_after_spaces() {
+        let line: Vec<char> = "the quick brown fox".chars().collect();
+        assert_eq!(wrap(&line, 10), vec![0, 10]);
+        assert_eq!(wrap(&line, 30), vec![0]);
+        let word: Vec<char> = "abcdefghij".chars().collect();
+        assert_eq!(wrap(&word, 4), vec![0, 4, 8]);
+
+        let buffer = Buffer::new("the quick brown fox");
+        let (rows, cursor) = buffer.layout(10);
+        assert_eq!(rows, vec!["the quick ", "brown fox"]);
+        assert_eq!(cursor, (1, 9));
+    }
+
+    #[test]
+    fn test_viewport_top() {
+        assert_eq!(viewport_top(3, 10, true, 0), -2);
+        assert_eq!(viewport_top(3, 10, false, 0), 0);
+        assert_eq!(viewport_top(12, 10, false, 0), 3);
+        assert_eq!(viewport_top(2, 10, false, 5), 2);
+    }
+
+    #[test]
+    fn test_session_keeps_frontmatter() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("16-080000-draft.md");
+        fs::write(&path, "---\n# kept as written\ntags:\n  - draft\ntitle: 'Draft'\n---\n\n# Draft\n").unwrap();
+
+        let mut session = Session::open(&path, Keymap::new(None).unwrap(), Theme::from_config(None).unwrap(), Language::English, false, Duration::from_secs(5)).unwrap();
+        session.handle_key(KeyEvent::from(KeyCode::Enter));
+        for c in "More.".chars() {
+            session.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert!(!session.handle_key(KeyEvent::from(KeyCode::Esc)));
+        session.save();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "---\n# kept as written\ntags:\n  - draft\ntitle: 'Draft'\n---\n\n# Draft\nMore.\n"
+        );
+    }
+}
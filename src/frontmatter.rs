@@ -0,0 +1,279 @@
+//! Minimal YAML-style frontmatter (`---` delimited `key: value` lines).
+//!
+//! Only what entries need is supported: scalar values and lists written either
+//! inline (`tags: [a, b]`) or as `- item` lines under a key.
+
+/// Frontmatter fields in file order
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Frontmatter {
+    fields: Vec<(String, Value)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Scalar(String),
+    List(Vec<String>),
+}
+
+/// A value as written, without its quotes: the `\\`, `\"` and `\n` escapes `quote_if_needed`
+/// writes are read back inside double quotes, and `''` inside single quotes, as in YAML
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        let mut unescaped = String::with_capacity(inner.len());
+        let mut chars = inner.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, chars.peek()) {
+                ('\\', Some('\\' | '"')) => unescaped.extend(chars.next()),
+                ('\\', Some('n')) => {
+                    chars.next();
+                    unescaped.push('\n');
+                }
+                _ => unescaped.push(c),
+            }
+        }
+        return unescaped;
+    }
+    if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return inner.replace("''", "'");
+    }
+    value.to_string()
+}
+
+/// Split an inline list body on commas that are not inside quotes, and unquote each item
+fn split_inline_list(inner: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+
+    for (i, c) in inner.char_indices() {
+        match (quote, c) {
+            _ if escaped => escaped = false,
+            (Some('"'), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            // Only a quote that opens an item quotes it
+            (None, '"' | '\'') if inner[start..i].trim().is_empty() => quote = Some(c),
+            (None, ',') => {
+                items.push(&inner[start..i]);
+                start = i + 1;
+            }
+            (None, _) => {}
+        }
+    }
+    items.push(&inner[start..]);
+
+    items.into_iter().map(str::trim).filter(|item| !item.is_empty()).map(unquote).collect()
+}
+
+fn quote_if_needed(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value.starts_with(['[', '{', '"', '\'', '-', '#', ' '])
+        || value.ends_with(' ')
+        || value.contains(": ")
+        || value.contains([',', '\n']);
+    if needs_quotes {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+    } else {
+        value.to_string()
+    }
+}
+
+impl Frontmatter {
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Get a scalar value
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.iter().find(|(k, _)| k == key).and_then(|(_, v)| match v {
+            Value::Scalar(s) => Some(s.as_str()),
+            Value::List(_) => None,
+        })
+    }
+
+    /// Every field in file order
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.fields.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Get a field as written, scalar or list
+    pub fn value(&self, key: &str) -> Option<&Value> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Get a list value; a scalar counts as a one-item list
+    pub fn list(&self, key: &str) -> Vec<String> {
+        match self.fields.iter().find(|(k, _)| k == key) {
+            Some((_, Value::List(items))) => items.clone(),
+            Some((_, Value::Scalar(s))) if !s.is_empty() => vec![s.clone()],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Set a field, replacing it in place if it already exists
+    pub fn set(&mut self, key: &str, value: Value) {
+        match self.fields.iter_mut().find(|(k, _)| k == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.fields.push((key.to_string(), value)),
+        }
+    }
+
+    /// Render as a `---` block followed by a blank line (empty string if there are no fields)
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+
+        let mut out = String::from("---\n");
+        for (key, value) in &self.fields {
+            match value {
+                Value::Scalar(s) => out.push_str(&format!("{}: {}\n", key, quote_if_needed(s))),
+                Value::List(items) => {
+                    let items: Vec<String> = items.iter().map(|i| quote_if_needed(i)).collect();
+                    out.push_str(&format!("{}: [{}]\n", key, items.join(", ")));
+                }
+            }
+        }
+        out.push_str("---\n\n");
+        out
+    }
+}
+
+/// Split an entry into its frontmatter and the remaining body.
+/// Content without a leading `---` block yields empty frontmatter.
+pub fn parse(content: &str) -> (Frontmatter, &str) {
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return (Frontmatter::default(), content);
+    };
+
+    // Find the closing delimiter
+    let mut offset = 0;
+    let mut block_end = None;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            block_end = Some((offset, offset + line.len()));
+            break;
+        }
+        offset += line.len();
+    }
+    let Some((block_len, body_start)) = block_end else {
+        return (Frontmatter::default(), content);
+    };
+
+    let mut frontmatter = Frontmatter::default();
+    for line in rest[..block_len].lines() {
+        if let Some(item) = line.trim_start().strip_prefix("- ") {
+            // Block list item belonging to the previous key
+            if let Some((_, value)) = frontmatter.fields.last_mut() {
+                match value {
+                    Value::List(items) => items.push(unquote(item)),
+                    Value::Scalar(s) if s.is_empty() => *value = Value::List(vec![unquote(item)]),
+                    Value::Scalar(_) => {}
+                }
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() || key.starts_with('#') {
+            continue;
+        }
+
+        let value = value.trim();
+        let value = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            Some(inner) => Value::List(split_inline_list(inner)),
+            None => Value::Scalar(unquote(value)),
+        };
+        frontmatter.set(key, value);
+    }
+
+    let body = rest[body_start..].trim_start_matches(['\n', '\r']);
+    (frontmatter, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scalars_and_lists() {
+        let content = "---\nauthor: alice\ntags: [work, \"team\"]\nprojects:\n  - atlas\n  - zeus\n---\n\n# Title\n";
+        let (frontmatter, body) = parse(content);
+
+        let mut expected = Frontmatter::default();
+        expected.set("author", Value::Scalar("alice".to_string()));
+        expected.set("tags", Value::List(vec!["work".to_string(), "team".to_string()]));
+        expected.set("projects", Value::List(vec!["atlas".to_string(), "zeus".to_string()]));
+        assert_eq!(frontmatter, expected);
+        assert_eq!(frontmatter.get("author"), Some("alice"));
+        assert_eq!(frontmatter.list("projects"), vec!["atlas", "zeus"]);
+        assert_eq!(frontmatter.list("author"), vec!["alice"]);
+        assert!(frontmatter.list("missing").is_empty());
+        assert_eq!(body, "# Title\n");
+    }
+
+    #[test]
+    fn test_parse_without_frontmatter() {
+        let content = "# Title\n\nDate: 17-02-2026\n";
+        let (frontmatter, body) = parse(content);
+        assert!(frontmatter.is_empty());
+        assert_eq!(body, content);
+
+        // An unterminated block is treated as plain content
+        let (frontmatter, body) = parse("---\nauthor: alice\n# Title\n");
+        assert!(frontmatter.is_empty());
+        assert_eq!(body, "---\nauthor: alice\n# Title\n");
+    }
+
+    #[test]
+    fn test_render_roundtrip() {
+        let mut frontmatter = Frontmatter::default();
+        frontmatter.set("author", Value::Scalar("alice".to_string()));
+        frontmatter.set("tags", Value::List(vec!["work".to_string(), "a, b".to_string()]));
+        let rendered = frontmatter.render();
+
+        assert_eq!(rendered, "---\nauthor: alice\ntags: [work, \"a, b\"]\n---\n\n");
+        let (parsed, body) = parse(&rendered);
+        assert_eq!(parsed, frontmatter);
+        assert_eq!(body, "");
+    }
+
+    #[test]
+    fn test_quoting_roundtrip() {
+        let tricky = [
+            "say \"hi\", then leave",
+            "C:\\notes\\, old",
+            "ends with a backslash, \\",
+            " padded ",
+            "it's, fine",
+            "it's",
+            "two\nlines",
+            "",
+            "- not a list item",
+            "key: value",
+            "\"quoted\"",
+        ];
+        let mut frontmatter = Frontmatter::default();
+        for (i, value) in tricky.iter().enumerate() {
+            frontmatter.set(&format!("v{}", i), Value::Scalar(value.to_string()));
+        }
+        frontmatter.set("list", Value::List(tricky.iter().map(|value| value.to_string()).collect()));
+        assert_eq!(parse(&frontmatter.render()).0, frontmatter);
+
+        // Hand-written quoting reads the same after it's written back
+        let content = "---\ntitle: \"He said \\\"hi\\\"\"\nnote: 'it''s'\ntags: [\"a, b\", 'c', d, it's]\n---\n\n# T\n";
+        let (parsed, _) = parse(content);
+        assert_eq!(parsed.get("title"), Some("He said \"hi\""));
+        assert_eq!(parsed.get("note"), Some("it's"));
+        assert_eq!(parsed.list("tags"), ["a, b", "c", "d", "it's"]);
+        assert_eq!(parse(&parsed.render()).0, parsed);
+    }
+}
@@ -0,0 +1,132 @@
+//! GitHub activity import (requires the `network` feature).
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde_json::Value;
+
+const API_URL: &str = "https://api.github.com";
+
+fn get_json(url: &str, token: Option<&str>) -> Result<Value, String> {
+    let mut request = ureq::get(url)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "file-journal");
+    if let Some(token) = token {
+        request = request.header("Authorization", &format!("Bearer {}", token));
+    }
+
+    request
+        .call()
+        .map_err(|e| format!("GitHub request to {} failed: {}", url, e))?
+        .body_mut()
+        .read_json::<Value>()
+        .map_err(|e| format!("Invalid response from {}: {}", url, e))
+}
+
+/// Resolve `me` to the login owning the token
+pub fn resolve_user(user: &str, token: Option<&str>) -> Result<String, String> {
+    if user != "me" {
+        return Ok(user.to_string());
+    }
+    let token = token.ok_or("'--user me' needs a GitHub token (github_token in config or GITHUB_TOKEN)")?;
+    let me = get_json(&format!("{}/user", API_URL), Some(token))?;
+    me["login"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "Could not determine the token's GitHub login".to_string())
+}
+
+/// Fetch the user's most recent public (and, with a token, private) events
+pub fn fetch_events(user: &str, token: Option<&str>) -> Result<Vec<Value>, String> {
+    let url = format!("{}/users/{}/events?per_page=100", API_URL, user);
+    match get_json(&url, token)? {
+        Value::Array(events) => Ok(events),
+        other => Err(format!("Unexpected events response: {}", other)),
+    }
+}
+
+fn event_date(event: &Value) -> Option<NaiveDate> {
+    let created = event["created_at"].as_str()?;
+    let created = DateTime::parse_from_rfc3339(created).ok()?;
+    Some(created.with_timezone(&Local).date_naive())
+}
+
+fn issue_ref(repo: &str, item: &Value) -> String {
+    let number = item["number"].as_u64().map(|n| format!("#{}", n)).unwrap_or_default();
+    let title = item["title"].as_str().unwrap_or_default();
+    format!("{}{}: {}", repo, number, title)
+}
+
+/// Summarize the events from `date` as Markdown bullets, oldest first
+pub fn summarize_events(events: &[Value], date: NaiveDate) -> Vec<String> {
+    let mut lines: Vec<String> = events
+        .iter()
+        .filter(|event| event_date(event) == Some(date))
+        .filter_map(|event| {
+            let repo = event["repo"]["name"].as_str().unwrap_or("unknown repo");
+            let payload = &event["payload"];
+            match event["type"].as_str()? {
+                "PushEvent" => {
+                    let commits = payload["size"]
+                        .as_u64()
+                        .or_else(|| payload["commits"].as_array().map(|c| c.len() as u64))
+                        .unwrap_or(0);
+                    let branch = payload["ref"].as_str().unwrap_or_default().trim_start_matches("refs/heads/");
+                    let noun = if commits == 1 { "commit" } else { "commits" };
+                    Some(format!("Pushed {} {} to {} ({})", commits, noun, repo, branch))
+                }
+                "PullRequestReviewEvent" => {
+                    Some(format!("Reviewed PR {}", issue_ref(repo, &payload["pull_request"])))
+                }
+                "PullRequestEvent" if payload["action"] == "opened" => {
+                    Some(format!("Opened PR {}", issue_ref(repo, &payload["pull_request"])))
+                }
+                "PullRequestEvent" if payload["action"] == "closed" && payload["pull_request"]["merged"] == true => {
+                    Some(format!("Merged PR {}", issue_ref(repo, &payload["pull_request"])))
+                }
+                "IssueCommentEvent" => Some(format!("Commented on {}", issue_ref(repo, &payload["issue"]))),
+                "IssuesEvent" if payload["action"] == "opened" => {
+                    Some(format!("Opened issue {}", issue_ref(repo, &payload["issue"])))
+                }
+                _ => None,
+            }
+        })
+        .map(|line| format!("- {}", line))
+        .collect();
+
+    // The API returns newest first
+    lines.reverse();
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_events_for_day() {
+        let today = Local::now();
+        let now = today.to_rfc3339();
+        let events: Vec<Value> = serde_json::from_str(&format!(
+            r#"[
+                {{"type": "IssueCommentEvent", "created_at": "{now}", "repo": {{"name": "o/r"}},
+                  "payload": {{"issue": {{"number": 7, "title": "Crash on start"}}}}}},
+                {{"type": "PullRequestReviewEvent", "created_at": "{now}", "repo": {{"name": "o/r"}},
+                  "payload": {{"pull_request": {{"number": 12, "title": "Add cache"}}}}}},
+                {{"type": "PushEvent", "created_at": "{now}", "repo": {{"name": "o/r"}},
+                  "payload": {{"size": 3, "ref": "refs/heads/main"}}}},
+                {{"type": "WatchEvent", "created_at": "{now}", "repo": {{"name": "o/r"}}, "payload": {{}}}},
+                {{"type": "PushEvent", "created_at": "2001-01-01T00:00:00Z", "repo": {{"name": "o/old"}},
+                  "payload": {{"size": 1}}}}
+            ]"#
+        ))
+        .unwrap();
+
+        assert_eq!(
+            summarize_events(&events, today.date_naive()),
+            vec![
+                "- Pushed 3 commits to o/r (main)",
+                "- Reviewed PR o/r#12: Add cache",
+                "- Commented on o/r#7: Crash on start",
+            ]
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! `POST /graphql` for `serve`: read-only queries over entries, tags and stats.
+
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::{entry, find_all_entries, metrics, resolve_journal_selector, search};
+
+pub type JournalSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Schema answering queries against the journal at `journal_path`
+pub fn schema(journal_path: PathBuf) -> JournalSchema {
+    Schema::build(Query, EmptyMutation, EmptySubscription).data(journal_path).finish()
+}
+
+/// Run one GraphQL request body (`{"query": ..., "variables": ...}`), returning the JSON response
+pub fn execute(schema: &JournalSchema, body: &str) -> serde_json::Value {
+    let response = match serde_json::from_str::<async_graphql::Request>(body) {
+        Ok(request) => pollster::block_on(schema.execute(request)),
+        Err(e) => async_graphql::Response::from_errors(vec![async_graphql::ServerError::new(
+            format!("Invalid request body: {}", e),
+            None,
+        )]),
+    };
+    serde_json::to_value(response).unwrap_or_default()
+}
+
+#[derive(SimpleObject, Clone)]
+pub struct Entry {
+    /// Path relative to the journal, as used by `/entries/{id}`
+    path: String,
+    /// YYYY-MM-DD
+    date: Option<String>,
+    /// HH:MM:SS
+    time: Option<String>,
+    title: Option<String>,
+    tags: Vec<String>,
+    author: Option<String>,
+    words: usize,
+    /// Markdown body without frontmatter
+    body: String,
+}
+
+#[derive(SimpleObject)]
+pub struct Tag {
+    name: String,
+    count: usize,
+    entries: Vec<Entry>,
+}
+
+#[derive(SimpleObject)]
+pub struct Stats {
+    entries: usize,
+    words: usize,
+    tags: usize,
+    /// Consecutive days with an entry, ending today
+    streak: u32,
+    first_date: Option<String>,
+    last_date: Option<String>,
+}
+
+fn load(journal_path: &Path, path: &Path) -> Option<Entry> {
+    let entry = entry::Entry::read(path).ok()?;
+    Some(Entry {
+        path: path.strip_prefix(journal_path).unwrap_or(path).to_string_lossy().to_string(),
+        date: entry.date.map(|d| d.to_string()),
+        time: entry.time.map(|t| t.format("%H:%M:%S").to_string()),
+        words: entry.words(),
+        tags: entry.frontmatter.list("tags"),
+        author: entry.frontmatter.get("author").map(str::to_string),
+        title: entry.title,
+        body: entry.body,
+    })
+}
+
+/// Every entry, newest first
+fn load_all(journal_path: &Path) -> async_graphql::Result<Vec<Entry>> {
+    let mut entries: Vec<Entry> =
+        find_all_entries(journal_path)?.iter().filter_map(|p| load(journal_path, p)).collect();
+    entries.reverse();
+    Ok(entries)
+}
+
+fn take(entries: Vec<Entry>, limit: Option<usize>) -> Vec<Entry> {
+    entries.into_iter().take(limit.unwrap_or(usize::MAX)).collect()
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// Entries newest first, optionally narrowed to a selector (today, YYYY-MM, ...) and/or a tag
+    async fn entries(
+        &self,
+        ctx: &Context<'_>,
+        selector: Option<String>,
+        tag: Option<String>,
+        limit: Option<usize>,
+    ) -> async_graphql::Result<Vec<Entry>> {
+        let journal_path = ctx.data::<PathBuf>()?;
+        let mut entries = match selector {
+            Some(selector) => {
+                let paths = resolve_journal_selector(journal_path, &selector)?;
+                paths.iter().rev().filter_map(|p| load(journal_path, p)).collect()
+            }
+            None => load_all(journal_path)?,
+        };
+        if let Some(tag) = tag {
+            entries.retain(|e| e.tags.contains(&tag));
+        }
+        Ok(take(entries, limit))
+    }
+
+    /// One entry by its path relative to the journal
+    async fn entry(&self, ctx: &Context<'_>, path: String) -> async_graphql::Result<Option<Entry>> {
+        let journal_path = ctx.data::<PathBuf>()?;
+        // Only paths the journal actually lists, so nothing outside it can be read
+        let wanted = journal_path.join(&path);
+        Ok(find_all_entries(journal_path)?.iter().find(|p| **p == wanted).and_then(|p| load(journal_path, p)))
+    }
+
+    /// Tags by number of entries, most used first
+    async fn tags(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Tag>> {
+        let mut by_tag: BTreeMap<String, Vec<Entry>> = BTreeMap::new();
+        for entry in load_all(ctx.data::<PathBuf>()?)? {
+            for tag in &entry.tags {
+                by_tag.entry(tag.clone()).or_default().push(entry.clone());
+            }
+        }
+        let mut tags: Vec<Tag> = by_tag
+            .into_iter()
+            .map(|(name, entries)| Tag { name, count: entries.len(), entries })
+            .collect();
+        tags.sort_by_key(|t| std::cmp::Reverse(t.count));
+        Ok(tags)
+    }
+
+    async fn stats(&self, ctx: &Context<'_>) -> async_graphql::Result<Stats> {
+        let entries = load_all(ctx.data::<PathBuf>()?)?;
+        let dates: BTreeSet<_> = entries.iter().filter_map(|e| e.date.as_deref()?.parse().ok()).collect();
+        let tags: BTreeSet<&String> = entries.iter().flat_map(|e| &e.tags).collect();
+        Ok(Stats {
+            entries: entries.len(),
+            words: entries.iter().map(|e| e.words).sum(),
+            tags: tags.len(),
+            streak: metrics::streak(&dates, chrono::Local::now().date_naive()),
+            first_date: dates.first().map(|d| d.to_string()),
+            last_date: dates.last().map(|d| d.to_string()),
+        })
+    }
+
+    /// Entries containing every word of `query` (case-insensitive), newest first
+    async fn search(&self, ctx: &Context<'_>, query: String, limit: Option<usize>) -> async_graphql::Result<Vec<Entry>> {
+        let mut entries = load_all(ctx.data::<PathBuf>()?)?;
+        entries.retain(|e| search::matches(&format!("{} {}", e.body, e.tags.join(" ")), &query));
+        Ok(take(entries, limit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_nested_query() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        fs::write(month.join("16-080000-standup.md"), "---\ntags: [work]\n---\n\n# Standup\n\nShipped the parser\n")
+            .unwrap();
+        fs::write(month.join("17-080000-hike.md"), "---\ntags: [outdoors, weekend]\n---\n\n# Hike\n\nLong walk\n")
+            .unwrap();
+
+        let schema = schema(dir.path().to_path_buf());
+        let body = r#"{"query": "{ entries(limit: 1) { title tags } tags { name entries { date } } stats { entries words } search(query: \"PARSER\") { path } }"}"#;
+        let response = execute(&schema, body);
+        assert!(response.get("errors").is_none(), "{}", response);
+
+        let data = &response["data"];
+        assert_eq!(data["entries"], serde_json::json!([{ "title": "Hike", "tags": ["outdoors", "weekend"] }]));
+        assert_eq!(data["tags"][0]["entries"].as_array().unwrap().len(), 1);
+        assert_eq!(data["stats"]["entries"], 2);
+        assert_eq!(data["stats"]["words"], 9);
+        assert_eq!(data["search"][0]["path"], "2026/02/16-080000-standup.md");
+    }
+
+    #[test]
+    fn test_entries_selector_outside_journal_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let schema = schema(dir.path().to_path_buf());
+        let response = execute(&schema, r#"{"query": "{ entries(selector: \"/etc/hostname\") { body } }"}"#);
+        assert!(response.get("errors").is_some(), "{}", response);
+        assert_eq!(response["data"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_entry_outside_journal_is_not_found() {
+        let dir = TempDir::new().unwrap();
+        let schema = schema(dir.path().to_path_buf());
+        let response = execute(&schema, r#"{"query": "{ entry(path: \"../../etc/passwd\") { body } }"}"#);
+        assert_eq!(response["data"]["entry"], serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,130 @@
+//! HTML to Markdown for clippings (`POST /clip`), with `html2md`. Scripts and styles are
+//! dropped, and links and images only keep targets that are safe to click in rendered Markdown.
+
+use std::collections::HashMap;
+
+use html2md::common::get_tag_attr;
+use html2md::{Handle, StructuredPrinter, TagHandler, TagHandlerFactory};
+
+/// Elements whose content is never shown
+const HIDDEN: [&str; 6] = ["script", "style", "head", "noscript", "template", "svg"];
+
+/// Schemes that run code or smuggle content when a link is followed
+const UNSAFE_SCHEMES: [&str; 3] = ["javascript:", "vbscript:", "data:"];
+
+/// `url` as a Markdown link target: characters that would end the target or break it up
+/// (spaces, parentheses and angle brackets) are percent-encoded
+pub fn link_target(url: &str) -> String {
+    let mut target = String::with_capacity(url.len());
+    for c in url.trim().chars() {
+        match c {
+            ' ' | '(' | ')' | '<' | '>' | '"' | '\\' => target.push_str(&format!("%{:02X}", c as u32)),
+            c if c.is_control() => target.push_str(&format!("%{:02X}", c as u32)),
+            c => target.push(c),
+        }
+    }
+    target
+}
+
+/// Whether following `url` could run a script: browsers ignore case, whitespace and control
+/// characters in the scheme, so `JaVa\tScript:` counts too
+fn is_unsafe(url: &str) -> bool {
+    let scheme: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .take(12)
+        .collect::<String>()
+        .to_lowercase();
+    UNSAFE_SCHEMES.iter().any(|unsafe_scheme| scheme.starts_with(unsafe_scheme))
+}
+
+/// Drops the element and everything in it
+struct Hidden;
+
+impl TagHandler for Hidden {
+    fn handle(&mut self, _tag: &Handle, _printer: &mut StructuredPrinter) {}
+
+    fn after_handle(&mut self, _printer: &mut StructuredPrinter) {}
+
+    fn skip_descendants(&self) -> bool {
+        true
+    }
+}
+
+/// `[text](href)`, or just the text when the link is unsafe or goes nowhere
+#[derive(Default)]
+struct Link {
+    start: usize,
+    href: Option<String>,
+}
+
+impl TagHandler for Link {
+    fn handle(&mut self, tag: &Handle, printer: &mut StructuredPrinter) {
+        self.start = printer.data.len();
+        self.href = get_tag_attr(tag, "href").filter(|href| !href.trim().is_empty() && !is_unsafe(href));
+    }
+
+    fn after_handle(&mut self, printer: &mut StructuredPrinter) {
+        if let Some(href) = &self.href {
+            printer.insert_str(self.start, "[");
+            printer.append_str(&format!("]({})", link_target(href)));
+        }
+    }
+}
+
+/// `![alt](src)`, or just the alt text when the source is unsafe or missing
+struct Image;
+
+impl TagHandler for Image {
+    fn handle(&mut self, tag: &Handle, printer: &mut StructuredPrinter) {
+        let alt = get_tag_attr(tag, "alt").unwrap_or_default();
+        match get_tag_attr(tag, "src").filter(|src| !src.trim().is_empty() && !is_unsafe(src)) {
+            Some(src) => printer.append_str(&format!("![{}]({})", alt.replace(['[', ']'], ""), link_target(&src))),
+            None => printer.append_str(&alt),
+        }
+    }
+
+    fn after_handle(&mut self, _printer: &mut StructuredPrinter) {}
+}
+
+struct Factory(fn() -> Box<dyn TagHandler>);
+
+impl TagHandlerFactory for Factory {
+    fn instantiate(&self) -> Box<dyn TagHandler> {
+        (self.0)()
+    }
+}
+
+/// Markdown for a fragment of HTML
+pub fn to_markdown(html: &str) -> String {
+    let mut handlers: HashMap<String, Box<dyn TagHandlerFactory>> = HashMap::new();
+    for name in HIDDEN {
+        handlers.insert(name.to_string(), Box::new(Factory(|| Box::new(Hidden))));
+    }
+    handlers.insert("a".to_string(), Box::new(Factory(|| Box::new(Link::default()))));
+    handlers.insert("img".to_string(), Box::new(Factory(|| Box::new(Image))));
+    html2md::parse_html_custom(html, &handlers).trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_markdown() {
+        let html = r#"<p>Read the <a href="https://doc.rust-lang.org/book/">book</a>, then <strong>practice</strong>.</p>
+            <script>alert("no")</script><style>p { color: red }</style>
+            <ul><li>one</li><li>two</li></ul>
+            <p><a href=" JavaScript:alert(1)">click</a> <img src="java&#x09;script:alert(1)" alt="bad">
+            <a href="https://en.wikipedia.org/wiki/Rust (programming language)">wiki</a>
+            <img src="/my cat.png" alt="a cat"></p>"#;
+        assert_eq!(
+            to_markdown(html),
+            "Read the [book](https://doc.rust-lang.org/book/), then **practice**.\n\n\
+             * one\n\
+             * two\n\n\
+             click bad [wiki](https://en.wikipedia.org/wiki/Rust%20%28programming%20language%29) \
+             ![a cat](/my%20cat.png)"
+        );
+    }
+}
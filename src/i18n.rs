@@ -0,0 +1,304 @@
+//! Translated messages and month/weekday names.
+//!
+//! The language comes from `locale` in the config, then the LC_ALL, LC_MESSAGES
+//! and LANG environment variables; anything without a catalog falls back to English.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+}
+
+/// Messages shown in generated entries, exports and the terminal views
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(all(feature = "network", feature = "tui")), allow(dead_code))]
+pub enum Message {
+    /// `{}` is the month, e.g. "February 2026"
+    SummaryOf,
+    Worklog,
+    NoEntries,
+    NotFound,
+    SwitchPane,
+    Scroll,
+    Search,
+    LinkedScrolling,
+    On,
+    Off,
+    PreviousNextDay,
+    Quit,
+    Words,
+    /// Followed by the time spent, e.g. "+120 words in 12:30"
+    WordsIn,
+    Saved,
+    Unsaved,
+    SaveFailed,
+    Save,
+    Typewriter,
+    /// Worklog group for entries without a `project` field
+    NoProject,
+    /// `{}` is the new entry's path
+    CreatedEntry,
+    /// `{}` is the entry the message went into
+    AppendedTo,
+    /// `{}` is the selector
+    NoEntriesMatch,
+    /// `{}` is the number of years
+    YearAgo,
+    YearsAgo,
+    ExportInterrupted,
+}
+
+const MONTHS: [[&str; 12]; 4] = [
+    [
+        "January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November",
+        "December",
+    ],
+    [
+        "Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August", "September", "Oktober", "November",
+        "Dezember",
+    ],
+    [
+        "janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre",
+        "décembre",
+    ],
+    [
+        "enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre", "octubre", "noviembre",
+        "diciembre",
+    ],
+];
+
+const SHORT_MONTHS: [[&str; 12]; 4] = [
+    ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"],
+    ["Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez"],
+    ["janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.", "déc."],
+    ["ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic"],
+];
+
+/// Monday first
+const WEEKDAYS: [[&str; 7]; 4] = [
+    ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"],
+    ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"],
+    ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+    ["lunes", "martes", "miércoles", "jueves", "viernes", "sábado", "domingo"],
+];
+
+const SHORT_WEEKDAYS: [[&str; 7]; 4] = [
+    ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+    ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+    ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+];
+
+impl Language {
+    /// Language of a locale name such as "de", "fr_FR.UTF-8" or "es-MX"
+    pub fn from_locale(locale: &str) -> Option<Language> {
+        let code = locale.split(['_', '-', '.', '@']).next().unwrap_or_default().to_lowercase();
+        match code.as_str() {
+            "en" | "c" | "posix" => Some(Language::English),
+            "de" => Some(Language::German),
+            "fr" => Some(Language::French),
+            "es" => Some(Language::Spanish),
+            _ => None,
+        }
+    }
+
+    /// The configured locale, else the first locale variable that is set
+    pub fn detect(configured: Option<&str>) -> Language {
+        let from_env = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty());
+        configured.map(str::to_string).or(from_env).and_then(|l| Language::from_locale(&l)).unwrap_or_default()
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn month(self, month: u32) -> &'static str {
+        MONTHS[self.index()][(month.clamp(1, 12) - 1) as usize]
+    }
+
+    pub fn weekday(self, weekday: Weekday) -> &'static str {
+        WEEKDAYS[self.index()][weekday.num_days_from_monday() as usize]
+    }
+
+    /// "Monday, 16 February 2026" and its equivalents
+    pub fn long_date(self, date: NaiveDate) -> String {
+        let (weekday, day, month, year) = (self.weekday(date.weekday()), date.day(), self.month(date.month()), date.year());
+        match self {
+            Language::English => format!("{}, {} {} {}", weekday, day, month, year),
+            Language::German => format!("{}, {}. {} {}", weekday, day, month, year),
+            Language::French => format!("{} {} {} {}", weekday, day, month, year),
+            Language::Spanish => format!("{}, {} de {} de {}", weekday, day, month, year),
+        }
+    }
+
+    /// "Mon 16 Feb 2026" and its equivalents
+    pub fn short_date(self, date: NaiveDate) -> String {
+        let weekday = SHORT_WEEKDAYS[self.index()][date.weekday().num_days_from_monday() as usize];
+        let month = SHORT_MONTHS[self.index()][date.month0() as usize];
+        match self {
+            Language::German => format!("{} {}. {} {}", weekday, date.day(), month, date.year()),
+            _ => format!("{} {:02} {} {}", weekday, date.day(), month, date.year()),
+        }
+    }
+
+    /// "February 2026" and its equivalents
+    pub fn month_year(self, date: NaiveDate) -> String {
+        match self {
+            Language::Spanish => format!("{} de {}", self.month(date.month()), date.year()),
+            _ => format!("{} {}", self.month(date.month()), date.year()),
+        }
+    }
+
+    /// `message` with its `{}` filled in
+    pub fn with(self, message: Message, value: impl std::fmt::Display) -> String {
+        self.text(message).replace("{}", &value.to_string())
+    }
+
+    pub fn text(self, message: Message) -> &'static str {
+        use Language::*;
+        use Message::*;
+        match (message, self) {
+            (SummaryOf, English) => "Summary of {}",
+            (SummaryOf, German) => "Zusammenfassung {}",
+            (SummaryOf, French) => "Résumé de {}",
+            (SummaryOf, Spanish) => "Resumen de {}",
+            (Worklog, English) => "Worklog",
+            (Worklog, German) => "Arbeitsprotokoll",
+            (Worklog, French) => "Journal de travail",
+            (Worklog, Spanish) => "Registro de trabajo",
+            (NoEntries, English) => "No entries.",
+            (NoEntries, German) => "Keine Einträge.",
+            (NoEntries, French) => "Aucune entrée.",
+            (NoEntries, Spanish) => "Sin entradas.",
+            (NotFound, English) => "Not found",
+            (NotFound, German) => "Nicht gefunden",
+            (NotFound, French) => "Introuvable",
+            (NotFound, Spanish) => "No encontrado",
+            (SwitchPane, English) => "switch pane",
+            (SwitchPane, German) => "Bereich wechseln",
+            (SwitchPane, French) => "changer de volet",
+            (SwitchPane, Spanish) => "cambiar panel",
+            (Scroll, English) => "scroll",
+            (Scroll, German) => "blättern",
+            (Scroll, French) => "défiler",
+            (Scroll, Spanish) => "desplazar",
+            (Search, English) => "search",
+            (Search, German) => "suchen",
+            (Search, French) => "rechercher",
+            (Search, Spanish) => "buscar",
+            (LinkedScrolling, English) => "linked scrolling",
+            (LinkedScrolling, German) => "gemeinsam blättern",
+            (LinkedScrolling, French) => "défilement lié",
+            (LinkedScrolling, Spanish) => "desplazamiento conjunto",
+            (On, English) => "on",
+            (On, German) => "an",
+            (On, French) => "activé",
+            (On, Spanish) => "activado",
+            (Off, English) => "off",
+            (Off, German) => "aus",
+            (Off, French) => "désactivé",
+            (Off, Spanish) => "desactivado",
+            (PreviousNextDay, English) => "previous/next day",
+            (PreviousNextDay, German) => "vorheriger/nächster Tag",
+            (PreviousNextDay, French) => "jour précédent/suivant",
+            (PreviousNextDay, Spanish) => "día anterior/siguiente",
+            (Quit, English) => "quit",
+            (Quit, German) => "beenden",
+            (Quit, French) => "quitter",
+            (Quit, Spanish) => "salir",
+            (Words, English) => "words",
+            (Words, German) => "Wörter",
+            (Words, French) => "mots",
+            (Words, Spanish) => "palabras",
+            (WordsIn, English) => "words in",
+            (WordsIn, German) => "Wörter in",
+            (WordsIn, French) => "mots en",
+            (WordsIn, Spanish) => "palabras en",
+            (Saved, English) => "saved",
+            (Saved, German) => "gespeichert",
+            (Saved, French) => "enregistré",
+            (Saved, Spanish) => "guardado",
+            (Unsaved, English) => "unsaved",
+            (Unsaved, German) => "nicht gespeichert",
+            (Unsaved, French) => "non enregistré",
+            (Unsaved, Spanish) => "sin guardar",
+            (SaveFailed, English) => "save failed",
+            (SaveFailed, German) => "Speichern fehlgeschlagen",
+            (SaveFailed, French) => "échec de l'enregistrement",
+            (SaveFailed, Spanish) => "error al guardar",
+            (Save, English) => "save",
+            (Save, German) => "speichern",
+            (Save, French) => "enregistrer",
+            (Save, Spanish) => "guardar",
+            (Typewriter, English) => "typewriter",
+            (Typewriter, German) => "Schreibmaschine",
+            (Typewriter, French) => "machine à écrire",
+            (Typewriter, Spanish) => "máquina de escribir",
+            (NoProject, English) => "(no project)",
+            (NoProject, German) => "(kein Projekt)",
+            (NoProject, French) => "(sans projet)",
+            (NoProject, Spanish) => "(sin proyecto)",
+            (CreatedEntry, English) => "Created journal entry: {}",
+            (CreatedEntry, German) => "Eintrag angelegt: {}",
+            (CreatedEntry, French) => "Entrée créée : {}",
+            (CreatedEntry, Spanish) => "Entrada creada: {}",
+            (AppendedTo, English) => "Appended message to {}",
+            (AppendedTo, German) => "Nachricht angehängt an {}",
+            (AppendedTo, French) => "Message ajouté à {}",
+            (AppendedTo, Spanish) => "Mensaje añadido a {}",
+            (NoEntriesMatch, English) => "No entries match '{}'",
+            (NoEntriesMatch, German) => "Keine Einträge passen zu '{}'",
+            (NoEntriesMatch, French) => "Aucune entrée ne correspond à '{}'",
+            (NoEntriesMatch, Spanish) => "Ninguna entrada coincide con '{}'",
+            (YearAgo, English) => "{} year ago",
+            (YearAgo, German) => "vor {} Jahr",
+            (YearAgo, French) => "il y a {} an",
+            (YearAgo, Spanish) => "hace {} año",
+            (YearsAgo, English) => "{} years ago",
+            (YearsAgo, German) => "vor {} Jahren",
+            (YearsAgo, French) => "il y a {} ans",
+            (YearsAgo, Spanish) => "hace {} años",
+            (ExportInterrupted, English) => "Export interrupted; nothing was written",
+            (ExportInterrupted, German) => "Export abgebrochen; nichts wurde geschrieben",
+            (ExportInterrupted, French) => "Export interrompu ; rien n'a été écrit",
+            (ExportInterrupted, Spanish) => "Exportación interrumpida; no se escribió nada",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_locale() {
+        assert_eq!(Language::from_locale("de_DE.UTF-8"), Some(Language::German));
+        assert_eq!(Language::from_locale("es-MX"), Some(Language::Spanish));
+        assert_eq!(Language::from_locale("C"), Some(Language::English));
+        assert_eq!(Language::from_locale("ja_JP"), None);
+        assert_eq!(Language::detect(Some("fr")), Language::French);
+    }
+
+    #[test]
+    fn test_dates() {
+        let date = NaiveDate::from_ymd_opt(2026, 2, 16).unwrap();
+        assert_eq!(Language::English.long_date(date), "Monday, 16 February 2026");
+        assert_eq!(Language::German.long_date(date), "Montag, 16. Februar 2026");
+        assert_eq!(Language::French.long_date(date), "lundi 16 février 2026");
+        assert_eq!(Language::Spanish.long_date(date), "lunes, 16 de febrero de 2026");
+        assert_eq!(Language::Spanish.month_year(date), "febrero de 2026");
+        assert_eq!(Language::English.short_date(date), "Mon 16 Feb 2026");
+        assert_eq!(Language::German.short_date(date), "Mo 16. Feb 2026");
+        assert_eq!(Language::French.short_date(date), "lun. 16 févr. 2026");
+        assert_eq!(Language::German.with(Message::SummaryOf, "Februar 2026"), "Zusammenfassung Februar 2026");
+        assert_eq!(Language::Spanish.with(Message::YearsAgo, 3), "hace 3 años");
+    }
+}
@@ -0,0 +1,166 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::frontmatter::{self, Frontmatter, Value};
+use crate::{changes, entry_title, find_all_entries, read_entry, record_change};
+
+/// Severity used when `new --incident` is given without `--severity`
+pub const DEFAULT_SEVERITY: &str = "sev3";
+
+/// Sections appended to the body of incident entries
+pub const INCIDENT_SECTIONS: &str = "## Timeline\n\n## Impact\n\n## Resolution\n";
+
+/// An incident entry as shown by `incidents list`
+#[derive(Debug, PartialEq)]
+pub struct Incident {
+    pub id: String,
+    pub path: PathBuf,
+    pub severity: String,
+    pub status: String,
+    pub title: String,
+}
+
+/// Check a severity like `sev1`..`sev5`
+pub fn validate_severity(severity: &str) -> Result<String, String> {
+    let severity = severity.to_lowercase();
+    match severity.strip_prefix("sev").and_then(|n| n.parse::<u8>().ok()) {
+        Some(1..=5) => Ok(severity),
+        _ => Err(format!("Invalid severity '{}': expected sev1 to sev5", severity)),
+    }
+}
+
+/// Frontmatter for a newly opened incident
+pub fn incident_frontmatter(severity: &str, opened: &str) -> Frontmatter {
+    let mut fm = Frontmatter::default();
+    fm.set("kind", Value::Scalar("incident".to_string()));
+    fm.set("severity", Value::Scalar(severity.to_string()));
+    fm.set("status", Value::Scalar("open".to_string()));
+    fm.set("opened", Value::Scalar(opened.to_string()));
+    fm
+}
+
+/// The incident id is the entry filename without its extension
+fn incident_id(path: &Path) -> String {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    name.trim_end_matches(".gz").trim_end_matches(".md").to_string()
+}
+
+/// List all incidents in the journal, optionally only the open ones
+pub fn list_incidents(journal_path: &Path, open_only: bool) -> Result<Vec<Incident>, String> {
+    let mut incidents = Vec::new();
+    for path in find_all_entries(journal_path)? {
+        let Ok(content) = read_entry(&path) else {
+            continue;
+        };
+        let (fm, body) = frontmatter::parse(&content);
+        if fm.get("kind") != Some("incident") {
+            continue;
+        }
+
+        let status = fm.get("status").unwrap_or("open").to_string();
+        if open_only && status != "open" {
+            continue;
+        }
+
+        incidents.push(Incident {
+            id: incident_id(&path),
+            severity: fm.get("severity").unwrap_or(DEFAULT_SEVERITY).to_string(),
+            status,
+            title: entry_title(body).unwrap_or_default(),
+            path,
+        });
+    }
+    Ok(incidents)
+}
+
+/// Mark an incident as closed. `id` may be the full id or a unique part of it.
+pub fn close_incident(journal_path: &Path, id: &str, closed: &str) -> Result<PathBuf, String> {
+    let incidents = list_incidents(journal_path, false)?;
+    let exact: Vec<&Incident> = incidents.iter().filter(|i| i.id == id).collect();
+    let matches: Vec<&Incident> = if exact.is_empty() {
+        incidents.iter().filter(|i| i.id.contains(id)).collect()
+    } else {
+        exact
+    };
+
+    let incident = match matches.as_slice() {
+        [incident] => *incident,
+        [] => return Err(format!("No incident matches '{}'", id)),
+        _ => {
+            let ids: Vec<&str> = matches.iter().map(|i| i.id.as_str()).collect();
+            return Err(format!("'{}' matches several incidents: {}", id, ids.join(", ")));
+        }
+    };
+
+    if incident.status == "closed" {
+        return Err(format!("Incident {} is already closed", incident.id));
+    }
+    if !incident.path.to_string_lossy().ends_with(".md") {
+        return Err(format!("Incident {} is compressed or archived and can't be edited", incident.id));
+    }
+
+    let content = fs::read_to_string(&incident.path)
+        .map_err(|e| format!("Failed to read {}: {}", incident.path.display(), e))?;
+    let before = changes::hash(&incident.path);
+    let (mut fm, body) = frontmatter::parse(&content);
+    fm.set("status", Value::Scalar("closed".to_string()));
+    fm.set("closed", Value::Scalar(closed.to_string()));
+
+    fs::write(&incident.path, format!("{}{}", fm.render(), body))
+        .map_err(|e| format!("Failed to write {}: {}", incident.path.display(), e))?;
+    record_change(journal_path, changes::Action::Edit, &incident.path, None, before);
+    Ok(incident.path.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal_with_incidents() -> tempfile::TempDir {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let month = temp_dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        let open = incident_frontmatter("sev2", "2026-02-17T08:15:03+01:00");
+        fs::write(month.join("17-081503-db-outage.md"), format!("{}# db outage\n", open.render())).unwrap();
+        let mut closed = incident_frontmatter("sev4", "2026-02-18T09:00:00+01:00");
+        closed.set("status", Value::Scalar("closed".to_string()));
+        fs::write(month.join("18-090000-slow-ci.md"), format!("{}# slow ci\n", closed.render())).unwrap();
+        fs::write(month.join("18-100000-notes.md"), "# just notes\n").unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_validate_severity() {
+        assert_eq!(validate_severity("SEV2").unwrap(), "sev2");
+        assert!(validate_severity("sev0").is_err());
+        assert!(validate_severity("high").is_err());
+    }
+
+    #[test]
+    fn test_list_incidents_open_filter() {
+        let temp_dir = journal_with_incidents();
+        assert_eq!(list_incidents(temp_dir.path(), false).unwrap().len(), 2);
+
+        let open = list_incidents(temp_dir.path(), true).unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].id, "17-081503-db-outage");
+        assert_eq!(open[0].severity, "sev2");
+        assert_eq!(open[0].title, "db outage");
+    }
+
+    #[test]
+    fn test_close_incident_by_partial_id() {
+        let temp_dir = journal_with_incidents();
+        let path = close_incident(temp_dir.path(), "db-outage", "2026-02-17T10:00:00+01:00").unwrap();
+
+        let content = fs::read_to_string(path).unwrap();
+        let (fm, body) = frontmatter::parse(&content);
+        assert_eq!(fm.get("status"), Some("closed"));
+        assert_eq!(fm.get("closed"), Some("2026-02-17T10:00:00+01:00"));
+        assert_eq!(body, "# db outage\n");
+        assert!(list_incidents(temp_dir.path(), true).unwrap().is_empty());
+
+        assert!(close_incident(temp_dir.path(), "slow-ci", "now").is_err());
+        assert!(close_incident(temp_dir.path(), "missing", "now").is_err());
+    }
+}
@@ -0,0 +1,33 @@
+//! Ctrl-C during bulk operations (`import`, `export`, `compress`, indexing): the first press
+//! asks the command to stop after the step it's on, so no entry, archive or index is left
+//! half-written; a second press exits straight away.
+
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Exit status after stopping on Ctrl-C, as shells report for SIGINT
+pub const EXIT_CODE: i32 = 130;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static HANDLER: OnceLock<()> = OnceLock::new();
+
+/// Catch Ctrl-C for the rest of the run. Only for commands that check `requested`
+/// between steps; everything else keeps the default of stopping at once.
+pub fn catch() {
+    HANDLER.get_or_init(|| {
+        let installed = ctrlc::set_handler(|| {
+            if INTERRUPTED.swap(true, Ordering::SeqCst) {
+                std::process::exit(EXIT_CODE);
+            }
+            eprintln!("\nInterrupted: finishing the current step (press Ctrl-C again to quit now)");
+        });
+        if let Err(e) = installed {
+            eprintln!("Warning: Ctrl-C will stop immediately ({})", e);
+        }
+    });
+}
+
+/// Whether Ctrl-C has been pressed since `catch`
+pub fn requested() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
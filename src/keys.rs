@@ -0,0 +1,237 @@
+//! Key bindings for the terminal views, configurable under `[tui.keys]`.
+//!
+//! Keys are written vim-style: plain characters (`j`, `G`), sequences (`gg`),
+//! and named or control keys in angle brackets (`<Down>`, `<C-f>`, `<Esc>`).
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    ScrollDown,
+    ScrollUp,
+    PageDown,
+    PageUp,
+    Top,
+    Bottom,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    SwitchPane,
+    LeftPane,
+    RightPane,
+    LinkScroll,
+    PreviousDay,
+    NextDay,
+    Save,
+    ToggleTypewriter,
+}
+
+/// Vim-like defaults; an action listed in the config replaces all of its defaults
+const DEFAULTS: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q", "<Esc>", "<C-q>"]),
+    (Action::ScrollDown, &["j", "<Down>"]),
+    (Action::ScrollUp, &["k", "<Up>"]),
+    (Action::PageDown, &["<C-f>", "<PageDown>", "<Space>"]),
+    (Action::PageUp, &["<C-b>", "<PageUp>"]),
+    (Action::Top, &["gg", "<Home>"]),
+    (Action::Bottom, &["G", "<End>"]),
+    (Action::Search, &["/"]),
+    (Action::NextMatch, &["n"]),
+    (Action::PreviousMatch, &["N"]),
+    (Action::SwitchPane, &["<Tab>", "<C-w>w"]),
+    (Action::LeftPane, &["h", "<Left>"]),
+    (Action::RightPane, &["l", "<Right>"]),
+    (Action::LinkScroll, &["s"]),
+    (Action::PreviousDay, &["["]),
+    (Action::NextDay, &["]"]),
+    (Action::Save, &["<C-s>"]),
+    (Action::ToggleTypewriter, &["<C-t>"]),
+];
+
+/// One key or a list of keys for an action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Bindings {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// `[tui]` section of the config
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TuiConfig {
+    /// Keys per action, e.g. `scroll_down = ["j", "<C-e>"]`
+    pub keys: Option<BTreeMap<Action, Bindings>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Key {
+    code: KeyCode,
+    control: bool,
+}
+
+impl Key {
+    fn from_event(event: &KeyEvent) -> Self {
+        Key { code: event.code, control: event.modifiers.contains(KeyModifiers::CONTROL) }
+    }
+}
+
+fn named_key(name: &str) -> Option<KeyCode> {
+    Some(match name.to_lowercase().as_str() {
+        "esc" => KeyCode::Esc,
+        "enter" | "cr" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "bs" | "backspace" => KeyCode::Backspace,
+        "del" | "delete" => KeyCode::Delete,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "lt" => KeyCode::Char('<'),
+        _ => return None,
+    })
+}
+
+/// Keys of a sequence such as `gg`, `<C-w>w` or `<PageDown>`
+fn parse_sequence(spec: &str) -> Result<Vec<Key>, String> {
+    let invalid = || format!("Invalid key '{}'", spec);
+    let mut keys = Vec::new();
+    let mut rest = spec;
+    while let Some(c) = rest.chars().next() {
+        if c == '<'
+            && let Some(end) = rest.find('>')
+        {
+            let name = &rest[1..end];
+            let key = match name.strip_prefix("C-").or_else(|| name.strip_prefix("c-")) {
+                Some(inner) => {
+                    let mut chars = inner.chars();
+                    let code = match (chars.next(), chars.next()) {
+                        (Some(c), None) => KeyCode::Char(c.to_ascii_lowercase()),
+                        _ => named_key(inner).ok_or_else(invalid)?,
+                    };
+                    Key { code, control: true }
+                }
+                None => Key { code: named_key(name).ok_or_else(invalid)?, control: false },
+            };
+            keys.push(key);
+            rest = &rest[end + 1..];
+        } else {
+            keys.push(Key { code: KeyCode::Char(c), control: false });
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    if keys.is_empty() { Err(invalid()) } else { Ok(keys) }
+}
+
+pub struct Keymap {
+    /// Key sequence, its action and how it was written
+    bindings: Vec<(Vec<Key>, Action, String)>,
+    /// Keys typed so far of a sequence that is not complete yet
+    pending: Vec<Key>,
+}
+
+impl Keymap {
+    /// The defaults, with the actions in `keys` rebound
+    pub fn new(keys: Option<&BTreeMap<Action, Bindings>>) -> Result<Self, String> {
+        let mut specs: BTreeMap<Action, Vec<String>> =
+            DEFAULTS.iter().map(|(action, keys)| (*action, keys.iter().map(|k| k.to_string()).collect())).collect();
+        for (action, bindings) in keys.into_iter().flatten() {
+            let list = match bindings {
+                Bindings::One(key) => vec![key.clone()],
+                Bindings::Many(keys) => keys.clone(),
+            };
+            specs.insert(*action, list);
+        }
+
+        let mut bindings = Vec::new();
+        for (action, list) in specs {
+            for spec in list {
+                bindings.push((parse_sequence(&spec)?, action, spec));
+            }
+        }
+        Ok(Keymap { bindings, pending: Vec::new() })
+    }
+
+    /// Action of a single key press, ignoring sequences
+    pub fn lookup(&self, event: &KeyEvent) -> Option<Action> {
+        let key = Key::from_event(event);
+        self.bindings.iter().find(|(keys, _, _)| keys.as_slice() == [key]).map(|(_, action, _)| *action)
+    }
+
+    /// Feed a key press; returns the action once a whole sequence has been typed
+    pub fn feed(&mut self, event: &KeyEvent) -> Option<Action> {
+        self.pending.push(Key::from_event(event));
+        if let Some((_, action, _)) = self.bindings.iter().find(|(keys, _, _)| *keys == self.pending) {
+            self.pending.clear();
+            return Some(*action);
+        }
+        if self.bindings.iter().any(|(keys, _, _)| keys.starts_with(&self.pending)) {
+            return None;
+        }
+        // Not part of any sequence: start over from this key alone
+        let started_sequence = self.pending.len() > 1;
+        self.pending.clear();
+        if started_sequence { self.feed(event) } else { None }
+    }
+
+    /// How the first key bound to `action` is written, for help lines
+    pub fn label(&self, action: Action) -> &str {
+        self.bindings.iter().find(|(_, a, _)| *a == action).map(|(_, _, spec)| spec.as_str()).unwrap_or("(unbound)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::from(code)
+    }
+
+    #[test]
+    fn test_parse_sequence() {
+        let ctrl_w = Key { code: KeyCode::Char('w'), control: true };
+        let w = Key { code: KeyCode::Char('w'), control: false };
+        assert_eq!(parse_sequence("<C-w>w").unwrap(), vec![ctrl_w, w]);
+        assert_eq!(parse_sequence("<PageDown>").unwrap(), vec![Key { code: KeyCode::PageDown, control: false }]);
+        assert_eq!(parse_sequence("gg").unwrap().len(), 2);
+        assert!(parse_sequence("<Hyper>").is_err());
+        assert!(parse_sequence("").is_err());
+    }
+
+    #[test]
+    fn test_feed_sequences() {
+        let mut keymap = Keymap::new(None).unwrap();
+        assert_eq!(keymap.feed(&key(KeyCode::Char('g'))), None);
+        assert_eq!(keymap.feed(&key(KeyCode::Char('g'))), Some(Action::Top));
+        assert_eq!(keymap.feed(&key(KeyCode::Char('G'))), Some(Action::Bottom));
+        // An abandoned sequence doesn't swallow the next key
+        assert_eq!(keymap.feed(&key(KeyCode::Char('g'))), None);
+        assert_eq!(keymap.feed(&key(KeyCode::Char('j'))), Some(Action::ScrollDown));
+        assert_eq!(keymap.feed(&key(KeyCode::Char('x'))), None);
+    }
+
+    #[test]
+    fn test_config_replaces_defaults() {
+        let keys = BTreeMap::from([
+            (Action::ScrollDown, Bindings::Many(vec!["<C-e>".to_string(), "<Down>".to_string()])),
+            (Action::Quit, Bindings::One("Q".to_string())),
+        ]);
+        let mut keymap = Keymap::new(Some(&keys)).unwrap();
+        assert_eq!(keymap.feed(&key(KeyCode::Char('j'))), None);
+        assert_eq!(keymap.feed(&KeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL)), Some(Action::ScrollDown));
+        assert_eq!(keymap.lookup(&key(KeyCode::Esc)), None);
+        assert_eq!(keymap.label(Action::Quit), "Q");
+
+        let parsed: TuiConfig = toml::from_str("[keys]\nscroll_up = \"k\"\ntop = [\"gg\", \"<Home>\"]\n").unwrap();
+        assert_eq!(parsed.keys.unwrap().len(), 2);
+    }
+}
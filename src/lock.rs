@@ -0,0 +1,85 @@
+//! Single-writer locks on state shared between processes (the search index, a backend's
+//! sync index, import checkpoints), so the daemon, `serve` and CLI commands running at
+//! once can't interleave their read-modify-write cycles. A writer that finds the lock taken
+//! waits its turn for up to `TIMEOUT`.
+//!
+//! Locks are advisory OS file locks on `<file>.lock`, released when the `Lock` is dropped
+//! or the process exits, so a crash never leaves a stale lock behind.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long to wait for another process to finish with the file
+const TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_POLL: Duration = Duration::from_millis(250);
+
+/// Held until dropped
+#[derive(Debug)]
+pub struct Lock {
+    _file: File,
+}
+
+fn lock_path(resource: &Path) -> PathBuf {
+    let mut name = resource.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    resource.with_file_name(name)
+}
+
+/// Take the lock on `resource`, waiting while another process holds it
+pub fn acquire(resource: &Path) -> Result<Lock, String> {
+    acquire_within(resource, TIMEOUT)
+}
+
+fn acquire_within(resource: &Path, timeout: Duration) -> Result<Lock, String> {
+    let path = lock_path(resource);
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+
+    let started = Instant::now();
+    let mut poll = Duration::from_millis(10);
+    let mut announced = false;
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(Lock { _file: file }),
+            Err(TryLockError::WouldBlock) => {}
+            Err(TryLockError::Error(e)) => return Err(format!("Failed to lock {}: {}", path.display(), e)),
+        }
+        if started.elapsed() >= timeout {
+            return Err(format!("{} is still in use by another file-journal process", resource.display()));
+        }
+        if !announced && started.elapsed() >= Duration::from_secs(1) {
+            eprintln!("Waiting for another file-journal process to finish with {}...", resource.display());
+            announced = true;
+        }
+        std::thread::sleep(poll);
+        poll = (poll * 2).min(MAX_POLL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_single_writer() {
+        let dir = TempDir::new().unwrap();
+        let index = dir.path().join(".semantic-index.json");
+
+        let held = acquire(&index).unwrap();
+        assert!(dir.path().join(".semantic-index.json.lock").exists());
+        let err = acquire_within(&index, Duration::from_millis(50)).unwrap_err();
+        assert!(err.contains("in use by another"));
+
+        // A waiting writer gets the lock once it's released
+        let waiter = std::thread::spawn(move || acquire_within(&index, Duration::from_secs(5)).is_ok());
+        std::thread::sleep(Duration::from_millis(30));
+        drop(held);
+        assert!(waiter.join().unwrap());
+    }
+}
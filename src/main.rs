@@ -1,10 +1,94 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand};
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use chrono::{Datelike, Timelike};
 
+mod archive;
+#[cfg(feature = "network")]
+mod ask;
+#[cfg(feature = "calendars")]
+mod calendar;
+mod changes;
+mod checkpoint;
+mod clock;
+#[cfg(feature = "tui")]
+mod compare;
+mod compress;
+mod conflicts;
+mod csv;
+mod cursor;
+mod daemon;
+mod dates;
+#[cfg(feature = "network")]
+mod dav;
+#[cfg(feature = "dictation")]
+mod dictate;
+mod doctor;
+mod editor;
+mod entry;
+mod export;
+mod filename;
+#[cfg(feature = "tui")]
+mod focus;
+mod frontmatter;
+#[cfg(feature = "network")]
+mod github;
+#[cfg(feature = "graphql")]
+mod graphql;
+#[cfg(feature = "network")]
+mod html;
+mod i18n;
+mod incidents;
+mod interrupt;
+#[cfg(feature = "tui")]
+mod keys;
+mod lock;
+mod markdown;
+#[cfg(feature = "network")]
+mod matrix;
+mod merge;
+mod metrics;
+mod onboarding;
+mod openapi;
+mod output;
+mod overview;
+#[cfg(feature = "plugins")]
+mod plugins;
+mod progress;
+mod schema;
+mod seal;
+mod search;
+#[cfg(feature = "semantic")]
+mod semantic;
+mod serve;
+#[cfg(unix)]
+mod service;
+mod signing;
+mod speech;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod ssh;
+mod standup;
+mod storage;
+#[cfg(feature = "network")]
+mod summarize;
+mod tags;
+#[cfg(feature = "network")]
+mod telegram;
+mod theme;
+mod todo;
+mod trash;
+#[cfg(feature = "network")]
+mod tsa;
+mod watch;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "network")]
+mod webhook;
+
 #[derive(Parser)]
 #[command(name = "file-journal")]
 #[command(about = "A CLI for creating journal entries")]
@@ -13,6 +97,18 @@ struct Cli {
     #[arg(short, long)]
     config: Option<PathBuf>,
 
+    /// Config profile (`[profile.<name>]`) to apply over the base settings (falls back to FILE_JOURNAL_PROFILE)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Fail with an error instead of offering first-run setup when no journal is configured
+    #[arg(long)]
+    no_onboarding: bool,
+
+    /// Don't show progress bars or hints about empty results
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -20,15 +116,7 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Create a new journal entry
-    New {
-        /// The title for the journal entry (should end with .md)
-        title: String,
-        /// The note content to store in the file
-        note: Option<String>,
-        /// Override the default journal path
-        #[arg(short, long)]
-        path: Option<PathBuf>,
-    },
+    New(NewArgs),
     /// Initialize a new journal configuration
     Init {
         /// Path to the journal directory
@@ -36,382 +124,5865 @@ enum Commands {
         path: Option<PathBuf>,
     },
     /// Get journal entries for a specific date
-    Get {
+    Get(GetArgs),
+    /// Today's entries (`get` with today's date)
+    Today(ListArgs),
+    /// Yesterday's entries (`get` with yesterday's date)
+    Yesterday(ListArgs),
+    /// Show the most recent entries, newest first
+    Last {
+        /// How many entries to show
+        #[arg(default_value_t = 10)]
+        count: usize,
+        /// Output format: 'paths' (default), 'content', 'json' (paths), 'json-full' (metadata) or 'ndjson' (a line each)
+        #[arg(short, long, default_value = "paths")]
+        format: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// The entry after an entry or day, in date order
+    Next {
+        /// Entry path or day: today, yesterday, YYYY-MM-DD, last monday, ...
+        #[arg(default_value = "today")]
+        selector: String,
+        /// Output format: 'paths' (default), 'content', 'json' (paths), 'json-full' (metadata) or 'ndjson' (a line each)
+        #[arg(short, long, default_value = "paths")]
+        format: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// The entry before an entry or day, in date order
+    Prev {
+        /// Entry path or day: today, yesterday, YYYY-MM-DD, last monday, ...
+        #[arg(default_value = "today")]
+        selector: String,
+        /// Output format: 'paths' (default), 'content', 'json' (paths), 'json-full' (metadata) or 'ndjson' (a line each)
+        #[arg(short, long, default_value = "paths")]
+        format: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Show the most recently modified entries, last edited first
+    Recent {
+        /// How many entries to show
+        #[arg(default_value_t = 10)]
+        count: usize,
+        /// Only entries modified within this long, e.g. 30m, 12h, 3d or 2w
+        #[arg(long)]
+        since: Option<String>,
+        /// Output format: 'paths' (default), 'content', 'json' (paths), 'json-full' (metadata) or 'ndjson' (a line each)
+        #[arg(short, long, default_value = "paths")]
+        format: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Show a random entry from the whole journal or one year
+    Random {
+        /// Only pick from this year
+        #[arg(short, long)]
+        year: Option<i32>,
+        /// Output format: 'content' (default), 'paths', or 'json'
+        #[arg(short, long, default_value = "content")]
+        format: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Entries written on today's day and month in previous years, grouped by year
+    OnThisDay {
+        /// Look back from this day instead of today (YYYY-MM-DD)
+        #[arg(long)]
+        date: Option<String>,
+        /// Output format: 'paths' (default), 'content', 'json' and 'json-full' (grouped by year)
+        /// or 'ndjson' (a line each, with its year)
+        #[arg(short, long, default_value = "paths")]
+        format: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Entries created, edited, moved or deleted through file-journal, from changes.log
+    Changes {
+        /// Only changes from this day on (today, yesterday, YYYY-MM-DD) or this RFC 3339 time
+        #[arg(long)]
+        since: Option<String>,
+        /// Output format: 'text' (default) or 'json'
+        #[arg(short, long, default_value = "text")]
+        format: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Delete entries for a date into the journal's .trash (see `undo`), optionally only those whose title matches
+    Delete {
         /// Day of month (1-31), defaults to today if not specified
         #[arg(short, long)]
         day: Option<u32>,
-        /// Month (1-12), defaults to current month if not specified
+        /// Month (1-12 or a name such as feb), defaults to current month if not specified
+        #[arg(short, long, value_parser = parse_month)]
+        month: Option<u32>,
+        /// Year (e.g., 2024), defaults to current year if not specified
+        #[arg(short, long)]
+        year: Option<i32>,
+        /// Only delete entries whose title or filename contains this text (ignoring case)
+        #[arg(short, long)]
+        title: Option<String>,
+        /// Delete without asking for confirmation
+        #[arg(short, long)]
+        force: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// List copies of entries that sync tools (file-journal, Dropbox, Google Drive) left after a conflict
+    Conflicts {
+        /// Remove copies identical to their entry and rename the rest to <name>-conflict.md
+        #[arg(long)]
+        resolve: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Two-way sync with a copy of the journal on another machine, over SSH
+    Sync {
+        /// The other journal, as [user@]host:path (path relative to the remote home)
+        #[arg(long)]
+        remote: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Take back the last delete, merge, doctor --fix or conflicts --resolve, restoring entries from .trash
+    Undo {
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Combine a day's entries into one, with a section for each, earliest first
+    Merge {
+        /// Day of month (1-31), defaults to today if not specified
         #[arg(short, long)]
+        day: Option<u32>,
+        /// Month (1-12 or a name such as feb), defaults to current month if not specified
+        #[arg(short, long, value_parser = parse_month)]
         month: Option<u32>,
         /// Year (e.g., 2024), defaults to current year if not specified
         #[arg(short, long)]
         year: Option<i32>,
-        /// Get entries for the current week (overrides day/month)
-        #[arg(long, conflicts_with = "day")]
-        week: bool,
+        /// Title of the merged entry
+        #[arg(short, long, default_value = "journal")]
+        title: String,
+        /// Delete the original entries instead of moving them to the journal's .trash folder
+        #[arg(short, long)]
+        force: bool,
         /// Override the default journal path
         #[arg(short, long)]
         path: Option<PathBuf>,
-        /// Output format: 'paths' (default), 'content', or 'json'
-        #[arg(short, long, default_value = "paths")]
+    },
+    /// Move an entry to another day, renaming it and rewriting its `Date:` line
+    #[command(alias = "redate")]
+    Move {
+        /// Entry to move: a path, today, yesterday or YYYY-MM-DD
+        selector: String,
+        /// New day: today, yesterday or YYYY-MM-DD
+        date: String,
+        /// Which of the day's entries to move (1 = earliest) instead of picking one
+        #[arg(short, long)]
+        index: Option<usize>,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Print a single entry, through $PAGER when output is a terminal
+    #[command(alias = "cat")]
+    Show {
+        /// Day or entry: today, yesterday, YYYY-MM-DD or a path
+        #[arg(default_value = "today")]
+        selector: String,
+        /// Which of the day's entries to show (1 = earliest) instead of the most recent
+        #[arg(short, long)]
+        index: Option<usize>,
+        /// Only consider entries whose title or filename contains this text (ignoring case)
+        #[arg(short, long)]
+        title: Option<String>,
+        /// Print straight to the terminal instead of through the pager
+        #[arg(long)]
+        no_pager: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Give an entry a new title, renaming its file and rewriting its `# ` heading
+    Rename {
+        /// Entry to rename: a path, today, yesterday, YYYY-MM-DD, or text in its title or filename
+        entry: String,
+        /// The new title
+        title: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Add a timestamped bullet to today's latest entry, creating one if there is none
+    Append {
+        /// Text of the bullet
+        text: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Seal the journal by recording a merkle root over all entries
+    Seal {
+        #[command(subcommand)]
+        action: Option<SealAction>,
+        /// Also request an RFC 3161 timestamp for the seal (needs the 'network' feature)
+        #[arg(long)]
+        timestamp: bool,
+        /// Override the default journal path
+        #[arg(short, long, global = true)]
+        path: Option<PathBuf>,
+    },
+    /// Sign entries with the key configured in [signing]
+    Sign {
+        /// Entry selector: a file path, 'today', 'yesterday', YYYY-MM-DD, YYYY-MM or YYYY
+        selector: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Verify the detached signatures of entries
+    VerifySignature {
+        /// Entry selector: a file path, 'today', 'yesterday', YYYY-MM-DD, YYYY-MM or YYYY
+        selector: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Track incidents created with `new --incident`
+    Incidents {
+        #[command(subcommand)]
+        action: IncidentsAction,
+        /// Override the default journal path
+        #[arg(short, long, global = true)]
+        path: Option<PathBuf>,
+    },
+    /// List every tag used in the journal with how many entries use it
+    Tags {
+        /// Print a JSON array of {"tag", "count"} objects
+        #[arg(long)]
+        json: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Count entries and words for a day, month, year or range of days
+    Count(CountArgs),
+    /// List open `- [ ]` tasks from entries, with the file and line they're on
+    Todo {
+        /// List completed `- [x]` tasks instead
+        #[arg(long)]
+        done: bool,
+        /// Only entries on or after this day (YYYY-MM-DD)
+        #[arg(long)]
+        from: Option<String>,
+        /// Only entries on or before this day (YYYY-MM-DD)
+        #[arg(long)]
+        to: Option<String>,
+        /// Print a JSON array of {"path", "line", "date", "text", "done"} objects
+        #[arg(long)]
+        json: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Manage entry tags
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+        /// Override the default journal path
+        #[arg(short, long, global = true)]
+        path: Option<PathBuf>,
+    },
+    /// Print a standup message from yesterday's and today's entries
+    Standup {
+        /// Format for pasting into Slack (mrkdwn bold headings and bullets)
+        #[arg(long)]
+        slack: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+        #[command(flatten)]
+        output: output::OutputArgs,
+    },
+    /// Export entries, e.g. as a client-ready worklog
+    Export(ExportArgs),
+    /// The JSON Schema of `export --format json`
+    Schema {
+        #[command(subcommand)]
+        action: SchemaAction,
+    },
+    /// Work with `clock: HH:MM-HH:MM project [label]` lines in entries
+    Clock {
+        #[command(subcommand)]
+        action: ClockAction,
+    },
+    /// Append today's GitHub activity (pushes, reviews, comments) to today's entry
+    #[cfg(feature = "network")]
+    GithubActivity {
+        /// GitHub login, or 'me' for the owner of the configured token
+        #[arg(long, default_value = "me")]
+        user: String,
+        /// Print the summary instead of appending it
+        #[arg(long)]
+        dry_run: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Post today's entries to a Slack or Discord webhook
+    #[cfg(feature = "network")]
+    PostSummary {
+        /// Destination: 'slack' or 'discord' (webhook URL from [webhooks] in config)
+        #[arg(long)]
+        to: String,
+        /// Only send the first section of each entry
+        #[arg(long)]
+        first_section: bool,
+        /// Print the JSON payload instead of posting it
+        #[arg(long)]
+        dry_run: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Answer a question from your entries with a language model, citing the entries used
+    #[cfg(feature = "network")]
+    Ask {
+        question: String,
+        /// Number of entries to give the model as context
+        #[arg(short = 'n', long, default_value_t = ask::CONTEXT_ENTRIES)]
+        limit: usize,
+        /// Print the prompt instead of sending it
+        #[arg(long)]
+        dry_run: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Summarize a month's entries with a language model and save the summary as a new entry
+    #[cfg(feature = "network")]
+    Summarize {
+        /// Month to summarize (1-12 or a name such as feb)
+        #[arg(long, value_parser = parse_month)]
+        month: u32,
+        /// Year of that month (defaults to the current year)
+        #[arg(long)]
+        year: Option<i32>,
+        /// Print the prompt instead of sending it
+        #[arg(long)]
+        dry_run: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Record from the microphone until Enter, transcribe it and save the transcript as an entry
+    #[cfg(feature = "dictation")]
+    Dictate {
+        /// Entry title (default: "dictation")
+        #[arg(short, long)]
+        title: Option<String>,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Serve the journal API as JSON-RPC over a unix socket for editors and other front-ends
+    #[cfg(unix)]
+    Daemon {
+        /// Socket to listen on (default: $XDG_RUNTIME_DIR/file-journal.sock)
+        #[arg(long)]
+        socket: Option<PathBuf>,
+        /// Also serve Prometheus /metrics over HTTP on this address (e.g. 127.0.0.1:9464)
+        #[arg(long)]
+        metrics: Option<String>,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Serve the journal API over HTTP (POST /rpc) with Prometheus /metrics
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8787")]
+        listen: String,
+        /// Print the OpenAPI description of the HTTP API and exit
+        #[arg(long)]
+        openapi: bool,
+        /// Add a new token to the config and show a QR code of the server's network address
+        /// with it, for setting up a phone in one scan
+        #[arg(long, conflicts_with = "openapi")]
+        pair: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Install and inspect background services (systemd user units / launchd agents)
+    #[cfg(unix)]
+    Service {
+        #[command(subcommand)]
+        action: ServiceAction,
+    },
+    /// Listen in a private Matrix room and append its messages to the entry of the day each was sent
+    #[cfg(feature = "network")]
+    MatrixBridge {
+        /// Process pending messages and exit instead of listening forever
+        #[arg(long)]
+        once: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Long-poll a Telegram bot and append incoming messages to the entry of the day each was sent
+    #[cfg(feature = "network")]
+    TelegramBridge {
+        /// Process pending messages and exit instead of polling forever
+        #[arg(long)]
+        once: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Open an entry in $VISUAL/$EDITOR (or `editor` from the config), picking one if several match
+    Edit {
+        /// Entry to edit: a path, today, yesterday or YYYY-MM-DD
+        #[arg(default_value = "today")]
+        selector: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Print a path for scripts: the journal root (default), this year's or month's folder,
+    /// or today's latest entry; exits 1 without output if it doesn't exist
+    Path {
+        /// Today's most recent entry
+        #[arg(long, conflicts_with_all = ["month", "year", "root"])]
+        today: bool,
+        /// This month's folder
+        #[arg(long, conflicts_with_all = ["year", "root"])]
+        month: bool,
+        /// This year's folder
+        #[arg(long, conflicts_with = "root")]
+        year: bool,
+        /// The journal folder itself
+        #[arg(long)]
+        root: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Show how many entries each year and month holds
+    Ls {
+        /// Indent months under their year
+        #[arg(long)]
+        tree: bool,
+        /// Print the counts as JSON
+        #[arg(long, conflicts_with = "tree")]
+        json: bool,
+        /// Also count words
+        #[arg(short, long)]
+        words: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Open the journal folder in the file manager, or print its path
+    Open {
+        /// Print the folder instead, e.g. for `cd $(file-journal open --print)`
+        #[arg(long)]
+        print: bool,
+        /// The current month's folder instead of the journal root (created if missing)
+        #[arg(short, long)]
+        month: bool,
+        /// Open the folder in the editor instead of the file manager
+        #[arg(short, long, conflicts_with = "print")]
+        editor: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Create entries from another app's export: a CSV file, or a file a WASM plugin converts (--plugin)
+    #[command(args_conflicts_with_subcommands = true)]
+    Import {
+        #[command(subcommand)]
+        source: Option<ImportSource>,
+        /// Plugin module (`<name>.wasm` in the plugins folder)
+        #[cfg(feature = "wasm")]
+        #[arg(long)]
+        plugin: Option<String>,
+        /// File to import (default: stdin)
+        #[cfg(feature = "wasm")]
+        file: Option<PathBuf>,
+        /// Override the default journal path
+        #[cfg(feature = "wasm")]
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Subcommands defined by plugin scripts or `file-journal-<name>` programs on PATH
+    #[command(external_subcommand)]
+    External(Vec<String>),
+    /// Show two days side by side, by default this day a year ago next to today
+    #[cfg(feature = "tui")]
+    Compare {
+        /// First day: today, yesterday or YYYY-MM-DD (default: a year before the second)
+        first: Option<String>,
+        /// Second day (default: today)
+        second: Option<String>,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Write in a distraction-free, full-screen editor with word count, autosave and a session timer
+    #[cfg(feature = "tui")]
+    Focus {
+        /// Entry to write in: a path, today, yesterday or YYYY-MM-DD (today's is created if missing)
+        #[arg(default_value = "today")]
+        selector: String,
+        /// Start a new entry with this title instead
+        #[arg(long, conflicts_with = "selector")]
+        title: Option<String>,
+        /// Keep the line being written in the middle of the screen
+        #[arg(long)]
+        typewriter: bool,
+        /// Seconds between automatic saves
+        #[arg(long, default_value_t = 5)]
+        autosave: u64,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Read entries out loud through a text-to-speech command ([speech] command, or say/espeak)
+    ReadAloud {
+        /// Entries to read: a path, today, yesterday, YYYY-MM-DD, YYYY-MM or YYYY
+        selector: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Print lines matching a query across the journal, grep-style (path:line:text)
+    Search {
+        query: String,
+        /// Match regardless of case
+        #[arg(short, long)]
+        ignore_case: bool,
+        /// Treat the query as a regular expression
+        #[arg(long)]
+        regex: bool,
+        /// Only search this year (e.g., 2024)
+        #[arg(short, long)]
+        year: Option<i32>,
+        /// Only search this month (1-12 or a name such as feb), of the current year unless --year is given
+        #[arg(short, long, value_parser = parse_month)]
+        month: Option<u32>,
+        /// Output format: 'lines' (path:line:text, default) or 'grep' (path:line:column:text)
+        #[arg(short, long, default_value = "lines")]
         format: String,
+        /// Lines of context to print after each match
+        #[arg(short = 'A', long, value_name = "NUM")]
+        after_context: Option<usize>,
+        /// Lines of context to print before each match
+        #[arg(short = 'B', long, value_name = "NUM")]
+        before_context: Option<usize>,
+        /// Lines of context to print before and after each match
+        #[arg(short = 'C', long, value_name = "NUM")]
+        context: Option<usize>,
+        /// Rank entries by meaning with a local embedding model instead of matching lines
+        #[cfg(feature = "semantic")]
+        #[arg(long, conflicts_with_all = ["ignore_case", "regex", "year", "month", "format", "after_context", "before_context", "context"])]
+        semantic: bool,
+        /// Maximum number of entries to print (default: all, or 10 with --semantic)
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Report misnamed entries, stray folders, wrong Date: lines and empty folders
+    Doctor {
+        /// Correct what can be corrected safely (Date: lines and empty folders)
+        #[arg(long)]
+        fix: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Check entries' frontmatter against the `[schemas.<kind>]` of their kind
+    Lint {
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Bundle a year's folder into YYYY.tar.gz (or YYYY.zip) and verify it
+    Archive {
+        /// Year to archive (e.g., 2021)
+        #[arg(short, long)]
+        year: i32,
+        /// Write YYYY.zip, which `get` and the other commands can still read, instead of a tarball
+        #[arg(long)]
+        zip: bool,
+        /// Delete the year's folder once the archive is verified
+        #[arg(long)]
+        remove: bool,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Gzip entries from years before the given year
+    Compress {
+        /// Compress entries from years strictly before this one (e.g., 2023)
+        #[arg(long)]
+        before: i32,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
     },
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-struct Config {
-    /// Default journal path
-    pub default_path: Option<PathBuf>,
+#[derive(Args)]
+struct NewArgs {
+    /// The title for the journal entry (should end with .md)
+    title: String,
+    /// The note content to store in the file
+    note: Option<String>,
+    /// Create the entry from the incident template (status: open)
+    #[arg(long)]
+    incident: bool,
+    /// Incident severity (sev1-sev5), defaults to sev3
+    #[arg(long, requires = "incident")]
+    severity: Option<String>,
+    /// Project the entry belongs to (stored in frontmatter, used by worklog exports)
+    #[arg(long)]
+    project: Option<String>,
+    /// Tag the entry (repeatable, stored in frontmatter)
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+    /// Kind of entry, stored as `kind:` and checked against `[schemas.<kind>]` in the config
+    #[arg(long, conflicts_with = "incident")]
+    kind: Option<String>,
+    /// Set a frontmatter field (repeatable); fields the schema lists take comma-separated values
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    fields: Vec<String>,
+    /// Override the default journal path
+    #[arg(short, long)]
+    path: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    /// Export format: 'worklog' (Markdown grouped by project) or 'json' (every field, see `schema print`)
+    #[arg(short, long, default_value = "worklog")]
+    format: String,
+    /// Version of the JSON export format to write, for consumers coded against an older one
+    #[arg(long, default_value_t = export::LATEST_JSON_SCHEMA)]
+    schema_version: u32,
+    /// Export through `<name>.wasm` from the plugins folder instead of a built-in format
+    #[cfg(feature = "wasm")]
+    #[arg(long, conflicts_with = "format")]
+    plugin: Option<String>,
+    /// Only include entries for this project
+    #[arg(long)]
+    project: Option<String>,
+    /// Month (1-12 or a name such as feb), defaults to the current month unless only --year is given
+    #[arg(short, long, value_parser = parse_month)]
+    month: Option<u32>,
+    /// Year (e.g., 2024), defaults to current year if not specified
+    #[arg(short, long)]
+    year: Option<i32>,
+    /// Override the default journal path
+    #[arg(short, long)]
+    path: Option<PathBuf>,
+    #[command(flatten)]
+    output: output::OutputArgs,
+}
+
+#[derive(Args)]
+struct CountArgs {
+    /// Day of month (1-31); without any date options, counts today
+    #[arg(short, long)]
+    day: Option<u32>,
+    /// Month (1-12 or a name such as feb), defaults to current month if not specified
+    #[arg(short, long, value_parser = parse_month)]
+    month: Option<u32>,
+    /// Year (e.g., 2024), defaults to current year if not specified
+    #[arg(short, long)]
+    year: Option<i32>,
+    /// First day of a range (YYYY-MM-DD)
+    #[arg(long, conflicts_with_all = ["day", "month", "year"])]
+    from: Option<String>,
+    /// Last day of a range (YYYY-MM-DD)
+    #[arg(long, conflicts_with_all = ["day", "month", "year"])]
+    to: Option<String>,
+    /// Print {"entries", "words", "average_words"} as JSON
+    #[arg(long)]
+    json: bool,
+    /// Override the default journal path
+    #[arg(short, long)]
+    path: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct GetArgs {
+    /// Day of month (1-31), defaults to today if not specified
+    #[arg(short, long)]
+    day: Option<u32>,
+    /// Month (1-12 or a name such as feb), defaults to current month if not specified
+    #[arg(short, long, value_parser = parse_month)]
+    month: Option<u32>,
+    /// Year (e.g., 2024), defaults to current year if not specified
+    #[arg(short, long)]
+    year: Option<i32>,
+    /// Day as an expression instead of numbers: yesterday, last monday, 3 days ago, 2026-02-17
+    #[arg(long, conflicts_with_all = ["day", "month", "year"])]
+    date: Option<String>,
+    /// Get entries for a week, Monday to Sunday: the current one, or an ISO week such as 2026-W07
+    /// (overrides day/month)
+    #[arg(long, value_name = "YYYY-Www", num_args = 0..=1, default_missing_value = "", conflicts_with_all = ["day", "date"])]
+    week: Option<String>,
+    /// Weeks after (or, negative, before) the one --week picks, e.g. -1 for last week
+    #[arg(long, allow_negative_numbers = true, conflicts_with_all = ["day", "date"])]
+    week_offset: Option<i32>,
+    /// Quarter (1-4) of --year, or of this year; JSON output groups it by month
+    #[arg(long, conflicts_with_all = ["day", "month", "date", "week", "week_offset"])]
+    quarter: Option<u32>,
+    /// Half (1-2) of --year, or of this year; JSON output groups it by month
+    #[arg(long, conflicts_with_all = ["day", "month", "date", "week", "week_offset", "quarter"])]
+    half: Option<u32>,
+    /// First day of a range (YYYY-MM-DD), which may span months and years
+    #[arg(long, conflicts_with_all = ["day", "month", "year", "date", "week", "week_offset", "quarter", "half"])]
+    from: Option<String>,
+    /// Last day of a range (YYYY-MM-DD)
+    #[arg(long, conflicts_with_all = ["day", "month", "year", "date", "week", "week_offset", "quarter", "half"])]
+    to: Option<String>,
+    /// Rolling window of days ending today, e.g. 10d or 2w
+    #[arg(
+        long,
+        conflicts_with_all = ["day", "month", "year", "date", "week", "week_offset", "quarter", "half", "from", "to"]
+    )]
+    last: Option<String>,
+    /// When nothing matches, get the entries of the closest day before or after that has some
+    #[arg(long)]
+    nearest: bool,
+    /// Print how the query would run (folders read, estimated cost) instead of its entries
+    #[arg(long, conflicts_with = "pick")]
+    explain: bool,
+    /// Only entries created or modified since the last run with the same --cursor
+    #[arg(
+        long,
+        requires = "cursor",
+        conflicts_with_all = [
+            "day", "month", "year", "date", "week", "week_offset", "quarter", "half", "from", "to", "last", "nearest",
+            "pick"
+        ]
+    )]
+    since_last_run: bool,
+    /// Name of the consumer (e.g. backup-script) whose last run --since-last-run continues from
+    #[arg(long, requires = "since_last_run")]
+    cursor: Option<String>,
+    #[command(flatten)]
+    list: ListArgs,
+}
+
+/// What `get`, `today` and `yesterday` share once the day is known
+#[derive(Args)]
+struct ListArgs {
+    /// Only include entries whose frontmatter author matches
+    #[arg(long)]
+    author: Option<String>,
+    /// Only include entries with this tag (repeatable; entries need every tag given)
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+    /// Only include entries whose filename title contains this (ignoring case)
+    #[arg(long, conflicts_with = "title_regex")]
+    title: Option<String>,
+    /// Only include entries whose filename title matches this regular expression
+    #[arg(long)]
+    title_regex: Option<String>,
+    /// Override the default journal path
+    #[arg(short, long)]
+    path: Option<PathBuf>,
+    /// Output format: 'paths' (default), 'content', 'json' (paths), 'json-full' (metadata) or 'ndjson' (a line
+    /// each, written as the entries are found unless --reverse, --offset or --limit need the whole list first)
+    #[arg(short, long, default_value = "paths")]
+    format: String,
+    /// Newest first instead of oldest first
+    #[arg(long)]
+    reverse: bool,
+    /// Skip this many entries, for paging with --limit
+    #[arg(long, default_value_t = 0)]
+    offset: usize,
+    /// Show at most this many entries
+    #[arg(long)]
+    limit: Option<usize>,
+    /// Choose one of the entries from a fuzzy-searchable list and print its path
+    #[arg(long)]
+    pick: bool,
+    /// Open the picked entry in the editor instead of printing its path
+    #[arg(long, requires = "pick")]
+    edit: bool,
+    #[command(flatten)]
+    output: output::OutputArgs,
+}
+
+#[derive(Subcommand)]
+enum ImportSource {
+    /// Rows of a CSV file with a header row, one entry per row
+    Csv(CsvImportArgs),
+}
+
+#[derive(Args)]
+struct CsvImportArgs {
+    /// CSV file to import (default: stdin)
+    file: Option<PathBuf>,
+    /// Entry field and the column it comes from, e.g. date=Date (repeatable); fields are date
+    /// (required), time, title, body and tags
+    #[arg(long = "map", value_name = "FIELD=COLUMN", required = true)]
+    maps: Vec<String>,
+    /// Format of the date column, e.g. %d/%m/%Y (default: detected from its values)
+    #[arg(long)]
+    date_format: Option<String>,
+    /// Field separator
+    #[arg(long, default_value_t = ',')]
+    delimiter: char,
+    /// Show how the first rows would be imported and stop, without writing anything
+    #[arg(long)]
+    preview: bool,
+    /// Override the default journal path
+    #[arg(short, long)]
+    path: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum SchemaAction {
+    /// Print the JSON Schema of an export format version
+    Print {
+        /// Format version (defaults to the latest)
+        #[arg(long)]
+        version: Option<u32>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SealAction {
+    /// Check the journal against the most recent seal
+    Verify,
+}
+
+#[derive(Subcommand)]
+enum IncidentsAction {
+    /// List incidents
+    List {
+        /// Only show incidents that are still open
+        #[arg(long)]
+        open: bool,
+    },
+    /// Close an incident by id (the entry filename without .md, or a unique part of it)
+    Close {
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum TagAction {
+    /// Propose tags for entries and add the ones you accept to their frontmatter
+    Suggest {
+        /// Entries to tag: a path, today, yesterday, YYYY-MM-DD, YYYY-MM or YYYY
+        selector: String,
+        /// Most tags to propose per entry
+        #[arg(short = 'n', long, default_value_t = 5)]
+        limit: usize,
+        /// Ask the [summarize] language model instead of extracting keywords
+        #[cfg(feature = "network")]
+        #[arg(long)]
+        llm: bool,
+        /// Accept every suggestion without asking
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[cfg(unix)]
+#[derive(Subcommand)]
+enum ServiceAction {
+    /// Write the unit for a long-running mode, then enable and start it
+    Install {
+        /// Mode to run: daemon, serve, or telegram-bridge/matrix-bridge with --features network
+        mode: String,
+        /// Print the unit instead of installing it
+        #[arg(long)]
+        dry_run: bool,
+        /// Journal path passed to the service (default: from config)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Show whether each mode's service is installed and running
+    Status {
+        /// Only show this mode
+        mode: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ClockAction {
+    /// Summarize billable hours per project/label
+    Invoice {
+        /// Month (1-12 or a name such as feb), defaults to current month if not specified
+        #[arg(short, long, value_parser = parse_month)]
+        month: Option<u32>,
+        /// Year (e.g., 2024), defaults to current year if not specified
+        #[arg(short, long)]
+        year: Option<i32>,
+        /// Hourly rate used to compute amounts
+        #[arg(long)]
+        rate: Option<f64>,
+        /// Output format: 'md' (default) or 'csv'
+        #[arg(short, long, default_value = "md")]
+        format: String,
+        /// Override the default journal path
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct Config {
+    /// Default journal path
+    pub default_path: Option<PathBuf>,
+    /// Remote journal (`dav://user@host/path`), synced into a local cache
+    #[cfg(feature = "network")]
+    pub journal_url: Option<String>,
+    /// WebDAV password for `journal_url` (falls back to FILE_JOURNAL_DAV_PASSWORD)
+    #[cfg(feature = "network")]
+    pub dav_password: Option<String>,
+    /// Entry storage: "fs" (default) or "sqlite"
+    pub storage: Option<String>,
+    /// Database file used when `storage = "sqlite"`
+    pub sqlite_path: Option<PathBuf>,
+    /// RFC 3161 timestamp authority used by `seal --timestamp`
+    pub tsa_url: Option<String>,
+    /// Secondary calendar shown next to entry dates: "hebrew", "hijri" or "persian"
+    #[cfg(feature = "calendars")]
+    pub secondary_calendar: Option<String>,
+    /// Calendar that names the YYYY/MM folders and entry days: "gregorian" (default), "persian" or "hijri"
+    #[cfg(feature = "calendars")]
+    pub layout_calendar: Option<String>,
+    /// Language for dates and messages, e.g. "de" or "fr_FR" (falls back to LC_ALL, LC_MESSAGES and LANG)
+    pub locale: Option<String>,
+    /// Entry filename pattern using {day}, {time} (HHMMSS) and {title}; default "{day}-{time}-{title}"
+    pub filename_format: Option<String>,
+    /// Colours for `get --format content` and the terminal views
+    pub theme: Option<theme::ThemeConfig>,
+    /// Key bindings for `focus` and `compare`
+    #[cfg(feature = "tui")]
+    pub tui: Option<keys::TuiConfig>,
+    /// Editor command for `edit` (falls back to $VISUAL, then $EDITOR)
+    pub editor: Option<String>,
+    /// Author stamped into new entries' frontmatter and filenames
+    pub author: Option<String>,
+    /// Section headings used by `standup`
+    pub standup: Option<standup::StandupConfig>,
+    /// GitHub token for `github-activity` (falls back to GITHUB_TOKEN)
+    pub github_token: Option<String>,
+    /// Webhook URLs used by `post-summary`
+    #[cfg(feature = "network")]
+    pub webhooks: Option<webhook::WebhookConfig>,
+    /// Bot token and allowed chats for `telegram-bridge`
+    #[cfg(feature = "network")]
+    pub telegram: Option<telegram::TelegramConfig>,
+    /// Homeserver, token and room for `matrix-bridge`
+    #[cfg(feature = "network")]
+    pub matrix: Option<matrix::MatrixConfig>,
+    /// Language model provider and prompt for `summarize`
+    #[cfg(feature = "network")]
+    pub summarize: Option<summarize::SummarizeConfig>,
+    /// API tokens and rate limits for `serve`
+    pub serve: Option<serve::ServeConfig>,
+    /// Transcription command for `dictate`
+    #[cfg(feature = "dictation")]
+    pub dictation: Option<dictate::DictationConfig>,
+    /// Text-to-speech command for `read-aloud`
+    pub speech: Option<speech::SpeechConfig>,
+    /// Folder of Rhai plugin scripts and WASM modules (default ~/.config/file-journal/plugins)
+    #[cfg(any(feature = "plugins", feature = "wasm"))]
+    pub plugins_dir: Option<PathBuf>,
+    /// Keys used by `sign` and `verify-signature`
+    pub signing: Option<signing::SigningConfig>,
+    /// Required and typed frontmatter fields by entry kind, checked by `lint` and `new`
+    pub schemas: Option<schema::Schemas>,
+}
+
+/// Config profile chosen with `--profile` or FILE_JOURNAL_PROFILE
+static PROFILE: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+/// Config file first-run onboarding writes; unset with `--no-onboarding`
+static ONBOARDING_CONFIG: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+
+/// Entry filename pattern from the config, set before the command runs
+static FILENAME_FORMAT: std::sync::OnceLock<filename::FilenameFormat> = std::sync::OnceLock::new();
+
+/// Storage backend checked out into a local working copy during this run
+static CHECKOUT: std::sync::OnceLock<(storage::Backend, PathBuf)> = std::sync::OnceLock::new();
+
+/// Secondary calendar from the config, set before the command runs
+#[cfg(feature = "calendars")]
+static SECONDARY_CALENDAR: std::sync::OnceLock<calendar::Calendar> = std::sync::OnceLock::new();
+
+/// Non-Gregorian calendar naming the journal's folders and files, set alongside `SECONDARY_CALENDAR`
+#[cfg(feature = "calendars")]
+static LAYOUT_CALENDAR: std::sync::OnceLock<calendar::Calendar> = std::sync::OnceLock::new();
+
+#[cfg(all(test, feature = "calendars"))]
+thread_local! {
+    /// Layout calendar for a test's own thread, leaving other tests Gregorian
+    static TEST_LAYOUT_CALENDAR: std::cell::Cell<Option<calendar::Calendar>> = const { std::cell::Cell::new(None) };
+}
+
+/// The calendar naming the journal's folders and files, unless that's the Gregorian one
+#[cfg(feature = "calendars")]
+fn layout_calendar() -> Option<calendar::Calendar> {
+    #[cfg(test)]
+    if let Some(calendar) = TEST_LAYOUT_CALENDAR.get() {
+        return Some(calendar);
+    }
+    LAYOUT_CALENDAR.get().copied()
+}
+
+fn main() {
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if let Some(profile) = cli.profile.clone().or_else(|| env::var("FILE_JOURNAL_PROFILE").ok()).filter(|p| !p.is_empty()) {
+        let _ = PROFILE.set(profile);
+    }
+    progress::set_quiet(cli.quiet);
+    if !cli.no_onboarding {
+        let config_file = cli.config.clone().or_else(|| {
+            let dir = env::var_os("FILE_JOURNAL_CONFIG_DIR").map(PathBuf::from);
+            dir.or_else(|| dirs::home_dir().map(|home| home.join(".config").join("file-journal"))).map(|dir| dir.join("config.toml"))
+        });
+        if let Some(config_file) = config_file {
+            let _ = ONBOARDING_CONFIG.set(config_file);
+        }
+    }
+    if cli.command.uses_journal() {
+        let config = load_config(cli.config.clone());
+        configure(config.as_ref());
+        if !explicit_path(&matches) {
+            check_out_backend(config.as_ref());
+        }
+    }
+
+    match cli.command {
+        Commands::New(args) => create_entry(args, cli.config),
+        Commands::Init { path } => init_config(path),
+        Commands::Get(args) => get_entries(args, cli.config, cli.quiet),
+        Commands::Today(list) => get_entries_on(chrono::Local::now().date_naive(), list, cli.config, cli.quiet),
+        Commands::Yesterday(list) => match chrono::Local::now().date_naive().pred_opt() {
+            Some(yesterday) => get_entries_on(yesterday, list, cli.config, cli.quiet),
+            None => exit(1),
+        },
+        Commands::Last { count, format, path } => last_entries(count, format, path, cli.config),
+        Commands::Next { selector, format, path } => step_entry(&selector, true, format, path, cli.config),
+        Commands::Prev { selector, format, path } => step_entry(&selector, false, format, path, cli.config),
+        Commands::Recent { count, since, format, path } => recent_entries(count, since, format, path, cli.config),
+        Commands::Random { year, format, path } => random_entry(year, format, path, cli.config),
+        Commands::OnThisDay { date, format, path } => on_this_day(date, format, path, cli.config),
+        Commands::Changes { since, format, path } => list_changes(since, format, path, cli.config),
+        Commands::Path { today, month, year, root: _, path } => print_path(today, month, year, path, cli.config),
+        Commands::Ls { tree, json, words, path } => list_journal(tree, json, words, path, cli.config),
+        Commands::Open { print, month, editor, path } => open_journal(print, month, editor, path, cli.config),
+        Commands::External(args) => run_external_command(args, cli.config),
+        Commands::Delete { day, month, year, title, force, path } => {
+            delete_entries(day, month, year, title, force, path, cli.config)
+        }
+        Commands::Move { selector, date, index, path } => move_entry(selector, date, index, path, cli.config),
+        Commands::Conflicts { resolve, path } => list_conflicts(resolve, path, cli.config),
+        Commands::Sync { remote, path } => sync_remote(remote, path, cli.config),
+        Commands::Undo { path } => undo(path, cli.config),
+        Commands::Merge { day, month, year, title, force, path } => {
+            merge_entries(day, month, year, title, force, path, cli.config)
+        }
+        Commands::Show { selector, index, title, no_pager, path } => {
+            show_entry(selector, index, title, no_pager, path, cli.config)
+        }
+        Commands::Rename { entry, title, path } => rename_entry(entry, title, path, cli.config),
+        Commands::Append { text, path } => append_bullet(text, path, cli.config),
+        Commands::Seal { action, timestamp, path } => seal_journal(action, timestamp, path, cli.config),
+        Commands::Sign { selector, path } => sign_entries(selector, path, cli.config),
+        Commands::VerifySignature { selector, path } => verify_signatures(selector, path, cli.config),
+        Commands::Incidents { action, path } => manage_incidents(action, path, cli.config),
+        Commands::Tags { json, path } => list_tags(json, path, cli.config),
+        Commands::Count(args) => count_entries(args, cli.config),
+        Commands::Todo { done, from, to, json, path } => list_todos(done, from, to, json, path, cli.config),
+        Commands::Tag { action, path } => manage_tags(action, path, cli.config),
+        Commands::Standup { slack, path, output } => print_standup(slack, path, output, cli.config),
+        Commands::Export(args) => export_entries(args, cli.config),
+        Commands::Schema { action: SchemaAction::Print { version } } => print_schema(version),
+        Commands::Import { source: Some(ImportSource::Csv(args)), .. } => import_csv(args, cli.config),
+        #[cfg(feature = "wasm")]
+        Commands::Import { source: None, plugin: Some(plugin), file, path } => {
+            import_entries(plugin, file, path, cli.config)
+        }
+        Commands::Import { .. } => {
+            eprintln!("Error: Say what to import, e.g. `import csv data.csv --map date=Date`");
+            exit(1);
+        }
+        Commands::Clock { action } => match action {
+            ClockAction::Invoice { month, year, rate, format, path } => {
+                clock_invoice(month, year, rate, format, path, cli.config)
+            }
+        },
+        #[cfg(feature = "network")]
+        Commands::GithubActivity { user, dry_run, path } => {
+            import_github_activity(user, dry_run, path, cli.config)
+        }
+        #[cfg(feature = "network")]
+        Commands::PostSummary { to, first_section, dry_run, path } => {
+            post_summary(to, first_section, dry_run, path, cli.config)
+        }
+        #[cfg(feature = "network")]
+        Commands::Ask { question, limit, dry_run, path } => ask_journal(question, limit, dry_run, path, cli.config),
+        #[cfg(feature = "network")]
+        Commands::Summarize { month, year, dry_run, path } => summarize_month(month, year, dry_run, path, cli.config),
+        #[cfg(feature = "dictation")]
+        Commands::Dictate { title, path } => dictate_entry(title, path, cli.config),
+        #[cfg(unix)]
+        Commands::Daemon { socket, metrics, path } => run_daemon(socket, metrics, path, cli.config),
+        Commands::Serve { listen, openapi, pair, path } => serve_journal(listen, openapi, pair, path, cli.config),
+        #[cfg(unix)]
+        Commands::Service { action } => manage_service(action, cli.config),
+        #[cfg(feature = "network")]
+        Commands::MatrixBridge { once, path } => matrix_bridge(once, path, cli.config),
+        #[cfg(feature = "network")]
+        Commands::TelegramBridge { once, path } => telegram_bridge(once, path, cli.config),
+        Commands::Edit { selector, path } => edit_entry(selector, path, cli.config),
+        #[cfg(feature = "tui")]
+        Commands::Compare { first, second, path } => compare_days(first, second, path, cli.config),
+        #[cfg(feature = "tui")]
+        Commands::Focus { selector, title, typewriter, autosave, path } => {
+            focus_entry(selector, title, typewriter, autosave, path, cli.config)
+        }
+        Commands::ReadAloud { selector, path } => read_aloud(selector, path, cli.config),
+        #[cfg(feature = "semantic")]
+        Commands::Search { query, semantic: true, limit, path, .. } => {
+            semantic_search(query, limit.unwrap_or(10), path, cli.config)
+        }
+        Commands::Search { query, ignore_case, regex, year, month, format, after_context, before_context, context, limit, path, .. } => {
+            let query = SearchQuery {
+                query,
+                ignore_case,
+                regex,
+                year,
+                month,
+                format,
+                before: before_context.or(context).unwrap_or(0),
+                after: after_context.or(context).unwrap_or(0),
+                limit,
+            };
+            search_entries(query, path, cli.config)
+        }
+        Commands::Compress { before, path } => compress_entries(before, path, cli.config),
+        Commands::Archive { year, zip, remove, path } => archive_year(year, zip, remove, path, cli.config),
+        Commands::Doctor { fix, path } => doctor(fix, path, cli.config),
+        Commands::Lint { path } => lint(path, cli.config),
+    }
+
+    upload_checkout();
+}
+
+/// Leave with `code`, first storing what the command changed in a checked-out backend's working
+/// copy. Commands use this rather than `std::process::exit`, which skips the end of `main`.
+fn exit(code: i32) -> ! {
+    upload_checkout();
+    std::process::exit(code)
+}
+
+/// Store changes made in a checked-out backend's working copy (once per run)
+fn upload_checkout() {
+    static UPLOADED: std::sync::Once = std::sync::Once::new();
+    UPLOADED.call_once(|| {
+        if let Some((backend, cache)) = CHECKOUT.get() {
+            match storage::sync(backend.as_ref(), &storage::FsStorage::new(cache)) {
+                Ok(report) if !report.pushed.is_empty() || !report.pushed_deletions.is_empty() => {
+                    let changed = report.pushed.len() + report.pushed_deletions.len();
+                    println!("Stored {} changed entries in the journal backend", changed)
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Warning: Failed to upload changes ({}); they stay in {}", e, cache.display()),
+            }
+        }
+    });
+}
+
+impl Commands {
+    /// Whether the command works on the journal, so a configured backend is checked out for it
+    fn uses_journal(&self) -> bool {
+        match self {
+            Commands::Init { .. } | Commands::Schema { .. } => false,
+            #[cfg(unix)]
+            Commands::Service { .. } => false,
+            _ => true,
+        }
+    }
+}
+
+/// Whether the (sub)command was given `--path`, which bypasses a configured backend
+fn explicit_path(matches: &ArgMatches) -> bool {
+    match matches.subcommand() {
+        Some((_, sub)) => matches!(sub.try_get_one::<PathBuf>("path"), Ok(Some(_))) || explicit_path(sub),
+        None => false,
+    }
+}
+
+/// The config file `load_config` reads, if any: `--config`, then `config.toml` in
+/// FILE_JOURNAL_CONFIG_DIR, then `./.file-journal.toml`, then `~/.config/file-journal/config.toml`,
+/// whichever exists first
+fn config_file(config_path: Option<&Path>) -> Option<PathBuf> {
+    let candidates = [
+        config_path.map(Path::to_path_buf),
+        env::var_os("FILE_JOURNAL_CONFIG_DIR").map(|dir| PathBuf::from(dir).join("config.toml")),
+        env::current_dir().ok().map(|dir| dir.join(".file-journal.toml")),
+        dirs::home_dir().map(|home| home.join(".config").join("file-journal").join("config.toml")),
+    ];
+    candidates.into_iter().flatten().find(|path| path.exists())
+}
+
+/// Parse a config file, applying `profile`'s settings over the base ones. The outer error is a
+/// profile the file doesn't define, rather than quietly using the base settings; the inner one
+/// is a file that isn't a valid config.
+fn parse_config(content: &str, file: &Path, profile: Option<&str>) -> Result<Result<Config, String>, String> {
+    let invalid = |e: toml::de::Error| format!("Failed to parse config at {}: {}", file.display(), e);
+    let mut table: toml::Table = match toml::from_str(content) {
+        Ok(table) => table,
+        Err(e) => return Ok(Err(invalid(e))),
+    };
+    let profiles = table.remove("profile");
+    if let Some(name) = profile {
+        match profiles.as_ref().and_then(|p| p.get(name)).and_then(toml::Value::as_table) {
+            Some(profile) => merge_tables(&mut table, profile.clone()),
+            None => return Err(format!("No [profile.{}] section in {}", name, file.display())),
+        }
+    }
+    Ok(toml::Value::Table(table).try_into().map_err(invalid))
+}
+
+/// Overlay `overrides` onto `base`, merging nested tables key by key
+fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(value)) => merge_tables(base, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// The config for this run; the file is only read and parsed the first time
+fn load_config(config_path: Option<PathBuf>) -> Option<Config> {
+    static LOADED: std::sync::OnceLock<(Option<PathBuf>, Option<Config>)> = std::sync::OnceLock::new();
+    if let Some((path, config)) = LOADED.get()
+        && *path == config_path
+    {
+        return config.clone();
+    }
+    let config = read_config(config_path.clone()).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        exit(1);
+    });
+    let _ = LOADED.set((config_path, config.clone()));
+    config
+}
+
+/// The config file found by `config_file`, read and parsed. One that can't be read or parsed is
+/// warned about and left out, so commands still run on defaults and `--path`; only a missing
+/// profile is an error
+fn read_config(config_path: Option<PathBuf>) -> Result<Option<Config>, String> {
+    // If set, FILE_JOURNAL_CONFIG_DIR must point to a directory containing config.toml.
+    if let Some(config_dir) = env::var_os("FILE_JOURNAL_CONFIG_DIR") {
+        let config_file_path = PathBuf::from(config_dir).join("config.toml");
+        if !config_file_path.exists() && !config_path.as_ref().is_some_and(|path| path.exists()) {
+            eprintln!("Warning: FILE_JOURNAL_CONFIG_DIR is set but {} not found", config_file_path.display());
+        }
+    }
+
+    let Some(path) = config_file(config_path.as_deref()) else {
+        return Ok(None);
+    };
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Warning: Failed to read config at {}: {}", path.display(), e);
+            return Ok(None);
+        }
+    };
+    match parse_config(&content, &path, PROFILE.get().map(String::as_str))? {
+        Ok(config) => Ok(Some(config)),
+        Err(e) => {
+            eprintln!("Warning: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Remember the config's entry filename format and calendars for this run, so every
+/// command reads entry dates the same way
+fn configure(config: Option<&Config>) {
+    let Some(config) = config else {
+        return;
+    };
+    #[cfg(feature = "calendars")]
+    configure_calendars(config);
+    if let Some(pattern) = config.filename_format.as_deref() {
+        match filename::FilenameFormat::new(pattern) {
+            Ok(format) => {
+                let _ = FILENAME_FORMAT.set(format);
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+    }
+}
+
+fn get_journal_path(explicit_path: Option<PathBuf>, config: Option<&Config>) -> Option<PathBuf> {
+    // Explicit path takes priority
+    if let Some(path) = explicit_path {
+        return Some(path);
+    }
+
+    // Then a non-filesystem backend, worked on through a local cache
+    if let Some(cfg) = config
+        && let Some(cache) = backend_cache(cfg)
+    {
+        return Some(cache);
+    }
+
+    // Then config default_path
+    if let Some(cfg) = config
+        && let Some(path) = &cfg.default_path
+    {
+        return Some(path.clone());
+    }
+
+    None
+}
+
+/// Remember the configured secondary and layout calendars for this run
+#[cfg(feature = "calendars")]
+fn configure_calendars(config: &Config) {
+    let secondary = config.secondary_calendar.as_deref().map(calendar::Calendar::from_name).transpose();
+    let layout = match config.layout_calendar.as_deref() {
+        None | Some("gregorian") => Ok(None),
+        Some(name) => calendar::Calendar::for_layout(name).map(Some),
+    };
+    match (secondary, layout) {
+        (Ok(secondary), Ok(layout)) => {
+            if let Some(calendar) = secondary {
+                let _ = SECONDARY_CALENDAR.set(calendar);
+            }
+            if let Some(calendar) = layout {
+                let _ = LAYOUT_CALENDAR.set(calendar);
+            }
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Local working copy of the backend selected in config, unless that's the plain filesystem
+fn backend_cache(cfg: &Config) -> Option<PathBuf> {
+    #[cfg(feature = "network")]
+    if let Some(url) = &cfg.journal_url {
+        return dav::parse_url(url).ok().map(|(base, _)| storage::cache_dir(&base));
+    }
+    match cfg.storage.as_deref() {
+        Some("sqlite") => cfg.sqlite_path.as_ref().map(|path| storage::cache_dir(&path.to_string_lossy())),
+        _ => None,
+    }
+}
+
+/// Backend selected in config other than the plain filesystem
+fn open_backend(cfg: &Config) -> Result<Option<storage::Backend>, String> {
+    #[cfg(feature = "network")]
+    if let Some(url) = &cfg.journal_url {
+        let (base, user) = dav::parse_url(url)?;
+        let password = cfg.dav_password.clone().or_else(|| env::var("FILE_JOURNAL_DAV_PASSWORD").ok());
+        return Ok(Some(Box::new(dav::DavStorage::new(base, user, password))));
+    }
+
+    match cfg.storage.as_deref() {
+        None | Some("fs") => Ok(None),
+        #[cfg(feature = "sqlite")]
+        Some("sqlite") => {
+            let path = cfg.sqlite_path.clone().ok_or("storage = \"sqlite\" needs sqlite_path")?;
+            Ok(Some(Box::new(sqlite::SqliteStorage::open(&path)?)))
+        }
+        #[cfg(not(feature = "sqlite"))]
+        Some("sqlite") => Err("storage = \"sqlite\" requires building with --features sqlite".to_string()),
+        Some(other) => Err(format!("Unknown storage '{}' (expected fs or sqlite)", other)),
+    }
+}
+
+/// Sync the configured backend into its local cache before a command works on it; `exit` and
+/// the end of `main` upload the changes
+fn check_out_backend(config: Option<&Config>) {
+    let Some(cfg) = config else {
+        return;
+    };
+    let (backend, cache) = match open_backend(cfg) {
+        Ok(Some(backend)) => (backend, backend_cache(cfg).expect("an open backend has a cache")),
+        Ok(None) => return,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    if let Err(e) = fs::create_dir_all(&cache) {
+        eprintln!("Error: Failed to create cache {}: {}", cache.display(), e);
+        exit(1);
+    }
+
+    match storage::sync(backend.as_ref(), &storage::FsStorage::new(&cache)) {
+        Ok(report) => {
+            for path in report.conflicts {
+                eprintln!("Warning: {} changed locally and in the backend; backend copy saved as *-conflict.md", path);
+            }
+        }
+        Err(e) => eprintln!("Warning: Could not sync journal backend ({}); using cached copy", e),
+    }
+
+    let _ = CHECKOUT.set((backend, cache));
+}
+
+fn sync_remote(remote: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let local = storage::FsStorage::new(&journal_path);
+    let result = ssh::SshStorage::new(&remote).and_then(|ssh| {
+        let before: std::collections::BTreeSet<String> =
+            storage::Storage::list(&local)?.into_iter().map(|(path, _)| path).collect();
+        let report = storage::sync_with_index(&ssh, &local, &storage::index_name(&remote))?;
+        Ok((before, report))
+    });
+    let (before, report) = match result {
+        Ok(synced) => synced,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    for pulled in &report.pulled {
+        let action = if before.contains(pulled) { changes::Action::Edit } else { changes::Action::Create };
+        record_change(&journal_path, action, &journal_path.join(pulled), None, None);
+    }
+    for deleted in &report.pulled_deletions {
+        record_change(&journal_path, changes::Action::Delete, &journal_path.join(deleted), None, None);
+    }
+    println!("Pulled {} and pushed {} entries", report.pulled.len(), report.pushed.len());
+    if !report.pulled_deletions.is_empty() || !report.pushed_deletions.is_empty() {
+        println!(
+            "Deleted {} entries here (`file-journal undo` restores them) and {} on the remote",
+            report.pulled_deletions.len(),
+            report.pushed_deletions.len()
+        );
+    }
+    for conflict in &report.conflicts {
+        let copy = storage::conflict_path(conflict);
+        record_change(&journal_path, changes::Action::Create, &journal_path.join(&copy), None, None);
+        eprintln!("Conflict: {} changed on both sides; kept this copy, saved the other as {}", conflict, copy);
+    }
+
+    // Copies left by Dropbox or Drive on either machine come down like any entry
+    let remaining = match conflicts::find(&journal_path) {
+        Ok(found) => resolve_conflicts(&journal_path, found),
+        Err(e) => {
+            eprintln!("Warning: {}", e);
+            Vec::new()
+        }
+    };
+    if !remaining.is_empty() {
+        let copies = if remaining.len() == 1 { "copy needs" } else { "copies need" };
+        println!("{} conflict {} merging by hand (see `file-journal conflicts`)", remaining.len(), copies);
+        exit(1);
+    }
+}
+
+fn list_conflicts(resolve: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let found = match conflicts::find(&journal_path) {
+        Ok(found) => found,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    if found.is_empty() {
+        println!("No conflict copies in {}", journal_path.display());
+        return;
+    }
+
+    if resolve {
+        let remaining = resolve_conflicts(&journal_path, found);
+        if !remaining.is_empty() {
+            let copies = if remaining.len() == 1 { "copy needs" } else { "copies need" };
+            println!("{} conflict {} merging by hand:", remaining.len(), copies);
+            for copy in &remaining {
+                println!("  {}", copy.strip_prefix(&journal_path).unwrap_or(copy).display());
+            }
+            exit(1);
+        }
+        return;
+    }
+
+    for conflict in &found {
+        let shown = conflict.copy.strip_prefix(&journal_path).unwrap_or(&conflict.copy);
+        let original = conflict.original.file_name().unwrap_or_default().to_string_lossy();
+        let identical = if conflict.identical() { " (identical)" } else { "" };
+        println!("{}: {} copy of {}{}", shown.display(), conflict.provider.name(), original, identical);
+    }
+    exit(1);
+}
+
+/// Drop conflict copies identical to their entry (into the trash, so `undo` brings them back)
+/// and give the rest file-journal's `<name>-conflict.md` name. Returns the copies that
+/// still need merging.
+fn resolve_conflicts(journal_path: &Path, found: Vec<conflicts::Conflict>) -> Vec<PathBuf> {
+    let mut operation = trash::Operation::new("conflicts --resolve");
+    let mut remaining = Vec::new();
+    for conflict in found {
+        let shown = conflict.copy.strip_prefix(journal_path).unwrap_or(&conflict.copy).to_path_buf();
+        let original = conflict.original.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let before = changes::hash(&conflict.copy);
+        if conflict.identical() {
+            match operation.trash(journal_path, &conflict.copy) {
+                Ok(_) => {
+                    record_change(journal_path, changes::Action::Delete, &conflict.copy, None, before);
+                    println!("Removed {} (same as {})", shown.display(), original);
+                    continue;
+                }
+                Err(e) => eprintln!("Warning: {}", e),
+            }
+        } else if let Some(target) = conflict.normalized() {
+            match fs::rename(&conflict.copy, &target) {
+                Ok(()) => {
+                    record_change(journal_path, changes::Action::Move, &target, Some(&conflict.copy), before);
+                    let renamed = target.file_name().unwrap_or_default().to_string_lossy();
+                    println!("Renamed {} copy {} -> {}", conflict.provider.name(), shown.display(), renamed);
+                    remaining.push(target);
+                    continue;
+                }
+                Err(e) => eprintln!("Warning: Failed to rename {}: {}", conflict.copy.display(), e),
+            }
+        }
+        remaining.push(conflict.copy);
+    }
+    record_undo(journal_path, operation);
+    remaining
+}
+
+/// Resolve the journal path from `--path` or config, exiting with a hint if neither is set
+fn require_journal_path(path: Option<PathBuf>, config: Option<&Config>) -> PathBuf {
+    match get_journal_path(path, config) {
+        Some(p) => {
+            #[cfg(feature = "calendars")]
+            {
+                let folders = gregorian_folders(&p);
+                if !folders.is_empty() {
+                    eprintln!(
+                        "Warning: Gregorian year folders ({}) are read in layout_calendar and give their entries \
+                         the wrong dates; move them into folders named in that calendar or remove layout_calendar",
+                        folders.join(", ")
+                    );
+                }
+            }
+            p
+        }
+        None => {
+            // Nothing configured at all: a first run, so offer to set things up
+            if config.is_none()
+                && let Some(config_file) = ONBOARDING_CONFIG.get()
+                && onboarding::interactive()
+            {
+                let suggested = dirs::document_dir().or_else(dirs::home_dir).unwrap_or_default().join("journal");
+                match onboarding::run(&mut std::io::stdin().lock(), config_file, &suggested) {
+                    Ok(Some(p)) => return p,
+                    Ok(None) => exit(1),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        exit(1);
+                    }
+                }
+            }
+            eprintln!("Error: No journal path specified. Use --path or set up config with 'init'");
+            exit(1);
+        }
+    }
+}
+
+/// The `YYYY/MM` folder `date` is filed under (named by the layout calendar), created if missing
+fn resolve_target_dir(journal_path: &Path, date: chrono::NaiveDate) -> Result<PathBuf, String> {
+    let (year, month, _) = layout_date(date);
+
+    // Build path: journal_path/YYYY/MM
+    let target_dir = journal_path.join(year.to_string()).join(format!("{:02}", month));
+
+    // Create directories if they don't exist
+    if !target_dir.exists() {
+        fs::create_dir_all(&target_dir)
+            .map_err(|e| format!("Failed to create directories: {}", e))?;
+    }
+
+    // Validate the structure
+    let month_folder = target_dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or("Failed to get month folder name")?;
+
+    if !is_valid_month(month_folder) {
+        return Err(format!("Invalid month folder: {}", month_folder));
+    }
+
+    let year_folder = target_dir
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|name| name.to_str())
+        .ok_or("Failed to get year folder name")?;
+
+    if !is_valid_year(year_folder) {
+        return Err(format!("Invalid year folder: {}", year_folder));
+    }
+
+    Ok(target_dir)
+}
+
+fn create_entry(args: NewArgs, config_path: Option<PathBuf>) {
+    let NewArgs { title, note, incident, severity, project, tags, kind, fields, path } = args;
+
+    // Check if title ends with .md
+    if !title.ends_with(".md") {
+        eprintln!("Error: Title must end with .md");
+        exit(1);
+    }
+
+    let severity = match severity.as_deref().map(incidents::validate_severity) {
+        Some(Ok(severity)) => severity,
+        Some(Err(e)) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+        None => incidents::DEFAULT_SEVERITY.to_string(),
+    };
+
+    // Load config
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let author = config.as_ref().and_then(|c| c.author.clone());
+
+    // Determine journal path
+    let journal_path = match get_journal_path(path, config.as_ref()) {
+        Some(p) => p,
+        None => {
+            // Fall back to current directory
+            env::current_dir().expect("Failed to get current directory")
+        }
+    };
+
+    let mut entry_frontmatter = if incident {
+        incidents::incident_frontmatter(&severity, &chrono::Local::now().to_rfc3339())
+    } else {
+        frontmatter::Frontmatter::default()
+    };
+    if let Some(project) = project {
+        entry_frontmatter.set("project", frontmatter::Value::Scalar(project));
+    }
+    let tags = normalize_tags(&tags);
+    if !tags.is_empty() {
+        entry_frontmatter.set("tags", frontmatter::Value::List(tags));
+    }
+    if let Some(kind) = kind {
+        entry_frontmatter.set("kind", frontmatter::Value::Scalar(kind));
+    }
+    let schemas = config.as_ref().and_then(|c| c.schemas.clone()).unwrap_or_default();
+    let kind = entry_frontmatter.get("kind").map(str::to_string);
+    let schema = kind.as_ref().and_then(|kind| schemas.get(kind));
+    for field in &fields {
+        let Some((key, value)) = field.split_once('=') else {
+            eprintln!("Error: Invalid --set '{}': expected key=value", field);
+            exit(1);
+        };
+        let key = key.trim();
+        let value = if schema.is_some_and(|schema| schema::is_list(schema, key)) {
+            let items = value.split(',').map(str::trim).filter(|item| !item.is_empty());
+            frontmatter::Value::List(items.map(String::from).collect())
+        } else {
+            frontmatter::Value::Scalar(value.trim().to_string())
+        };
+        entry_frontmatter.set(key, value);
+    }
+    if let (Some(kind), Some(schema)) = (&kind, schema) {
+        match schema::validate(schema, &entry_frontmatter) {
+            Ok(problems) if problems.is_empty() => {}
+            Ok(problems) => {
+                eprintln!("Error: The entry doesn't fit [schemas.{}] (add fields with --set key=value):", kind);
+                for problem in problems {
+                    eprintln!("  {}", problem);
+                }
+                exit(1);
+            }
+            Err(e) => {
+                eprintln!("Error: [schemas.{}]: {}", kind, e);
+                exit(1);
+            }
+        }
+    }
+
+    let mut note_content = note.unwrap_or_default();
+    if incident {
+        if !note_content.is_empty() {
+            note_content.push_str("\n\n");
+        }
+        note_content.push_str(incidents::INCIDENT_SECTIONS.trim_end());
+    }
+
+    #[cfg(feature = "plugins")]
+    let plugins = load_plugins(config.as_ref(), &journal_path);
+    match write_entry(journal_path.clone(), title.trim_end_matches(".md"), &note_content, entry_frontmatter, author) {
+        Ok(filepath) => {
+            #[cfg(feature = "plugins")]
+            {
+                let before = changes::hash(&filepath);
+                match plugins.on_new(&filepath) {
+                    Ok(()) => record_change(&journal_path, changes::Action::Edit, &filepath, None, before),
+                    Err(e) => eprintln!("Warning: {}", e),
+                }
+            }
+            println!("{}", language.with(i18n::Message::CreatedEntry, filepath.display()))
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Write a new entry stamped with the current time into the journal's YYYY/MM folder
+fn write_entry(
+    journal_path: PathBuf,
+    title: &str,
+    note: &str,
+    entry_frontmatter: frontmatter::Frontmatter,
+    author: Option<String>,
+) -> Result<PathBuf, String> {
+    write_entry_at(journal_path, chrono::Local::now().naive_local(), title, note, entry_frontmatter, author)
+}
+
+/// Create an entry as if written at `at`, in that day's folder
+fn write_entry_at(
+    journal_path: PathBuf,
+    at: chrono::NaiveDateTime,
+    title: &str,
+    note: &str,
+    mut entry_frontmatter: frontmatter::Frontmatter,
+    author: Option<String>,
+) -> Result<PathBuf, String> {
+    // Resolve target directory (create year/month folders if needed)
+    let target_dir = resolve_target_dir(&journal_path, at.date())?;
+
+    let year = at.year();
+    let month = at.month();
+    let day = at.day();
+    let (_, _, layout_day) = layout_date(at.date());
+    let hour = at.hour();
+    let minute = at.minute();
+    let second = at.second();
+
+    // Create filename: dd-HHMMSS-<title>.md for easy sorting, unless `filename_format` says otherwise
+    let safe_title = sanitize_title(title);
+    // In shared journals the author goes before the title so teammates never collide
+    let slug = match &author {
+        Some(author) => format!("{}-{}", sanitize_title(author), safe_title),
+        None => safe_title,
+    };
+    let time = format!("{:02}{:02}{:02}", hour, minute, second);
+    let filename = filename_format().render(layout_day, &time, &slug);
+    let filepath = target_dir.join(&filename);
+
+    // Check if file already exists
+    if filepath.exists() {
+        return Err(format!("File '{}' already exists", filename));
+    }
+
+    if let Some(author) = author {
+        entry_frontmatter.set("author", frontmatter::Value::Scalar(author));
+    }
+
+    // Create the file with a template (DD-MM-YYYY format, then the secondary calendar's date if configured)
+    let secondary = secondary_date(at.date()).map(|d| format!(" ({})", d)).unwrap_or_default();
+    let template = format!(
+        "{}# {}\n\nDate: {:02}-{:02}-{}{}\n\n{}\n",
+        entry_frontmatter.render(),
+        title,
+        day,
+        month,
+        year,
+        secondary,
+        note
+    );
+
+    fs::write(&filepath, template).map_err(|e| format!("Failed to create file: {}", e))?;
+    record_change(&journal_path, changes::Action::Create, &filepath, None, None);
+    Ok(filepath)
+}
+
+/// Log a change to the journal's change feed; failing to log doesn't undo the change itself
+fn record_change(journal_path: &Path, action: changes::Action, path: &Path, from: Option<&Path>, before: Option<String>) {
+    if let Err(e) = changes::record(journal_path, action, path, from, before) {
+        eprintln!("Warning: {}", e);
+    }
+}
+
+/// Year, month and day naming `date`'s folders and file: Gregorian unless `layout_calendar` is set
+fn layout_date(date: chrono::NaiveDate) -> (i32, u32, u32) {
+    #[cfg(feature = "calendars")]
+    if let Some(ymd) = layout_calendar().and_then(|calendar| calendar.ymd(date)) {
+        return ymd;
+    }
+    (date.year(), date.month(), date.day())
+}
+
+/// Year folders and `YYYY.zip` archives that would put their entries more than a year in the
+/// future when read in the layout calendar: Gregorian ones left from before it was set
+#[cfg(feature = "calendars")]
+fn gregorian_folders(journal_path: &Path) -> Vec<String> {
+    let (Some(calendar), Ok(items)) = (layout_calendar(), fs::read_dir(journal_path)) else {
+        return Vec::new();
+    };
+    let next_year = chrono::Local::now().year() + 1;
+    let mut folders: Vec<String> = items
+        .flatten()
+        .filter_map(|item| item.file_name().to_str().map(str::to_string))
+        .filter(|name| {
+            let year = name.strip_suffix(".zip").unwrap_or(name);
+            let starts = year.parse().ok().and_then(|year| calendar.to_gregorian(year, 1, 1));
+            is_valid_year(year) && starts.is_none_or(|start| start.year() > next_year)
+        })
+        .collect();
+    folders.sort();
+    folders
+}
+
+/// Gregorian date of a folder year, month and file day; the inverse of `layout_date`
+fn date_from_layout(year: i32, month: u32, day: u32) -> Option<chrono::NaiveDate> {
+    #[cfg(feature = "calendars")]
+    if let Some(calendar) = layout_calendar() {
+        return calendar.to_gregorian(year, month, day);
+    }
+    chrono::NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// `date` in the configured secondary calendar, shown after Gregorian dates
+fn secondary_date(date: chrono::NaiveDate) -> Option<String> {
+    #[cfg(feature = "calendars")]
+    return SECONDARY_CALENDAR.get().and_then(|calendar| calendar.format(date));
+    #[cfg(not(feature = "calendars"))]
+    {
+        let _ = date;
+        None
+    }
+}
+
+/// The most recent plain-text entry written on `date`, if any (compressed entries can't be appended to)
+fn latest_entry_on(journal_path: &Path, date: chrono::NaiveDate) -> Result<Option<PathBuf>, String> {
+    Ok(find_entries(journal_path, Some(date.day()), Some(date.month()), Some(date.year()))?
+        .into_iter()
+        .rfind(|entry| entry.to_string_lossy().ends_with(".md")))
+}
+
+/// Append text to today's most recent entry as a new paragraph, creating a "journal" entry if there is none
+#[cfg_attr(not(feature = "network"), allow(dead_code))]
+fn append_to_today(journal_path: &Path, text: &str, author: Option<String>) -> Result<PathBuf, String> {
+    append_to_day(journal_path, chrono::Local::now().naive_local(), &format!("\n{}", text), author)
+}
+
+/// Append lines to the most recent entry on `at`'s day, creating a "journal" entry written at
+/// `at` if there is none. Text is added on a new line; include a leading blank line to start a
+/// new block.
+fn append_to_day(
+    journal_path: &Path,
+    at: chrono::NaiveDateTime,
+    text: &str,
+    author: Option<String>,
+) -> Result<PathBuf, String> {
+    let (entry, before) = match latest_entry_on(journal_path, at.date())? {
+        Some(entry) => {
+            let before = changes::hash(&entry);
+            (entry, before)
+        }
+        None => {
+            let entry_frontmatter = frontmatter::Frontmatter::default();
+            (write_entry_at(journal_path.to_path_buf(), at, "journal", "", entry_frontmatter, author)?, None)
+        }
+    };
+
+    // Only add to the end of the file, so existing content is never rewritten
+    let content = fs::read(&entry).map_err(|e| format!("Failed to read {}: {}", entry.display(), e))?;
+    let mut addition = String::new();
+    if content.last().is_some_and(|b| *b != b'\n') {
+        addition.push('\n');
+    }
+    addition.push_str(text.trim_end());
+    addition.push('\n');
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(&entry)
+        .map_err(|e| format!("Failed to open {}: {}", entry.display(), e))?;
+    std::io::Write::write_all(&mut file, addition.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", entry.display(), e))?;
+    // A new entry was already logged as created
+    if before.is_some() {
+        record_change(journal_path, changes::Action::Edit, &entry, None, before);
+    }
+
+    Ok(entry)
+}
+
+/// `- HH:MM text`, with further lines indented to stay inside the bullet
+fn timestamped_bullet(time: chrono::NaiveTime, text: &str) -> String {
+    let mut lines = text.trim().lines();
+    let mut bullet = format!("- {} {}", time.format("%H:%M"), lines.next().unwrap_or_default());
+    for line in lines {
+        bullet.push('\n');
+        if !line.trim().is_empty() {
+            bullet.push_str("  ");
+            bullet.push_str(line);
+        }
+    }
+    bullet
+}
+
+/// Local time of a chat message's unix timestamp, for the Telegram and Matrix bridges
+#[cfg_attr(not(feature = "network"), allow(dead_code))]
+fn message_time(timestamp: i64) -> chrono::DateTime<chrono::Local> {
+    use chrono::TimeZone;
+    chrono::Local.timestamp_opt(timestamp, 0).single().unwrap_or_else(chrono::Local::now)
+}
+
+/// Journal line for a chat message: `- HH:MM text` on one line, with an image link when a
+/// photo was saved
+#[cfg_attr(not(feature = "network"), allow(dead_code))]
+fn message_line(time: &chrono::DateTime<chrono::Local>, text: Option<&str>, image: Option<&str>) -> String {
+    let text = text.unwrap_or_default().replace('\n', " ");
+    let mut line = format!("- {}", time.format("%H:%M"));
+    if !text.is_empty() {
+        line.push(' ');
+        line.push_str(text.trim());
+    }
+    if let Some(image) = image {
+        line.push_str(&format!(" ![{}]({})", if text.is_empty() { "photo" } else { "" }, image));
+    }
+    line
+}
+
+fn append_bullet(text: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    if text.trim().is_empty() {
+        eprintln!("Error: Nothing to append");
+        exit(1);
+    }
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+    let author = config.and_then(|c| c.author);
+
+    let now = chrono::Local::now().naive_local();
+    match append_to_day(&journal_path, now, &timestamped_bullet(now.time(), &text), author) {
+        Ok(entry) => println!("Appended to {}", entry.display()),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Whether an entry's title or filename contains `needle`, ignoring case
+fn title_matches(entry: &Path, needle: &str) -> bool {
+    let needle = needle.to_lowercase();
+    let filename = entry.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let title = read_entry(entry).ok().and_then(|content| entry_title(frontmatter::parse(&content).1));
+    filename.contains(&needle) || title.is_some_and(|t| t.to_lowercase().contains(&needle))
+}
+
+fn delete_entries(
+    day: Option<u32>,
+    month: Option<u32>,
+    year: Option<i32>,
+    title: Option<String>,
+    force: bool,
+    path: Option<PathBuf>,
+    config_path: Option<PathBuf>,
+) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let mut entries = match find_entries(&journal_path, day, month, year) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    if let Some(title) = &title {
+        entries.retain(|entry| title_matches(entry, title));
+    }
+    if entries.is_empty() {
+        eprintln!("Error: No matching entries");
+        exit(1);
+    }
+    if let Some(archived) = entries.iter().find(|entry| archive::split_zip_path(entry).is_some()) {
+        eprintln!("Error: {} is inside an archive and can't be deleted", archived.display());
+        exit(1);
+    }
+
+    for entry in &entries {
+        println!("{}", entry.display());
+    }
+    if !force {
+        print!("Delete {} {}? [y/N] ", entries.len(), if entries.len() == 1 { "entry" } else { "entries" });
+        let _ = std::io::Write::flush(&mut std::io::stdout());
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).is_err() || !answer.trim().eq_ignore_ascii_case("y") {
+            println!("Nothing deleted");
+            return;
+        }
+    }
+
+    let mut operation = trash::Operation::new("delete");
+    for entry in &entries {
+        let before = changes::hash(entry);
+        if let Err(e) = operation.trash(&journal_path, entry) {
+            record_undo(&journal_path, operation);
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+        record_change(&journal_path, changes::Action::Delete, entry, None, before);
+    }
+    record_undo(&journal_path, operation);
+    let (noun, pronoun) = if entries.len() == 1 { ("entry", "it") } else { ("entries", "them") };
+    println!("Deleted {} {}; `file-journal undo` restores {}", entries.len(), noun, pronoun);
+}
+
+fn list_changes(since: Option<String>, format: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let since = since.map(|value| {
+        let midnight = parse_day(&value)
+            .and_then(|day| day.and_hms_opt(0, 0, 0)?.and_local_timezone(chrono::Local).earliest())
+            .map(|time| time.fixed_offset());
+        midnight.or_else(|| chrono::DateTime::parse_from_rfc3339(&value).ok()).unwrap_or_else(|| {
+            eprintln!("Error: Invalid --since '{}': expected today, yesterday, YYYY-MM-DD or an RFC 3339 time", value);
+            exit(1);
+        })
+    });
+    let changes = match changes::since(&journal_path, since) {
+        Ok(changes) => changes,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    match format.as_str() {
+        "json" => match serde_json::to_string_pretty(&changes) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error: Failed to serialize to JSON: {}", e);
+                exit(1);
+            }
+        },
+        _ => {
+            for change in &changes {
+                let time = change.at().map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_default();
+                let from = change.from.as_ref().map(|from| format!(" (from {})", from)).unwrap_or_default();
+                println!("{}  {:<6}  {}{}", time, change.action.as_str(), change.path, from);
+            }
+        }
+    }
+}
+
+fn merge_entries(
+    day: Option<u32>,
+    month: Option<u32>,
+    year: Option<i32>,
+    title: String,
+    force: bool,
+    path: Option<PathBuf>,
+    config_path: Option<PathBuf>,
+) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let today = chrono::Local::now().date_naive();
+    let (year, month, day) = (year.unwrap_or(today.year()), month.unwrap_or(today.month()), day.unwrap_or(today.day()));
+    let Some(date) = chrono::NaiveDate::from_ymd_opt(year, month, day) else {
+        eprintln!("Error: Invalid date {}-{:02}-{:02}", year, month, day);
+        exit(1);
+    };
+    match merge_day(&journal_path, date, &title, force) {
+        Ok((merged, count)) => {
+            println!("Merged {} entries into {}", count, merged.display());
+            if force {
+                println!("Deleted the originals");
+            } else {
+                println!("Moved the originals to {}", journal_path.join(trash::TRASH_DIR).display());
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Replace the entries on `date` with one merged entry, returning it and how many it
+/// combines. The originals go to the trash first, so the merged entry can take the
+/// earliest one's time, and come back if writing it fails.
+fn merge_day(journal_path: &Path, date: chrono::NaiveDate, title: &str, force: bool) -> Result<(PathBuf, usize), String> {
+    let paths = find_entries(journal_path, Some(date.day()), Some(date.month()), Some(date.year()))?;
+    if paths.len() < 2 {
+        return Err(format!("{} has {} {}, nothing to merge", date, paths.len(), if paths.len() == 1 { "entry" } else { "entries" }));
+    }
+    if let Some(packed) = paths.iter().find(|path| !path.to_string_lossy().ends_with(".md")) {
+        return Err(format!("{} is compressed or archived and can't be merged", packed.display()));
+    }
+    let entries = paths
+        .iter()
+        .map(|path| entry::Entry::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let earliest = entries.iter().filter_map(|entry| entry.time).min().unwrap_or_default();
+    let (merged_frontmatter, note) = merge::combine(entries);
+    let author = merged_frontmatter.get("author").map(str::to_string);
+
+    let mut operation = trash::Operation::new("merge");
+    for path in &paths {
+        let before = changes::hash(path);
+        match operation.trash(journal_path, path) {
+            Ok(_) => record_change(journal_path, changes::Action::Delete, path, None, before),
+            Err(e) => {
+                restore_trashed(journal_path, &operation);
+                return Err(e);
+            }
+        }
+    }
+    let merged = match write_entry_at(journal_path.to_path_buf(), date.and_time(earliest), title, &note, merged_frontmatter, author) {
+        Ok(merged) => merged,
+        Err(e) => {
+            restore_trashed(journal_path, &operation);
+            return Err(e);
+        }
+    };
+    if force {
+        for trashed in &operation.trashed {
+            trash::purge(journal_path, &journal_path.join(&trashed.trash))?;
+        }
+    } else {
+        operation.wrote(journal_path, &merged)?;
+        record_undo(journal_path, operation);
+    }
+    Ok((merged, paths.len()))
+}
+
+/// Put entries an unfinished operation moved to the trash back where they were
+fn restore_trashed(journal_path: &Path, operation: &trash::Operation) {
+    for trashed in &operation.trashed {
+        let (path, moved) = (journal_path.join(&trashed.path), journal_path.join(&trashed.trash));
+        match fs::rename(&moved, &path) {
+            Ok(()) => record_change(journal_path, changes::Action::Create, &path, None, None),
+            Err(e) => eprintln!("Warning: Failed to restore {} from {}: {}", path.display(), moved.display(), e),
+        }
+    }
+}
+
+/// Log an operation for `undo`; if that fails the operation itself still stands
+fn record_undo(journal_path: &Path, operation: trash::Operation) {
+    let command = operation.command.clone();
+    if let Err(e) = trash::record(journal_path, operation) {
+        eprintln!("Warning: {} (the {} can't be undone)", e, command);
+    }
+}
+
+fn undo(path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    match trash::undo(&journal_path) {
+        Ok(Some(operation)) => {
+            let when = chrono::DateTime::parse_from_rfc3339(&operation.time)
+                .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or(operation.time.clone());
+            println!("Undid {} from {}", operation.command, when);
+            let replaced = |path: &str| operation.replaced.iter().any(|r| r.path == path);
+            for written in operation.written.iter().filter(|w| !replaced(&w.path)) {
+                println!("  removed {}", written.path);
+            }
+            for restored in operation.replaced.iter().chain(&operation.trashed) {
+                println!("  restored {}", restored.path);
+            }
+            for folder in &operation.folders {
+                println!("  recreated {}/", folder);
+            }
+        }
+        Ok(None) => println!("Nothing to undo"),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+fn move_entry(selector: String, date: String, index: Option<usize>, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let Some(date) = parse_day(&date) else {
+        eprintln!("Error: Invalid date '{}': expected today, yesterday or YYYY-MM-DD", date);
+        exit(1);
+    };
+    let entries = match resolve_selector(&journal_path, &selector) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    let entry = match (entries.as_slice(), index) {
+        ([], _) => {
+            eprintln!("Error: {}", language.with(i18n::Message::NoEntriesMatch, &selector));
+            exit(1);
+        }
+        (_, Some(index)) => match index.checked_sub(1).and_then(|i| entries.get(i)) {
+            Some(entry) => entry.clone(),
+            None => {
+                eprintln!("Error: No entry {} among the {} matching '{}'", index, entries.len(), selector);
+                exit(1);
+            }
+        },
+        ([entry], None) => entry.clone(),
+        (_, None) => match pick_entry(&entries) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => return,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        },
+    };
+
+    match redate_entry(&journal_path, &entry, date) {
+        Ok(moved) => println!("Moved: {} -> {}", entry.display(), moved.display()),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Move `entry` into `date`'s folder with that day in its filename (keeping the time and title)
+/// and rewrite its `Date:` line
+fn redate_entry(journal_path: &Path, entry: &Path, date: chrono::NaiveDate) -> Result<PathBuf, String> {
+    let filename = entry.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    if !filename.ends_with(".md") {
+        return Err(format!("{} is compressed or archived and can't be moved", entry.display()));
+    }
+    let parsed = filename_format()
+        .parse(filename)
+        .ok_or_else(|| format!("{} doesn't follow the entry filename format", filename))?;
+
+    let (_, _, day) = layout_date(date);
+    let renamed = filename_format().render(day, parsed.time.unwrap_or_default(), parsed.title.unwrap_or_default());
+    let target = resolve_target_dir(journal_path, date)?.join(renamed);
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+
+    let content = fs::read_to_string(entry).map_err(|e| format!("Failed to read {}: {}", entry.display(), e))?;
+    let before = changes::hash(entry);
+    fs::write(&target, set_date_line(&content, date)).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+    fs::remove_file(entry).map_err(|e| format!("Failed to remove {}: {}", entry.display(), e))?;
+    record_change(journal_path, changes::Action::Move, &target, Some(entry), before);
+    Ok(target)
+}
+
+/// `content` with its first `Date: ` line rewritten for `date`
+fn set_date_line(content: &str, date: chrono::NaiveDate) -> String {
+    let secondary = secondary_date(date).map(|d| format!(" ({})", d)).unwrap_or_default();
+    let date_line = format!("Date: {}{}", date.format("%d-%m-%Y"), secondary);
+    let mut replaced = false;
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            if !replaced && line.starts_with("Date: ") {
+                replaced = true;
+                let ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+                format!("{}{}", date_line, ending)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+fn show_entry(
+    selector: String,
+    index: Option<usize>,
+    title: Option<String>,
+    no_pager: bool,
+    path: Option<PathBuf>,
+    config_path: Option<PathBuf>,
+) {
+    use std::io::IsTerminal;
+
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let mut entries = match resolve_selector(&journal_path, &selector) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    if let Some(title) = &title {
+        entries.retain(|entry| title_matches(entry, title));
+    }
+    // Entries are in the order they were written, so the last is the most recent
+    let entry = match index {
+        Some(index) => index.checked_sub(1).and_then(|i| entries.get(i)),
+        None => entries.last(),
+    };
+    let Some(entry) = entry else {
+        match index {
+            Some(index) if !entries.is_empty() => {
+                eprintln!("Error: No entry {} among the {} matching '{}'", index, entries.len(), selector)
+            }
+            _ => eprintln!("Error: {}", language.with(i18n::Message::NoEntriesMatch, &selector)),
+        }
+        exit(1);
+    };
+
+    let content = match read_entry(entry) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Error reading {}: {}", entry.display(), e);
+            exit(1);
+        }
+    };
+    let content = if theme::use_color() {
+        match config_theme(config.as_ref()) {
+            Ok(theme) => theme.paint_entry(&content),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+    } else {
+        content
+    };
+
+    if no_pager || !std::io::stdout().is_terminal() {
+        print!("{}", content);
+    } else if let Err(e) = editor::page(&editor::pager(), &content) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+fn rename_entry(needle: String, title: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    // A selector if it parses as one, otherwise text to look for in titles and filenames
+    let entries = resolve_selector(&journal_path, &needle).or_else(|_| {
+        find_all_entries(&journal_path).map(|entries| entries.into_iter().filter(|e| title_matches(e, &needle)).collect())
+    });
+    let entries = match entries {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    let entry = match entries.as_slice() {
+        [] => {
+            eprintln!("Error: {}", language.with(i18n::Message::NoEntriesMatch, &needle));
+            exit(1);
+        }
+        [entry] => entry.clone(),
+        _ => match pick_entry(&entries) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => return,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        },
+    };
+
+    let before = changes::hash(&entry);
+    match retitle_entry(&entry, &title) {
+        Ok(renamed) => {
+            if renamed == entry {
+                record_change(&journal_path, changes::Action::Edit, &renamed, None, before);
+            } else {
+                record_change(&journal_path, changes::Action::Move, &renamed, Some(&entry), before);
+            }
+            println!("Renamed: {} -> {}", entry.display(), renamed.display())
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Rename `entry` after `title`, keeping its day, time and author, and rewrite its
+/// first `# ` heading (adding one if the body has none)
+fn retitle_entry(entry: &Path, title: &str) -> Result<PathBuf, String> {
+    let filename = entry.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    if !filename.ends_with(".md") {
+        return Err(format!("{} is compressed or archived and can't be renamed", entry.display()));
+    }
+    let parsed = filename_format()
+        .parse(filename)
+        .ok_or_else(|| format!("{} doesn't follow the entry filename format", filename))?;
+
+    let content = fs::read_to_string(entry).map_err(|e| format!("Failed to read {}: {}", entry.display(), e))?;
+    let (fm, body) = frontmatter::parse(&content);
+    let title = title.trim().trim_end_matches(".md");
+    let slug = match fm.get("author") {
+        Some(author) => format!("{}-{}", sanitize_title(author), sanitize_title(title)),
+        None => sanitize_title(title),
+    };
+    let target = entry.with_file_name(filename_format().render(parsed.day, parsed.time.unwrap_or_default(), &slug));
+    if target != entry && target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+
+    let head = &content[..content.len() - body.len()];
+    let heading = format!("# {}", title);
+    let body = match body.lines().position(|line| line.starts_with("# ")) {
+        Some(index) => {
+            let mut lines: Vec<&str> = body.split_inclusive('\n').collect();
+            let ending = &lines[index][lines[index].trim_end_matches(['\r', '\n']).len()..];
+            let replaced = format!("{}{}", heading, ending);
+            lines[index] = &replaced;
+            lines.concat()
+        }
+        None => format!("{}\n\n{}", heading, body),
+    };
+
+    fs::write(entry, format!("{}{}", head, body)).map_err(|e| format!("Failed to write {}: {}", entry.display(), e))?;
+    if target != entry {
+        fs::rename(entry, &target).map_err(|e| format!("Failed to rename {}: {}", entry.display(), e))?;
+    }
+    Ok(target)
+}
+
+/// `get` for one date
+fn get_entries_on(date: chrono::NaiveDate, list: ListArgs, config_path: Option<PathBuf>, quiet: bool) {
+    let args = GetArgs {
+        day: Some(date.day()),
+        month: Some(date.month()),
+        year: Some(date.year()),
+        date: None,
+        week: None,
+        week_offset: None,
+        quarter: None,
+        half: None,
+        from: None,
+        to: None,
+        last: None,
+        nearest: false,
+        explain: false,
+        since_last_run: false,
+        cursor: None,
+        list,
+    };
+    get_entries(args, config_path, quiet);
+}
+
+fn get_entries(mut args: GetArgs, config_path: Option<PathBuf>, quiet: bool) {
+    if let Some(expression) = args.date.take() {
+        let Some(date) = parse_day(&expression) else {
+            eprintln!("Error: Invalid --date '{}': expected e.g. yesterday, last monday, 3 days ago or YYYY-MM-DD", expression);
+            exit(1);
+        };
+        (args.day, args.month, args.year) = (Some(date.day()), Some(date.month()), Some(date.year()));
+    }
+    if let Some(span) = args.last.take() {
+        let today = chrono::Local::now().date_naive();
+        let Some(from) = window_start(&span, today) else {
+            eprintln!("Error: Invalid --last '{}': expected days or weeks, e.g. 10d or 2w", span);
+            exit(1);
+        };
+        (args.from, args.to) = (Some(from.to_string()), Some(today.to_string()));
+    }
+    let week = (args.week.is_some() || args.week_offset.is_some()).then(|| {
+        let today = chrono::Local::now().date_naive();
+        let monday = match args.week.as_deref().unwrap_or_default() {
+            "" => today - chrono::Duration::days(today.weekday().num_days_from_monday().into()),
+            spec => dates::iso_week(spec).unwrap_or_else(|| {
+                eprintln!("Error: Invalid --week '{}': expected an ISO week such as 2026-W07", spec);
+                exit(1);
+            }),
+        };
+        monday + chrono::Duration::weeks(args.week_offset.unwrap_or(0).into())
+    });
+    let period = match (args.quarter, args.half) {
+        (Some(quarter), _) => Some(period_months(args.year, "Q", quarter, 3)),
+        (None, Some(half)) => Some(period_months(args.year, "H", half, 6)),
+        (None, None) => None,
+    };
+    let ranged = args.from.is_some() || args.to.is_some();
+    let (from, to) = if let Some(monday) = week {
+        (Some(monday), Some(monday + chrono::Duration::days(6)))
+    } else if let Some(period) = &period {
+        (Some(period.first_day()), Some(period.last_day()))
+    } else if ranged {
+        (parse_date_flag(args.from.take()), parse_date_flag(args.to.take()))
+    } else {
+        searched_days(&args, chrono::Local::now().date_naive())
+    };
+
+    // Load config
+    let config = load_config(config_path);
+
+    // Determine journal path
+    let journal_path = require_journal_path(args.list.path.take(), config.as_ref());
+
+    if args.explain {
+        // Ranges and cursors go through every entry; so does everything under a layout calendar
+        let full_walk = ranged || args.since_last_run;
+        #[cfg(feature = "calendars")]
+        let full_walk = full_walk || layout_calendar().is_some();
+        let list = &args.list;
+        let filters: Vec<&str> = [
+            ("--author", list.author.is_some()),
+            ("--tag", !list.tags.is_empty()),
+            ("--title", list.title.is_some()),
+            ("--title-regex", list.title_regex.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(flag, given)| given.then_some(flag))
+        .collect();
+        explain_query(&journal_path, from, to, full_walk, &filters);
+        return;
+    }
+
+    let title = match title_pattern(&args.list) {
+        Ok(title) => title,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    let cursor = match args.cursor.as_deref().map(|name| cursor::Cursor::open(&journal_path, name)).transpose() {
+        Ok(cursor) => cursor,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    // get can't read inside tarballs, so say when any year the query covers is only in one;
+    // a cursor looks through every year
+    let (first_year, last_year) = match cursor {
+        Some(_) => (None, None),
+        None => (from.map(|from| from.year()), to.map(|to| to.year())),
+    };
+    for (year, tarball) in archive::tar_only_years(&journal_path, first_year, last_year) {
+        eprintln!("Warning: {} is archived in {}; extract it to see its entries", year, tarball.display());
+    }
+
+    let wanted = normalize_tags(&args.list.tags);
+    let wanted_author = args.list.author.as_deref();
+    let keep = |entry: &PathBuf| {
+        if title.as_ref().is_some_and(|title| !title.is_match(entry_slug(entry))) {
+            return false;
+        }
+        if wanted_author.is_none() && wanted.is_empty() {
+            return true;
+        }
+        let entry_frontmatter = entry_frontmatter(entry);
+        wanted_author.is_none_or(|author| entry_frontmatter.get("author") == Some(author))
+            && has_tags(&entry_frontmatter, &wanted)
+    };
+
+    #[cfg(feature = "plugins")]
+    let plugins = load_plugins(config.as_ref(), &journal_path);
+
+    // ndjson goes out as the walk finds each match, unless something needs all of them first
+    let list = &args.list;
+    let streams = list.format == "ndjson"
+        && (from.is_some() || to.is_some())
+        && cursor.is_none()
+        && !args.nearest
+        && !list.pick
+        && !list.reverse
+        && list.offset == 0
+        && list.limit.is_none();
+    #[cfg(feature = "plugins")]
+    let streams = streams && !plugins.has_on_get();
+    if streams {
+        let (mut unfiltered, mut found) = (0, 0);
+        let streamed = list.output.stream(|out| {
+            for entry in walk_entries(&journal_path, from, to)? {
+                let entry = entry?;
+                unfiltered += 1;
+                if keep(&entry) {
+                    found += 1;
+                    write_ndjson_line(out, &entry)?;
+                }
+            }
+            Ok(())
+        });
+        if let Err(e) = streamed {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+        if found == 0 {
+            if !quiet {
+                explain_no_entries(&journal_path, from, to, unfiltered);
+            }
+            exit(1);
+        }
+        return;
+    }
+
+    let mut entries = if let Some(cursor) = &cursor {
+        match find_all_entries(&journal_path) {
+            Ok(e) => cursor.changed(e),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+    } else if ranged {
+        match find_entries_between(&journal_path, from, to) {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+    } else if let Some(monday) = week {
+        match find_entries_week(&journal_path, monday) {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+    } else if let Some(period) = &period {
+        let mut entries = Vec::new();
+        for month in period.months.clone() {
+            match find_entries(&journal_path, None, Some(month), Some(period.year)) {
+                Ok(e) => entries.extend(e),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    exit(1);
+                }
+            }
+        }
+        entries
+    } else {
+        match find_entries(&journal_path, args.day, args.month, args.year) {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+    };
+
+    let unfiltered = entries.len();
+    entries.retain(keep);
+    if entries.is_empty() && args.nearest && cursor.is_none() {
+        let (before, after) = nearest_entry_dates(&journal_path, from, to, keep);
+        // Ties go to the earlier day
+        let nearest = match (before, after, from, to) {
+            (Some(before), Some(after), Some(from), Some(to)) => {
+                Some(if from - before <= after - to { before } else { after })
+            }
+            (before, after, ..) => before.or(after),
+        };
+        if let Some(day) = nearest {
+            entries = find_entries_between(&journal_path, Some(day), Some(day)).unwrap_or_default();
+            entries.retain(keep);
+            if !quiet {
+                eprintln!("Nothing on the days asked for; showing the nearest day with entries, {}", day);
+            }
+        }
+    }
+    if entries.is_empty() && cursor.is_none() && !quiet {
+        explain_no_entries(&journal_path, from, to, unfiltered);
+    }
+
+    #[cfg(feature = "plugins")]
+    let entries = match plugins.on_get(entries) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    let entries = page(entries, &args.list);
+
+    if args.list.pick {
+        let entry = match entries.as_slice() {
+            [] => exit(1),
+            [entry] => entry.clone(),
+            _ => {
+                let labels: Vec<String> = entries.iter().map(|entry| pick_label(entry)).collect();
+                match editor::fuzzy_pick("Entry", &labels) {
+                    Ok(Some(i)) => entries[i].clone(),
+                    Ok(None) => exit(1),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        exit(1);
+                    }
+                }
+            }
+        };
+        if args.list.edit {
+            open_in_editor(&journal_path, &entry, config.as_ref());
+        } else {
+            println!("{}", entry.display());
+        }
+        return;
+    }
+
+    match &period {
+        Some(period) if args.list.format == "json" || args.list.format == "json-full" => {
+            print_period_json(period, &entries, args.list.format == "json-full", &args.list.output)
+        }
+        _ => print_entries(&entries, &args.list.format, config.as_ref(), &args.list.output),
+    }
+
+    if let Some(cursor) = cursor
+        && let Err(e) = cursor.advance()
+    {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+
+    // Exit with error code if no entries found (useful for scripts)
+    if entries.is_empty() {
+        exit(1);
+    }
+}
+
+/// `path`'s metadata as one line of `--format ndjson`
+fn write_ndjson_line(out: &mut dyn std::io::Write, path: &Path) -> Result<(), String> {
+    let meta = entry::EntryMeta::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::to_writer(&mut *out, &meta)
+        .and_then(|_| out.write_all(b"\n").map_err(serde_json::Error::io))
+        .map_err(|e| format!("Failed to write JSON: {}", e))
+}
+
+/// The part of the sorted, filtered entries that --reverse, --offset and --limit ask for
+fn page(mut entries: Vec<PathBuf>, list: &ListArgs) -> Vec<PathBuf> {
+    if list.reverse {
+        entries.reverse();
+    }
+    entries.into_iter().skip(list.offset).take(list.limit.unwrap_or(usize::MAX)).collect()
+}
+
+/// A quarter or half of a year, for `get --quarter` and `--half`
+struct Period {
+    /// `2026-Q1`, `2026-H2`
+    label: String,
+    year: i32,
+    months: std::ops::RangeInclusive<u32>,
+}
+
+impl Period {
+    fn first_day(&self) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(self.year, *self.months.start(), 1).unwrap_or_default()
+    }
+
+    fn last_day(&self) -> chrono::NaiveDate {
+        let next = chrono::NaiveDate::from_ymd_opt(self.year, *self.months.end(), 1)
+            .and_then(|first| first.checked_add_months(chrono::Months::new(1)));
+        next.and_then(|next| next.pred_opt()).unwrap_or_default()
+    }
+}
+
+/// The `number`th run of `length` months in `year` (this year by default), exiting if there
+/// is no such part of the year
+fn period_months(year: Option<i32>, kind: &str, number: u32, length: u32) -> Period {
+    let parts = 12 / length;
+    if !(1..=parts).contains(&number) {
+        let flag = if kind == "Q" { "--quarter" } else { "--half" };
+        eprintln!("Error: Invalid {} {}: expected 1 to {}", flag, number, parts);
+        exit(1);
+    }
+    let year = year.unwrap_or(chrono::Local::now().year());
+    let first = (number - 1) * length + 1;
+    Period { label: format!("{}-{}{}", year, kind, number), year, months: first..=first + length - 1 }
+}
+
+/// `entries` as a JSON array of their paths, or of their metadata with `full` (`--format json-full`)
+fn entries_json(entries: &[PathBuf], full: bool) -> Result<serde_json::Value, String> {
+    if !full {
+        return Ok(serde_json::json!(entries.iter().map(|p| p.to_string_lossy()).collect::<Vec<_>>()));
+    }
+    let metas = entries
+        .iter()
+        .map(|path| entry::EntryMeta::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e)))
+        .collect::<Result<Vec<_>, String>>()?;
+    serde_json::to_value(metas).map_err(|e| format!("Failed to serialize to JSON: {}", e))
+}
+
+/// `{"period": "2026-Q1", "months": {"2026-01": [paths], ...}}`, listing every month of the
+/// period even when it has no entries; with `full`, each month lists the entries' metadata
+fn print_period_json(period: &Period, entries: &[PathBuf], full: bool, output: &output::OutputArgs) {
+    let mut months = serde_json::Map::new();
+    for month in period.months.clone() {
+        let in_month: Vec<PathBuf> = entries
+            .iter()
+            .filter(|entry| entry_date(entry).is_some_and(|date| date.month() == month))
+            .cloned()
+            .collect();
+        match entries_json(&in_month, full) {
+            Ok(json) => months.insert(format!("{}-{:02}", period.year, month), json),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        };
+    }
+    let json = serde_json::json!({ "period": period.label, "months": months });
+    if let Err(e) = output.write(format!("{}\n", json).as_bytes()) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+/// First and last day `get` looks at for --day, --month and --year: a day, a month or a year
+fn searched_days(args: &GetArgs, today: chrono::NaiveDate) -> (Option<chrono::NaiveDate>, Option<chrono::NaiveDate>) {
+    let year = args.year.unwrap_or(today.year());
+    let month = args.month.unwrap_or(today.month());
+    match (args.day, args.month, args.year) {
+        (Some(day), _, _) => {
+            let day = chrono::NaiveDate::from_ymd_opt(year, month, day);
+            (day, day)
+        }
+        (None, Some(_), _) => {
+            let first = chrono::NaiveDate::from_ymd_opt(year, month, 1);
+            (first, first.and_then(|first| first.checked_add_months(chrono::Months::new(1))?.pred_opt()))
+        }
+        (None, None, Some(_)) => {
+            (chrono::NaiveDate::from_ymd_opt(year, 1, 1), chrono::NaiveDate::from_ymd_opt(year, 12, 31))
+        }
+        (None, None, None) => (Some(today), Some(today)),
+    }
+}
+
+/// ` on 2026-02-17`, ` from … to …`, ` since …` or ` up to …`
+fn describe_days(from: Option<chrono::NaiveDate>, to: Option<chrono::NaiveDate>) -> String {
+    match (from, to) {
+        (Some(from), Some(to)) if from == to => format!(" on {}", from),
+        (Some(from), Some(to)) => format!(" from {} to {}", from, to),
+        (Some(from), None) => format!(" since {}", from),
+        (None, Some(to)) => format!(" up to {}", to),
+        (None, None) => String::new(),
+    }
+}
+
+/// Year and month folders holding the days from `from` to `to`
+fn month_folders(from: chrono::NaiveDate, to: chrono::NaiveDate) -> Vec<(i32, u32)> {
+    let mut folders: Vec<(i32, u32)> = from
+        .iter_days()
+        .take_while(|day| *day <= to)
+        .map(|day| {
+            let (year, month, _) = layout_date(day);
+            (year, month)
+        })
+        .collect();
+    folders.dedup();
+    folders
+}
+
+/// `get --explain`: there's no index, so say which folders (or `YYYY.zip` archives) the walk
+/// reads and how many entry files that means
+fn explain_query(
+    journal_path: &Path,
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+    full_walk: bool,
+    filters: &[&str],
+) {
+    let folders = match (from, to) {
+        (Some(from), Some(to)) if !full_walk => month_folders(from, to),
+        _ => journal_month_folders(journal_path),
+    };
+    println!("Days: {}", describe_days(from, to).trim_start());
+    println!("Plan: filesystem walk (no index)");
+    if full_walk {
+        println!("Reads: every month folder, keeping entries by their date");
+    } else {
+        println!("Reads: only the month folders of those days");
+    }
+
+    let mut total = 0;
+    for (year, month) in &folders {
+        let folder = journal_path.join(year.to_string()).join(format!("{:02}", month));
+        let zip = archive::zip_path(journal_path, *year);
+        let (count, place) = if folder.is_dir() {
+            let count = fs::read_dir(&folder).map(|files| {
+                files.flatten().filter(|file| file.file_name().to_str().is_some_and(is_entry_file)).count()
+            });
+            (count.unwrap_or(0), folder.display().to_string())
+        } else if zip.is_file() {
+            let count = archive::list_zip_entries(&zip, Some(*month), None).map(|entries| entries.len());
+            (count.unwrap_or(0), format!("{} ({}-{:02})", zip.display(), year, month))
+        } else {
+            println!("  {}  missing", folder.display());
+            continue;
+        };
+        total += count;
+        println!("  {}  {} {}", place, count, if count == 1 { "entry" } else { "entries" });
+    }
+
+    // --title and --title-regex only look at file names; --author and --tag read each entry
+    let reads = if filters.iter().any(|flag| matches!(*flag, "--author" | "--tag")) { ", each read" } else { "" };
+    let filtered = if filters.is_empty() { String::new() } else { format!(" and filtered by {}", filters.join(", ")) };
+    let folders = if folders.len() == 1 { "1 folder".to_string() } else { format!("{} folders", folders.len()) };
+    println!("Estimated cost: {} listed, {} entry files considered{}{}", folders, total, reads, filtered);
+}
+
+/// Every year/month folder in the journal, and the months inside `YYYY.zip` archives whose
+/// folder is gone
+fn journal_month_folders(journal_path: &Path) -> Vec<(i32, u32)> {
+    let mut folders = Vec::new();
+    let Ok(items) = fs::read_dir(journal_path) else {
+        return folders;
+    };
+    for item in items.flatten() {
+        let name = item.file_name().to_string_lossy().to_string();
+        if is_valid_year(&name) && item.path().is_dir() {
+            let Ok(months) = fs::read_dir(item.path()) else {
+                continue;
+            };
+            for month in months.flatten() {
+                if let Some(month) = month.file_name().to_str().filter(|m| is_valid_month(m)) {
+                    folders.push((name.parse().unwrap_or_default(), month.parse().unwrap_or_default()));
+                }
+            }
+        } else if let Some(year) = name.strip_suffix(".zip")
+            && is_valid_year(year)
+            && !journal_path.join(year).exists()
+        {
+            let year: i32 = year.parse().unwrap_or_default();
+            let zip = archive::zip_path(journal_path, year);
+            for month in 1..=12 {
+                if archive::list_zip_entries(&zip, Some(month), None).is_ok_and(|entries| !entries.is_empty()) {
+                    folders.push((year, month));
+                }
+            }
+        }
+    }
+    folders.sort();
+    folders
+}
+
+/// The latest day before `from` and the earliest after `to` with entries that `keep` accepts
+fn nearest_entry_dates(
+    journal_path: &Path,
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+    keep: impl Fn(&PathBuf) -> bool,
+) -> (Option<chrono::NaiveDate>, Option<chrono::NaiveDate>) {
+    let entries = find_all_entries(journal_path).unwrap_or_default();
+    let dated = || entries.iter().filter_map(|entry| Some((entry_date(entry)?, entry)));
+    let before = from.and_then(|from| dated().filter(|(date, entry)| *date < from && keep(entry)).max());
+    let after = to.and_then(|to| dated().filter(|(date, entry)| *date > to && keep(entry)).min());
+    (before.map(|(date, _)| date), after.map(|(date, _)| date))
+}
+
+/// Folders an empty-result explanation names before summing up the rest
+const MAX_LISTED_FOLDERS: usize = 6;
+
+/// Tell on stderr what an empty `get` searched: the days, the folders they'd be in, and the
+/// closest days that do have entries, or how many entries --author/--tag/--title left out
+fn explain_no_entries(
+    journal_path: &Path,
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+    unfiltered: usize,
+) {
+    eprintln!("No entries found{}", describe_days(from, to));
+    eprintln!("  journal: {}", journal_path.display());
+
+    if let (Some(from), Some(to)) = (from, to) {
+        let folders = month_folders(from, to);
+        for (year, month) in folders.iter().take(MAX_LISTED_FOLDERS) {
+            let folder = journal_path.join(year.to_string()).join(format!("{:02}", month));
+            let zip = archive::zip_path(journal_path, *year);
+            let state = if folder.is_dir() {
+                String::new()
+            } else if zip.exists() {
+                format!(" (archived in {})", zip.display())
+            } else if let Some(tarball) = archive::tar_only(journal_path, *year) {
+                format!(" (archived in {})", tarball.display())
+            } else {
+                " (missing)".to_string()
+            };
+            eprintln!("  checked: {}{}", folder.display(), state);
+        }
+        if folders.len() > MAX_LISTED_FOLDERS {
+            eprintln!("  checked: ... and {} more folders", folders.len() - MAX_LISTED_FOLDERS);
+        }
+    }
+
+    if unfiltered > 0 {
+        let noun = if unfiltered == 1 { "entry" } else { "entries" };
+        eprintln!("  {} {} left out by --author/--tag/--title", unfiltered, noun);
+        return;
+    }
+    match nearest_entry_dates(journal_path, from, to, |_| true) {
+        (None, None) => eprintln!("  no entries on other days either"),
+        (before, after) => {
+            let before = before.map(|date| format!("{} (before)", date));
+            let after = after.map(|date| format!("{} (after)", date));
+            let nearest: Vec<String> = [before, after].into_iter().flatten().collect();
+            eprintln!("  nearest entry: {}", nearest.join(", "));
+        }
+    }
+}
+
+/// `YYYY-MM-DD HH:MM  Title` for an entry in the `--pick` list, falling back to the filename
+fn pick_label(path: &Path) -> String {
+    let filename = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let Ok(entry) = entry::Entry::read(path) else {
+        return filename;
+    };
+    let date = entry.date.map(|d| d.to_string()).unwrap_or_default();
+    let time = entry.time.map(|t| t.format(" %H:%M").to_string()).unwrap_or_default();
+    format!("{}{}  {}", date, time, entry.title.unwrap_or(filename))
+}
+
+fn last_entries(count: usize, format: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let entries = match find_latest_entries(&journal_path, count) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    print_entries(&entries, &format, config.as_ref(), &output::OutputArgs::default());
+    if entries.is_empty() {
+        exit(1);
+    }
+}
+
+fn step_entry(selector: &str, forward: bool, format: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    match adjacent_entry(&journal_path, selector, forward) {
+        Ok(Some(entry)) => print_entries(&[entry], &format, config.as_ref(), &output::OutputArgs::default()),
+        Ok(None) => {
+            eprintln!("No entry {} {}", if forward { "after" } else { "before" }, selector);
+            exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// The entry right after (or before) an entry file or a day, ordering entries like
+/// `sort_entries`: by date, then the time in the filename, then filename
+fn adjacent_entry(journal_path: &Path, selector: &str, forward: bool) -> Result<Option<PathBuf>, String> {
+    let key = |path: &Path| {
+        let (date, time, _) = entry_order(path);
+        Some((date?, time.map(str::to_string), path.file_name()?.to_os_string()))
+    };
+    let as_path = PathBuf::from(selector);
+    // An entry compares by its own position; a day sits after (or before) all of its entries
+    let position = if as_path.is_file() {
+        key(&as_path).ok_or_else(|| format!("{} is not a dated journal entry", selector))?
+    } else if let Some(day) = parse_day(selector) {
+        let day = if forward { day.succ_opt() } else { Some(day) };
+        (day.ok_or_else(|| format!("No day after {}", selector))?, None, std::ffi::OsString::new())
+    } else {
+        return Err(format!(
+            "Invalid selector '{}': expected an entry file or a day such as yesterday or YYYY-MM-DD",
+            selector
+        ));
+    };
+
+    let mut entries: Vec<_> =
+        find_all_entries(journal_path)?.into_iter().filter_map(|entry| Some((key(&entry)?, entry))).collect();
+    entries.sort();
+    let found = if forward {
+        entries.into_iter().find(|(entry_key, _)| *entry_key > position)
+    } else {
+        entries.into_iter().rev().find(|(entry_key, _)| *entry_key < position)
+    };
+    Ok(found.map(|(_, entry)| entry))
+}
+
+fn recent_entries(count: usize, since: Option<String>, format: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let since = since.map(|value| match parse_age(&value) {
+        Some(age) => chrono::Utc::now() - age,
+        None => {
+            eprintln!("Error: Invalid --since '{}': expected an age like 30m, 12h, 3d or 2w", value);
+            exit(1);
+        }
+    });
+    let entries = match find_recent_entries(&journal_path, count, since) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    print_entries(&entries, &format, config.as_ref(), &output::OutputArgs::default());
+    if entries.is_empty() {
+        exit(1);
+    }
+}
+
+fn random_entry(year: Option<i32>, format: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let entries = match year {
+        Some(year) => find_entries(&journal_path, None, None, Some(year)),
+        None => find_all_entries(&journal_path),
+    };
+    let entries = match entries {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    let Some(entry) = fastrand::choice(&entries) else {
+        eprintln!("No entries to pick from");
+        exit(1);
+    };
+    print_entries(std::slice::from_ref(entry), &format, config.as_ref(), &output::OutputArgs::default());
+}
+
+fn on_this_day(date: Option<String>, format: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let journal_path = require_journal_path(path, config.as_ref());
+    let date = parse_date_flag(date).unwrap_or_else(|| chrono::Local::now().date_naive());
+
+    let years = match find_entries_on_this_day(&journal_path, date) {
+        Ok(years) => years,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    if years.is_empty() {
+        exit(1);
+    }
+
+    // The JSON formats stay one document (or one object a line), grouped by year rather than
+    // under the text headings
+    if format == "json" || format == "json-full" {
+        let groups: Result<Vec<serde_json::Value>, String> = years
+            .iter()
+            .map(|(year, entries)| {
+                let entries = entries_json(entries, format == "json-full")?;
+                Ok(serde_json::json!({ "year": year, "entries": entries }))
+            })
+            .collect();
+        match groups {
+            Ok(groups) => println!("{}", serde_json::Value::Array(groups)),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+        return;
+    }
+    if format == "ndjson" {
+        for (year, entries) in &years {
+            for path in entries {
+                let meta = entry::EntryMeta::read(path)
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+                    .and_then(|meta| serde_json::to_value(meta).map_err(|e| format!("Failed to write JSON: {}", e)));
+                match meta {
+                    Ok(mut meta) => {
+                        meta["year"] = serde_json::json!(year);
+                        println!("{}", meta);
+                    }
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        exit(1);
+                    }
+                }
+            }
+        }
+        return;
+    }
+    for (i, (year, entries)) in years.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        let ago = date.year() - year;
+        let ago = language.with(if ago == 1 { i18n::Message::YearAgo } else { i18n::Message::YearsAgo }, ago);
+        println!("{} ({})", year, ago);
+        print_entries(entries, &format, config.as_ref(), &output::OutputArgs::default());
+    }
+}
+
+/// Entries on `date`'s day and month in each earlier year (including archived ones), newest year first
+fn find_entries_on_this_day(journal_path: &Path, date: chrono::NaiveDate) -> Result<Vec<(i32, Vec<PathBuf>)>, String> {
+    // Folder years in another calendar aren't Gregorian years, so group every entry by its date
+    #[cfg(feature = "calendars")]
+    if layout_calendar().is_some() {
+        let same_day = |day: chrono::NaiveDate| {
+            day.year() < date.year() && (day.month(), day.day()) == (date.month(), date.day())
+        };
+        let mut by_year: std::collections::BTreeMap<i32, Vec<PathBuf>> = std::collections::BTreeMap::new();
+        for entry in find_entries_where(journal_path, same_day)? {
+            if let Some(day) = entry_date(&entry) {
+                by_year.entry(day.year()).or_default().push(entry);
+            }
+        }
+        return Ok(by_year.into_iter().rev().collect());
+    }
+
+    let items = fs::read_dir(journal_path).map_err(|e| format!("Failed to read {}: {}", journal_path.display(), e))?;
+    let mut years: Vec<i32> = items
+        .flatten()
+        .filter_map(|item| {
+            let name = item.file_name().to_str()?.to_string();
+            let year = name.strip_suffix(".zip").unwrap_or(&name);
+            is_valid_year(year).then(|| year.parse().ok()).flatten()
+        })
+        .filter(|year| *year < date.year())
+        .collect();
+    years.sort_unstable_by(|a, b| b.cmp(a));
+    years.dedup();
+
+    let mut found = Vec::new();
+    for year in years {
+        let entries = find_entries(journal_path, Some(date.day()), Some(date.month()), Some(year))?;
+        if !entries.is_empty() {
+            found.push((year, entries));
+        }
+    }
+    Ok(found)
+}
+
+/// Print entries as paths, a JSON array of paths, or their content (coloured on a terminal),
+/// to stdout or the `--output` file
+fn print_entries(entries: &[PathBuf], format: &str, config: Option<&Config>, output: &output::OutputArgs) {
+    use std::fmt::Write;
+
+    let mut text = String::new();
+    match format {
+        "json" => {
+            let paths: Vec<String> = entries.iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect();
+            match serde_json::to_string(&paths) {
+                Ok(json) => text = format!("{}\n", json),
+                Err(e) => {
+                    eprintln!("Error: Failed to serialize to JSON: {}", e);
+                    exit(1);
+                }
+            }
+        }
+        "json-full" => {
+            let metas: Result<Vec<entry::EntryMeta>, String> = entries
+                .iter()
+                .map(|path| {
+                    entry::EntryMeta::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+                })
+                .collect();
+            let json = metas.and_then(|metas| {
+                serde_json::to_string(&metas).map_err(|e| format!("Failed to serialize to JSON: {}", e))
+            });
+            match json {
+                Ok(json) => text = format!("{}\n", json),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    exit(1);
+                }
+            }
+        }
+        "ndjson" => {
+            // One line per entry, each read and written before the next: the matching paths are
+            // already known, but no entry's content is held any longer than its own line takes
+            let streamed = output.stream(|out| entries.iter().try_for_each(|path| write_ndjson_line(out, path)));
+            if let Err(e) = streamed {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+            return;
+        }
+        "content" => {
+            let theme = if theme::use_color() && !output.to_file() {
+                match config_theme(config) {
+                    Ok(theme) => Some(theme),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        exit(1);
+                    }
+                }
+            } else {
+                None
+            };
+            for entry in entries {
+                let _ = match &theme {
+                    Some(theme) => writeln!(text, "{}", theme.paint(theme::Role::Date, &entry.display().to_string())),
+                    None => writeln!(text, "{}", entry.display()),
+                };
+                let _ = writeln!(text, "{}", "-".repeat(40));
+                match read_entry(entry) {
+                    Ok(content) => {
+                        let _ = match &theme {
+                            Some(theme) => writeln!(text, "{}", theme.paint_entry(&content)),
+                            None => writeln!(text, "{}", content),
+                        };
+                    }
+                    Err(e) => eprintln!("Error reading {}: {}", entry.display(), e),
+                }
+                text.push('\n');
+            }
+        }
+        _ => {
+            // Default: just paths
+            for entry in entries {
+                let _ = writeln!(text, "{}", entry.display());
+            }
+        }
+    }
+    if let Err(e) = output.write(text.as_bytes()) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+fn seal_journal(
+    action: Option<SealAction>,
+    timestamp: bool,
+    path: Option<PathBuf>,
+    config_path: Option<PathBuf>,
+) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    match action {
+        None => {
+            let (mut seal, leaves) = match seal::create_seal(&journal_path) {
+                Ok(sealed) => sealed,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    exit(1);
+                }
+            };
+
+            if timestamp {
+                let tsa_url = config.as_ref().and_then(|c| c.tsa_url.clone());
+                match timestamp_seal(&journal_path, &seal.root, tsa_url) {
+                    Ok(token) => seal.timestamp_token = Some(token.to_string_lossy().to_string()),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        exit(1);
+                    }
+                }
+            }
+
+            if let Err(e) = seal::write_seal(&journal_path, &seal, &leaves) {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+            println!("Sealed {} entries at {}", seal.entries, seal.timestamp);
+            println!("Root: {}", seal.root);
+            if let Some(token) = &seal.timestamp_token {
+                println!("Timestamp token: {}", journal_path.join(token).display());
+            }
+        }
+        Some(SealAction::Verify) => {
+            let seals = match seal::read_seals(&journal_path) {
+                Ok(seals) => seals,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    exit(1);
+                }
+            };
+            let Some(latest) = seals.last() else {
+                eprintln!("Error: No seals found in {}", journal_path.join(seal::SEAL_LOG).display());
+                exit(1);
+            };
+            let verification = match seal::verify(&journal_path, latest) {
+                Ok(verification) => verification,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    exit(1);
+                }
+            };
+
+            if verification.is_ok() {
+                println!("OK: {} entries match the seal from {}", latest.entries, latest.timestamp);
+                if !verification.added.is_empty() {
+                    println!("{} entries added since aren't covered by it:", verification.added.len());
+                    for added in &verification.added {
+                        println!("  added:    {}", added);
+                    }
+                }
+                if let Some(token) = &latest.timestamp_token {
+                    println!(
+                        "Timestamp token: {} (check with: openssl ts -verify -digest {} -in <token> -CAfile <tsa-ca.pem>)",
+                        journal_path.join(token).display(),
+                        latest.root
+                    );
+                }
+            } else {
+                eprintln!("MISMATCH: journal differs from the seal taken at {}", latest.timestamp);
+                for modified in &verification.modified {
+                    eprintln!("  modified: {}", modified);
+                }
+                for missing in &verification.missing {
+                    eprintln!("  missing:  {}", missing);
+                }
+                for added in &verification.added {
+                    eprintln!("  added:    {}", added);
+                }
+                if latest.leaves.is_none() {
+                    eprintln!("  (this seal predates leaf lists, so the whole journal was compared with its root)");
+                } else if verification.modified.is_empty() && verification.missing.is_empty() {
+                    eprintln!("  the leaf list in {} doesn't match the sealed root", seal::SEAL_DIR);
+                }
+                exit(1);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "network")]
+fn timestamp_seal(journal_path: &Path, root: &str, tsa_url: Option<String>) -> Result<PathBuf, String> {
+    let tsa_url = tsa_url.unwrap_or_else(|| tsa::DEFAULT_TSA_URL.to_string());
+    tsa::timestamp_root(journal_path, root, &tsa_url)
+}
+
+#[cfg(not(feature = "network"))]
+fn timestamp_seal(_journal_path: &Path, _root: &str, _tsa_url: Option<String>) -> Result<PathBuf, String> {
+    Err("Timestamping requires building with '--features network'".to_string())
+}
+
+/// Resolve the journal path, the selected entries and the signing config for sign/verify
+fn signing_context(
+    selector: &str,
+    path: Option<PathBuf>,
+    config_path: Option<PathBuf>,
+) -> (Vec<PathBuf>, signing::SigningConfig) {
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let Some(signing_config) = config.and_then(|c| c.signing) else {
+        eprintln!("Error: No [signing] section in config");
+        exit(1);
+    };
+
+    let entries = match resolve_selector(&journal_path, selector) {
+        Ok(entries) if !entries.is_empty() => entries,
+        Ok(_) => {
+            eprintln!("Error: {}", language.with(i18n::Message::NoEntriesMatch, selector));
+            exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    (entries, signing_config)
+}
+
+fn sign_entries(selector: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let (entries, signing_config) = signing_context(&selector, path, config_path);
+
+    let mut failed = false;
+    for entry in &entries {
+        match signing::sign_entry(&signing_config, entry) {
+            Ok(signature) => println!("Signed: {} -> {}", entry.display(), signature.display()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        exit(1);
+    }
+}
+
+fn verify_signatures(selector: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let (entries, signing_config) = signing_context(&selector, path, config_path);
+
+    let mut failed = false;
+    let bar = progress::bar(entries.len(), "Verifying");
+    for entry in &entries {
+        match signing::verify_entry(&signing_config, entry) {
+            Ok(()) => bar.suspend(|| println!("OK: {}", entry.display())),
+            Err(e) => {
+                bar.suspend(|| println!("FAILED: {} ({})", entry.display(), e));
+                failed = true;
+            }
+        }
+        bar.inc(1);
+    }
+    bar.finish_and_clear();
+
+    if failed {
+        exit(1);
+    }
+}
+
+fn manage_incidents(action: IncidentsAction, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    match action {
+        IncidentsAction::List { open } => match incidents::list_incidents(&journal_path, open) {
+            Ok(list) => {
+                for incident in &list {
+                    println!(
+                        "{}\t{}\t{}\t{}",
+                        incident.id, incident.severity, incident.status, incident.title
+                    );
+                }
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        },
+        IncidentsAction::Close { id } => {
+            let closed = chrono::Local::now().to_rfc3339();
+            match incidents::close_incident(&journal_path, &id, &closed) {
+                Ok(path) => println!("Closed incident: {}", path.display()),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    exit(1);
+                }
+            }
+        }
+    }
+}
+
+fn list_tags(json: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let counts = match tags::journal_tags(&journal_path) {
+        Ok(counts) => counts,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    let ranked = tags::by_count(counts);
+
+    if json {
+        let list: Vec<serde_json::Value> =
+            ranked.iter().map(|(tag, count)| serde_json::json!({ "tag": tag, "count": count })).collect();
+        println!("{}", serde_json::Value::Array(list));
+    } else {
+        let width = ranked.first().map(|(_, count)| count.to_string().len()).unwrap_or(1);
+        for (tag, count) in &ranked {
+            println!("{:>width$}  {}", count, tag, width = width);
+        }
+    }
+    if ranked.is_empty() {
+        exit(1);
+    }
+}
+
+fn list_todos(
+    done: bool,
+    from: Option<String>,
+    to: Option<String>,
+    json: bool,
+    path: Option<PathBuf>,
+    config_path: Option<PathBuf>,
+) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let entries = match find_entries_between(&journal_path, parse_date_flag(from), parse_date_flag(to)) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    let tasks = todo::collect(&entries, done);
+    if json {
+        match serde_json::to_string(&tasks) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error: Failed to serialize to JSON: {}", e);
+                exit(1);
+            }
+        }
+    } else {
+        print!("{}", todo::render(&tasks));
+    }
+    if tasks.is_empty() {
+        exit(1);
+    }
+}
+
+fn count_entries(args: CountArgs, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(args.path, config.as_ref());
+
+    let entries = if args.from.is_some() || args.to.is_some() {
+        find_entries_between(&journal_path, parse_date_flag(args.from), parse_date_flag(args.to))
+    } else {
+        find_entries(&journal_path, args.day, args.month, args.year)
+    };
+    let entries = match entries {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    let words: usize = entries.iter().filter_map(|path| entry::Entry::read(path).ok()).map(|e| e.words()).sum();
+    let average = if entries.is_empty() { 0.0 } else { words as f64 / entries.len() as f64 };
+    if args.json {
+        println!("{}", serde_json::json!({ "entries": entries.len(), "words": words, "average_words": average }));
+    } else {
+        println!("Entries: {}", entries.len());
+        println!("Words:   {}", words);
+        println!("Average: {:.0} words per entry", average);
+    }
+}
+
+fn manage_tags(action: TagAction, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let result = match action {
+        #[cfg(feature = "network")]
+        TagAction::Suggest { selector, limit, llm, yes } => {
+            let settings = config.as_ref().and_then(|c| c.summarize.clone()).filter(|_| llm);
+            if llm && settings.is_none() {
+                eprintln!("Error: No language model configured; add a [summarize] section to the config");
+                exit(1);
+            }
+            suggest_tags(&journal_path, &selector, limit, yes, |body, known| match &settings {
+                Some(settings) => {
+                    let prompt = tags::llm_prompt(body, known, limit);
+                    summarize::complete(settings, &prompt).map(|reply| tags::parse_llm_tags(&reply))
+                }
+                None => Ok(Vec::new()),
+            })
+        }
+        #[cfg(not(feature = "network"))]
+        TagAction::Suggest { selector, limit, yes } => {
+            suggest_tags(&journal_path, &selector, limit, yes, |_, _| Ok(Vec::new()))
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+/// Offer suggestions for each selected entry and write the accepted ones. `ask_model`
+/// returns tags from a language model, or nothing to fall back to keyword extraction.
+fn suggest_tags<F>(journal_path: &Path, selector: &str, limit: usize, yes: bool, mut ask_model: F) -> Result<(), String>
+where
+    F: FnMut(&str, &std::collections::BTreeMap<String, usize>) -> Result<Vec<String>, String>,
+{
+    let known = tags::journal_tags(journal_path)?;
+    let entries = resolve_selector(journal_path, selector)?;
+    if entries.is_empty() {
+        return Err(format!("No entries match '{}'", selector));
+    }
+
+    for entry in entries {
+        let content = read_entry(&entry).map_err(|e| format!("Failed to read {}: {}", entry.display(), e))?;
+        let (entry_frontmatter, body) = frontmatter::parse(&content);
+        let existing = entry_frontmatter.list("tags");
+        let mut suggestions: Vec<String> =
+            ask_model(body, &known)?.into_iter().filter(|t| !existing.contains(t)).take(limit).collect();
+        if suggestions.is_empty() {
+            suggestions = tags::suggest(body, &existing, &known, limit);
+        }
+        if suggestions.is_empty() {
+            continue;
+        }
+
+        println!("{}", entry.display());
+        let mut accepted = Vec::new();
+        let mut quit = false;
+        for tag in suggestions {
+            if yes {
+                accepted.push(tag);
+                continue;
+            }
+            print!("  add '{}'? [y/N/q] ", tag);
+            std::io::Write::flush(&mut std::io::stdout()).map_err(|e| e.to_string())?;
+            let mut answer = String::new();
+            // End of input (or q) stops asking but still saves what was accepted so far
+            let read = std::io::stdin().read_line(&mut answer).map_err(|e| e.to_string())?;
+            match answer.trim().to_lowercase().as_str() {
+                "y" | "yes" => accepted.push(tag),
+                "q" => quit = true,
+                _ if read == 0 => quit = true,
+                _ => {}
+            }
+            if quit {
+                println!();
+                break;
+            }
+        }
+        if !accepted.is_empty() {
+            let before = changes::hash(&entry);
+            tags::add_tags(&entry, &accepted)?;
+            record_change(journal_path, changes::Action::Edit, &entry, None, before);
+            println!("  tagged: {}", accepted.join(", "));
+        }
+        if quit {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn print_standup(slack: bool, path: Option<PathBuf>, output: output::OutputArgs, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+    let standup_config = config.and_then(|c| c.standup).unwrap_or_default();
+
+    let today = chrono::Local::now().date_naive();
+    let mut entries = Vec::new();
+    for date in [today.pred_opt(), Some(today)].into_iter().flatten() {
+        match find_entries(&journal_path, Some(date.day()), Some(date.month()), Some(date.year())) {
+            Ok(found) => entries.extend(found),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+    }
+
+    let bodies: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| read_entry(entry).ok())
+        .map(|content| frontmatter::parse(&content).1.to_string())
+        .collect();
+
+    let sections = standup::collect_sections(&standup_config.headings, &bodies);
+    if let Err(e) = output.write(format!("{}\n", standup::render(&sections, slack)).as_bytes()) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+fn export_entries(args: ExportArgs, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let journal_path = require_journal_path(args.path, config.as_ref());
+
+    #[cfg(feature = "wasm")]
+    let plugin = args.plugin.as_deref().map(|name| {
+        wasm::find(&plugins_dir(config.as_ref()), name).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            exit(1);
+        })
+    });
+    #[cfg(not(feature = "wasm"))]
+    let plugin: Option<PathBuf> = None;
+
+    if plugin.is_none() && args.format != "worklog" && args.format != "json" {
+        eprintln!("Error: Unsupported export format '{}' (expected 'worklog' or 'json')", args.format);
+        exit(1);
+    }
+    if !export::JSON_SCHEMA_VERSIONS.contains(&args.schema_version) {
+        eprintln!("Error: {}", unsupported_schema_version(args.schema_version));
+        exit(1);
+    }
+
+    // Without a month or year, export the current month
+    let now = chrono::Local::now();
+    let month = match (args.month, args.year) {
+        (None, None) => Some(now.month()),
+        (month, _) => month,
+    };
+    let year = args.year.unwrap_or(now.year());
+
+    let entries = match find_entries(&journal_path, None, month, Some(year)) {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    #[cfg(feature = "wasm")]
+    if let Some(module) = plugin {
+        export_with_plugin(&module, &entries, args.project.as_deref(), &args.output, language);
+        return;
+    }
+
+    interrupt::catch();
+    let mut items = Vec::new();
+    let mut json_entries = Vec::new();
+    let bar = progress::bar(entries.len(), "Exporting");
+    for path in &entries {
+        if interrupt::requested() {
+            bar.finish_and_clear();
+            eprintln!("{}", language.text(i18n::Message::ExportInterrupted));
+            exit(interrupt::EXIT_CODE);
+        }
+        bar.inc(1);
+        let entry = match entry::Entry::read(path) {
+            Ok(entry) => entry,
+            Err(e) => {
+                bar.suspend(|| eprintln!("Error reading {}: {}", path.display(), e));
+                continue;
+            }
+        };
+        let Some(date) = entry.date else {
+            continue;
+        };
+        let project = entry.frontmatter.get("project").map(str::to_string);
+        if args.project.is_some() && project != args.project {
+            continue;
+        }
+        if args.format == "json" {
+            let relative = path.strip_prefix(&journal_path).unwrap_or(path);
+            let relative: Vec<String> =
+                relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+            json_entries.push(export::json_entry(&entry, date, &relative.join("/")));
+            continue;
+        }
+
+        items.push(export::WorklogItem {
+            date,
+            project,
+            title: entry.title.unwrap_or_else(|| entry.path.display().to_string()),
+            bullets: export::summarize(&entry.body),
+        });
+    }
+    bar.finish_and_clear();
+
+    if args.format == "json" {
+        let exported_at = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+        let json = export::render_json(args.schema_version, &exported_at, json_entries);
+        if let Err(e) = args.output.write(json.as_bytes()) {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+        return;
+    }
+
+    let period = match month.and_then(|m| chrono::NaiveDate::from_ymd_opt(year, m, 1)) {
+        Some(first) => language.month_year(first),
+        None => year.to_string(),
+    };
+    let worklog = language.text(i18n::Message::Worklog);
+    let heading = match &args.project {
+        Some(project) => format!("{}: {}, {}", worklog, project, period),
+        None => format!("{}: {}", worklog, period),
+    };
+    if let Err(e) = args.output.write(export::render_worklog(&heading, &items, language).as_bytes()) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+fn unsupported_schema_version(version: u32) -> String {
+    let known: Vec<String> = export::JSON_SCHEMA_VERSIONS.iter().map(u32::to_string).collect();
+    format!("Unknown export schema version {} (this build knows {})", version, known.join(", "))
+}
+
+fn print_schema(version: Option<u32>) {
+    let version = version.unwrap_or(export::LATEST_JSON_SCHEMA);
+    match export::json_schema(version) {
+        Some(schema) => println!("{:#}", schema),
+        None => {
+            eprintln!("Error: {}", unsupported_schema_version(version));
+            exit(1);
+        }
+    }
+}
+
+/// Hand the selected entries to a WASM export module and write out what it produces
+#[cfg(feature = "wasm")]
+fn export_with_plugin(
+    module: &Path,
+    entries: &[PathBuf],
+    project: Option<&str>,
+    output: &output::OutputArgs,
+    language: i18n::Language,
+) {
+    interrupt::catch();
+    let mut exported = Vec::new();
+    let bar = progress::bar(entries.len(), "Exporting");
+    for entry in entries {
+        if interrupt::requested() {
+            bar.finish_and_clear();
+            eprintln!("{}", language.text(i18n::Message::ExportInterrupted));
+            exit(interrupt::EXIT_CODE);
+        }
+        bar.inc(1);
+        let content = match read_entry(entry) {
+            Ok(content) => content,
+            Err(e) => {
+                bar.suspend(|| eprintln!("Error reading {}: {}", entry.display(), e));
+                continue;
+            }
+        };
+        let parsed = entry::Entry::parse(entry, &content);
+        if project.is_some() && parsed.frontmatter.get("project") != project {
+            continue;
+        }
+        exported.push(wasm::ExportedEntry {
+            path: entry.display().to_string(),
+            date: parsed.date.map(|d| d.to_string()),
+            title: parsed.title,
+            content,
+        });
+    }
+    bar.finish_and_clear();
+
+    if let Err(e) = wasm::export(module, &exported).and_then(|bytes| output.write(&bytes)) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+#[cfg(feature = "wasm")]
+fn import_entries(plugin: String, file: Option<PathBuf>, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    use std::io::Read;
+
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+    let author = config.as_ref().and_then(|c| c.author.clone());
+
+    let input = match &file {
+        Some(file) => fs::read(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e)),
+        None => {
+            let mut input = Vec::new();
+            std::io::stdin().read_to_end(&mut input).map(|_| input).map_err(|e| format!("Failed to read stdin: {}", e))
+        }
+    };
+    let (input, imported) = input
+        .and_then(|input| {
+            let imported = wasm::import(&wasm::find(&plugins_dir(config.as_ref()), &plugin)?, &input)?;
+            Ok((input, imported))
+        })
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            exit(1);
+        });
+    let times = wasm::entry_times(&imported, chrono::Local::now().naive_local()).unwrap_or_else(|e| {
+        eprintln!("Error: Plugin {}: {}", plugin, e);
+        exit(1);
+    });
+    let imported = imported
+        .into_iter()
+        .zip(times)
+        .map(|(entry, written)| ImportedEntry { written: Some(written), title: entry.title, note: entry.note, tags: entry.tags })
+        .collect();
+    write_imported(&journal_path, &[plugin.as_bytes(), &input], imported, author);
+}
+
+/// Rows `import csv --preview` shows
+const CSV_PREVIEW_ROWS: usize = 5;
+
+fn import_csv(args: CsvImportArgs, config_path: Option<PathBuf>) {
+    use std::io::Read;
+
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(args.path, config.as_ref());
+    let author = config.as_ref().and_then(|c| c.author.clone());
+
+    let input = match &args.file {
+        Some(file) => fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e)),
+        None => {
+            let mut input = String::new();
+            let read = std::io::stdin().read_to_string(&mut input);
+            read.map(|_| input).map_err(|e| format!("Failed to read stdin: {}", e))
+        }
+    };
+    let parsed = input.and_then(|input| {
+        let records = csv::parse(&input, args.delimiter)?;
+        let header = records.first().ok_or("The CSV file is empty")?;
+        let mapping = csv::Mapping::new(&args.maps, header)?;
+        let date_format = match &args.date_format {
+            Some(format) => (format.as_str(), ""),
+            None => (csv::detect_date_format(&mapping.dates(&records))?, " (detected)"),
+        };
+        let rows = csv::rows(&records, &mapping, date_format.0)?;
+        Ok((input, date_format, rows))
+    });
+    let (input, (date_format, detected), rows) = parsed.unwrap_or_else(|e: String| {
+        eprintln!("Error: {}", e);
+        exit(1);
+    });
+
+    if args.preview {
+        println!("Date format: {}{}", date_format, detected);
+        for row in rows.iter().take(CSV_PREVIEW_ROWS) {
+            let tags = if row.tags.is_empty() { String::new() } else { format!("  [{}]", row.tags.join(", ")) };
+            println!("Row {}: {}  {}{}", row.number, row.written, row.title, tags);
+            if let Some(line) = row.body.lines().next() {
+                let more = if line.chars().count() > 72 || row.body.lines().nth(1).is_some() { "..." } else { "" };
+                println!("  {}{}", line.chars().take(72).collect::<String>(), more);
+            }
+        }
+        if rows.len() > CSV_PREVIEW_ROWS {
+            println!("... and {} more rows", rows.len() - CSV_PREVIEW_ROWS);
+        }
+        return;
+    }
+
+    let entries = rows
+        .into_iter()
+        .map(|row| ImportedEntry { written: Some(row.written), title: row.title, note: row.body, tags: row.tags })
+        .collect();
+    let mapping = args.maps.join("\n");
+    let source: [&[u8]; 4] = [b"csv", mapping.as_bytes(), date_format.as_bytes(), input.as_bytes()];
+    write_imported(&journal_path, &source, entries, author);
+}
+
+/// An entry to create in a bulk import, and when it was written if the source says
+struct ImportedEntry {
+    written: Option<chrono::NaiveDateTime>,
+    title: String,
+    note: String,
+    tags: Vec<String>,
+}
+
+/// Create `entries` one by one with a progress bar, resumably: `source` identifies the import
+/// for its checkpoint, so running it again after Ctrl-C or a crash skips what's already written
+fn write_imported(journal_path: &Path, source: &[&[u8]], entries: Vec<ImportedEntry>, author: Option<String>) {
+    let mut checkpoint = checkpoint::Checkpoint::open(journal_path, source).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        exit(1);
+    });
+    if checkpoint.resumed() > 0 {
+        println!("Resuming import: {} of {} entries were imported before", checkpoint.resumed(), entries.len());
+    }
+
+    interrupt::catch();
+    let total = entries.len();
+    let bar = progress::bar(total, "Importing");
+    for (i, entry) in entries.into_iter().enumerate() {
+        if interrupt::requested() {
+            bar.finish_and_clear();
+            eprintln!("Import interrupted after {} of {} entries; run it again to resume", checkpoint.resumed(), total);
+            exit(interrupt::EXIT_CODE);
+        }
+        if checkpoint.is_done(i) {
+            bar.inc(1);
+            continue;
+        }
+        let mut entry_frontmatter = frontmatter::Frontmatter::default();
+        let tags = normalize_tags(&entry.tags);
+        if !tags.is_empty() {
+            entry_frontmatter.set("tags", frontmatter::Value::List(tags));
+        }
+        let journal_path = journal_path.to_path_buf();
+        let written = match entry.written {
+            Some(at) => write_entry_at(journal_path, at, &entry.title, &entry.note, entry_frontmatter, author.clone()),
+            None => write_entry(journal_path, &entry.title, &entry.note, entry_frontmatter, author.clone()),
+        };
+        let written = written.and_then(|filepath| checkpoint.mark_done(i, &filepath).map(|_| filepath));
+        match written {
+            Ok(filepath) => bar.suspend(|| println!("Created: {}", filepath.display())),
+            Err(e) => {
+                bar.finish_and_clear();
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+        bar.inc(1);
+    }
+    bar.finish_and_clear();
+    if let Err(e) = checkpoint.finish() {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+fn clock_invoice(
+    month: Option<u32>,
+    year: Option<i32>,
+    rate: Option<f64>,
+    format: String,
+    path: Option<PathBuf>,
+    config_path: Option<PathBuf>,
+) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let now = chrono::Local::now();
+    let month = month.unwrap_or(now.month());
+    let entries = match find_entries(&journal_path, None, Some(month), Some(year.unwrap_or(now.year()))) {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    let lines: Vec<clock::ClockLine> = entries
+        .iter()
+        .filter_map(|entry| read_entry(entry).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter_map(clock::parse_clock_line)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    match clock::render_invoice(&clock::summarize(&lines), rate, &format) {
+        Ok(invoice) => print!("{}", invoice),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+#[cfg(feature = "network")]
+fn import_github_activity(user: String, dry_run: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+    let token = config
+        .as_ref()
+        .and_then(|c| c.github_token.clone())
+        .or_else(|| env::var("GITHUB_TOKEN").ok());
+
+    let lines = github::resolve_user(&user, token.as_deref())
+        .and_then(|login| github::fetch_events(&login, token.as_deref()))
+        .map(|events| github::summarize_events(&events, chrono::Local::now().date_naive()));
+    let lines = match lines {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    if lines.is_empty() {
+        println!("No GitHub activity found for today");
+        return;
+    }
+
+    let section = format!("## GitHub activity\n\n{}", lines.join("\n"));
+    if dry_run {
+        println!("{}", section);
+        return;
+    }
+    let author = config.and_then(|c| c.author);
+    match append_to_today(&journal_path, &section, author) {
+        Ok(entry) => println!("Added {} GitHub items to {}", lines.len(), entry.display()),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+#[cfg(feature = "network")]
+fn post_summary(
+    to: String,
+    first_section: bool,
+    dry_run: bool,
+    path: Option<PathBuf>,
+    config_path: Option<PathBuf>,
+) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+    let webhooks = config.and_then(|c| c.webhooks).unwrap_or_default();
+
+    let url = match to.as_str() {
+        "slack" => webhooks.slack,
+        "discord" => webhooks.discord,
+        other => {
+            eprintln!("Error: Unknown destination '{}' (expected slack or discord)", other);
+            exit(1);
+        }
+    };
+
+    let entries = match find_entries(&journal_path, None, None, None) {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    if entries.is_empty() {
+        eprintln!("Error: No entries for today");
+        exit(1);
+    }
+
+    let rendered: Vec<(String, String)> = entries
+        .iter()
+        .filter_map(|entry| read_entry(entry).ok())
+        .map(|content| {
+            let (_, body) = frontmatter::parse(&content);
+            let title = entry_title(body).unwrap_or_else(|| "Journal".to_string());
+            let text = webhook::strip_template(body);
+            let text = if first_section { webhook::first_section(&text) } else { text };
+            (title, text)
+        })
+        .collect();
+
+    let payload = if to == "slack" {
+        webhook::slack_payload(&rendered)
+    } else {
+        webhook::discord_payload(&rendered)
+    };
+
+    if dry_run {
+        println!("{}", serde_json::to_string_pretty(&payload).unwrap_or_default());
+        return;
+    }
+
+    let Some(url) = url else {
+        eprintln!("Error: No {} webhook configured under [webhooks]", to);
+        exit(1);
+    };
+    match webhook::post(&url, &payload) {
+        Ok(()) => println!("Posted {} entries to {}", rendered.len(), to),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+#[cfg(feature = "network")]
+fn ask_journal(question: String, limit: usize, dry_run: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+    let settings = config.as_ref().and_then(|c| c.summarize.clone());
+    if settings.is_none() && !dry_run {
+        eprintln!("Error: No language model configured; add a [summarize] section to the config");
+        exit(1);
+    }
+
+    // Embeddings find entries that answer the question in other words; otherwise match keywords
+    #[cfg(feature = "semantic")]
+    let nearest: Option<Vec<ask::Excerpt>> = semantic::search(&journal_path, &question, limit)
+        .map(|found| found.iter().filter_map(|(entry, _)| ask::Excerpt::load(&journal_path, entry)).collect())
+        .inspect_err(|e| eprintln!("Warning: {}; falling back to keyword matching", e))
+        .ok();
+    #[cfg(not(feature = "semantic"))]
+    let nearest: Option<Vec<ask::Excerpt>> = None;
+    let excerpts = match nearest {
+        Some(excerpts) => Ok(excerpts),
+        None => find_all_entries(&journal_path)
+            .map(|entries| ask::keyword_matches(&journal_path, &entries, &question, limit)),
+    };
+    let excerpts = match excerpts {
+        Ok(excerpts) if excerpts.is_empty() => {
+            eprintln!("Error: No entries relate to the question");
+            exit(1);
+        }
+        Ok(excerpts) => excerpts,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    let prompt = ask::build_prompt(&question, &excerpts);
+    let Some(settings) = settings.filter(|_| !dry_run) else {
+        println!("{}", prompt);
+        return;
+    };
+    match summarize::complete(&settings, &prompt) {
+        Ok(answer) => {
+            println!("{}\n\nSources:", answer);
+            for source in ask::sources(&answer, &excerpts) {
+                println!("  {}", journal_path.join(source).display());
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+#[cfg(feature = "network")]
+fn summarize_month(month: u32, year: Option<i32>, dry_run: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let journal_path = require_journal_path(path, config.as_ref());
+    let year = year.unwrap_or_else(|| chrono::Local::now().year());
+    let Some(first_day) = chrono::NaiveDate::from_ymd_opt(year, month, 1) else {
+        eprintln!("Error: Invalid month {}", month);
+        exit(1);
+    };
+    let Some(settings) = config.as_ref().and_then(|c| c.summarize.clone()) else {
+        eprintln!("Error: No language model configured; add a [summarize] section to the config");
+        exit(1);
+    };
+
+    let entries = match find_entries(&journal_path, None, Some(month), Some(year)) {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    let mut sections = Vec::new();
+    for entry in &entries {
+        let Ok(content) = read_entry(entry) else {
+            continue;
+        };
+        let (entry_frontmatter, body) = frontmatter::parse(&content);
+        // Earlier summaries would only be summarized again
+        if entry_frontmatter.list("tags").iter().any(|t| t == "summary") {
+            continue;
+        }
+        let date = entry_date(entry).map(|d| d.to_string()).unwrap_or_default();
+        let title = entry_title(body).unwrap_or_default();
+        sections.push(format!("## {} {}\n\n{}", date, title, webhook::strip_template(body)));
+    }
+    if sections.is_empty() {
+        eprintln!("Error: No entries for {}", first_day.format("%Y-%m"));
+        exit(1);
+    }
+
+    let label = language.month_year(first_day);
+    let template = settings.prompt.as_deref().unwrap_or(summarize::DEFAULT_PROMPT);
+    let prompt = summarize::render_prompt(template, &label, &sections.join("\n\n"));
+    if dry_run {
+        println!("{}", prompt);
+        return;
+    }
+
+    println!("Summarizing {} entries from {} with {}...", sections.len(), label, settings.model);
+    let summary = match summarize::complete(&settings, &prompt) {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    let mut summary_frontmatter = frontmatter::Frontmatter::default();
+    summary_frontmatter.set("tags", frontmatter::Value::List(vec!["summary".to_string()]));
+    let title = language.with(i18n::Message::SummaryOf, &label);
+    let author = config.and_then(|c| c.author);
+    match write_entry(journal_path, &title, &summary, summary_frontmatter, author) {
+        Ok(path) => println!("{}", language.with(i18n::Message::CreatedEntry, path.display())),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+#[cfg(feature = "network")]
+fn telegram_bridge(once: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let journal_path = require_journal_path(path, config.as_ref());
+    let author = config.as_ref().and_then(|c| c.author.clone());
+    let settings = config.and_then(|c| c.telegram).unwrap_or_default();
+
+    let Some(token) = settings.bot_token else {
+        eprintln!("Error: No bot_token configured under [telegram]");
+        exit(1);
+    };
+    if settings.allowed_chats.is_empty() {
+        eprintln!("Warning: allowed_chats is empty under [telegram]; all messages will be ignored");
+    }
+
+    let bot = telegram::Bot::new(token);
+    let mut offset = telegram::load_offset(&journal_path);
+    if !once {
+        println!("Listening for Telegram messages (Ctrl-C to stop)");
+    }
+
+    loop {
+        let updates = match bot.get_updates(offset, !once) {
+            Ok(updates) => updates,
+            Err(e) if !once => {
+                eprintln!("Warning: {}; retrying", e);
+                std::thread::sleep(std::time::Duration::from_secs(5));
+                continue;
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        };
+        if once && updates.is_empty() {
+            return;
+        }
+
+        for update in updates {
+            offset = update.update_id + 1;
+            match update.chat_id {
+                // Edits and other updates that aren't new messages
+                None => {}
+                Some(chat_id) if !settings.allowed_chats.contains(&chat_id) => {
+                    eprintln!("Ignoring message from chat {} (not in allowed_chats)", chat_id)
+                }
+                Some(_) if update.text.is_some() || update.photo_file_id.is_some() => {
+                    match capture_telegram_message(&bot, &journal_path, &update, author.clone()) {
+                        Ok(entry) => println!("{}", language.with(i18n::Message::AppendedTo, entry.display())),
+                        Err(e) => eprintln!("Warning: {}", e),
+                    }
+                }
+                Some(_) => {}
+            }
+            if let Err(e) = telegram::save_offset(&journal_path, offset) {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn run_daemon(socket: Option<PathBuf>, metrics: Option<String>, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+    let socket = socket.unwrap_or_else(daemon::default_socket);
+
+    println!("Serving {} on {}", journal_path.display(), socket.display());
+    let serve_config = config.as_ref().and_then(|c| c.serve.clone()).unwrap_or_default();
+    let server = Arc::new(Mutex::new(daemon::Daemon::new(journal_path, config.and_then(|c| c.author))));
+    if let Some(addr) = metrics {
+        let server = Arc::clone(&server);
+        println!("Metrics on http://{}/metrics", addr);
+        std::thread::spawn(move || {
+            if let Err(e) = serve::serve(server, &addr, serve_config, true) {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        });
+    }
+    if let Err(e) = daemon::serve(server, &socket) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+fn serve_journal(listen: String, openapi: bool, pair: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    if openapi {
+        match openapi::spec().to_pretty_json() {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+        return;
+    }
+
+    let config_file = config_file(config_path.as_deref());
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let mut serve_config = config.as_ref().and_then(|c| c.serve.clone()).unwrap_or_default();
+    if pair {
+        let paired = serve::lan_url(&listen, serve_config.base_path.as_deref()).and_then(|url| {
+            let token = serve::ApiToken {
+                name: format!("paired-{}", chrono::Local::now().format("%Y%m%d-%H%M%S")),
+                token: serve::mint_token()?,
+                scopes: vec!["read".to_string(), "write".to_string()],
+                rate_limit: None,
+            };
+            let code = qrcodegen::QrCode::encode_binary(
+                format!("{}/quick?token={}", url, token.token).as_bytes(),
+                qrcodegen::QrCodeEcc::Medium,
+            )
+            .map_err(|e| format!("Can't fit the pairing URL in a QR code: {}", e))?;
+            Ok((url, token, code))
+        });
+        let (url, token, code) = paired.unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            exit(1);
+        });
+        match &config_file {
+            Some(file) => match save_serve_token(file, &token) {
+                Ok(()) => println!("Added token '{}' to {}", token.name, file.display()),
+                Err(e) => eprintln!("Warning: {}; the token only works until serve stops", e),
+            },
+            None => eprintln!("Warning: no config file to save the token in; it only works until serve stops"),
+        }
+        if serve_config.tokens.is_empty() {
+            println!("Clients now need a token; requests without one get a 401");
+        }
+        print!("{}", serve::render_qr(&code));
+        println!("{}/quick?token={}", url, token.token);
+        println!("Scan with the phone, then POST text=... to that URL (the token also works as a Bearer token)");
+        serve_config.tokens.push(token);
+    }
+
+    if let Err(e) = serve::check_access(&listen, &serve_config) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+    println!("Serving {} on http://{}", journal_path.display(), listen);
+    let server = Arc::new(Mutex::new(daemon::Daemon::new(journal_path, config.and_then(|c| c.author))));
+    if let Err(e) = serve::serve(server, &listen, serve_config, false) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+/// Add `[[serve.tokens]]` to the end of the config file, under the selected profile if it
+/// lists tokens of its own, since those replace the base ones
+fn save_serve_token(file: &Path, token: &serve::ApiToken) -> Result<(), String> {
+    let content = fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    let table: toml::Table = toml::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", file.display(), e))?;
+    let profile_tokens = |profile: &String| {
+        table.get("profile")?.get(profile)?.get("serve")?.get("tokens").map(|_| profile.clone())
+    };
+    let header = match PROFILE.get().and_then(profile_tokens) {
+        Some(profile) => format!("profile.{}.serve.tokens", toml::Value::String(profile)),
+        None => "serve.tokens".to_string(),
+    };
+    let fields = toml::to_string(token).map_err(|e| format!("Failed to encode token: {}", e))?;
+    let separator = if content.is_empty() || content.ends_with('\n') { "" } else { "\n" };
+    let updated = format!("{}{}\n[[{}]]\n{}", content, separator, header, fields);
+    // Refuse rather than break a config that defines tokens in a way this can't extend
+    toml::from_str::<toml::Table>(&updated).map_err(|_| format!("Can't add a [[{}]] entry to {}", header, file.display()))?;
+    let tmp = file.with_extension("toml.tmp");
+    fs::write(&tmp, updated)
+        .and_then(|_| fs::rename(&tmp, file))
+        .map_err(|e| format!("Failed to write {}: {}", file.display(), e))
+}
+
+#[cfg(unix)]
+fn manage_service(action: ServiceAction, config_path: Option<PathBuf>) {
+    let absolute = |path: PathBuf| env::current_dir().map(|dir| dir.join(&path)).unwrap_or(path);
+
+    match action {
+        ServiceAction::Install { mode, dry_run, path } => {
+            let exe = match service::validate_mode(&mode).and_then(|_| {
+                env::current_exe().map_err(|e| format!("Could not locate the file-journal binary: {}", e))
+            }) {
+                Ok(exe) => exe,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    exit(1);
+                }
+            };
+
+            // Services don't start in the current directory, so pass absolute paths along
+            let mut args = Vec::new();
+            if let Some(config) = config_path {
+                args.extend(["--config".to_string(), absolute(config).to_string_lossy().to_string()]);
+            }
+            if let Some(profile) = PROFILE.get() {
+                args.extend(["--profile".to_string(), profile.clone()]);
+            }
+            args.push(mode.clone());
+            if let Some(path) = path {
+                args.extend(["--path".to_string(), absolute(path).to_string_lossy().to_string()]);
+            }
+
+            let contents = service::render(&mode, &exe, &args);
+            if dry_run {
+                print!("{}", contents);
+                return;
+            }
+            match service::install(&mode, &contents) {
+                Ok(unit) => println!("Installed and started {} ({})", mode, unit.display()),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    exit(1);
+                }
+            }
+        }
+        ServiceAction::Status { mode } => {
+            let modes = match mode {
+                Some(mode) => vec![mode],
+                None => service::available_modes().into_iter().map(str::to_string).collect(),
+            };
+            for mode in modes {
+                match service::validate_mode(&mode).and_then(|_| service::status(&mode)) {
+                    Ok(state) => println!("{:<16} {}", mode, state),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        exit(1);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "network")]
+fn matrix_bridge(once: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let journal_path = require_journal_path(path, config.as_ref());
+    let author = config.as_ref().and_then(|c| c.author.clone());
+    let settings = config.and_then(|c| c.matrix).unwrap_or_default();
+
+    let (Some(homeserver), Some(token), Some(room_id)) = (settings.homeserver, settings.access_token, settings.room_id)
+    else {
+        eprintln!("Error: homeserver, access_token and room_id must be set under [matrix]");
+        exit(1);
+    };
+
+    let client = matrix::Client::new(&homeserver, token, room_id);
+    let mut since = matrix::load_since(&journal_path);
+    if since.is_none() {
+        // First run: start from now rather than importing the room's history
+        match client.sync(None, false) {
+            Ok((_, next_batch)) => since = Some(next_batch),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        }
+    }
+    if !once {
+        println!("Listening for Matrix messages (Ctrl-C to stop)");
+    }
+
+    loop {
+        let (messages, next_batch) = match client.sync(since.as_deref(), !once) {
+            Ok(result) => result,
+            Err(e) if !once => {
+                eprintln!("Warning: {}; retrying", e);
+                std::thread::sleep(std::time::Duration::from_secs(5));
+                continue;
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        };
+
+        for message in &messages {
+            if !settings.allowed_senders.is_empty() && !settings.allowed_senders.contains(&message.sender) {
+                continue;
+            }
+            let time = message_time(message.timestamp / 1000);
+            let line = message_line(&time, Some(&message.body), None);
+            match append_to_day(&journal_path, time.naive_local(), &line, author.clone()) {
+                Ok(entry) => println!("{}", language.with(i18n::Message::AppendedTo, entry.display())),
+                Err(e) => eprintln!("Warning: {}", e),
+            }
+        }
+
+        if let Err(e) = matrix::save_since(&journal_path, &next_batch) {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+        since = Some(next_batch);
+        if once {
+            return;
+        }
+    }
+}
+
+/// Append one message to the entry of the day it was sent, saving an attached photo under `assets/YYYY/MM/`
+#[cfg(feature = "network")]
+fn capture_telegram_message(
+    bot: &telegram::Bot,
+    journal_path: &Path,
+    update: &telegram::Incoming,
+    author: Option<String>,
+) -> Result<PathBuf, String> {
+    let time = message_time(update.date);
+    let image = match &update.photo_file_id {
+        Some(file_id) => {
+            let month_dir = PathBuf::from(time.format("%Y").to_string()).join(time.format("%m").to_string());
+            let stem = format!("{}-telegram-{}", time.format("%d-%H%M%S"), update.update_id);
+            let saved = bot.download(file_id, &journal_path.join("assets").join(&month_dir), &stem)?;
+            let name = saved.file_name().unwrap_or_default().to_string_lossy().to_string();
+            // Entries live in YYYY/MM/, two levels below the journal root
+            Some(format!("../../assets/{}/{}", month_dir.display(), name))
+        }
+        None => None,
+    };
+
+    let line = message_line(&time, update.text.as_deref(), image.as_deref());
+    append_to_day(journal_path, time.naive_local(), &line, author)
+}
+
+#[cfg(feature = "dictation")]
+fn dictate_entry(title: Option<String>, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let journal_path = require_journal_path(path, config.as_ref());
+    let Some(command) = config.as_ref().and_then(|c| c.dictation.clone()).map(|d| d.transcribe_command) else {
+        eprintln!("Error: No transcription command configured; set transcribe_command under [dictation]");
+        exit(1);
+    };
+
+    println!("Recording... press Enter to stop");
+    let recording = match dictate::record_until_enter() {
+        Ok(recording) if recording.samples.is_empty() => {
+            eprintln!("Error: Nothing was recorded");
+            exit(1);
+        }
+        Ok(recording) => recording,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    // The recording is kept next to other attachments, so nothing is lost if transcription fails
+    let now = chrono::Local::now();
+    let month_dir = PathBuf::from(now.format("%Y").to_string()).join(now.format("%m").to_string());
+    let name = format!("{}-dictation.wav", now.format("%d-%H%M%S"));
+    let audio = journal_path.join("assets").join(&month_dir).join(&name);
+    if let Err(e) = dictate::save_wav(&recording, &audio) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+    println!("Saved {:.0}s of audio to {}; transcribing...", recording.seconds(), audio.display());
+
+    let transcript = match dictate::transcribe(&command, &audio) {
+        Ok(transcript) => transcript,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    // Entries live in YYYY/MM/, two levels below the journal root
+    let note = format!("{}\n\n[Recording](../../assets/{}/{})", transcript, month_dir.display(), name);
+    let title = title.unwrap_or_else(|| "dictation".to_string());
+    let author = config.and_then(|c| c.author);
+    match write_entry(journal_path, &title, &note, frontmatter::Frontmatter::default(), author) {
+        Ok(path) => println!("{}", language.with(i18n::Message::CreatedEntry, path.display())),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+fn edit_entry(selector: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let entries = match resolve_selector(&journal_path, &selector) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    let entry = match entries.as_slice() {
+        [] => {
+            eprintln!("Error: {}", language.with(i18n::Message::NoEntriesMatch, &selector));
+            exit(1);
+        }
+        [entry] => entry.clone(),
+        _ => match pick_entry(&entries) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => return,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+        },
+    };
+
+    open_in_editor(&journal_path, &entry, config.as_ref());
+}
+
+/// Open an entry in the configured editor, refusing compressed and archived ones
+fn open_in_editor(journal_path: &Path, entry: &Path, config: Option<&Config>) {
+    if !entry.to_string_lossy().ends_with(".md") {
+        eprintln!("Error: {} is compressed or archived and can't be edited", entry.display());
+        exit(1);
+    }
+    let command = editor::command(config.and_then(|c| c.editor.as_deref()));
+    let before = changes::hash(entry);
+    if let Err(e) = editor::open(&command, entry) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+    record_change(journal_path, changes::Action::Edit, entry, None, before);
+}
+
+/// Numbered picker over several matching entries; `None` if cancelled
+fn pick_entry(entries: &[PathBuf]) -> Result<Option<PathBuf>, String> {
+    for (i, entry) in entries.iter().enumerate() {
+        let title = read_entry(entry)
+            .ok()
+            .and_then(|content| entry_title(frontmatter::parse(&content).1))
+            .unwrap_or_default();
+        println!("{:>3}. {}  {}", i + 1, entry.display(), title);
+    }
+    loop {
+        print!("Edit which entry? [1-{}, q to cancel] ", entries.len());
+        std::io::Write::flush(&mut std::io::stdout()).map_err(|e| e.to_string())?;
+        let mut answer = String::new();
+        if std::io::stdin().read_line(&mut answer).map_err(|e| e.to_string())? == 0 {
+            return Ok(None);
+        }
+        match editor::parse_choice(&answer, entries.len()) {
+            Ok(choice) => return Ok(choice.map(|i| entries[i].clone())),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+/// Key bindings from `[tui.keys]` on top of the defaults
+#[cfg(feature = "tui")]
+fn tui_keymap(config: Option<&Config>) -> Result<keys::Keymap, String> {
+    keys::Keymap::new(config.and_then(|c| c.tui.as_ref()).and_then(|t| t.keys.as_ref()))
+}
+
+fn config_theme(config: Option<&Config>) -> Result<theme::Theme, String> {
+    theme::Theme::from_config(config.and_then(|c| c.theme.as_ref()))
+}
+
+fn config_language(config: Option<&Config>) -> i18n::Language {
+    i18n::Language::detect(config.and_then(|c| c.locale.as_deref()))
+}
+
+/// `plugins_dir` from the config, else ~/.config/file-journal/plugins
+#[cfg(any(feature = "plugins", feature = "wasm"))]
+fn plugins_dir(config: Option<&Config>) -> PathBuf {
+    config.and_then(|c| c.plugins_dir.clone()).unwrap_or_else(|| {
+        dirs::home_dir().map(|home| home.join(".config").join("file-journal").join("plugins")).unwrap_or_default()
+    })
+}
+
+/// Plugin scripts from `plugins_dir` or the default folder, exiting if one doesn't compile
+#[cfg(feature = "plugins")]
+fn load_plugins(config: Option<&Config>, journal_path: &Path) -> plugins::Plugins {
+    let author = config.and_then(|c| c.author.clone());
+    plugins::Plugins::load(&plugins_dir(config), journal_path, author).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        exit(1);
+    })
+}
+
+/// `file-journal-<name>` on PATH, git-style
+fn find_external_command(name: &str) -> Option<PathBuf> {
+    if name.contains(['/', '\\']) {
+        return None;
+    }
+    let program = format!("file-journal-{}{}", name, env::consts::EXE_SUFFIX);
+    env::split_paths(&env::var_os("PATH")?).map(|dir| dir.join(&program)).find(|path| path.is_file())
+}
+
+/// Run an unknown subcommand: a plugin script command if one matches, else `file-journal-<name>`
+/// from PATH with FILE_JOURNAL_PATH and FILE_JOURNAL_CONFIG set
+fn run_external_command(args: Vec<String>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path.clone());
+    let (name, rest) = args.split_first().expect("clap passes the subcommand name");
+    let journal_path = get_journal_path(None, config.as_ref());
+
+    #[cfg(feature = "plugins")]
+    let plugins = {
+        // Like `new`, fall back to the current directory so a mistyped command still gets a useful error
+        let journal_path = journal_path.clone().unwrap_or_else(|| env::current_dir().expect("Failed to get current directory"));
+        let plugins = load_plugins(config.as_ref(), &journal_path);
+        match plugins.run_command(name, rest) {
+            Some(Ok(())) => return,
+            Some(Err(e)) => {
+                eprintln!("Error: {}", e);
+                exit(1);
+            }
+            None => plugins,
+        }
+    };
+
+    let Some(program) = find_external_command(name) else {
+        eprintln!("Error: Unknown command '{}'", name);
+        #[cfg(feature = "plugins")]
+        {
+            let commands = plugins.commands();
+            if !commands.is_empty() {
+                eprintln!("Plugin commands: {}", commands.join(", "));
+            }
+        }
+        exit(2);
+    };
+
+    let mut command = std::process::Command::new(&program);
+    command.args(rest);
+    if let Some(journal_path) = journal_path {
+        command.env("FILE_JOURNAL_PATH", journal_path);
+    }
+    if let Some(config_file) = config_file(config_path.as_deref()) {
+        command.env("FILE_JOURNAL_CONFIG", config_file);
+    }
+    if let Some(profile) = PROFILE.get() {
+        command.env("FILE_JOURNAL_PROFILE", profile);
+    }
+    let status = command.status().unwrap_or_else(|e| {
+        eprintln!("Error: Failed to run {}: {}", program.display(), e);
+        exit(1);
+    });
+    if !status.success() {
+        exit(status.code().unwrap_or(1));
+    }
+}
+
+fn list_journal(tree: bool, json: bool, words: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let years = match overview::journal_overview(&journal_path, words) {
+        Ok(years) => years,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    if json {
+        match serde_json::to_string(&years) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error: Failed to serialize to JSON: {}", e);
+                exit(1);
+            }
+        }
+    } else if tree {
+        print!("{}", overview::render_tree(&years));
+    } else {
+        print!("{}", overview::render_flat(&years));
+    }
+    if years.is_empty() {
+        exit(1);
+    }
+}
+
+fn print_path(today: bool, month: bool, year: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let date = chrono::Local::now().date_naive();
+    let (layout_year, layout_month, _) = layout_date(date);
+    let target = if today {
+        latest_entry_on(&journal_path, date).ok().flatten()
+    } else if month {
+        Some(journal_path.join(layout_year.to_string()).join(format!("{:02}", layout_month)))
+    } else if year {
+        Some(journal_path.join(layout_year.to_string()))
+    } else {
+        Some(journal_path)
+    };
+
+    match target.filter(|target| target.exists()) {
+        Some(target) => println!("{}", target.display()),
+        None => exit(1),
+    }
+}
+
+fn open_journal(print: bool, month: bool, editor: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let dir = if month { resolve_target_dir(&journal_path, chrono::Local::now().date_naive()) } else { Ok(journal_path) };
+    let result = dir.and_then(|dir| {
+        if print {
+            println!("{}", dir.display());
+            Ok(())
+        } else if editor {
+            editor::open(&editor::command(config.as_ref().and_then(|c| c.editor.as_deref())), &dir)
+        } else {
+            editor::open(editor::file_manager(), &dir)
+        }
+    });
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+#[cfg(feature = "tui")]
+fn compare_days(first: Option<String>, second: Option<String>, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let day = |selector: &str| {
+        parse_day(selector).unwrap_or_else(|| {
+            eprintln!("Error: Invalid day '{}': expected today, yesterday or YYYY-MM-DD", selector);
+            exit(1);
+        })
+    };
+    let (first, second) = match (first, second) {
+        (Some(first), Some(second)) => (day(&first), day(&second)),
+        (Some(first), None) => (day(&first), day("today")),
+        (None, _) => {
+            let today = day("today");
+            // Same day a year ago; 29 February falls back to the 28th
+            (today.checked_sub_months(chrono::Months::new(12)).unwrap_or(today), today)
+        }
+    };
+
+    let result = tui_keymap(config.as_ref()).and_then(|keymap| {
+        let theme = config_theme(config.as_ref())?;
+        compare::Comparison::new(&journal_path, keymap, theme, config_language(config.as_ref()), first, second).run()
+    });
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+#[cfg(feature = "tui")]
+fn focus_entry(
+    selector: String,
+    title: Option<String>,
+    typewriter: bool,
+    autosave: u64,
+    path: Option<PathBuf>,
+    config_path: Option<PathBuf>,
+) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+    let author = config.as_ref().and_then(|c| c.author.clone());
+
+    let entry = match title {
+        Some(title) => write_entry(journal_path.clone(), title.trim_end_matches(".md"), "", frontmatter::Frontmatter::default(), author),
+        None => match resolve_selector(&journal_path, &selector) {
+            Ok(entries) if entries.is_empty() && selector == "today" => {
+                write_entry(journal_path.clone(), "journal", "", frontmatter::Frontmatter::default(), author)
+            }
+            Ok(entries) if entries.is_empty() => Err(format!("No entries match '{}'", selector)),
+            Ok(entries) if entries.len() == 1 => Ok(entries[0].clone()),
+            Ok(entries) => match pick_entry(&entries) {
+                Ok(Some(entry)) => Ok(entry),
+                Ok(None) => return,
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        },
+    };
+    let autosave = std::time::Duration::from_secs(autosave.max(1));
+    let result = entry
+        .and_then(|entry| Ok((entry, tui_keymap(config.as_ref())?, config_theme(config.as_ref())?)))
+        .and_then(|(entry, keymap, theme)| {
+            let before = changes::hash(&entry);
+            let summary = focus::Session::open(&entry, keymap, theme, config_language(config.as_ref()), typewriter, autosave)
+                .and_then(focus::Session::run)?;
+            record_change(&journal_path, changes::Action::Edit, &entry, None, before);
+            Ok(summary)
+        });
+    match result {
+        Ok(summary) => println!("{}", summary),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+fn read_aloud(selector: String, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let language = config_language(config.as_ref());
+    let journal_path = require_journal_path(path, config.as_ref());
+    let command = config
+        .and_then(|c| c.speech)
+        .and_then(|s| s.command)
+        .unwrap_or_else(|| speech::default_command().to_string());
+
+    let entries = match resolve_selector(&journal_path, &selector) {
+        Ok(entries) if entries.is_empty() => {
+            eprintln!("Error: {}", language.with(i18n::Message::NoEntriesMatch, &selector));
+            exit(1);
+        }
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    for entry in entries {
+        let content = match read_entry(&entry) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", entry.display(), e);
+                continue;
+            }
+        };
+        println!("{}", entry.display());
+        let script = speech::script(entry_date(&entry), frontmatter::parse(&content).1, language);
+        if let Err(e) = speech::speak(&command, &script) {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
+}
+
+/// Options of a plain `search`
+struct SearchQuery {
+    query: String,
+    ignore_case: bool,
+    regex: bool,
+    year: Option<i32>,
+    month: Option<u32>,
+    format: String,
+    /// Context lines before and after each match
+    before: usize,
+    after: usize,
+    limit: Option<usize>,
+}
+
+fn search_entries(search: SearchQuery, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let grep = match search.format.as_str() {
+        "lines" => false,
+        "grep" => true,
+        other => {
+            eprintln!("Error: Unsupported search format '{}' (expected 'lines' or 'grep')", other);
+            exit(1);
+        }
+    };
+    let pattern = match search::pattern(&search.query, search.regex, search.ignore_case) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    let entries = if search.year.is_some() || search.month.is_some() {
+        find_entries(&journal_path, None, search.month, search.year)
+    } else {
+        find_all_entries(&journal_path)
+    };
+    let entries = match entries {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    // Newest first
+    let mut found = 0;
+    for entry in entries.iter().rev() {
+        if search.limit.is_some_and(|limit| found >= limit) {
+            break;
+        }
+        let Ok(content) = read_entry(entry) else {
+            continue;
+        };
+        let lines = search::lines_with_context(&content, &pattern, search.before, search.after);
+        if lines.is_empty() {
+            continue;
+        }
+        // Groups from different entries are set apart like gaps within one
+        if found > 0 && search.before + search.after > 0 {
+            println!("--");
+        }
+        print!("{}", search::render(&entry.display().to_string(), &lines, grep));
+        found += 1;
+    }
+    if found == 0 {
+        exit(1);
+    }
+}
+
+#[cfg(feature = "semantic")]
+fn semantic_search(query: String, limit: usize, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    interrupt::catch();
+    match semantic::search(&journal_path, &query, limit) {
+        Ok(found) if found.is_empty() => exit(1),
+        Ok(found) => {
+            for (entry, score) in found {
+                println!("{:.2}  {}", score, entry.display());
+            }
+        }
+        Err(_) if interrupt::requested() => {
+            eprintln!("Indexing interrupted; entries embedded so far are saved");
+            exit(interrupt::EXIT_CODE);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    }
 }
 
-fn main() {
-    let cli = Cli::parse();
+fn compress_entries(before: i32, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
 
-    match cli.command {
-        Commands::New { title, note, path } => create_entry(title, note, path, cli.config),
-        Commands::Init { path } => init_config(path),
-        Commands::Get { day, month, year, week, path, format } => {
-            get_entries(day, month, year, week, path, cli.config, format)
+    interrupt::catch();
+    match compress::compress_before(&journal_path, before) {
+        Ok(compressed) => {
+            for entry in &compressed {
+                println!("Compressed: {}", entry.display());
+            }
+            println!("Compressed {} entries from before {}", compressed.len(), before);
+            if interrupt::requested() {
+                eprintln!("Compression interrupted; run it again to compress the rest");
+                exit(interrupt::EXIT_CODE);
+            }
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
         }
     }
 }
 
-fn load_config(config_path: Option<PathBuf>) -> Option<Config> {
-    // If config path is specified, use that file.
-    if let Some(path) = config_path {
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => {
-                    match toml::from_str(&content) {
-                        Ok(config) => return Some(config),
-                        Err(e) => {
-                            eprintln!("Warning: Failed to parse config at {}: {}", path.display(), e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Warning: Failed to read config at {}: {}", path.display(), e);
-                }
-            }
+fn archive_year(year: i32, zip: bool, remove: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let format = if zip { archive::Format::Zip } else { archive::Format::TarGz };
+    let (archived, count) = match archive::create(&journal_path, year, format) {
+        Ok(archived) => archived,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+    let files = if count == 1 { "file" } else { "files" };
+    println!("Archived {} {} from {} into {} (verified)", count, files, year, archived.display());
+
+    if remove {
+        let year_dir = journal_path.join(year.to_string());
+        if let Err(e) = fs::remove_dir_all(&year_dir) {
+            eprintln!("Error: Failed to remove {}: {}", year_dir.display(), e);
+            exit(1);
         }
+        println!("Removed {}", year_dir.display());
     }
+}
 
-    // If set, FILE_JOURNAL_CONFIG_DIR must point to a directory containing config.toml.
-    if let Some(config_dir) = env::var_os("FILE_JOURNAL_CONFIG_DIR") {
-        let config_file_path = PathBuf::from(config_dir).join("config.toml");
-        if config_file_path.exists() {
-            match fs::read_to_string(&config_file_path) {
-                Ok(content) => {
-                    match toml::from_str(&content) {
-                        Ok(config) => return Some(config),
-                        Err(e) => {
-                            eprintln!("Warning: Failed to parse config at {}: {}", config_file_path.display(), e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Warning: Failed to read config at {}: {}", config_file_path.display(), e);
+fn doctor(fix: bool, path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let problems = match doctor::check(&journal_path) {
+        Ok(problems) => problems,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
+
+    let mut remaining = 0;
+    let mut operation = trash::Operation::new("doctor --fix");
+    for problem in &problems {
+        let shown = problem.path().strip_prefix(&journal_path).unwrap_or(problem.path());
+        if fix && problem.fixable() {
+            match fix_problem(&journal_path, problem, &mut operation) {
+                Ok(()) => {
+                    println!("Fixed {}: {}", shown.display(), problem.describe());
+                    continue;
                 }
+                Err(e) => eprintln!("Warning: {}", e),
             }
-        } else {
-            eprintln!("Warning: FILE_JOURNAL_CONFIG_DIR is set but {} not found", config_file_path.display());
         }
+        println!("{}: {}", shown.display(), problem.describe());
+        remaining += 1;
     }
+    record_undo(&journal_path, operation);
 
-    // Try current directory .file-journal.toml
-    let local_config = Path::new(".file-journal.toml");
-    if local_config.exists() {
-        if let Ok(content) = fs::read_to_string(local_config) {
-            if let Ok(config) = toml::from_str(&content) {
-                return Some(config);
-            }
+    if problems.is_empty() {
+        println!("No problems found in {}", journal_path.display());
+    } else if remaining > 0 {
+        let fixable = problems.iter().filter(|p| p.fixable()).count();
+        if !fix && fixable > 0 {
+            println!("{} problem(s) found, {} fixable with --fix", remaining, fixable);
+        } else {
+            println!("{} problem(s) need fixing by hand", remaining);
         }
+        exit(1);
+    }
+}
+
+fn lint(path: Option<PathBuf>, config_path: Option<PathBuf>) {
+    let config = load_config(config_path);
+    let journal_path = require_journal_path(path, config.as_ref());
+
+    let schemas = config.as_ref().and_then(|c| c.schemas.clone()).unwrap_or_default();
+    if schemas.is_empty() {
+        println!("No [schemas] in the config, so there is nothing to check");
+        return;
     }
+    let entries = match find_all_entries(&journal_path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+    };
 
-    // Try home directory ~/.config/file-journal/config.toml
-    if let Some(home) = dirs::home_dir() {
-        let home_config = home.join(".config").join("file-journal").join("config.toml");
-        if home_config.exists() {
-            if let Ok(content) = fs::read_to_string(&home_config) {
-                if let Ok(config) = toml::from_str(&content) {
-                    return Some(config);
+    let mut checked = 0;
+    let mut problems = 0;
+    for entry in &entries {
+        let entry_frontmatter = entry_frontmatter(entry);
+        let Some((kind, schema)) = entry_frontmatter.get("kind").and_then(|kind| schemas.get_key_value(kind)) else {
+            continue;
+        };
+        checked += 1;
+        let shown = entry.strip_prefix(&journal_path).unwrap_or(entry);
+        match schema::validate(schema, &entry_frontmatter) {
+            Ok(found) => {
+                for problem in &found {
+                    println!("{}: {}", shown.display(), problem);
                 }
+                problems += found.len();
+            }
+            Err(e) => {
+                eprintln!("Error: [schemas.{}]: {}", kind, e);
+                exit(1);
             }
         }
     }
 
-    None
-}
-
-fn get_journal_path(explicit_path: Option<PathBuf>, config: Option<Config>) -> Option<PathBuf> {
-    // Explicit path takes priority
-    if let Some(path) = explicit_path {
-        return Some(path);
+    if problems == 0 {
+        println!("{} entries checked against their schema, no problems", checked);
+    } else {
+        println!("{} problem(s) in {} checked entries", problems, checked);
+        exit(1);
     }
+}
 
-    // Then config default_path
-    if let Some(cfg) = config {
-        if let Some(path) = cfg.default_path {
-            return Some(path);
+/// Fix one problem, keeping what `undo` needs to take the fix back
+fn fix_problem(journal_path: &Path, problem: &doctor::Problem, operation: &mut trash::Operation) -> Result<(), String> {
+    match problem {
+        doctor::Problem::DateMismatch { path, .. } => {
+            let before = changes::hash(path);
+            operation.keep(journal_path, path)?;
+            problem.fix()?;
+            operation.wrote(journal_path, path)?;
+            record_change(journal_path, changes::Action::Edit, path, None, before);
+        }
+        doctor::Problem::EmptyFolder(folder) => {
+            problem.fix()?;
+            operation.removed_folder(journal_path, folder)?;
         }
+        _ => problem.fix()?,
     }
-
-    None
+    Ok(())
 }
 
-fn resolve_target_dir(journal_path: PathBuf) -> Result<PathBuf, String> {
-    let now = chrono::Local::now();
-    let year = now.year().to_string();
-    let month = format!("{:02}", now.month());
-    let _day = now.day();
+fn init_config(path: Option<PathBuf>) {
+    let config_path = if let Some(p) = path {
+        p
+    } else if let Some(home) = dirs::home_dir() {
+        home.join(".config").join("file-journal").join("config.toml")
+    } else {
+        eprintln!("Error: Could not determine config path");
+        exit(1);
+    };
 
-    // Build path: journal_path/YYYY/MM
-    let target_dir = journal_path.join(&year).join(&month);
+    // Ask for default journal path
+    println!("Enter the default journal path (e.g., /Users/t/Documents/journal):");
+    let mut input = String::new();
+    std::io::stdin()
+        .read_line(&mut input)
+        .expect("Failed to read input");
+    let default_path = PathBuf::from(input.trim());
 
-    // Create directories if they don't exist
-    if !target_dir.exists() {
-        fs::create_dir_all(&target_dir)
-            .map_err(|e| format!("Failed to create directories: {}", e))?;
+    let config = Config {
+        default_path: Some(default_path),
+        ..Default::default()
+    };
+
+    // Create parent directories if needed
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent).expect("Failed to create config directory");
     }
 
-    // Validate the structure
-    let month_folder = target_dir
-        .file_name()
-        .and_then(|name| name.to_str())
-        .ok_or("Failed to get month folder name")?;
+    let toml_string = toml::to_string_pretty(&config).expect("Failed to serialize config");
+    fs::write(&config_path, toml_string).expect("Failed to write config");
 
-    if !is_valid_month(month_folder) {
-        return Err(format!("Invalid month folder: {}", month_folder));
+    println!("Created config at: {}", config_path.display());
+}
+
+fn is_valid_month(folder_name: &str) -> bool {
+    if folder_name.len() != 2 {
+        return false;
     }
 
-    let year_folder = target_dir
-        .parent()
-        .and_then(|p| p.file_name())
-        .and_then(|name| name.to_str())
-        .ok_or("Failed to get year folder name")?;
+    match folder_name.parse::<u32>() {
+        Ok(month) => (1..=12).contains(&month),
+        Err(_) => false,
+    }
+}
 
-    if !is_valid_year(year_folder) {
-        return Err(format!("Invalid year folder: {}", year_folder));
+fn is_valid_year(folder_name: &str) -> bool {
+    if folder_name.len() != 4 {
+        return false;
     }
 
-    Ok(target_dir)
+    folder_name.parse::<u32>().is_ok()
 }
 
-fn create_entry(title: String, note: Option<String>, path: Option<PathBuf>, config_path: Option<PathBuf>) {
-    // Check if title ends with .md
-    if !title.ends_with(".md") {
-        eprintln!("Error: Title must end with .md");
-        std::process::exit(1);
+fn sanitize_title(title: &str) -> String {
+    let mut safe = title
+        .replace([' ', '/', '\\', ':', '?', '*', '"', '\'', '<', '>', '|'], "-");
+
+    // Collapse multiple hyphens
+    while safe.contains("--") {
+        safe = safe.replace("--", "-");
     }
 
-    // Load config
-    let config = load_config(config_path);
+    // Trim trailing hyphen
+    safe.trim_end_matches('-').to_string()
+}
 
-    // Determine journal path
-    let journal_path = match get_journal_path(path, config) {
-        Some(p) => p,
-        None => {
-            // Fall back to current directory
-            env::current_dir().expect("Failed to get current directory")
-        }
-    };
+/// Parse an entry's frontmatter (empty if the entry has none or can't be read)
+fn entry_frontmatter(path: &Path) -> frontmatter::Frontmatter {
+    read_entry(path)
+        .map(|content| frontmatter::parse(&content).0)
+        .unwrap_or_default()
+}
 
-    // Resolve target directory (create year/month folders if needed)
-    let target_dir = match resolve_target_dir(journal_path) {
-        Ok(dir) => dir,
-        Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+/// Tags as given on the command line: `#` prefixes and blanks removed, duplicates dropped
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').trim();
+        if !tag.is_empty() && !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            normalized.push(tag.to_string());
         }
-    };
+    }
+    normalized
+}
 
-    let now = chrono::Local::now();
-    let year = now.year();
-    let month = now.month();
-    let day = now.day();
-    let hour = now.hour();
-    let minute = now.minute();
-    let second = now.second();
-
-    // Create filename: dd-HHMMSS-<title>.md for easy sorting
-    let title_part = title.trim_end_matches(".md");
-    let safe_title = sanitize_title(title_part);
-    let filename = format!("{:02}-{:02}{:02}{:02}-{}.md", day, hour, minute, second, safe_title);
-    let filepath = target_dir.join(&filename);
+/// Whether frontmatter lists every one of `tags`, ignoring case
+fn has_tags(entry_frontmatter: &frontmatter::Frontmatter, tags: &[String]) -> bool {
+    let own = entry_frontmatter.list("tags");
+    tags.iter().all(|tag| own.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+}
 
-    // Check if file already exists
-    if filepath.exists() {
-        eprintln!("Error: File '{}' already exists", filename);
-        std::process::exit(1);
+/// The configured entry filename format, or the default `{day}-{time}-{title}`
+fn filename_format() -> &'static filename::FilenameFormat {
+    static DEFAULT: std::sync::LazyLock<filename::FilenameFormat> = std::sync::LazyLock::new(Default::default);
+    FILENAME_FORMAT.get().unwrap_or(&DEFAULT)
+}
+
+/// Title part of an entry's filename (the whole filename if it doesn't follow the format)
+fn entry_slug(path: &Path) -> &str {
+    let filename = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    filename_format().parse(filename).and_then(|parsed| parsed.title).unwrap_or(filename)
+}
+
+/// `--title` or `--title-regex` as a pattern for `entry_slug`
+fn title_pattern(list: &ListArgs) -> Result<Option<regex::Regex>, String> {
+    match (&list.title, &list.title_regex) {
+        (Some(title), _) => search::pattern(title, false, true).map(Some),
+        (None, Some(title)) => search::pattern(title, true, false).map(Some),
+        (None, None) => Ok(None),
     }
+}
 
-    // Create the file with a template (DD-MM-YYYY format)
-    let note_content = note.unwrap_or_default();
-    let template = format!(
-        "# {}\n\nDate: {:02}-{:02}-{}\n\n{}\n",
-        title.trim_end_matches(".md"),
-        day,
-        month,
-        year,
-        note_content
-    );
+/// Day of month an entry's filename says it was written on
+fn entry_day(filename: &str) -> Option<u32> {
+    filename_format().day(filename)
+}
 
-    fs::write(&filepath, template).expect("Failed to create file");
+/// Gregorian date of an entry derived from its `YYYY/MM/dd-...` location
+fn entry_date(path: &Path) -> Option<chrono::NaiveDate> {
+    let day = entry_day(path.file_name()?.to_str()?)?;
+    let month_dir = path.parent()?;
+    let month = month_dir.file_name()?.to_str()?.parse().ok()?;
+    let year = month_dir.parent()?.file_name()?.to_str()?.parse().ok()?;
+    date_from_layout(year, month, day)
+}
 
-    println!("Created journal entry: {}", filepath.display());
+/// Sort key that puts entries in the order they were written: their date, then the time in
+/// the filename, then the path (entries without a date come first)
+fn entry_order(path: &Path) -> (Option<chrono::NaiveDate>, Option<&str>, &Path) {
+    let filename = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    let time = filename_format().parse(filename).and_then(|parsed| parsed.time);
+    (entry_date(path), time, path)
 }
 
-fn get_entries(
-    day: Option<u32>,
-    month: Option<u32>,
-    year: Option<i32>,
-    week: bool,
-    path: Option<PathBuf>,
-    config_path: Option<PathBuf>,
-    format: String,
-) {
-    // Load config
-    let config = load_config(config_path);
+/// Sort entries into the order they were written
+fn sort_entries(entries: &mut [PathBuf]) {
+    entries.sort_by(|a, b| entry_order(a).cmp(&entry_order(b)));
+}
 
-    // Determine journal path
-    let journal_path = match get_journal_path(path, config) {
-        Some(p) => p,
-        None => {
-            eprintln!("Error: No journal path specified. Use --path or set up config with 'init'");
-            std::process::exit(1);
-        }
-    };
+/// Title from the first `# ` heading of an entry body
+fn entry_title(body: &str) -> Option<String> {
+    body.lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string())
+}
 
-    let entries = if week {
-        match find_entries_week(&journal_path) {
-            Ok(e) => e,
-            Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
-            }
-        }
-    } else {
-        match find_entries(&journal_path, day, month, year) {
-            Ok(e) => e,
-            Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
-            }
-        }
-    };
+/// Whether a filename looks like a journal entry (plain or gzipped markdown)
+fn is_entry_file(filename: &str) -> bool {
+    filename.ends_with(".md") || filename.ends_with(compress::GZ_EXTENSION)
+}
 
-    // Output results
-    match format.as_str() {
-        "json" => {
-            let paths: Vec<String> = entries.iter()
-                .map(|p| p.to_string_lossy().to_string())
-                .collect();
-            match serde_json::to_string(&paths) {
-                Ok(json) => println!("{}", json),
-                Err(e) => {
-                    eprintln!("Error: Failed to serialize to JSON: {}", e);
-                    std::process::exit(1);
-                }
-            }
-        }
-        "content" => {
-            for entry in &entries {
-                println!("{}", entry.display());
-                println!("{}", "-".repeat(40));
-                match fs::read_to_string(entry) {
-                    Ok(content) => println!("{}", content),
-                    Err(e) => eprintln!("Error reading {}: {}", entry.display(), e),
-                }
-                println!();
-            }
-        }
-        _ => {
-            // Default: just paths
-            for entry in &entries {
-                println!("{}", entry.display());
-            }
-        }
+/// Read an entry's content, transparently decompressing `.md.gz` files and
+/// reading entries that live inside `YYYY.zip` archives
+fn read_entry(path: &Path) -> std::io::Result<String> {
+    if !path.exists()
+        && let Some((zip_path, name)) = archive::split_zip_path(path)
+    {
+        archive::read_zip_entry(&zip_path, &name)
+    } else if path.to_string_lossy().ends_with(compress::GZ_EXTENSION) {
+        compress::read_gz(path)
+    } else {
+        fs::read_to_string(path)
     }
+}
 
-    // Exit with error code if no entries found (useful for scripts)
-    if entries.is_empty() {
-        std::process::exit(1);
+/// A `--from`/`--to` style day option (see `parse_day`), exiting if it doesn't parse
+fn parse_date_flag(value: Option<String>) -> Option<chrono::NaiveDate> {
+    value.map(|value| {
+        parse_day(&value).unwrap_or_else(|| {
+            eprintln!("Error: Invalid date '{}': expected YYYY-MM-DD or e.g. last monday", value);
+            exit(1);
+        })
+    })
+}
+
+/// Entries dated between `from` and `to`, both inclusive; an open end is unbounded
+fn find_entries_between(
+    journal_path: &Path,
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+) -> Result<Vec<PathBuf>, String> {
+    if from.is_none() && to.is_none() {
+        return find_all_entries(journal_path);
     }
+    walk_entries(journal_path, from, to)?.collect()
 }
 
-fn init_config(path: Option<PathBuf>) {
-    let config_path = if let Some(p) = path {
-        p
-    } else if let Some(home) = dirs::home_dir() {
-        home.join(".config").join("file-journal").join("config.toml")
-    } else {
-        eprintln!("Error: Could not determine config path");
-        std::process::exit(1);
+/// `find_entries_between` a month folder at a time, in the order the entries were written:
+/// only the folders that can hold days in the range are read, each when the walk reaches it
+fn walk_entries(
+    journal_path: &Path,
+    from: Option<chrono::NaiveDate>,
+    to: Option<chrono::NaiveDate>,
+) -> Result<impl Iterator<Item = Result<PathBuf, String>>, String> {
+    let folder = |date: chrono::NaiveDate| {
+        let (year, month, _) = layout_date(date);
+        (year, month)
+    };
+    let (first, last) = (from.map(folder), to.map(folder));
+    let wanted = move |year: i32, month: Option<u32>| {
+        first.is_none_or(|first| (year, month.unwrap_or(12)) >= first)
+            && last.is_none_or(|last| (year, month.unwrap_or(1)) <= last)
     };
 
-    // Ask for default journal path
-    println!("Enter the default journal path (e.g., /Users/t/Documents/journal):");
-    let mut input = String::new();
-    std::io::stdin()
-        .read_line(&mut input)
-        .expect("Failed to read input");
-    let default_path = PathBuf::from(input.trim());
+    let items = fs::read_dir(journal_path)
+        .map_err(|e| format!("Failed to read {}: {}", journal_path.display(), e))?;
+    let mut years: Vec<(i32, PathBuf)> = items
+        .flatten()
+        .filter_map(|item| {
+            let name = item.file_name().to_str()?.to_string();
+            let path = item.path();
+            if is_valid_year(&name) && path.is_dir() {
+                return Some((name.parse().ok()?, path));
+            }
+            // Archived years are read straight from YYYY.zip when the folder is gone
+            let year = name.strip_suffix(".zip").filter(|year| is_valid_year(year) && !journal_path.join(year).exists())?;
+            Some((year.parse().ok()?, path))
+        })
+        .filter(|(year, _)| wanted(*year, None))
+        .collect();
+    years.sort();
 
-    let config = Config {
-        default_path: Some(default_path),
+    type Folders = Box<dyn Iterator<Item = Result<Vec<PathBuf>, String>>>;
+    let folders = years.into_iter().flat_map(move |(year, path)| -> Folders {
+        if !path.is_dir() {
+            return Box::new(std::iter::once(archive::list_zip_entries(&path, None, None)));
+        }
+        let Ok(months) = fs::read_dir(&path) else {
+            return Box::new(std::iter::empty());
+        };
+        let mut months: Vec<(u32, PathBuf)> = months
+            .flatten()
+            .filter_map(|month| {
+                let name = month.file_name().to_str().filter(|name| is_valid_month(name))?.to_string();
+                Some((name.parse().ok()?, month.path()))
+            })
+            .filter(|(month, _)| wanted(year, Some(*month)))
+            .collect();
+        months.sort();
+        Box::new(months.into_iter().map(|(_, folder)| {
+            let files = fs::read_dir(folder).into_iter().flatten().flatten();
+            Ok(files.filter(|file| file.file_name().to_str().is_some_and(is_entry_file)).map(|file| file.path()).collect())
+        }))
+    });
+
+    let in_range = move |entry: &PathBuf| {
+        entry_date(entry).is_some_and(|date| from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to))
     };
+    Ok(folders.flat_map(move |folder| match folder {
+        Ok(mut entries) => {
+            entries.retain(in_range);
+            sort_entries(&mut entries);
+            entries.into_iter().map(Ok).collect()
+        }
+        Err(e) => vec![Err(e)],
+    }))
+}
 
-    // Create parent directories if needed
-    if let Some(parent) = config_path.parent() {
-        fs::create_dir_all(parent).expect("Failed to create config directory");
+/// `--month` as a number or an English name, full or abbreviated
+fn parse_month(value: &str) -> Result<u32, String> {
+    dates::month(value).ok_or_else(|| "expected 1-12 or a month name such as feb or February".to_string())
+}
+
+/// A single day given as `today`, `yesterday`, `YYYY-MM-DD` or another expression `dates`
+/// understands, such as `last monday` or `3 days ago`
+fn parse_day(selector: &str) -> Option<chrono::NaiveDate> {
+    dates::parse(selector, chrono::Local::now().date_naive())
+}
+
+/// Resolve an entry selector into matching entries.
+///
+/// Accepts a path to an entry file, `today`, `yesterday`, `YYYY-MM-DD`, `YYYY-MM` or `YYYY`.
+fn resolve_selector(journal_path: &Path, selector: &str) -> Result<Vec<PathBuf>, String> {
+    let as_path = PathBuf::from(selector);
+    if as_path.is_file() {
+        return Ok(vec![as_path]);
     }
 
-    let toml_string = toml::to_string_pretty(&config).expect("Failed to serialize config");
-    fs::write(&config_path, toml_string).expect("Failed to write config");
+    if let Some(date) = parse_day(selector) {
+        return find_entries(journal_path, Some(date.day()), Some(date.month()), Some(date.year()));
+    }
 
-    println!("Created config at: {}", config_path.display());
+    let parts: Vec<&str> = selector.split('-').collect();
+    match parts.as_slice() {
+        [year, month] if is_valid_year(year) && is_valid_month(month) => find_entries(
+            journal_path,
+            None,
+            Some(month.parse().unwrap_or_default()),
+            Some(year.parse().unwrap_or_default()),
+        ),
+        [year] if is_valid_year(year) => {
+            find_entries(journal_path, None, None, Some(year.parse().unwrap_or_default()))
+        }
+        _ => Err(format!(
+            "Invalid selector '{}': expected a file, 'today', 'yesterday', YYYY-MM-DD, YYYY-MM or YYYY",
+            selector
+        )),
+    }
 }
 
-fn is_valid_month(folder_name: &str) -> bool {
-    if folder_name.len() != 2 {
-        return false;
+/// `resolve_selector` for callers that aren't the journal's owner at a shell (the daemon,
+/// the HTTP APIs): a path only selects an entry when it lies inside the journal, relative
+/// paths being taken from the journal root
+pub fn resolve_journal_selector(journal_path: &Path, selector: &str) -> Result<Vec<PathBuf>, String> {
+    let as_path = journal_path.join(selector);
+    if as_path.is_file() {
+        let root =
+            journal_path.canonicalize().map_err(|e| format!("Failed to read {}: {}", journal_path.display(), e))?;
+        return match as_path.canonicalize() {
+            Ok(path) if path.starts_with(&root) => Ok(vec![as_path]),
+            _ => Err(format!("{} is outside the journal", selector)),
+        };
+    }
+    if Path::new(selector).is_absolute() || Path::new(selector).components().count() > 1 {
+        return Err(format!("No entry {} in the journal", selector));
     }
+    resolve_selector(journal_path, selector)
+}
 
-    match folder_name.parse::<u32>() {
-        Ok(month) => month >= 1 && month <= 12,
-        Err(_) => false,
+/// Modification time of an entry, or of the `YYYY.zip` holding it
+fn entry_modified(entry: &Path) -> Option<chrono::DateTime<chrono::Utc>> {
+    let file = entry.ancestors().find(|path| path.is_file())?;
+    Some(fs::metadata(file).ok()?.modified().ok()?.into())
+}
+
+/// An age such as `30m`, `12h`, `3d` or `2w`
+fn parse_age(age: &str) -> Option<chrono::Duration> {
+    let unit = age.chars().last()?;
+    let amount: i64 = age[..age.len() - unit.len_utf8()].parse().ok()?;
+    match unit {
+        'm' => chrono::Duration::try_minutes(amount),
+        'h' => chrono::Duration::try_hours(amount),
+        'd' => chrono::Duration::try_days(amount),
+        'w' => chrono::Duration::try_weeks(amount),
+        _ => None,
     }
 }
 
-fn is_valid_year(folder_name: &str) -> bool {
-    if folder_name.len() != 4 {
-        return false;
+/// First day of a window of `span` (e.g. `7d`, `2w`) whole days ending on `today`
+fn window_start(span: &str, today: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+    let days = parse_age(span)?.num_days();
+    if days < 1 || span.ends_with(['m', 'h']) {
+        return None;
     }
+    today.checked_sub_days(chrono::Days::new(days as u64 - 1))
+}
 
-    folder_name.parse::<u32>().is_ok()
+/// Up to `count` entries by when their file was last modified, most recent first, leaving
+/// out those not modified since `since`
+fn find_recent_entries(
+    journal_path: &Path,
+    count: usize,
+    since: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<Vec<PathBuf>, String> {
+    let mut entries: Vec<(chrono::DateTime<chrono::Utc>, PathBuf)> = find_all_entries(journal_path)?
+        .into_iter()
+        .filter_map(|entry| Some((entry_modified(&entry)?, entry)))
+        .filter(|(modified, _)| since.is_none_or(|since| *modified >= since))
+        .collect();
+    entries.sort_by(|a, b| b.cmp(a));
+    Ok(entries.into_iter().take(count).map(|(_, entry)| entry).collect())
 }
 
-fn sanitize_title(title: &str) -> String {
-    let mut safe = title
-        .replace(' ', "-")
-        .replace('/', "-")
-        .replace('\\', "-")
-        .replace(':', "-")
-        .replace('?', "-")
-        .replace('*', "-")
-        .replace('"', "-")
-        .replace('\'', "-")
-        .replace('<', "-")
-        .replace('>', "-")
-        .replace('|', "-");
+/// The `count` most recent entries, newest first, reading year and month folders
+/// from the latest back only until enough are found
+fn find_latest_entries(journal_path: &Path, count: usize) -> Result<Vec<PathBuf>, String> {
+    let items = fs::read_dir(journal_path)
+        .map_err(|e| format!("Failed to read {}: {}", journal_path.display(), e))?;
+    let mut years: Vec<String> = items
+        .flatten()
+        .filter_map(|item| item.file_name().to_str().map(str::to_string))
+        .filter_map(|name| match name.strip_suffix(".zip") {
+            Some(year) => Some(year.to_string()),
+            None => journal_path.join(&name).is_dir().then_some(name),
+        })
+        .filter(|year| is_valid_year(year))
+        .collect();
+    years.sort();
+    years.dedup();
 
-    // Collapse multiple hyphens
-    while safe.contains("--") {
-        safe = safe.replace("--", "-");
+    let mut latest = Vec::new();
+    for year in years.iter().rev() {
+        if latest.len() >= count {
+            break;
+        }
+        let year_dir = journal_path.join(year);
+        if !year_dir.is_dir() {
+            let mut archived = archive::list_zip_entries(&journal_path.join(format!("{}.zip", year)), None, None)?;
+            sort_entries(&mut archived);
+            latest.extend(archived.into_iter().rev().take(count - latest.len()));
+            continue;
+        }
+        let mut months: Vec<PathBuf> = fs::read_dir(&year_dir)
+            .map_err(|e| format!("Failed to read {}: {}", year_dir.display(), e))?
+            .flatten()
+            .filter(|month| month.file_name().to_str().is_some_and(is_valid_month))
+            .map(|month| month.path())
+            .collect();
+        months.sort();
+        for month in months.iter().rev() {
+            let Ok(files) = fs::read_dir(month) else {
+                continue;
+            };
+            let mut files: Vec<PathBuf> = files
+                .flatten()
+                .filter(|file| file.file_name().to_str().is_some_and(is_entry_file))
+                .map(|file| file.path())
+                .collect();
+            sort_entries(&mut files);
+            latest.extend(files.into_iter().rev().take(count - latest.len()));
+            if latest.len() >= count {
+                break;
+            }
+        }
     }
+    Ok(latest)
+}
 
-    // Trim trailing hyphen
-    safe.trim_end_matches('-').to_string()
+/// Every entry whose date satisfies `keep`
+#[cfg(feature = "calendars")]
+fn find_entries_where(journal_path: &Path, keep: impl Fn(chrono::NaiveDate) -> bool) -> Result<Vec<PathBuf>, String> {
+    let mut entries = find_all_entries(journal_path)?;
+    entries.retain(|entry| entry_date(entry).is_some_and(&keep));
+    Ok(entries)
+}
+
+/// Collect every entry in the journal, including those inside `YYYY.zip` archives
+fn find_all_entries(journal_path: &Path) -> Result<Vec<PathBuf>, String> {
+    let items = fs::read_dir(journal_path)
+        .map_err(|e| format!("Failed to read {}: {}", journal_path.display(), e))?;
+
+    let mut entries = Vec::new();
+    for item in items.flatten() {
+        let Some(name) = item.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let item_path = item.path();
+
+        if is_valid_year(&name) && item_path.is_dir() {
+            let Ok(months) = fs::read_dir(&item_path) else {
+                continue;
+            };
+            for month in months.flatten() {
+                if !month.file_name().to_str().is_some_and(is_valid_month) {
+                    continue;
+                }
+                let Ok(files) = fs::read_dir(month.path()) else {
+                    continue;
+                };
+                for file in files.flatten() {
+                    if file.file_name().to_str().is_some_and(is_entry_file) {
+                        entries.push(file.path());
+                    }
+                }
+            }
+        } else if let Some(year) = name.strip_suffix(".zip")
+            && is_valid_year(year)
+            && !journal_path.join(year).exists()
+        {
+            entries.extend(archive::list_zip_entries(&item_path, None, None)?);
+        }
+    }
+
+    sort_entries(&mut entries);
+    Ok(entries)
 }
 
 /// Find journal entries matching the given criteria
@@ -426,6 +5997,18 @@ fn find_entries(
     let target_month = month.unwrap_or(now.month());
     let target_day = day;
 
+    // Folders named in another calendar don't line up with Gregorian months, so match every entry's date
+    #[cfg(feature = "calendars")]
+    if layout_calendar().is_some() {
+        let whole_year = year.is_some() && month.is_none() && day.is_none();
+        let target_day = if (day, month, year) == (None, None, None) { Some(now.day()) } else { day };
+        return find_entries_where(journal_path, |date| {
+            date.year() == target_year
+                && (whole_year || date.month() == target_month)
+                && target_day.is_none_or(|day| date.day() == day)
+        });
+    }
+
     // Build search path
     let year_dir = journal_path.join(target_year.to_string());
     
@@ -441,26 +6024,38 @@ fn find_entries(
     // Collect matching entries
     let mut entries = Vec::new();
 
-    if let Some(day_val) = target_day {
+    // Archived years are read straight from YYYY.zip when the folder is gone
+    let zip_path = archive::zip_path(journal_path, target_year);
+    if !year_dir.exists() && zip_path.exists() {
+        let (month_filter, day_filter) = if target_day.is_some() {
+            (Some(target_month), target_day)
+        } else if month.is_some() {
+            (Some(target_month), None)
+        } else if year.is_some() {
+            (None, None)
+        } else {
+            (Some(target_month), Some(now.day()))
+        };
+        entries = archive::list_zip_entries(&zip_path, month_filter, day_filter)?;
+    } else if let Some(day_val) = target_day {
         // Looking for specific day
-        let day_prefix = format!("{:02}", day_val);
         if let Ok(files) = fs::read_dir(&search_dir) {
             for file in files.flatten() {
-                if let Some(filename) = file.file_name().to_str() {
-                    if filename.starts_with(&day_prefix) && filename.ends_with(".md") {
-                        entries.push(file.path());
-                    }
+                if let Some(filename) = file.file_name().to_str()
+                    && is_entry_file(filename) && entry_day(filename) == Some(day_val)
+                {
+                    entries.push(file.path());
                 }
             }
         }
     } else if month.is_some() {
-        // Looking for entire month - read all .md files in month dir
+        // Looking for entire month - read all entry files in month dir
         if let Ok(files) = fs::read_dir(&search_dir) {
             for file in files.flatten() {
-                if let Some(filename) = file.file_name().to_str() {
-                    if filename.ends_with(".md") {
-                        entries.push(file.path());
-                    }
+                if let Some(filename) = file.file_name().to_str()
+                    && is_entry_file(filename)
+                {
+                    entries.push(file.path());
                 }
             }
         }
@@ -468,44 +6063,43 @@ fn find_entries(
         // Looking for entire year - iterate all months from year directory
         for m in 1..=12 {
             let month_dir = year_dir.join(format!("{:02}", m));
-            if month_dir.exists() {
-                if let Ok(files) = fs::read_dir(&month_dir) {
-                    for file in files.flatten() {
-                        if let Some(filename) = file.file_name().to_str() {
-                            if filename.ends_with(".md") {
-                                entries.push(file.path());
-                            }
-                        }
+            if month_dir.exists()
+                && let Ok(files) = fs::read_dir(&month_dir)
+            {
+                for file in files.flatten() {
+                    if let Some(filename) = file.file_name().to_str()
+                        && is_entry_file(filename)
+                    {
+                        entries.push(file.path());
                     }
                 }
             }
         }
     } else {
         // Default: today's entries
-        let day_prefix = format!("{:02}", now.day());
         if let Ok(files) = fs::read_dir(&search_dir) {
             for file in files.flatten() {
-                if let Some(filename) = file.file_name().to_str() {
-                    if filename.starts_with(&day_prefix) && filename.ends_with(".md") {
-                        entries.push(file.path());
-                    }
+                if let Some(filename) = file.file_name().to_str()
+                    && is_entry_file(filename) && entry_day(filename) == Some(now.day())
+                {
+                    entries.push(file.path());
                 }
             }
         }
     }
 
-    // Sort entries by path for consistent ordering
-    entries.sort();
+    sort_entries(&mut entries);
     Ok(entries)
 }
 
-/// Find journal entries for the current week (Monday to Sunday)
-fn find_entries_week(journal_path: &Path) -> Result<Vec<PathBuf>, String> {
-    let now = chrono::Local::now();
-    let weekday = now.weekday().num_days_from_monday(); // 0 = Monday, 6 = Sunday
+/// Find journal entries for the week (Monday to Sunday) starting on `monday`
+fn find_entries_week(journal_path: &Path, monday: chrono::NaiveDate) -> Result<Vec<PathBuf>, String> {
+    #[cfg(feature = "calendars")]
+    if layout_calendar().is_some() {
+        return find_entries_where(journal_path, |date| (0..7).contains(&(date - monday).num_days()));
+    }
     
-    // Calculate start of week (Monday)
-    let start_of_week = now - chrono::Duration::days(weekday as i64);
+    let start_of_week = monday;
     let start_day = start_of_week.day();
     let start_month = start_of_week.month();
     let start_year = start_of_week.year();
@@ -518,28 +6112,34 @@ fn find_entries_week(journal_path: &Path) -> Result<Vec<PathBuf>, String> {
     
     let mut entries = Vec::new();
     
-    // Helper function to collect entries from a specific day
-    let mut collect_entries_for_day = |year: i32, month: u32, day: u32| {
+    // Helper function to collect entries from a specific day, from YYYY.zip when the year's
+    // folder is archived, as find_entries does
+    let mut collect_entries_for_day = |year: i32, month: u32, day: u32| -> Result<(), String> {
+        let zip_path = archive::zip_path(journal_path, year);
+        if !journal_path.join(year.to_string()).exists() && zip_path.exists() {
+            entries.extend(archive::list_zip_entries(&zip_path, Some(month), Some(day))?);
+            return Ok(());
+        }
         let month_dir = journal_path.join(year.to_string()).join(format!("{:02}", month));
-        if month_dir.exists() {
-            let day_prefix = format!("{:02}", day);
-            if let Ok(files) = fs::read_dir(&month_dir) {
-                for file in files.flatten() {
-                    if let Some(filename) = file.file_name().to_str() {
-                        if filename.starts_with(&day_prefix) && filename.ends_with(".md") {
-                            entries.push(file.path());
-                        }
-                    }
+        if month_dir.exists()
+            && let Ok(files) = fs::read_dir(&month_dir)
+        {
+            for file in files.flatten() {
+                if let Some(filename) = file.file_name().to_str()
+                    && is_entry_file(filename) && entry_day(filename) == Some(day)
+                {
+                    entries.push(file.path());
                 }
             }
         }
+        Ok(())
     };
     
     // Collect entries from start of week to end of week
     if start_year == end_year && start_month == end_month {
         // Same month - iterate days
         for day in start_day..=end_day {
-            collect_entries_for_day(start_year, start_month, day);
+            collect_entries_for_day(start_year, start_month, day)?;
         }
     } else {
         // Week spans multiple months
@@ -558,17 +6158,16 @@ fn find_entries_week(journal_path: &Path) -> Result<Vec<PathBuf>, String> {
         };
         
         for day in start_day..=days_in_start_month {
-            collect_entries_for_day(start_year, start_month, day);
+            collect_entries_for_day(start_year, start_month, day)?;
         }
         
         // Then collect from start of end month to end day
         for day in 1..=end_day {
-            collect_entries_for_day(end_year, end_month, day);
+            collect_entries_for_day(end_year, end_month, day)?;
         }
     }
     
-    // Sort entries by path for consistent ordering
-    entries.sort();
+    sort_entries(&mut entries);
     Ok(entries)
 }
 
@@ -663,6 +6262,21 @@ mod tests {
         assert!(template.contains("Date: 17-02-2026"));
     }
 
+    #[test]
+    fn test_write_entry_at_backdates() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let at = chrono::NaiveDate::from_ymd_opt(2025, 12, 24).unwrap().and_hms_opt(8, 15, 3).unwrap();
+
+        let entry = write_entry_at(temp_dir.path().to_path_buf(), at, "Eve", "Snow", frontmatter::Frontmatter::default(), None)
+            .expect("Failed to write entry");
+        assert_eq!(entry, temp_dir.path().join("2025").join("12").join("24-081503-Eve.md"));
+        assert_eq!(fs::read_to_string(&entry).unwrap(), "# Eve\n\nDate: 24-12-2025\n\nSnow\n");
+        // Only the target date's folders are created
+        let years: Vec<_> =
+            fs::read_dir(temp_dir.path()).unwrap().flatten().filter(|e| e.path().is_dir()).map(|e| e.file_name()).collect();
+        assert_eq!(years, ["2025"]);
+    }
+
     // Tests for find_entries functionality
     fn create_test_journal_dir() -> tempfile::TempDir {
         let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
@@ -777,4 +6391,490 @@ mod tests {
         assert_eq!(entries.len(), 1);
         assert!(entries[0].to_string_lossy().contains("note3"));
     }
+
+    #[test]
+    fn test_find_entries_reads_zip_archive() {
+        use std::io::Write;
+
+        let temp_dir = create_test_journal_dir();
+        let zip_path = archive::zip_path(temp_dir.path(), 2024);
+        let mut writer = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        writer
+            .start_file("2024/07/04-120000-archived.md", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(b"# archived\n\nArchived content").unwrap();
+        writer.finish().unwrap();
+
+        let entries = find_entries(temp_dir.path(), Some(4), Some(7), Some(2024))
+            .expect("Failed to find entries");
+
+        assert_eq!(entries.len(), 1);
+        let content = read_entry(&entries[0]).expect("Failed to read entry");
+        assert!(content.contains("Archived content"));
+    }
+
+    #[test]
+    fn test_find_entries_week_reads_zip_archive() {
+        use std::io::Write;
+
+        let temp_dir = create_test_journal_dir();
+        let zip_path = archive::zip_path(temp_dir.path(), 2021);
+        let mut writer = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        writer
+            .start_file("2021/03/09-100000-a.md", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(b"# a\n").unwrap();
+        writer.finish().unwrap();
+
+        let monday = chrono::NaiveDate::from_ymd_opt(2021, 3, 8).unwrap();
+        let entries = find_entries_week(temp_dir.path(), monday).expect("Failed to find entries");
+        assert_eq!(entries, vec![zip_path.join("2021/03/09-100000-a.md")]);
+    }
+
+    #[test]
+    fn test_page() {
+        let list = |args: &[&str]| match Cli::try_parse_from([&["file-journal", "today"], args].concat()) {
+            Ok(Cli { command: Commands::Today(list), .. }) => list,
+            _ => panic!("expected today's list arguments"),
+        };
+        let entries: Vec<PathBuf> = (1..=5).map(|day| PathBuf::from(format!("2026/02/{:02}-note.md", day))).collect();
+        let days = |page: Vec<PathBuf>| page.iter().map(|p| p.to_string_lossy()[8..10].to_string()).collect::<Vec<_>>();
+
+        assert_eq!(days(page(entries.clone(), &list(&[]))), ["01", "02", "03", "04", "05"]);
+        assert_eq!(days(page(entries.clone(), &list(&["--offset", "1", "--limit", "2"]))), ["02", "03"]);
+        assert_eq!(days(page(entries.clone(), &list(&["--reverse", "--limit", "2"]))), ["05", "04"]);
+        assert_eq!(days(page(entries.clone(), &list(&["--reverse", "--offset", "4"]))), ["01"]);
+        assert!(page(entries, &list(&["--offset", "9"])).is_empty());
+    }
+
+    #[test]
+    fn test_sort_entries_by_date_and_time() {
+        let mut entries: Vec<PathBuf> = [
+            "j/2026/02/5-imported.md",
+            "j/2026/02/17-081503-a.md",
+            "j/2026/01/31-235959-z.md",
+            "j/2026/02/09-120000-b.md",
+            "j/2026/02/17-070000-c.md.gz",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+        sort_entries(&mut entries);
+        let names: Vec<_> = entries.iter().map(|entry| entry.file_name().unwrap().to_str().unwrap()).collect();
+        assert_eq!(names, ["31-235959-z.md", "5-imported.md", "09-120000-b.md", "17-070000-c.md.gz", "17-081503-a.md"]);
+    }
+
+    #[test]
+    fn test_resolve_selector_forms() {
+        let temp_dir = create_test_journal_dir();
+
+        assert_eq!(resolve_selector(temp_dir.path(), "2026-02-17").unwrap().len(), 2);
+        assert_eq!(resolve_selector(temp_dir.path(), "2026-02").unwrap().len(), 3);
+        assert_eq!(resolve_selector(temp_dir.path(), "2026").unwrap().len(), 4);
+
+        let file = temp_dir.path().join("2026/03/01-120000-march-note.md");
+        let by_path = resolve_selector(temp_dir.path(), &file.to_string_lossy()).unwrap();
+        assert_eq!(by_path, vec![file]);
+
+        assert!(resolve_selector(temp_dir.path(), "someday").is_err());
+
+        // Paths from network callers stay inside the journal
+        let outside = tempfile::tempdir().unwrap();
+        let secret = outside.path().join("secret.md");
+        fs::write(&secret, "# secret\n").unwrap();
+        assert!(resolve_journal_selector(temp_dir.path(), &secret.to_string_lossy()).is_err());
+        assert!(resolve_journal_selector(temp_dir.path(), "../../etc/hostname").is_err());
+        assert_eq!(resolve_journal_selector(temp_dir.path(), "2026/02/17-081503-note1.md").unwrap().len(), 1);
+        assert_eq!(resolve_journal_selector(temp_dir.path(), "2026-02").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_entry_frontmatter_author() {
+        let temp_dir = create_test_journal_dir();
+        let entry = temp_dir.path().join("2026/02/19-100000-alice-deploy.md");
+        fs::write(&entry, "---\nauthor: alice\n---\n\n# deploy\n").unwrap();
+
+        assert_eq!(entry_frontmatter(&entry).get("author"), Some("alice"));
+        let plain = temp_dir.path().join("2026/02/18-090000-note3.md");
+        assert_eq!(entry_frontmatter(&plain).get("author"), None);
+    }
+
+    #[test]
+    fn test_entry_slug() {
+        assert_eq!(entry_slug(Path::new("/j/2026/02/17-081503-Team-standup.md")), "Team-standup");
+        assert_eq!(entry_slug(Path::new("/j/2026/02/17-081503-standup.md.gz")), "standup");
+        assert_eq!(entry_slug(Path::new("/j/2026/02/notes.md")), "notes.md");
+    }
+
+    #[test]
+    fn test_entry_date_from_path() {
+        let date = entry_date(Path::new("/j/2026/02/17-081503-note1.md"));
+        assert_eq!(date, chrono::NaiveDate::from_ymd_opt(2026, 2, 17));
+        let archived = entry_date(Path::new("/j/2021.zip/2021/05/03-080000-old.md"));
+        assert_eq!(archived, chrono::NaiveDate::from_ymd_opt(2021, 5, 3));
+        assert_eq!(entry_date(Path::new("/j/2026/02/notes.md")), None);
+    }
+
+    #[test]
+    fn test_append_to_today_creates_then_appends() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+
+        let created = append_to_today(temp_dir.path(), "first", None).expect("append failed");
+        assert!(created.to_string_lossy().ends_with("-journal.md"));
+        let again = append_to_today(temp_dir.path(), "second", None).expect("append failed");
+        assert_eq!(created, again);
+
+        let content = fs::read_to_string(created).unwrap();
+        assert!(content.starts_with("# journal\n"));
+        assert!(content.ends_with("\nfirst\n\nsecond\n"));
+    }
+
+    #[test]
+    fn test_append_to_day_uses_the_given_day() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let late = chrono::NaiveDate::from_ymd_opt(2026, 2, 16).unwrap().and_hms_opt(23, 58, 0).unwrap();
+
+        let created = append_to_day(temp_dir.path(), late, "- 23:58 first", None).expect("append failed");
+        assert!(created.ends_with("2026/02/16-235800-journal.md"));
+        let again = append_to_day(temp_dir.path(), late, "- 23:59 second", None).expect("append failed");
+        assert_eq!(created, again);
+        assert!(fs::read_to_string(created).unwrap().ends_with("\n- 23:58 first\n- 23:59 second\n"));
+    }
+
+    #[test]
+    fn test_tags_are_normalized_and_matched() {
+        let tags = normalize_tags(&["work".to_string(), "#team".to_string(), "Work".to_string(), " ".to_string()]);
+        assert_eq!(tags, vec!["work", "team"]);
+
+        let (entry_frontmatter, _) = frontmatter::parse("---\ntags: [Work, team, ops]\n---\n\n# Standup\n");
+        assert!(has_tags(&entry_frontmatter, &tags));
+        assert!(!has_tags(&entry_frontmatter, &["home".to_string()]));
+        assert!(has_tags(&entry_frontmatter, &[]));
+    }
+
+    #[test]
+    fn test_title_matches() {
+        let temp_dir = create_test_journal_dir();
+        let entry = temp_dir.path().join("2026/02/17-081503-note1.md");
+        assert!(title_matches(&entry, "NOTE 1"));
+        assert!(title_matches(&entry, "note1"));
+        assert!(!title_matches(&entry, "standup"));
+    }
+
+    #[test]
+    fn test_timestamped_bullet() {
+        let time = chrono::NaiveTime::from_hms_opt(14, 32, 5).unwrap();
+        assert_eq!(timestamped_bullet(time, "Call with Sam\n"), "- 14:32 Call with Sam");
+        assert_eq!(timestamped_bullet(time, "Plan:\n\nship it"), "- 14:32 Plan:\n\n  ship it");
+    }
+
+    #[test]
+    fn test_message_line() {
+        use chrono::TimeZone;
+        let time = chrono::Local.with_ymd_and_hms(2026, 2, 17, 14, 32, 0).unwrap();
+        assert_eq!(message_line(&time, Some("call\nmom"), None), "- 14:32 call mom");
+        assert_eq!(
+            message_line(&time, None, Some("../../assets/2026/02/p.jpg")),
+            "- 14:32 ![photo](../../assets/2026/02/p.jpg)"
+        );
+    }
+
+    #[test]
+    fn test_find_latest_entries() {
+        let temp_dir = create_test_journal_dir();
+        let latest = find_latest_entries(temp_dir.path(), 3).unwrap();
+        let names: Vec<_> = latest.iter().map(|p| p.file_name().unwrap().to_str().unwrap()).collect();
+        assert_eq!(names, ["01-120000-march-note.md", "18-090000-note3.md", "17-101200-note2.md"]);
+        assert_eq!(find_latest_entries(temp_dir.path(), 10).unwrap().len(), 5);
+        assert!(find_latest_entries(temp_dir.path(), 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_entries_between() {
+        let temp_dir = create_test_journal_dir();
+        let day = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let names = |entries: Vec<PathBuf>| -> Vec<String> {
+            entries.iter().map(|p| p.file_name().unwrap().to_string_lossy().to_string()).collect()
+        };
+
+        let sprint = find_entries_between(temp_dir.path(), Some(day(2026, 2, 18)), Some(day(2026, 3, 1))).unwrap();
+        assert_eq!(names(sprint), ["18-090000-note3.md", "01-120000-march-note.md"]);
+
+        // Across the year end, from the first entry when --from is left out
+        let all = find_entries_between(temp_dir.path(), None, Some(day(2026, 2, 17))).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all[0].starts_with(temp_dir.path().join("2025")));
+        assert!(find_entries_between(temp_dir.path(), Some(day(2026, 3, 2)), None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_walk_entries_in_written_order() {
+        use std::io::Write;
+
+        let temp_dir = create_test_journal_dir();
+        let mut writer = zip::ZipWriter::new(fs::File::create(archive::zip_path(temp_dir.path(), 2024)).unwrap());
+        writer.start_file("2024/07/04-120000-archived.md", zip::write::SimpleFileOptions::default()).unwrap();
+        writer.write_all(b"# archived").unwrap();
+        writer.finish().unwrap();
+        let day = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        let walked: Vec<PathBuf> =
+            walk_entries(temp_dir.path(), Some(day(2024, 1, 1)), None).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(walked, find_all_entries(temp_dir.path()).unwrap());
+        assert!(walked[0].starts_with(archive::zip_path(temp_dir.path(), 2024)));
+
+        let mut february = walk_entries(temp_dir.path(), Some(day(2026, 2, 1)), Some(day(2026, 2, 28))).unwrap();
+        assert!(february.next().unwrap().unwrap().starts_with(temp_dir.path().join("2026/02")));
+    }
+
+    #[test]
+    fn test_query_folders() {
+        let temp_dir = create_test_journal_dir();
+        assert_eq!(journal_month_folders(temp_dir.path()), [(2025, 1), (2026, 2), (2026, 3)]);
+        let day = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(month_folders(day(2025, 12, 30), day(2026, 2, 1)), [(2025, 12), (2026, 1), (2026, 2)]);
+    }
+
+    #[test]
+    fn test_period_months() {
+        let q3 = period_months(Some(2026), "Q", 3, 3);
+        assert_eq!((q3.label.as_str(), q3.months), ("2026-Q3", 7..=9));
+        let h1 = period_months(Some(2024), "H", 1, 6);
+        assert_eq!((h1.first_day().to_string(), h1.last_day().to_string()), ("2024-01-01".into(), "2024-06-30".into()));
+        assert_eq!(period_months(Some(2026), "Q", 4, 3).last_day().to_string(), "2026-12-31");
+    }
+
+    #[cfg(feature = "calendars")]
+    #[test]
+    fn test_find_entries_with_layout_calendar() {
+        TEST_LAYOUT_CALENDAR.set(Some(calendar::Calendar::Persian));
+        let temp_dir = tempfile::tempdir().unwrap();
+        let journal = temp_dir.path();
+        let entry = journal.join("1404").join("11").join("28-090000-hike.md");
+        fs::create_dir_all(entry.parent().unwrap()).unwrap();
+        fs::write(&entry, "# Hike\n").unwrap();
+        assert_eq!(layout_date(chrono::NaiveDate::from_ymd_opt(2026, 2, 17).unwrap()), (1404, 11, 28));
+
+        // Date flags stay Gregorian
+        for (day, month) in [(Some(17), Some(2)), (None, Some(2)), (None, None)] {
+            assert_eq!(find_entries(journal, day, month, Some(2026)).unwrap(), [entry.as_path()]);
+        }
+        assert!(find_entries(journal, None, Some(11), Some(1404)).unwrap().is_empty());
+        assert!(gregorian_folders(journal).is_empty());
+
+        // Folders from before layout_calendar was set are told apart
+        let old = journal.join("2025").join("03").join("01-090000-old.md");
+        fs::create_dir_all(old.parent().unwrap()).unwrap();
+        fs::write(&old, "# Old\n").unwrap();
+        fs::write(journal.join("2019.zip"), "").unwrap();
+        assert_eq!(gregorian_folders(journal), ["2019.zip", "2025"]);
+    }
+
+    #[cfg(feature = "calendars")]
+    #[test]
+    fn test_on_this_day_with_layout_calendar() {
+        TEST_LAYOUT_CALENDAR.set(Some(calendar::Calendar::Persian));
+        let temp_dir = tempfile::tempdir().unwrap();
+        let journal = temp_dir.path();
+        let write = |year, month, day| {
+            let (year, month, day) = layout_date(chrono::NaiveDate::from_ymd_opt(year, month, day).unwrap());
+            let month_dir = journal.join(year.to_string()).join(format!("{:02}", month));
+            let entry = month_dir.join(format!("{:02}-090000-note.md", day));
+            fs::create_dir_all(entry.parent().unwrap()).unwrap();
+            fs::write(&entry, "# Note\n").unwrap();
+            entry
+        };
+        let last_year = write(2025, 2, 17);
+        let two_years_ago = write(2024, 2, 17);
+        write(2025, 2, 18);
+        write(2026, 2, 17);
+        assert!(journal.join("1403").is_dir());
+
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 2, 17).unwrap();
+        let found = find_entries_on_this_day(journal, today).unwrap();
+        assert_eq!(found, [(2025, vec![last_year]), (2024, vec![two_years_ago])]);
+    }
+
+    #[test]
+    fn test_adjacent_entry() {
+        let temp_dir = create_test_journal_dir();
+        let journal = temp_dir.path();
+        let month = journal.join("2026").join("02");
+        let name = |entry: Option<PathBuf>| entry.map(|p| p.file_name().unwrap().to_string_lossy().to_string());
+
+        let first = month.join("17-081503-note1.md");
+        let first = first.to_str().unwrap();
+        assert_eq!(name(adjacent_entry(journal, first, true).unwrap()).unwrap(), "17-101200-note2.md");
+        assert_eq!(name(adjacent_entry(journal, first, false).unwrap()).unwrap(), "15-080000-2025-note.md");
+        assert_eq!(name(adjacent_entry(journal, "2026-02-17", true).unwrap()).unwrap(), "18-090000-note3.md");
+        assert_eq!(name(adjacent_entry(journal, "2026-02-18", false).unwrap()).unwrap(), "17-101200-note2.md");
+        assert_eq!(name(adjacent_entry(journal, "2026-02-20", true).unwrap()).unwrap(), "01-120000-march-note.md");
+        assert_eq!(adjacent_entry(journal, "2026-03-01", true).unwrap(), None);
+        assert!(adjacent_entry(journal, "someday", true).is_err());
+
+        // An imported entry without a time comes before the day's timed ones, whatever its name
+        fs::write(month.join("17-zzz-imported.md"), "# Imported\n").unwrap();
+        assert_eq!(name(adjacent_entry(journal, first, false).unwrap()).unwrap(), "17-zzz-imported.md");
+        let imported = month.join("17-zzz-imported.md");
+        assert_eq!(name(adjacent_entry(journal, imported.to_str().unwrap(), true).unwrap()).unwrap(), "17-081503-note1.md");
+
+        let day = |m, d| chrono::NaiveDate::from_ymd_opt(2026, m, d);
+        assert_eq!(nearest_entry_dates(journal, day(2, 19), day(2, 25), |_| true), (day(2, 18), day(3, 1)));
+        assert_eq!(nearest_entry_dates(journal, day(2, 19), None, |_| false), (None, None));
+    }
+
+    #[test]
+    fn test_find_recent_entries() {
+        let temp_dir = create_test_journal_dir();
+        let month = temp_dir.path().join("2026").join("02");
+        let now = std::time::SystemTime::now();
+        for (name, hours_ago) in [("17-081503-note1.md", 1), ("17-101200-note2.md", 30), ("18-090000-note3.md", 100)] {
+            let touched = now - std::time::Duration::from_secs(hours_ago * 3600);
+            fs::File::options().write(true).open(month.join(name)).unwrap().set_modified(touched).unwrap();
+        }
+        // Edited long after it was written, so it comes first
+        let old = temp_dir.path().join("2025").join("11").join("03-070000-old.md");
+        fs::create_dir_all(old.parent().unwrap()).unwrap();
+        fs::write(&old, "# Old\n").unwrap();
+        let edited = now + std::time::Duration::from_secs(60);
+        fs::File::options().write(true).open(&old).unwrap().set_modified(edited).unwrap();
+
+        let recent = find_recent_entries(temp_dir.path(), 3, None).unwrap();
+        let names: Vec<_> = recent.iter().map(|p| p.file_name().unwrap().to_str().unwrap()).collect();
+        assert_eq!(names[0], "03-070000-old.md");
+        assert_eq!(names.len(), 3);
+
+        let since = chrono::Utc::now() - parse_age("2d").unwrap();
+        let within = find_recent_entries(temp_dir.path(), 10, Some(since)).unwrap();
+        assert!(within.contains(&month.join("17-101200-note2.md")));
+        assert!(!within.contains(&month.join("18-090000-note3.md")));
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30m"), Some(chrono::Duration::minutes(30)));
+        assert_eq!(parse_age("3d"), Some(chrono::Duration::days(3)));
+        assert_eq!(parse_age("2w"), Some(chrono::Duration::weeks(2)));
+        assert_eq!(parse_age("d"), None);
+        assert_eq!(parse_age("3y"), None);
+        assert_eq!(parse_age("-"), None);
+    }
+
+    #[test]
+    fn test_window_start() {
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 3, 3).unwrap();
+        assert_eq!(window_start("1d", today), Some(today));
+        assert_eq!(window_start("7d", today), chrono::NaiveDate::from_ymd_opt(2026, 2, 25));
+        assert_eq!(window_start("2w", today), chrono::NaiveDate::from_ymd_opt(2026, 2, 18));
+        assert_eq!(window_start("0d", today), None);
+        assert_eq!(window_start("48h", today), None);
+    }
+
+    #[test]
+    fn test_redate_entry() {
+        let temp_dir = create_test_journal_dir();
+        let entry = temp_dir.path().join("2026").join("02").join("18-090000-note3.md");
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 4, 5).unwrap();
+
+        let moved = redate_entry(temp_dir.path(), &entry, date).unwrap();
+        assert_eq!(moved, temp_dir.path().join("2026").join("04").join("05-090000-note3.md"));
+        assert!(!entry.exists());
+        assert_eq!(fs::read_to_string(&moved).unwrap(), "# Note 3\n\nDate: 05-04-2026\n\nContent 3");
+
+        let other = temp_dir.path().join("2026").join("02").join("17-081503-note1.md");
+        fs::write(temp_dir.path().join("2026").join("02").join("18-081503-note1.md"), "").unwrap();
+        let next_day = chrono::NaiveDate::from_ymd_opt(2026, 2, 18).unwrap();
+        assert!(redate_entry(temp_dir.path(), &other, next_day).unwrap_err().contains("already exists"));
+        assert!(other.exists());
+    }
+
+    #[test]
+    fn test_find_entries_on_this_day() {
+        let temp_dir = create_test_journal_dir();
+        let old = temp_dir.path().join("2024").join("02");
+        fs::create_dir_all(&old).unwrap();
+        fs::write(old.join("17-070000-back-then.md"), "# Back then").unwrap();
+        fs::write(old.join("18-070000-next-day.md"), "# Next day").unwrap();
+
+        let date = chrono::NaiveDate::from_ymd_opt(2027, 2, 17).unwrap();
+        let found = find_entries_on_this_day(temp_dir.path(), date).unwrap();
+        assert_eq!(found.iter().map(|(year, entries)| (*year, entries.len())).collect::<Vec<_>>(), [(2026, 2), (2024, 1)]);
+
+        // The current year is not a previous one
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 2, 17).unwrap();
+        assert_eq!(find_entries_on_this_day(temp_dir.path(), date).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_pick_label() {
+        let temp_dir = create_test_journal_dir();
+        let month = temp_dir.path().join("2026").join("02");
+        assert_eq!(pick_label(&month.join("17-081503-note1.md")), "2026-02-17 08:15  Note 1");
+        fs::write(month.join("18-120000-untitled.md"), "No heading").unwrap();
+        assert_eq!(pick_label(&month.join("18-120000-untitled.md")), "2026-02-18 12:00  18-120000-untitled.md");
+    }
+
+    #[test]
+    fn test_retitle_entry() {
+        let temp_dir = create_test_journal_dir();
+        let month = temp_dir.path().join("2026").join("02");
+
+        let renamed = retitle_entry(&month.join("17-101200-note2.md"), "Call with Sam").unwrap();
+        assert_eq!(renamed, month.join("17-101200-Call-with-Sam.md"));
+        assert!(!month.join("17-101200-note2.md").exists());
+        assert_eq!(fs::read_to_string(&renamed).unwrap(), "# Call with Sam\n\nDate: 17-02-2026\n\nContent 2");
+
+        fs::write(month.join("17-120000-ana-draft.md"), "---\nauthor: ana\n---\n\n# Draft\nBody\n").unwrap();
+        let renamed = retitle_entry(&month.join("17-120000-ana-draft.md"), "Plan").unwrap();
+        assert_eq!(renamed, month.join("17-120000-ana-Plan.md"));
+        assert_eq!(fs::read_to_string(&renamed).unwrap(), "---\nauthor: ana\n---\n\n# Plan\nBody\n");
+
+        fs::write(month.join("18-090000-Other.md"), "").unwrap();
+        assert!(retitle_entry(&month.join("18-090000-note3.md"), "Other").unwrap_err().contains("already exists"));
+    }
+
+    #[test]
+    fn test_merge_profile_tables() {
+        let mut base: toml::Table = toml::from_str(
+            "default_path = \"/journal\"\neditor = \"vim\"\n[standup]\nheadings = [\"Done\"]\n[theme]\npreset = \"light\"\nheading = \"blue\"\n",
+        )
+        .unwrap();
+        let profile: toml::Table = toml::from_str("default_path = \"/srv/journal\"\n[theme]\nheading = \"red\"\n").unwrap();
+        merge_tables(&mut base, profile);
+
+        let config: Config = toml::Value::Table(base).try_into().unwrap();
+        assert_eq!(config.default_path, Some(PathBuf::from("/srv/journal")));
+        assert_eq!(config.editor.as_deref(), Some("vim"));
+        assert!(config.standup.is_some());
+        let theme = config.theme.unwrap();
+        assert_eq!((theme.preset.as_deref(), theme.heading.as_deref()), (Some("light"), Some("red")));
+    }
+
+    #[test]
+    fn test_parse_config_profiles() {
+        let content = "default_path = \"/base\"\n\n[profile.server]\ndefault_path = \"/srv/journal\"\n";
+        let file = Path::new("config.toml");
+        let base = parse_config(content, file, None).unwrap().unwrap();
+        assert_eq!(base.default_path, Some(PathBuf::from("/base")));
+        let server = parse_config(content, file, Some("server")).unwrap().unwrap();
+        assert_eq!(server.default_path, Some(PathBuf::from("/srv/journal")));
+        let missing = parse_config(content, file, Some("laptop")).unwrap_err();
+        assert_eq!(missing, "No [profile.laptop] section in config.toml");
+        let invalid = parse_config("default_path = [", file, Some("laptop")).unwrap().unwrap_err();
+        assert!(invalid.starts_with("Failed to parse config at"));
+    }
+
+    #[test]
+    fn test_find_entries_includes_compressed() {
+        let temp_dir = create_test_journal_dir();
+        compress::compress_before(temp_dir.path(), 2026).expect("Failed to compress");
+
+        let entries = find_entries(temp_dir.path(), None, None, Some(2025))
+            .expect("Failed to find entries");
+
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].to_string_lossy().ends_with("15-080000-2025-note.md.gz"));
+        let content = read_entry(&entries[0]).expect("Failed to read entry");
+        assert!(content.contains("2025 content"));
+    }
 }
@@ -0,0 +1,172 @@
+/// A heading and the lines below it up to the next heading of the same or higher level
+#[derive(Debug, PartialEq)]
+pub struct Section {
+    pub level: usize,
+    pub heading: String,
+    pub content: String,
+}
+
+/// Parse an ATX heading (`## Title`) into its level and text
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let text = line[level..].strip_prefix(' ')?;
+    Some((level, text.trim().trim_end_matches('#').trim()))
+}
+
+/// The fence character and length if `line` opens or closes a fenced code block
+fn parse_fence(line: &str) -> Option<(char, usize)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    let fence = &line[indent..];
+    let c = fence.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = fence.chars().take_while(|&f| f == c).count();
+    (indent < 4 && len >= 3).then_some((c, len))
+}
+
+/// Split a markdown body into its headed sections, in document order. `#` lines inside
+/// fenced code blocks (shell comments, say) are not headings.
+pub fn sections(body: &str) -> Vec<Section> {
+    let lines: Vec<&str> = body.lines().collect();
+    let mut fence: Option<(char, usize)> = None;
+    let mut headings: Vec<(usize, usize, &str)> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        match (fence, parse_fence(line)) {
+            (None, Some(open)) => fence = Some(open),
+            // A closing fence is at least as long as the opening one and has no info string
+            (Some((c, len)), Some((close, close_len)))
+                if close == c && close_len >= len && line.trim().chars().all(|f| f == c) =>
+            {
+                fence = None;
+            }
+            (None, None) => {
+                if let Some((level, text)) = parse_heading(line) {
+                    headings.push((i, level, text));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    headings
+        .iter()
+        .enumerate()
+        .map(|(n, (start, level, text))| {
+            let end = headings[n + 1..]
+                .iter()
+                .find(|(_, next_level, _)| next_level <= level)
+                .map(|(i, _, _)| *i)
+                .unwrap_or(lines.len());
+            Section {
+                level: *level,
+                heading: text.to_string(),
+                content: lines[start + 1..end].join("\n").trim().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Content of the first section whose heading matches (case-insensitive)
+pub fn find_section(body: &str, heading: &str) -> Option<String> {
+    sections(body)
+        .into_iter()
+        .find(|section| section.heading.eq_ignore_ascii_case(heading))
+        .map(|section| section.content)
+}
+
+/// Text with the Markdown syntax removed, e.g. for reading aloud: headings and list
+/// items become sentences, links keep their text and images are dropped
+pub fn to_plain_text(body: &str) -> String {
+    let mut out = Vec::new();
+    for line in body.lines() {
+        let trimmed = line.trim();
+        let text = match parse_heading(trimmed) {
+            Some((_, heading)) => heading,
+            None => trimmed
+                .strip_prefix("- [ ] ")
+                .or_else(|| trimmed.strip_prefix("- [x] "))
+                .or_else(|| trimmed.strip_prefix("- "))
+                .or_else(|| trimmed.strip_prefix("* "))
+                .or_else(|| trimmed.strip_prefix("> "))
+                .unwrap_or(trimmed),
+        };
+        if text.starts_with("```") || text == "---" {
+            continue;
+        }
+        let text = strip_inline(text);
+        if text.is_empty() {
+            continue;
+        }
+        // End each line as a sentence so speech pauses between items
+        if text.ends_with(['.', '!', '?', ':', ';']) {
+            out.push(text);
+        } else {
+            out.push(format!("{}.", text));
+        }
+    }
+    out.join("\n")
+}
+
+/// `[text](url)` -> `text`, images removed, emphasis and code markers dropped
+fn strip_inline(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('[') {
+        let Some(mid) = rest[start..].find("](").map(|i| start + i) else {
+            break;
+        };
+        let Some(end) = rest[mid..].find(')').map(|i| mid + i) else {
+            break;
+        };
+        let before = &rest[..start];
+        match before.strip_suffix('!') {
+            Some(before) => out.push_str(before),
+            None => {
+                out.push_str(before);
+                out.push_str(&rest[start + 1..mid]);
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out.replace("**", "").replace("__", "").replace(['`', '*'], "").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = "# Day\n\nDate: 17-02-2026\n\n## Done\n- shipped\n### Detail\nmore\n## Blockers\n\n- waiting on review\n";
+
+    #[test]
+    fn test_sections_nest_until_same_level() {
+        let all = sections(BODY);
+        assert_eq!(all.len(), 4);
+        assert_eq!(all[1].heading, "Done");
+        assert_eq!(all[1].content, "- shipped\n### Detail\nmore");
+        assert_eq!(all[2].content, "more");
+    }
+
+    #[test]
+    fn test_sections_skip_fenced_code() {
+        let body = "## Done\n```sh\n# not a heading\nmake\n```\n~~~~\n```\n## nor this\n~~~~\n## Blockers\nnone\n";
+        let all = sections(body);
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].content, "```sh\n# not a heading\nmake\n```\n~~~~\n```\n## nor this\n~~~~");
+        assert_eq!(all[1].heading, "Blockers");
+    }
+
+    #[test]
+    fn test_find_section_case_insensitive() {
+        assert_eq!(find_section(BODY, "blockers").as_deref(), Some("- waiting on review"));
+        assert_eq!(find_section(BODY, "Today"), None);
+        assert_eq!(parse_heading("#nospace"), None);
+    }
+
+    #[test]
+    fn test_to_plain_text() {
+        let body = "# Hike\n\n- Up **Mount Tam** with [Sam](https://sam.example)\n- ![view](../../assets/v.jpg)\n\nGreat day!\n";
+        assert_eq!(to_plain_text(body), "Hike.\nUp Mount Tam with Sam.\nGreat day!");
+    }
+}
@@ -0,0 +1,156 @@
+//! Matrix room capture bridge (requires the `network` feature).
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Milliseconds the homeserver holds a /sync request open waiting for events
+const SYNC_TIMEOUT_MS: u64 = 30_000;
+
+/// File in the journal root remembering the last /sync batch token
+pub const SINCE_FILE: &str = ".matrix-since";
+
+/// `[matrix]` section of the config
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MatrixConfig {
+    /// Homeserver base URL, e.g. `https://matrix.example.org`
+    pub homeserver: Option<String>,
+    /// Access token of the account the bridge logs in as
+    pub access_token: Option<String>,
+    /// Private room to capture from, e.g. `!abc123:example.org`
+    pub room_id: Option<String>,
+    /// Only capture messages from these user ids (all room members if empty)
+    #[serde(default)]
+    pub allowed_senders: Vec<String>,
+}
+
+/// A text message posted to the room
+#[derive(Debug, PartialEq)]
+pub struct RoomMessage {
+    pub sender: String,
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+    pub body: String,
+}
+
+/// Extract text messages for `room_id` from a /sync response
+pub fn parse_sync(response: &Value, room_id: &str) -> Vec<RoomMessage> {
+    let Some(events) = response["rooms"]["join"][room_id]["timeline"]["events"].as_array() else {
+        return Vec::new();
+    };
+
+    events
+        .iter()
+        .filter(|event| event["type"] == "m.room.message")
+        .filter(|event| matches!(event["content"]["msgtype"].as_str(), Some("m.text" | "m.notice")))
+        .filter_map(|event| {
+            Some(RoomMessage {
+                sender: event["sender"].as_str()?.to_string(),
+                timestamp: event["origin_server_ts"].as_i64().unwrap_or_default(),
+                body: event["content"]["body"].as_str()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub struct Client {
+    agent: ureq::Agent,
+    homeserver: String,
+    token: String,
+    room_id: String,
+}
+
+impl Client {
+    pub fn new(homeserver: &str, token: String, room_id: String) -> Self {
+        let config = ureq::Agent::config_builder()
+            .timeout_global(Some(Duration::from_millis(SYNC_TIMEOUT_MS + 15_000)))
+            .build();
+        Client {
+            agent: config.into(),
+            homeserver: homeserver.trim_end_matches('/').to_string(),
+            token,
+            room_id,
+        }
+    }
+
+    /// Run one /sync; returns the messages and the next batch token
+    pub fn sync(&self, since: Option<&str>, wait: bool) -> Result<(Vec<RoomMessage>, String), String> {
+        let filter = json!({
+            "room": { "rooms": [self.room_id], "timeline": { "types": ["m.room.message"] } },
+            "presence": { "types": [] },
+            "account_data": { "types": [] },
+        });
+        let timeout = if wait { SYNC_TIMEOUT_MS } else { 0 };
+
+        let mut request = self
+            .agent
+            .get(&format!("{}/_matrix/client/v3/sync", self.homeserver))
+            .header("Authorization", &format!("Bearer {}", self.token))
+            .query("timeout", timeout.to_string())
+            .query("filter", filter.to_string());
+        if let Some(since) = since {
+            request = request.query("since", since);
+        }
+
+        let response: Value = request
+            .call()
+            .map_err(|e| format!("Matrix sync failed: {}", e))?
+            .body_mut()
+            .read_json()
+            .map_err(|e| format!("Invalid Matrix sync response: {}", e))?;
+        let next_batch = response["next_batch"]
+            .as_str()
+            .ok_or("Matrix sync response has no next_batch")?
+            .to_string();
+
+        Ok((parse_sync(&response, &self.room_id), next_batch))
+    }
+}
+
+pub fn load_since(journal_path: &Path) -> Option<String> {
+    fs::read_to_string(journal_path.join(SINCE_FILE))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+pub fn save_since(journal_path: &Path, since: &str) -> Result<(), String> {
+    let path = journal_path.join(SINCE_FILE);
+    fs::write(&path, since).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sync_keeps_text_in_room() {
+        let response: Value = serde_json::from_str(
+            r#"{"next_batch": "s2", "rooms": {"join": {
+                "!journal:x.org": {"timeline": {"events": [
+                    {"type": "m.room.message", "sender": "@me:x.org", "origin_server_ts": 1700000000000,
+                     "content": {"msgtype": "m.text", "body": "bought milk"}},
+                    {"type": "m.room.message", "sender": "@me:x.org", "origin_server_ts": 1700000001000,
+                     "content": {"msgtype": "m.image", "body": "IMG_1.jpg"}},
+                    {"type": "m.reaction", "sender": "@me:x.org", "content": {}}
+                ]}},
+                "!other:x.org": {"timeline": {"events": [
+                    {"type": "m.room.message", "sender": "@me:x.org", "content": {"msgtype": "m.text", "body": "no"}}
+                ]}}
+            }}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            parse_sync(&response, "!journal:x.org"),
+            vec![RoomMessage {
+                sender: "@me:x.org".to_string(),
+                timestamp: 1_700_000_000_000,
+                body: "bought milk".to_string(),
+            }]
+        );
+        assert!(parse_sync(&response, "!missing:x.org").is_empty());
+    }
+}
@@ -0,0 +1,113 @@
+//! `merge`: a day's entries combined into one, each under a `## HH:MM Title` section,
+//! earliest first.
+
+use crate::entry::Entry;
+use crate::frontmatter::{Frontmatter, Value};
+
+/// An entry's text without its frontmatter, `# ` heading and `Date:` line
+fn section_body(body: &str) -> String {
+    let mut heading = false;
+    let mut date = false;
+    let lines: Vec<&str> = body
+        .lines()
+        .filter(|line| {
+            if !heading && line.starts_with("# ") {
+                heading = true;
+                return false;
+            }
+            if !date && line.starts_with("Date: ") {
+                date = true;
+                return false;
+            }
+            true
+        })
+        .collect();
+    lines.join("\n").trim().to_string()
+}
+
+/// Frontmatter and note for the merged entry. Tags from every entry are kept; other
+/// frontmatter fields are kept when all entries that have them agree.
+pub fn combine(mut entries: Vec<Entry>) -> (Frontmatter, String) {
+    entries.sort_by(|a, b| (a.time, &a.path).cmp(&(b.time, &b.path)));
+
+    let mut merged = Frontmatter::default();
+    let mut tags: Vec<String> = Vec::new();
+    for entry in &entries {
+        for tag in entry.frontmatter.list("tags") {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+    let mut keys: Vec<&str> = Vec::new();
+    for (key, _) in entries.iter().flat_map(|entry| entry.frontmatter.fields()) {
+        if key != "tags" && !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    for key in keys {
+        let mut values = entries.iter().filter_map(|entry| entry.frontmatter.value(key));
+        if let Some(first) = values.next()
+            && values.all(|value| value == first)
+        {
+            merged.set(key, first.clone());
+        }
+    }
+    if !tags.is_empty() {
+        merged.set("tags", Value::List(tags));
+    }
+
+    let sections: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let filename = entry.path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+            let title = entry.title.clone().unwrap_or(filename);
+            let heading = match entry.time {
+                Some(time) => format!("## {} {}", time.format("%H:%M"), title),
+                None => format!("## {}", title),
+            };
+            let body = section_body(&entry.body);
+            if body.is_empty() { heading } else { format!("{}\n\n{}", heading, body) }
+        })
+        .collect();
+    (merged, sections.join("\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_combine() {
+        let late = Entry::parse(
+            Path::new("2026/02/17-180000-gym.md"),
+            concat!(
+                "---\ntags: [health]\nauthor: ana\nmood: tired\nsource: gym app\n---\n",
+                "# Gym\n\nDate: 17-02-2026\n\nLegs day\n"
+            ),
+        );
+        let early = Entry::parse(
+            Path::new("2026/02/17-081503-standup.md"),
+            concat!(
+                "---\ntags: [work, health]\nauthor: ana\nmood: good\nprojects: [atlas]\n---\n",
+                "# Standup\n\nDate: 17-02-2026\n\nShipped it\n\n- notes\n"
+            ),
+        );
+        let untitled = Entry::parse(Path::new("2026/02/17-120000-lunch.md"), "Date: 17-02-2026\n");
+
+        let (fm, note) = combine(vec![late, untitled, early]);
+        assert_eq!(fm.list("tags"), ["work", "health"]);
+        assert_eq!(fm.get("author"), Some("ana"));
+        // Kept when the entries that have them agree, dropped when they don't
+        assert_eq!(fm.list("projects"), ["atlas"]);
+        assert_eq!(fm.get("source"), Some("gym app"));
+        assert_eq!(fm.get("mood"), None);
+        let keys: Vec<&str> = fm.fields().map(|(key, _)| key).collect();
+        assert_eq!(keys, ["author", "projects", "source", "tags"]);
+        assert_eq!(
+            note,
+            "## 08:15 Standup\n\nShipped it\n\n- notes\n\n## 12:00 17-120000-lunch\n\n## 18:00 Gym\n\nLegs day"
+        );
+    }
+}
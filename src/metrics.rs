@@ -0,0 +1,94 @@
+//! Prometheus metrics for `serve` and `daemon`.
+
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+
+/// Counters since the process started
+#[derive(Debug, Default)]
+pub struct Metrics {
+    pub entries_created: u64,
+    pub entries_appended: u64,
+    pub entries_updated: u64,
+    pub queries: u64,
+    pub errors: u64,
+}
+
+/// Consecutive days with at least one entry, ending today (or yesterday, if
+/// today has no entry yet so the streak isn't broken until the day is over)
+pub fn streak(dates: &BTreeSet<NaiveDate>, today: NaiveDate) -> u32 {
+    let mut day = if dates.contains(&today) {
+        today
+    } else {
+        match today.pred_opt() {
+            Some(yesterday) => yesterday,
+            None => return 0,
+        }
+    };
+
+    let mut length = 0;
+    while dates.contains(&day) {
+        length += 1;
+        match day.pred_opt() {
+            Some(previous) => day = previous,
+            None => break,
+        }
+    }
+    length
+}
+
+/// Prometheus text exposition format
+pub fn render(metrics: &Metrics, entries: usize, streak: u32) -> String {
+    let mut out = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+        out.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n{} {}\n", name, help, name, kind, name, value));
+    };
+
+    metric("file_journal_entries_created_total", "counter", "Entries created.", metrics.entries_created.to_string());
+    metric(
+        "file_journal_entries_appended_total",
+        "counter",
+        "Appends to today's entry.",
+        metrics.entries_appended.to_string(),
+    );
+    metric(
+        "file_journal_entries_updated_total",
+        "counter",
+        "Entries replaced through PUT /entries.",
+        metrics.entries_updated.to_string(),
+    );
+    metric("file_journal_queries_total", "counter", "List and read requests served.", metrics.queries.to_string());
+    metric("file_journal_request_errors_total", "counter", "Requests that failed.", metrics.errors.to_string());
+    metric("file_journal_entries", "gauge", "Entries in the journal.", entries.to_string());
+    metric("file_journal_streak_days", "gauge", "Consecutive days with an entry.", streak.to_string());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_streak() {
+        let dates: BTreeSet<NaiveDate> = ["2026-02-14", "2026-02-15", "2026-02-16", "2026-02-10"]
+            .iter()
+            .map(|d| date(d))
+            .collect();
+        assert_eq!(streak(&dates, date("2026-02-16")), 3);
+        // Today not written yet: yesterday's streak still counts
+        assert_eq!(streak(&dates, date("2026-02-17")), 3);
+        assert_eq!(streak(&dates, date("2026-02-18")), 0);
+    }
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics { entries_created: 2, ..Default::default() };
+        let text = render(&metrics, 40, 5);
+        assert!(text.contains("# TYPE file_journal_entries_created_total counter\nfile_journal_entries_created_total 2\n"));
+        assert!(text.contains("file_journal_entries 40\n"));
+        assert!(text.contains("file_journal_streak_days 5\n"));
+    }
+}
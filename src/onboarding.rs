@@ -0,0 +1,104 @@
+//! First run: with no config and no journal, offer to set both up (and write a sample entry)
+//! instead of stopping at "No journal path specified".
+
+use std::fs;
+use std::io::{BufRead, IsTerminal};
+use std::path::{Path, PathBuf};
+
+use crate::{frontmatter, write_entry, Config};
+
+const SAMPLE_NOTE: &str = "This is a sample entry. Entries are plain Markdown files filed by date,
+so you can edit, move or delete this one like any other file.
+
+- [ ] Write a first real entry with `file-journal new \"Today.md\"`";
+
+const QUICKSTART: &str = "Quick start:
+  file-journal new \"Standup.md\" \"Notes\"   create an entry for today
+  file-journal get                        list today's entries
+  file-journal show                       print today's latest entry
+  file-journal edit                       open it in your editor
+  file-journal last 5                     the five most recent entries
+  file-journal --help                     every command";
+
+/// Only offer the flow to someone at a terminal; scripts get the plain error
+pub fn interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
+/// Ask a question, returning the trimmed answer or `default` when it's left empty
+fn ask(input: &mut impl BufRead, prompt: &str, default: &str) -> Result<String, String> {
+    print!("{} ", prompt);
+    let _ = std::io::Write::flush(&mut std::io::stdout());
+    let mut answer = String::new();
+    input.read_line(&mut answer).map_err(|e| format!("Failed to read input: {}", e))?;
+    let answer = answer.trim();
+    Ok(if answer.is_empty() { default.to_string() } else { answer.to_string() })
+}
+
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.to_lowercase().as_str(), "y" | "yes")
+}
+
+/// `~/...` expanded to the home folder
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Walk through creating the journal folder and `config_file`, returning the folder,
+/// or `None` if the user would rather not set up now
+pub fn run(input: &mut impl BufRead, config_file: &Path, suggested: &Path) -> Result<Option<PathBuf>, String> {
+    println!("No journal is set up yet.");
+    if !is_yes(&ask(input, "Set one up now? [Y/n]", "y")?) {
+        println!("Run 'file-journal init' or pass --path when you're ready.");
+        return Ok(None);
+    }
+
+    let answer = ask(input, &format!("Journal folder [{}]:", suggested.display()), &suggested.display().to_string())?;
+    let journal_path = expand_home(&answer);
+    fs::create_dir_all(&journal_path).map_err(|e| format!("Failed to create {}: {}", journal_path.display(), e))?;
+
+    let config = Config { default_path: Some(journal_path.clone()), ..Default::default() };
+    if let Some(parent) = config_file.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let toml_string = toml::to_string_pretty(&config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    fs::write(config_file, toml_string).map_err(|e| format!("Failed to write {}: {}", config_file.display(), e))?;
+    println!("Created config at: {}", config_file.display());
+
+    if is_yes(&ask(input, "Add a sample entry? [Y/n]", "y")?) {
+        let mut entry_frontmatter = frontmatter::Frontmatter::default();
+        entry_frontmatter.set("tags", frontmatter::Value::List(vec!["welcome".to_string()]));
+        let entry = write_entry(journal_path.clone(), "Welcome", SAMPLE_NOTE, entry_frontmatter, None)?;
+        println!("Created journal entry: {}", entry.display());
+    }
+
+    println!("\n{}\n", QUICKSTART);
+    Ok(Some(journal_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_all_entries;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_run_creates_config_and_sample() {
+        let dir = TempDir::new().unwrap();
+        let config_file = dir.path().join("config").join("config.toml");
+        let journal = dir.path().join("journal");
+
+        let mut input = format!("\n{}\n\n", journal.display());
+        let created = run(&mut input.as_bytes(), &config_file, Path::new("/unused")).unwrap();
+        assert_eq!(created.as_deref(), Some(journal.as_path()));
+        let config: Config = toml::from_str(&fs::read_to_string(&config_file).unwrap()).unwrap();
+        assert_eq!(config.default_path, Some(journal.clone()));
+        assert_eq!(find_all_entries(&journal).unwrap().len(), 1);
+
+        input = "n\n".to_string();
+        assert_eq!(run(&mut input.as_bytes(), &config_file, &journal).unwrap(), None);
+    }
+}
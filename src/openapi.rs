@@ -0,0 +1,376 @@
+//! OpenAPI description of the `serve` HTTP API, printed by `serve --openapi`.
+
+use utoipa::openapi::path::{HttpMethod, OperationBuilder, ParameterBuilder, ParameterIn, PathItem};
+use utoipa::openapi::request_body::RequestBodyBuilder;
+use utoipa::openapi::schema::{ObjectBuilder, Schema, Type};
+use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityRequirement, SecurityScheme};
+use utoipa::openapi::{
+    ComponentsBuilder, ContentBuilder, HeaderBuilder, InfoBuilder, OpenApi, OpenApiBuilder, PathsBuilder, Ref, RefOr,
+    Required, ResponseBuilder,
+};
+
+fn string(description: &str) -> ObjectBuilder {
+    ObjectBuilder::new().schema_type(Type::String).description(Some(description))
+}
+
+fn schema_ref(name: &str) -> RefOr<Schema> {
+    Ref::from_schema_name(name).into()
+}
+
+fn text(description: &str) -> ResponseBuilder {
+    ResponseBuilder::new()
+        .description(description)
+        .content("text/plain", ContentBuilder::new().schema(Some(string(description))).build())
+}
+
+fn etag_header() -> utoipa::openapi::Header {
+    HeaderBuilder::new().schema(string("Quoted SHA-256 of the entry")).build()
+}
+
+fn schemas() -> ComponentsBuilder {
+    let any = || ObjectBuilder::new().description(Some("Any JSON value"));
+    ComponentsBuilder::new()
+        .schema(
+            "RpcRequest",
+            ObjectBuilder::new()
+                .description(Some("JSON-RPC 2.0 request"))
+                .property("jsonrpc", string("Always \"2.0\""))
+                .property("id", any())
+                .property("method", string("ping, list, read, create or append"))
+                .property(
+                    "params",
+                    ObjectBuilder::new()
+                        .description(Some("list: selector; read: path; create: title, note; append: text"))
+                        .additional_properties(Some(string("Parameter value"))),
+                )
+                .required("method"),
+        )
+        .schema(
+            "RpcError",
+            ObjectBuilder::new()
+                .property("code", ObjectBuilder::new().schema_type(Type::Integer))
+                .property("message", string("What went wrong"))
+                .required("code")
+                .required("message"),
+        )
+        .schema(
+            "RpcResponse",
+            ObjectBuilder::new()
+                .description(Some("JSON-RPC 2.0 response carrying either a result or an error"))
+                .property("jsonrpc", string("Always \"2.0\""))
+                .property("id", any())
+                .property("result", ObjectBuilder::new().description(Some("Method result; `list` returns EntrySummary items")))
+                .property("error", schema_ref("RpcError"))
+                .required("jsonrpc"),
+        )
+        .schema(
+            "EntrySummary",
+            ObjectBuilder::new()
+                .description(Some("Item of the `list` method's result"))
+                .property("path", string("Entry path"))
+                .property("date", string("YYYY-MM-DD"))
+                .property("title", string("Entry title"))
+                .required("path"),
+        )
+        .schema(
+            "EntryEvent",
+            ObjectBuilder::new()
+                .description(Some("Message pushed over /ws when an entry changes"))
+                .property("event", string("created or modified"))
+                .property("path", string("Entry path"))
+                .property("date", string("YYYY-MM-DD"))
+                .required("event")
+                .required("path"),
+        )
+        .security_scheme("bearer", SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)))
+}
+
+/// The HTTP API served by `file-journal serve`
+pub fn spec() -> OpenApi {
+    let bearer = || SecurityRequirement::new("bearer", Vec::<String>::new());
+    let unauthorized = || text("Missing or unknown token (only when tokens are configured)");
+    let forbidden = || text("Token lacks the required scope");
+    let limited = || text("Rate limit exceeded; see Retry-After");
+    let entry_id = || {
+        ParameterBuilder::new()
+            .name("id")
+            .parameter_in(ParameterIn::Path)
+            .required(Required::True)
+            .description(Some(
+                "Entry path relative to the journal, e.g. 2026/02/16-081503-idea.md; its slashes may be sent as %2F",
+            ))
+            .schema(Some(string("Entry path")))
+            .build()
+    };
+
+    let health = OperationBuilder::new()
+        .summary(Some("Liveness check"))
+        .operation_id(Some("health"))
+        .response("200", text("ok"));
+
+    let metrics = OperationBuilder::new()
+        .summary(Some("Prometheus metrics"))
+        .operation_id(Some("metrics"))
+        .security(bearer())
+        .response(
+            "200",
+            ResponseBuilder::new().description("Prometheus text exposition format").content(
+                "text/plain; version=0.0.4",
+                ContentBuilder::new().schema(Some(string("Metrics"))).build(),
+            ),
+        )
+        .response("401", unauthorized())
+        .response("403", forbidden())
+        .response("429", limited());
+
+    let rpc = OperationBuilder::new()
+        .summary(Some("JSON-RPC call, the same methods as the daemon socket"))
+        .description(Some("create and append need the write scope, everything else the read scope."))
+        .operation_id(Some("rpc"))
+        .security(bearer())
+        .request_body(Some(
+            RequestBodyBuilder::new()
+                .required(Some(Required::True))
+                .content("application/json", ContentBuilder::new().schema(Some(schema_ref("RpcRequest"))).build())
+                .build(),
+        ))
+        .response(
+            "200",
+            ResponseBuilder::new()
+                .description("Result or JSON-RPC error")
+                .content("application/json", ContentBuilder::new().schema(Some(schema_ref("RpcResponse"))).build()),
+        )
+        .response("400", text("Unreadable request body"))
+        .response("401", unauthorized())
+        .response(
+            "403",
+            ResponseBuilder::new()
+                .description("Token lacks the scope the method needs")
+                .content("application/json", ContentBuilder::new().schema(Some(schema_ref("RpcResponse"))).build()),
+        )
+        .response(
+            "415",
+            ResponseBuilder::new()
+                .description("Content-Type is not application/json")
+                .content("application/json", ContentBuilder::new().schema(Some(schema_ref("RpcResponse"))).build()),
+        )
+        .response("429", limited());
+
+    let ws = OperationBuilder::new()
+        .summary(Some("WebSocket stream of entry changes"))
+        .description(Some(
+            "Upgrade to a WebSocket; every message is an EntryEvent. Browsers may pass the token as ?access_token=.",
+        ))
+        .operation_id(Some("events"))
+        .security(bearer())
+        .parameter(
+            ParameterBuilder::new()
+                .name("access_token")
+                .parameter_in(ParameterIn::Query)
+                .required(Required::False)
+                .schema(Some(string("API token"))),
+        )
+        .response(
+            "101",
+            ResponseBuilder::new()
+                .description("Switching protocols")
+                .content("application/json", ContentBuilder::new().schema(Some(schema_ref("EntryEvent"))).build()),
+        )
+        .response("400", text("Not a WebSocket upgrade"))
+        .response("401", unauthorized())
+        .response("403", forbidden());
+
+    let quick = OperationBuilder::new()
+        .summary(Some("Append a line to today's entry"))
+        .description(Some(
+            "A plain form post for phone shortcuts; the token may be passed as ?token=. Needs the write scope.",
+        ))
+        .operation_id(Some("quickAdd"))
+        .security(bearer())
+        .parameter(
+            ParameterBuilder::new()
+                .name("token")
+                .parameter_in(ParameterIn::Query)
+                .required(Required::False)
+                .schema(Some(string("API token"))),
+        )
+        .request_body(Some(
+            RequestBodyBuilder::new()
+                .required(Some(Required::True))
+                .content(
+                    "application/x-www-form-urlencoded",
+                    ContentBuilder::new()
+                        .schema(Some(ObjectBuilder::new().property("text", string("Text to append")).required("text")))
+                        .build(),
+                )
+                .build(),
+        ))
+        .response("200", text("Added to the entry's path"))
+        .response("400", text("No text given"))
+        .response("401", unauthorized())
+        .response("403", forbidden())
+        .response("429", limited());
+
+    let get_entry = OperationBuilder::new()
+        .summary(Some("Read an entry"))
+        .operation_id(Some("getEntry"))
+        .security(bearer())
+        .parameter(entry_id())
+        .response(
+            "200",
+            ResponseBuilder::new()
+                .description("Entry contents")
+                .header("ETag", etag_header())
+                .content("text/markdown", ContentBuilder::new().schema(Some(string("Markdown"))).build()),
+        )
+        .response("401", unauthorized())
+        .response("403", forbidden())
+        .response("404", text("No such entry"))
+        .response("429", limited());
+
+    let put_entry = OperationBuilder::new()
+        .summary(Some("Replace an entry"))
+        .description(Some("If-Match must carry the ETag the client last read, so concurrent edits are not lost."))
+        .operation_id(Some("putEntry"))
+        .security(bearer())
+        .parameter(entry_id())
+        .parameter(
+            ParameterBuilder::new()
+                .name("If-Match")
+                .parameter_in(ParameterIn::Header)
+                .required(Required::True)
+                .schema(Some(string("ETag from the last read"))),
+        )
+        .request_body(Some(
+            RequestBodyBuilder::new()
+                .required(Some(Required::True))
+                .content("text/markdown", ContentBuilder::new().schema(Some(string("Markdown"))).build())
+                .build(),
+        ))
+        .response("204", ResponseBuilder::new().description("Saved").header("ETag", etag_header()))
+        .response("401", unauthorized())
+        .response("403", forbidden())
+        .response("404", text("No such entry"))
+        .response("409", text("Archived entries are read-only"))
+        .response("412", text("The entry changed since it was read"))
+        .response("428", text("If-Match is required"))
+        .response("429", limited());
+
+    let mut entries = PathItem::new(HttpMethod::Get, get_entry);
+    entries.put = Some(put_entry.build());
+
+    #[cfg_attr(not(any(feature = "graphql", feature = "network")), allow(unused_mut))]
+    let mut paths = PathsBuilder::new()
+        .path("/health", PathItem::new(HttpMethod::Get, health))
+        .path("/metrics", PathItem::new(HttpMethod::Get, metrics))
+        .path("/rpc", PathItem::new(HttpMethod::Post, rpc))
+        .path("/quick", PathItem::new(HttpMethod::Post, quick))
+        .path("/ws", PathItem::new(HttpMethod::Get, ws))
+        .path("/entries/{id}", entries);
+    #[cfg(feature = "network")]
+    {
+        let clip = OperationBuilder::new()
+            .summary(Some("Save a clipped page selection as a bookmark entry"))
+            .description(Some(
+                "The HTML is converted to Markdown and followed by a link to the page; the entry gets `source: <url>` and the bookmark tag. Needs the write scope.",
+            ))
+            .operation_id(Some("clip"))
+            .security(bearer())
+            .request_body(Some(
+                RequestBodyBuilder::new()
+                    .required(Some(Required::True))
+                    .content(
+                        "application/json",
+                        ContentBuilder::new()
+                            .schema(Some(
+                                ObjectBuilder::new()
+                                    .property("url", string("Page the selection came from"))
+                                    .property("html", string("Selected HTML"))
+                                    .property("title", string("Entry title; defaults to the page's host"))
+                                    .required("url"),
+                            ))
+                            .build(),
+                    )
+                    .build(),
+            ))
+            .response(
+                "201",
+                ResponseBuilder::new().description("Entry created").content(
+                    "application/json",
+                    ContentBuilder::new()
+                        .schema(Some(ObjectBuilder::new().property("path", string("Path of the new entry"))))
+                        .build(),
+                ),
+            )
+            .response("400", text("Invalid body or no url"))
+            .response("401", unauthorized())
+            .response("403", forbidden())
+            .response("429", limited());
+        paths = paths.path("/clip", PathItem::new(HttpMethod::Post, clip));
+    }
+    #[cfg(feature = "graphql")]
+    {
+        let graphql = OperationBuilder::new()
+            .summary(Some("Read-only GraphQL queries: entries, entry, tags, stats, search"))
+            .operation_id(Some("graphql"))
+            .security(bearer())
+            .request_body(Some(
+                RequestBodyBuilder::new()
+                    .required(Some(Required::True))
+                    .content(
+                        "application/json",
+                        ContentBuilder::new()
+                            .schema(Some(
+                                ObjectBuilder::new()
+                                    .property("query", string("GraphQL query"))
+                                    .property("variables", ObjectBuilder::new().description(Some("Query variables")))
+                                    .required("query"),
+                            ))
+                            .build(),
+                    )
+                    .build(),
+            ))
+            .response(
+                "200",
+                ResponseBuilder::new().description("GraphQL response with data and/or errors").content(
+                    "application/json",
+                    ContentBuilder::new().schema(Some(ObjectBuilder::new().description(Some("data, errors")))).build(),
+                ),
+            )
+            .response("401", unauthorized())
+            .response("403", forbidden())
+            .response("429", limited());
+        paths = paths.path("/graphql", PathItem::new(HttpMethod::Post, graphql));
+    }
+
+    OpenApiBuilder::new()
+        .info(
+            InfoBuilder::new()
+                .title("file-journal")
+                .version(env!("CARGO_PKG_VERSION"))
+                .description(Some("HTTP API of `file-journal serve`")),
+        )
+        .paths(paths)
+        .components(Some(schemas().build()))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_covers_every_route() {
+        let json: serde_json::Value = serde_json::from_str(&spec().to_pretty_json().unwrap()).unwrap();
+        for path in ["/health", "/metrics", "/rpc", "/quick", "/ws", "/entries/{id}"] {
+            assert!(json["paths"][path].is_object(), "{} missing", path);
+        }
+        assert_eq!(json["paths"]["/clip"].is_object(), cfg!(feature = "network"));
+        assert!(json["paths"]["/entries/{id}"]["put"]["responses"]["412"].is_object());
+        assert_eq!(
+            json["paths"]["/rpc"]["post"]["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/RpcRequest"
+        );
+        assert_eq!(json["components"]["securitySchemes"]["bearer"]["scheme"], "bearer");
+    }
+}
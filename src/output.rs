@@ -0,0 +1,148 @@
+//! `--output FILE` for commands that produce exports, so binary formats reach the file
+//! byte for byte instead of passing through shell redirection.
+
+use clap::Args;
+use std::fs;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Debug, Default)]
+pub struct OutputArgs {
+    /// Write to FILE instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+    /// Write to stdout (the default)
+    #[arg(long, conflicts_with = "output")]
+    pub stdout: bool,
+    /// Overwrite FILE without asking
+    #[arg(long, requires = "output")]
+    pub force: bool,
+}
+
+impl OutputArgs {
+    /// Whether output goes to a file, where colours and other terminal niceties don't belong
+    pub fn to_file(&self) -> bool {
+        self.output.is_some()
+    }
+
+    /// Write `bytes` to the chosen file, or stdout
+    pub fn write(&self, bytes: &[u8]) -> Result<(), String> {
+        match &self.output {
+            Some(path) => write_file(path, bytes, self.force, ask_to_overwrite),
+            None => {
+                let mut stdout = std::io::stdout().lock();
+                stdout.write_all(bytes).and_then(|_| stdout.flush()).map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    /// Hand `write` a writer to the chosen file or stdout, for output produced a piece at a
+    /// time: stdout gets each piece as it's written, while a file is still only replaced once
+    /// everything is
+    pub fn stream(&self, write: impl FnOnce(&mut dyn Write) -> Result<(), String>) -> Result<(), String> {
+        match &self.output {
+            Some(path) => stream_file(path, self.force, ask_to_overwrite, write),
+            None => {
+                let mut stdout = std::io::stdout().lock();
+                write(&mut stdout)?;
+                stdout.flush().map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+/// Ask whether to replace `path` when there's someone at the terminal to answer
+fn ask_to_overwrite(path: &Path) -> Result<bool, String> {
+    if !std::io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    confirm_overwrite(&mut std::io::stdin().lock(), path)
+}
+
+/// Ask on stderr whether to replace `path`; anything but y/yes keeps it
+fn confirm_overwrite(input: &mut impl BufRead, path: &Path) -> Result<bool, String> {
+    eprint!("{} already exists. Overwrite? [y/N] ", path.display());
+    let mut answer = String::new();
+    input.read_line(&mut answer).map_err(|e| format!("Failed to read input: {}", e))?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Write `bytes` to `path` through a temporary file beside it, so an interrupted write
+/// never leaves half a file. An existing file is only replaced with `force` or if `confirm` agrees.
+fn write_file(path: &Path, bytes: &[u8], force: bool, confirm: impl FnOnce(&Path) -> Result<bool, String>) -> Result<(), String> {
+    stream_file(path, force, confirm, |file| {
+        file.write_all(bytes).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    })
+}
+
+/// `write_file` for output that `write` produces a piece at a time
+fn stream_file(
+    path: &Path,
+    force: bool,
+    confirm: impl FnOnce(&Path) -> Result<bool, String>,
+    write: impl FnOnce(&mut dyn Write) -> Result<(), String>,
+) -> Result<(), String> {
+    if path.exists() && !force && !confirm(path)? {
+        return Err(format!("{} already exists (use --force to overwrite)", path.display()));
+    }
+    let name = path.file_name().ok_or_else(|| format!("{} is not a file path", path.display()))?;
+    let temp = path.with_file_name(format!(".{}.tmp", name.to_string_lossy()));
+    let file = fs::File::create(&temp).map_err(|e| format!("Failed to write {}: {}", temp.display(), e))?;
+    let mut file = std::io::BufWriter::new(file);
+    let written = write(&mut file)
+        .and_then(|_| file.flush().map_err(|e| format!("Failed to write {}: {}", temp.display(), e)))
+        .and_then(|_| fs::rename(&temp, path).map_err(|e| format!("Failed to write {}: {}", path.display(), e)));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    written
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("worklog.pdf");
+        let bytes = b"%PDF-1.7\n\x00\xff\x80";
+
+        write_file(&path, bytes, false, |_| panic!("nothing to overwrite")).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+
+        let err = write_file(&path, b"new", false, |_| Ok(false)).unwrap_err();
+        assert!(err.contains("use --force"));
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+
+        write_file(&path, b"confirmed", false, |_| Ok(true)).unwrap();
+        write_file(&path, b"forced", true, |_| panic!("--force doesn't ask")).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "forced");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_stream_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("entries.ndjson");
+        fs::write(&path, "old\n").unwrap();
+
+        let err = stream_file(&path, true, |_| Ok(true), |out| {
+            out.write_all(b"{}\n").unwrap();
+            Err("Failed to read entry".to_string())
+        });
+        assert_eq!(err.unwrap_err(), "Failed to read entry");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old\n");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        stream_file(&path, true, |_| Ok(true), |out| out.write_all(b"{}\n{}\n").map_err(|e| e.to_string())).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{}\n{}\n");
+    }
+
+    #[test]
+    fn test_confirm_overwrite() {
+        assert!(confirm_overwrite(&mut "y\n".as_bytes(), Path::new("a")).unwrap());
+        assert!(!confirm_overwrite(&mut "\n".as_bytes(), Path::new("a")).unwrap());
+    }
+}
@@ -0,0 +1,128 @@
+//! `ls`: how many entries (and optionally words) each year and month folder holds.
+
+use serde::Serialize;
+use std::path::Path;
+
+use crate::entry::Entry;
+use crate::find_all_entries;
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Month {
+    pub month: u32,
+    pub entries: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words: Option<usize>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Year {
+    pub year: i32,
+    pub entries: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words: Option<usize>,
+    pub months: Vec<Month>,
+}
+
+/// Year and month folder an entry is filed under
+fn folders(entry: &Path) -> Option<(i32, u32)> {
+    let month_dir = entry.parent()?;
+    let month = month_dir.file_name()?.to_str()?.parse().ok()?;
+    let year = month_dir.parent()?.file_name()?.to_str()?.parse().ok()?;
+    Some((year, month))
+}
+
+/// Words in an entry body, not counting frontmatter
+fn word_count(entry: &Path) -> usize {
+    Entry::read(entry).map(|entry| entry.words()).unwrap_or(0)
+}
+
+/// Entries per year and month, oldest first; words are counted only when asked for
+pub fn journal_overview(journal_path: &Path, count_words: bool) -> Result<Vec<Year>, String> {
+    let mut years: Vec<Year> = Vec::new();
+    // Entries come sorted by path, so folders arrive in order
+    for entry in find_all_entries(journal_path)? {
+        let Some((year, month)) = folders(&entry) else {
+            continue;
+        };
+        let words = count_words.then(|| word_count(&entry));
+        if years.last().is_none_or(|y| y.year != year) {
+            years.push(Year { year, entries: 0, words: words.map(|_| 0), months: Vec::new() });
+        }
+        let current = years.last_mut().expect("pushed above");
+        if current.months.last().is_none_or(|m| m.month != month) {
+            current.months.push(Month { month, entries: 0, words: words.map(|_| 0) });
+        }
+        let current_month = current.months.last_mut().expect("pushed above");
+        current.entries += 1;
+        current_month.entries += 1;
+        if let Some(words) = words {
+            current.words = current.words.map(|w| w + words);
+            current_month.words = current_month.words.map(|w| w + words);
+        }
+    }
+    Ok(years)
+}
+
+fn counts(entries: usize, words: Option<usize>) -> String {
+    let entries = format!("{} {}", entries, if entries == 1 { "entry" } else { "entries" });
+    match words {
+        Some(words) => format!("{}, {} words", entries, words),
+        None => entries,
+    }
+}
+
+/// One `YYYY-MM  counts` line per month
+pub fn render_flat(years: &[Year]) -> String {
+    let mut out = String::new();
+    for year in years {
+        for month in &year.months {
+            out.push_str(&format!("{}-{:02}  {}\n", year.year, month.month, counts(month.entries, month.words)));
+        }
+    }
+    out
+}
+
+/// Years with their months indented below them
+pub fn render_tree(years: &[Year]) -> String {
+    let mut out = String::new();
+    for year in years {
+        out.push_str(&format!("{} ({})\n", year.year, counts(year.entries, year.words)));
+        for (i, month) in year.months.iter().enumerate() {
+            let branch = if i + 1 == year.months.len() { "└──" } else { "├──" };
+            out.push_str(&format!("{} {:02}  {}\n", branch, month.month, counts(month.entries, month.words)));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_overview_and_rendering() {
+        let dir = TempDir::new().unwrap();
+        for (month, name, content) in [
+            ("2025/12", "24-090000-eve.md", "# Eve\n\nSnow all day\n"),
+            ("2026/02", "16-080000-a.md", "---\ntags: [work]\n---\n# A\n"),
+            ("2026/02", "17-080000-b.md", "# B\n\nTwo words\n"),
+            ("2026/03", "01-080000-c.md", "# C\n"),
+        ] {
+            fs::create_dir_all(dir.path().join(month)).unwrap();
+            fs::write(dir.path().join(month).join(name), content).unwrap();
+        }
+
+        let years = journal_overview(dir.path(), true).unwrap();
+        assert_eq!(years.len(), 2);
+        assert_eq!((years[1].year, years[1].entries, years[1].words), (2026, 3, Some(8)));
+        assert_eq!(years[1].months[0], Month { month: 2, entries: 2, words: Some(6) });
+
+        assert_eq!(
+            render_tree(&journal_overview(dir.path(), false).unwrap()),
+            "2025 (1 entry)\n└── 12  1 entry\n2026 (3 entries)\n├── 02  2 entries\n└── 03  1 entry\n"
+        );
+        assert_eq!(render_flat(&years).lines().nth(1), Some("2026-02  2 entries, 6 words"));
+    }
+}
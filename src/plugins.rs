@@ -0,0 +1,186 @@
+//! Rhai plugins (requires the `plugins` feature): `*.rhai` scripts in the plugins folder
+//! that hook into commands or add new ones, without changing the crate.
+//!
+//! A script may define any of:
+//! - `fn on_new(path, content)`: runs after `new` creates an entry; returning a string
+//!   replaces the entry's content
+//! - `fn on_get(paths)`: runs before `get` prints; returning an array replaces the entries
+//! - `fn command_<name>(args)`: runs for `file-journal <name> [args...]`, with underscores
+//!   in the name typed as dashes
+//!
+//! Scripts can call `journal_path()`, `entries()`, `read_entry(path)`, `entry_date(path)`
+//! and `new_entry(title, note)`.
+
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, Scope, AST};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{entry_date, find_all_entries, frontmatter, read_entry, write_entry};
+
+struct Plugin {
+    path: PathBuf,
+    ast: AST,
+}
+
+impl Plugin {
+    fn defines(&self, name: &str, params: usize) -> bool {
+        self.ast.iter_functions().any(|f| f.name == name && f.params.len() == params)
+    }
+}
+
+pub struct Plugins {
+    engine: Engine,
+    plugins: Vec<Plugin>,
+}
+
+fn script_error(e: impl std::fmt::Display) -> Box<EvalAltResult> {
+    e.to_string().into()
+}
+
+/// Engine with the journal functions scripts can call
+fn engine(journal_path: &Path, author: Option<String>) -> Engine {
+    let mut engine = Engine::new();
+    let root = journal_path.to_path_buf();
+    engine.register_fn("journal_path", move || root.display().to_string());
+    let root = journal_path.to_path_buf();
+    engine.register_fn("entries", move || -> Result<Array, Box<EvalAltResult>> {
+        let entries = find_all_entries(&root).map_err(script_error)?;
+        Ok(entries.into_iter().map(|p| Dynamic::from(p.display().to_string())).collect())
+    });
+    engine.register_fn("read_entry", |path: &str| read_entry(Path::new(path)).map_err(script_error));
+    engine.register_fn("entry_date", |path: &str| {
+        entry_date(Path::new(path)).map(|d| d.to_string()).unwrap_or_default()
+    });
+    let root = journal_path.to_path_buf();
+    engine.register_fn("new_entry", move |title: &str, note: &str| -> Result<String, Box<EvalAltResult>> {
+        let path = write_entry(root.clone(), title, note, frontmatter::Frontmatter::default(), author.clone())
+            .map_err(script_error)?;
+        Ok(path.display().to_string())
+    });
+    engine
+}
+
+impl Plugins {
+    /// Compile every `*.rhai` script in `dir`, in name order; a missing folder means no plugins
+    pub fn load(dir: &Path, journal_path: &Path, author: Option<String>) -> Result<Plugins, String> {
+        let engine = engine(journal_path, author);
+        let mut scripts: Vec<PathBuf> = match fs::read_dir(dir) {
+            Ok(items) => items.flatten().map(|item| item.path()).filter(|p| p.extension().is_some_and(|e| e == "rhai")).collect(),
+            Err(_) => Vec::new(),
+        };
+        scripts.sort();
+
+        let mut plugins = Vec::new();
+        for path in scripts {
+            let ast = engine.compile_file(path.clone()).map_err(|e| format!("Plugin {}: {}", path.display(), e))?;
+            plugins.push(Plugin { path, ast });
+        }
+        Ok(Plugins { engine, plugins })
+    }
+
+    fn call(&self, plugin: &Plugin, name: &str, args: impl rhai::FuncArgs) -> Result<Dynamic, String> {
+        // Only the function runs, not the script's top-level statements
+        let options = CallFnOptions::new().eval_ast(false);
+        self.engine
+            .call_fn_with_options(options, &mut Scope::new(), &plugin.ast, name, args)
+            .map_err(|e| format!("Plugin {}: {}", plugin.path.display(), e))
+    }
+
+    /// Run `on_new` hooks on a freshly created entry, saving any content they return
+    pub fn on_new(&self, entry: &Path) -> Result<(), String> {
+        let original = fs::read_to_string(entry).map_err(|e| format!("Failed to read {}: {}", entry.display(), e))?;
+        let mut content = original.clone();
+        for plugin in self.plugins.iter().filter(|p| p.defines("on_new", 2)) {
+            let result = self.call(plugin, "on_new", (entry.display().to_string(), content.clone()))?;
+            if let Ok(replaced) = result.into_immutable_string() {
+                content = replaced.to_string();
+            }
+        }
+        if content != original {
+            fs::write(entry, content).map_err(|e| format!("Failed to write {}: {}", entry.display(), e))?;
+        }
+        Ok(())
+    }
+
+    /// Whether any script has an `on_get` hook, which needs every entry before `get` prints
+    pub fn has_on_get(&self) -> bool {
+        self.plugins.iter().any(|p| p.defines("on_get", 1))
+    }
+
+    /// Run `on_get` hooks, each seeing the entries the previous one returned
+    pub fn on_get(&self, mut entries: Vec<PathBuf>) -> Result<Vec<PathBuf>, String> {
+        for plugin in self.plugins.iter().filter(|p| p.defines("on_get", 1)) {
+            let paths: Array = entries.iter().map(|p| Dynamic::from(p.display().to_string())).collect();
+            let result = self.call(plugin, "on_get", (paths,))?;
+            if let Some(returned) = result.try_cast::<Array>() {
+                entries = returned.into_iter().map(|p| PathBuf::from(p.to_string())).collect();
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Subcommands added by scripts, as typed on the command line
+    pub fn commands(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .plugins
+            .iter()
+            .flat_map(|p| p.ast.iter_functions())
+            .filter(|f| f.params.len() == 1)
+            .filter_map(|f| f.name.strip_prefix("command_").map(|name| name.replace('_', "-")))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Run the script command `name`; `None` if no plugin defines it
+    pub fn run_command(&self, name: &str, args: &[String]) -> Option<Result<(), String>> {
+        let function = format!("command_{}", name.replace('-', "_"));
+        let plugin = self.plugins.iter().find(|p| p.defines(&function, 1))?;
+        let args: Array = args.iter().map(|a| Dynamic::from(a.clone())).collect();
+        Some(self.call(plugin, &function, (args,)).map(|_| ()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn plugins(script: &str) -> (TempDir, Plugins) {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("plugins")).unwrap();
+        fs::write(dir.path().join("plugins").join("test.rhai"), script).unwrap();
+        fs::write(dir.path().join("plugins").join("notes.txt"), "not a plugin").unwrap();
+        let plugins = Plugins::load(&dir.path().join("plugins"), dir.path(), None).unwrap();
+        (dir, plugins)
+    }
+
+    #[test]
+    fn test_on_new_rewrites_content() {
+        let (dir, plugins) = plugins("fn on_new(path, content) { content + \"\\n#signed\\n\" }");
+        let entry = dir.path().join("entry.md");
+        fs::write(&entry, "# Entry\n").unwrap();
+        plugins.on_new(&entry).unwrap();
+        assert_eq!(fs::read_to_string(&entry).unwrap(), "# Entry\n\n#signed\n");
+    }
+
+    #[test]
+    fn test_on_get_filters_entries() {
+        let (_dir, plugins) = plugins("fn on_get(paths) { paths.filter(|p| !p.contains(\"draft\")) }");
+        let entries = vec![PathBuf::from("2026/02/16-080000-draft.md"), PathBuf::from("2026/02/16-090000-done.md")];
+        assert_eq!(plugins.on_get(entries).unwrap(), vec![PathBuf::from("2026/02/16-090000-done.md")]);
+        assert!(plugins.has_on_get());
+    }
+
+    #[test]
+    fn test_commands() {
+        let (dir, plugins) = plugins("fn command_word_count(args) { new_entry(args[0], \"from a plugin\") }\nfn helper() {}");
+        assert_eq!(plugins.commands(), vec!["word-count"]);
+        assert!(plugins.run_command("missing", &[]).is_none());
+        plugins.run_command("word-count", &["plugged".to_string()]).unwrap().unwrap();
+        let created = find_all_entries(dir.path()).unwrap();
+        assert_eq!(created.len(), 1);
+        assert!(read_entry(&created[0]).unwrap().contains("from a plugin"));
+    }
+}
@@ -0,0 +1,32 @@
+//! Progress bars on stderr for bulk operations over the whole journal, so they don't look
+//! frozen. Hidden when stderr isn't a terminal or under `--quiet`.
+
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+static QUIET: OnceLock<bool> = OnceLock::new();
+
+/// Turn progress output off for the rest of the run (`--quiet`)
+pub fn set_quiet(quiet: bool) {
+    let _ = QUIET.set(quiet);
+}
+
+fn visible() -> bool {
+    !QUIET.get().copied().unwrap_or(false) && std::io::stderr().is_terminal()
+}
+
+/// Bar over `len` items. Print through `ProgressBar::suspend` while it's shown.
+pub fn bar(len: usize, message: &str) -> ProgressBar {
+    if !visible() {
+        return ProgressBar::hidden();
+    }
+    let bar = ProgressBar::new(len as u64);
+    bar.set_style(
+        ProgressStyle::with_template("{msg} [{bar:30}] {pos}/{len} ({eta})")
+            .expect("valid template")
+            .progress_chars("=> "),
+    );
+    bar.set_message(message.to_string());
+    bar
+}
@@ -0,0 +1,88 @@
+//! Frontmatter schemas per entry kind (the `kind:` field), from `[schemas.<kind>]` in the
+//! config. Each key names a field and its type: `string`, `list`, `number`, `bool` or `date`
+//! (YYYY-MM-DD), with a trailing `?` when the field may be left out. `lint` checks the whole
+//! journal against them and `new` won't create an entry that doesn't fit.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::frontmatter::{Frontmatter, Value};
+
+/// Field name to type, e.g. `attendees = "list"`, `room = "string?"`
+pub type Schema = BTreeMap<String, String>;
+
+/// `[schemas]` section of the config, by kind
+pub type Schemas = HashMap<String, Schema>;
+
+const TYPES: [&str; 5] = ["string", "list", "number", "bool", "date"];
+
+/// The type a field spec names, and whether the field is required
+fn field_type(spec: &str) -> Result<(&str, bool), String> {
+    let (kind, required) = match spec.trim().strip_suffix('?') {
+        Some(kind) => (kind.trim(), false),
+        None => (spec.trim(), true),
+    };
+    if TYPES.contains(&kind) {
+        Ok((kind, required))
+    } else {
+        Err(format!("Unknown field type '{}': expected one of {}, optionally ending in ?", spec, TYPES.join(", ")))
+    }
+}
+
+/// Whether a field of `kind` holds a list, so `new --set` knows to split its value on commas
+pub fn is_list(schema: &Schema, field: &str) -> bool {
+    schema.get(field).and_then(|spec| field_type(spec).ok()).is_some_and(|(kind, _)| kind == "list")
+}
+
+/// What's wrong with `frontmatter` by `schema`, one message per field; an error if the schema
+/// itself names an unknown type
+pub fn validate(schema: &Schema, frontmatter: &Frontmatter) -> Result<Vec<String>, String> {
+    let mut problems = Vec::new();
+    for (field, spec) in schema {
+        let (kind, required) = field_type(spec)?;
+        let valid = match (kind, frontmatter.value(field)) {
+            (_, None) => {
+                if required {
+                    problems.push(format!("{}: missing ({})", field, kind));
+                }
+                continue;
+            }
+            ("list", Some(value)) => matches!(value, Value::List(_)),
+            (_, Some(Value::List(_))) => false,
+            ("string", Some(Value::Scalar(value))) => !value.is_empty(),
+            ("number", Some(Value::Scalar(value))) => value.parse::<f64>().is_ok(),
+            ("bool", Some(Value::Scalar(value))) => matches!(value.as_str(), "true" | "false"),
+            (_, Some(Value::Scalar(value))) => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+        };
+        if !valid {
+            problems.push(format!("{}: expected {}", field, kind));
+        }
+    }
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontmatter;
+
+    #[test]
+    fn test_validate() {
+        let schema: Schema = [("attendees", "list"), ("room", "string?"), ("duration", "number"), ("on", "date?")]
+            .into_iter()
+            .map(|(field, spec)| (field.to_string(), spec.to_string()))
+            .collect();
+        let (good, _) = frontmatter::parse("---\nkind: meeting\nattendees: [Ana, Bo]\nduration: 30\n---\n");
+        assert_eq!(validate(&schema, &good).unwrap(), Vec::<String>::new());
+
+        let (bad, _) = frontmatter::parse("---\nattendees: Ana\nroom: 4B\non: tuesday\n---\n");
+        assert_eq!(
+            validate(&schema, &bad).unwrap(),
+            ["attendees: expected list", "duration: missing (number)", "on: expected date"]
+        );
+        assert!(is_list(&schema, "attendees"));
+        assert!(!is_list(&schema, "room"));
+
+        let broken: Schema = [("attendees".to_string(), "people".to_string())].into_iter().collect();
+        assert!(validate(&broken, &good).is_err());
+    }
+}
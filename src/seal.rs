@@ -0,0 +1,319 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::{archive, find_all_entries, progress, read_entry};
+
+/// Tamper-evidence log kept at the journal root
+pub const SEAL_LOG: &str = ".seals.log";
+
+/// Directory (inside the journal root) for each seal's leaf list and timestamp response
+pub const SEAL_DIR: &str = ".seals";
+
+/// One line of the seal log
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Seal {
+    /// When the seal was taken (RFC 3339)
+    pub timestamp: String,
+    /// Number of entries covered by the root
+    pub entries: usize,
+    /// Hex-encoded merkle root over all entries
+    pub root: String,
+    /// Journal-relative path of an RFC 3161 timestamp response for this root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_token: Option<String>,
+    /// Journal-relative path of the `<leaf hash>  <path>` lines the root was built from, so
+    /// verify can tell entries changed since the seal from entries added after it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leaves: Option<String>,
+}
+
+/// An entry's sealed path and leaf hash
+pub type Leaf = (String, [u8; 32]);
+
+/// What `seal verify` found, comparing the journal with one seal
+#[derive(Debug, Default, PartialEq)]
+pub struct Verification {
+    /// Sealed entries whose content changed
+    pub modified: Vec<String>,
+    /// Sealed entries that are gone
+    pub missing: Vec<String>,
+    /// Entries written after the seal, which it doesn't cover
+    pub added: Vec<String>,
+    /// Whether the root recomputed over the sealed entries matches the seal
+    pub root_matches: bool,
+}
+
+impl Verification {
+    pub fn is_ok(&self) -> bool {
+        self.root_matches && self.modified.is_empty() && self.missing.is_empty()
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Prefixes that keep a leaf from ever hashing the same as an inner node
+const LEAF: u8 = 0x00;
+const NODE: u8 = 0x01;
+
+/// Combine leaf hashes pairwise until a single root remains.
+/// An odd node at the end of a level is carried up unchanged.
+pub fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return Sha256::digest(b"").into();
+    }
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => {
+                    let mut hasher = Sha256::new();
+                    hasher.update([NODE]);
+                    hasher.update(left);
+                    hasher.update(right);
+                    hasher.finalize().into()
+                }
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    level[0]
+}
+
+/// An entry's `YYYY/MM/<file>` path inside the journal, the same whether it's a loose file,
+/// compressed (the `.gz` suffix is dropped) or archived in a `YYYY.zip`
+fn sealed_path(journal_path: &Path, entry: &Path) -> String {
+    if let Some((_, name)) = archive::split_zip_path(entry) {
+        return name.trim_end_matches(".gz").to_string();
+    }
+    let relative = entry.strip_prefix(journal_path).unwrap_or(entry);
+    relative.to_string_lossy().replace('\\', "/").trim_end_matches(".gz").to_string()
+}
+
+/// Hash an entry as its sealed path plus its (decompressed) content, so compressing or
+/// archiving old entries keeps seals valid
+fn leaf_hash(journal_path: &Path, entry: &Path) -> Result<[u8; 32], String> {
+    let content = read_entry(entry)
+        .map_err(|e| format!("Failed to read {}: {}", entry.display(), e))?;
+
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF]);
+    hasher.update(sealed_path(journal_path, entry).as_bytes());
+    hasher.update([0u8]);
+    hasher.update(content.as_bytes());
+    Ok(hasher.finalize().into())
+}
+
+/// Every entry in the journal with its leaf hash, sorted by sealed path
+pub fn compute_leaves(journal_path: &Path) -> Result<Vec<Leaf>, String> {
+    let mut entries: Vec<PathBuf> = find_all_entries(journal_path)?;
+    entries.sort_by_key(|entry| sealed_path(journal_path, entry));
+
+    let bar = progress::bar(entries.len(), "Hashing");
+    let leaves = entries
+        .iter()
+        .map(|entry| {
+            bar.inc(1);
+            Ok((sealed_path(journal_path, entry), leaf_hash(journal_path, entry)?))
+        })
+        .collect::<Result<Vec<_>, String>>();
+    bar.finish_and_clear();
+    leaves
+}
+
+fn root_of(leaves: &[Leaf]) -> String {
+    let hashes: Vec<[u8; 32]> = leaves.iter().map(|(_, hash)| *hash).collect();
+    to_hex(&merkle_root(&hashes))
+}
+
+/// Compute a seal for the current state of the journal, with the leaves it covers
+pub fn create_seal(journal_path: &Path) -> Result<(Seal, Vec<Leaf>), String> {
+    let leaves = compute_leaves(journal_path)?;
+    let root = root_of(&leaves);
+    let seal = Seal {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        entries: leaves.len(),
+        leaves: Some(format!("{}/{}.leaves", SEAL_DIR, root)),
+        root,
+        timestamp_token: None,
+    };
+    Ok((seal, leaves))
+}
+
+/// Write a seal's leaf list, then append the seal to the seal log
+pub fn write_seal(journal_path: &Path, seal: &Seal, leaves: &[Leaf]) -> Result<(), String> {
+    if let Some(list) = &seal.leaves {
+        let list_path = journal_path.join(list);
+        if let Some(dir) = list_path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let lines: String = leaves.iter().map(|(path, hash)| format!("{}  {}\n", to_hex(hash), path)).collect();
+        fs::write(&list_path, lines).map_err(|e| format!("Failed to write {}: {}", list_path.display(), e))?;
+    }
+
+    let line = serde_json::to_string(seal)
+        .map_err(|e| format!("Failed to serialize seal: {}", e))?;
+    let log_path = journal_path.join(SEAL_LOG);
+    let mut log = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| format!("Failed to open {}: {}", log_path.display(), e))?;
+    writeln!(log, "{}", line)
+        .map_err(|e| format!("Failed to write {}: {}", log_path.display(), e))
+}
+
+/// Read every seal recorded in the log, oldest first
+pub fn read_seals(journal_path: &Path) -> Result<Vec<Seal>, String> {
+    let log_path = journal_path.join(SEAL_LOG);
+    let content = match fs::read_to_string(&log_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", log_path.display(), e)),
+    };
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line)
+                .map_err(|e| format!("Corrupt seal log line '{}': {}", line, e))
+        })
+        .collect()
+}
+
+/// Check the journal against `seal`. The root is recomputed over exactly the entries the seal
+/// listed, so entries written since are reported as added rather than as a mismatch. Seals
+/// taken before leaf lists were kept can only be compared with the whole journal.
+pub fn verify(journal_path: &Path, seal: &Seal) -> Result<Verification, String> {
+    let current = compute_leaves(journal_path)?;
+    let Some(list) = &seal.leaves else {
+        return Ok(Verification { root_matches: root_of(&current) == seal.root, ..Verification::default() });
+    };
+
+    let list_path = journal_path.join(list);
+    let content =
+        fs::read_to_string(&list_path).map_err(|e| format!("Failed to read {}: {}", list_path.display(), e))?;
+    let mut sealed: Vec<(String, String)> = Vec::new();
+    for line in content.lines().filter(|line| !line.is_empty()) {
+        let (hash, path) = line
+            .split_once("  ")
+            .ok_or_else(|| format!("Corrupt leaf list {}: '{}'", list_path.display(), line))?;
+        sealed.push((path.to_string(), hash.to_string()));
+    }
+
+    let mut current: BTreeMap<String, [u8; 32]> = current.into_iter().collect();
+    let mut verification = Verification::default();
+    let mut recomputed = Vec::new();
+    for (path, hash) in &sealed {
+        match current.remove(path) {
+            Some(now) => {
+                if to_hex(&now) != *hash {
+                    verification.modified.push(path.clone());
+                }
+                recomputed.push((path.clone(), now));
+            }
+            None => verification.missing.push(path.clone()),
+        }
+    }
+    verification.added = current.into_keys().collect();
+    verification.root_matches = verification.missing.is_empty() && root_of(&recomputed) == seal.root;
+    Ok(verification)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compute_root(journal_path: &Path) -> Result<(usize, String), String> {
+        let leaves = compute_leaves(journal_path)?;
+        Ok((leaves.len(), root_of(&leaves)))
+    }
+
+    fn journal_with_entry() -> tempfile::TempDir {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let month = temp_dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        fs::write(month.join("17-081503-note.md"), "# note\n").unwrap();
+        fs::write(month.join("18-090000-other.md"), "# other\n").unwrap();
+        temp_dir
+    }
+
+    #[test]
+    fn test_merkle_root_odd_leaf_is_carried() {
+        let a: [u8; 32] = Sha256::digest(b"a").into();
+        let b: [u8; 32] = Sha256::digest(b"b").into();
+        let c: [u8; 32] = Sha256::digest(b"c").into();
+        let ab: [u8; 32] = merkle_root(&[a, b]);
+        let mut hasher = Sha256::new();
+        hasher.update([NODE]);
+        hasher.update(ab);
+        hasher.update(c);
+        let expected: [u8; 32] = hasher.finalize().into();
+        assert_eq!(merkle_root(&[a, b, c]), expected);
+        assert_eq!(merkle_root(&[a]), a);
+    }
+
+    #[test]
+    fn test_seal_detects_modification() {
+        let temp_dir = journal_with_entry();
+        let (seal, leaves) = create_seal(temp_dir.path()).expect("seal failed");
+        write_seal(temp_dir.path(), &seal, &leaves).expect("write failed");
+        assert_eq!(seal.entries, 2);
+        assert_eq!(read_seals(temp_dir.path()).unwrap(), vec![seal]);
+
+        let (_, unchanged) = compute_root(temp_dir.path()).unwrap();
+        assert_eq!(unchanged, read_seals(temp_dir.path()).unwrap()[0].root);
+
+        fs::write(temp_dir.path().join("2026/02/17-081503-note.md"), "# edited\n").unwrap();
+        let (_, changed) = compute_root(temp_dir.path()).unwrap();
+        assert_ne!(changed, unchanged);
+    }
+
+    #[test]
+    fn test_verify_tells_added_from_modified() {
+        let temp_dir = journal_with_entry();
+        let (seal, leaves) = create_seal(temp_dir.path()).unwrap();
+        write_seal(temp_dir.path(), &seal, &leaves).unwrap();
+        assert!(verify(temp_dir.path(), &seal).unwrap().is_ok());
+
+        // A new entry isn't covered by the seal, but doesn't break it
+        fs::write(temp_dir.path().join("2026/02/19-100000-new.md"), "# new\n").unwrap();
+        let verification = verify(temp_dir.path(), &seal).unwrap();
+        assert!(verification.is_ok());
+        assert_eq!(verification.added, ["2026/02/19-100000-new.md"]);
+
+        fs::write(temp_dir.path().join("2026/02/17-081503-note.md"), "# edited\n").unwrap();
+        fs::remove_file(temp_dir.path().join("2026/02/18-090000-other.md")).unwrap();
+        let verification = verify(temp_dir.path(), &seal).unwrap();
+        assert!(!verification.is_ok());
+        assert_eq!(verification.modified, ["2026/02/17-081503-note.md"]);
+        assert_eq!(verification.missing, ["2026/02/18-090000-other.md"]);
+    }
+
+    #[test]
+    fn test_seal_survives_compression() {
+        let temp_dir = journal_with_entry();
+        let (_, before) = compute_root(temp_dir.path()).unwrap();
+        crate::compress::compress_before(temp_dir.path(), 2027).unwrap();
+        let (_, after) = compute_root(temp_dir.path()).unwrap();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_seal_survives_archiving() {
+        let temp_dir = journal_with_entry();
+        let (_, before) = compute_root(temp_dir.path()).unwrap();
+        archive::create(temp_dir.path(), 2026, archive::Format::Zip).unwrap();
+        fs::remove_dir_all(temp_dir.path().join("2026")).unwrap();
+        assert_eq!(compute_root(temp_dir.path()).unwrap(), (2, before));
+    }
+}
@@ -0,0 +1,124 @@
+//! Plain-text entry search.
+
+use regex::{Regex, RegexBuilder};
+
+/// Line pattern for `search`: the query taken literally, or as a regular expression
+pub fn pattern(query: &str, regex: bool, ignore_case: bool) -> Result<Regex, String> {
+    let source = if regex { query.to_string() } else { regex::escape(query) };
+    RegexBuilder::new(&source)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| format!("Invalid regular expression: {}", e))
+}
+
+/// Lines of `text` matching `pattern`, with their 1-based line numbers
+pub fn matching_lines<'a>(text: &'a str, pattern: &Regex) -> Vec<(usize, &'a str)> {
+    text.lines().enumerate().filter(|(_, line)| pattern.is_match(line)).map(|(i, line)| (i + 1, line)).collect()
+}
+
+/// A line printed by `search`: a match, or with `-A/-B/-C` a line of context around one
+#[derive(Debug, PartialEq)]
+pub struct Line<'a> {
+    /// 1-based line number
+    pub number: usize,
+    /// 1-based byte column where the first match starts; `None` for context lines
+    pub column: Option<usize>,
+    pub text: &'a str,
+}
+
+/// Matching lines of `text` plus up to `before` and `after` lines around each, in order
+pub fn lines_with_context<'a>(text: &'a str, pattern: &Regex, before: usize, after: usize) -> Vec<Line<'a>> {
+    let all: Vec<&str> = text.lines().collect();
+    let mut lines: Vec<Line> = Vec::new();
+    for (number, line) in matching_lines(text, pattern) {
+        let first = number.saturating_sub(before).max(1);
+        let last = (number + after).min(all.len());
+        for context in first..=last {
+            let column = (context == number).then(|| pattern.find(line).map(|m| m.start() + 1)).flatten();
+            // Overlapping context is printed once, and a match is never shown as context
+            if let Some(existing) = lines.iter_mut().rev().take_while(|l| l.number >= context).find(|l| l.number == context) {
+                existing.column = existing.column.or(column);
+                continue;
+            }
+            lines.push(Line { number: context, column, text: all[context - 1] });
+        }
+    }
+    lines
+}
+
+/// `search` output for one entry: `path:line:text` per match, or `path:line:column:text`
+/// with `grep` (the quickfix format of vim and VS Code); context lines use `-` separators,
+/// and `--` marks a gap between groups of lines as in grep
+pub fn render(path: &str, lines: &[Line], grep: bool) -> String {
+    let mut out = String::new();
+    let mut previous: Option<usize> = None;
+    for line in lines {
+        if previous.is_some_and(|p| line.number > p + 1) {
+            out.push_str("--\n");
+        }
+        previous = Some(line.number);
+        match line.column {
+            Some(column) if grep => out.push_str(&format!("{}:{}:{}:{}\n", path, line.number, column, line.text)),
+            Some(_) => out.push_str(&format!("{}:{}:{}\n", path, line.number, line.text)),
+            None => out.push_str(&format!("{}-{}-{}\n", path, line.number, line.text)),
+        }
+    }
+    out
+}
+
+/// Whether `text` contains every word of `query`, ignoring case
+#[cfg(feature = "graphql")]
+pub fn matches(text: &str, query: &str) -> bool {
+    let text = text.to_lowercase();
+    query.split_whitespace().all(|word| text.contains(&word.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_lines() {
+        let text = "# Infra\n\nPlanned the Postgres migration\npostgres 16 upgrade\nLunch\n";
+        let literal = pattern("Postgres", false, false).unwrap();
+        assert_eq!(matching_lines(text, &literal), vec![(3, "Planned the Postgres migration")]);
+
+        let ignore_case = pattern("postgres", false, true).unwrap();
+        assert_eq!(matching_lines(text, &ignore_case).len(), 2);
+
+        let regex = pattern(r"postgres \d+", true, false).unwrap();
+        assert_eq!(matching_lines(text, &regex), vec![(4, "postgres 16 upgrade")]);
+        // Without --regex, metacharacters are literal
+        assert!(matching_lines(text, &pattern("postgres.", false, true).unwrap()).is_empty());
+        assert!(pattern("(", true, false).is_err());
+    }
+
+    #[test]
+    fn test_context_and_grep_format() {
+        let text = "one\ntwo\nthe match\nfour\nfive\nsix\nanother match\n";
+        let lines = lines_with_context(text, &pattern("match", false, false).unwrap(), 1, 1);
+        assert_eq!(
+            render("e.md", &lines, true),
+            "e.md-2-two\ne.md:3:5:the match\ne.md-4-four\n--\ne.md-6-six\ne.md:7:9:another match\n"
+        );
+
+        // Context reaching into the next match doesn't repeat lines or hide the match
+        let lines = lines_with_context(text, &pattern("t", false, false).unwrap(), 0, 2);
+        assert_eq!(lines.iter().map(|l| (l.number, l.column)).collect::<Vec<_>>(), [
+            (2, Some(1)),
+            (3, Some(1)),
+            (4, None),
+            (5, None),
+            (7, Some(4)),
+        ]);
+        assert_eq!(render("e.md", &lines[..1], false), "e.md:2:two\n");
+    }
+
+    #[cfg(feature = "graphql")]
+    #[test]
+    fn test_matches_all_words_ignoring_case() {
+        assert!(matches("Shipped the Parser today", "parser shipped"));
+        assert!(!matches("Shipped the parser", "parser release"));
+        assert!(matches("anything", ""));
+    }
+}
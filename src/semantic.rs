@@ -0,0 +1,219 @@
+//! `search --semantic`: nearest entries by embedding similarity, computed locally.
+//!
+//! Vectors are kept in `.semantic-index.json` at the journal root and only
+//! recomputed for entries whose content changed since the last search.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{entry_title, find_all_entries, frontmatter, interrupt, lock, progress, read_entry};
+
+pub const INDEX_FILE: &str = ".semantic-index.json";
+
+/// Model used for both entries and queries; changing it invalidates the index
+const MODEL_NAME: &str = "all-MiniLM-L6-v2";
+/// Entries embedded per model call; Ctrl-C and the progress bar take effect between batches
+const EMBED_BATCH: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IndexedEntry {
+    /// SHA-256 of the text the vector was computed from
+    pub hash: String,
+    pub vector: Vec<f32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Index {
+    pub model: String,
+    /// Vectors by entry path relative to the journal
+    pub entries: BTreeMap<String, IndexedEntry>,
+}
+
+fn hash(text: &str) -> String {
+    Sha256::digest(text.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn load(journal_path: &Path) -> Index {
+    fs::read_to_string(journal_path.join(INDEX_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str::<Index>(&json).ok())
+        .filter(|index| index.model == MODEL_NAME)
+        .unwrap_or_else(|| Index { model: MODEL_NAME.to_string(), entries: BTreeMap::new() })
+}
+
+/// Write the index through a temporary file, so an interrupted save keeps the old one
+pub fn save(journal_path: &Path, index: &Index) -> Result<(), String> {
+    let path = journal_path.join(INDEX_FILE);
+    let json = serde_json::to_string(index).map_err(|e| format!("Failed to encode semantic index: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, &path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// What gets embedded for an entry: its title and body, without frontmatter
+fn entry_text(content: &str) -> String {
+    let body = frontmatter::parse(content).1;
+    match entry_title(body) {
+        Some(title) => format!("{}\n{}", title, body),
+        None => body.to_string(),
+    }
+}
+
+/// Bring the index in line with `entries`: embed new or changed entries and
+/// drop removed ones. Returns how many entries were embedded, which is fewer than
+/// needed if Ctrl-C stopped it between batches.
+pub fn update<F>(index: &mut Index, journal_path: &Path, entries: &[PathBuf], mut embed: F) -> Result<usize, String>
+where
+    F: FnMut(&[String]) -> Result<Vec<Vec<f32>>, String>,
+{
+    let mut stale = Vec::new();
+    let mut current = Vec::new();
+    for path in entries {
+        let Ok(content) = read_entry(path) else {
+            continue;
+        };
+        let key = path.strip_prefix(journal_path).unwrap_or(path).to_string_lossy().to_string();
+        let text = entry_text(&content);
+        let text_hash = hash(&text);
+        if index.entries.get(&key).is_none_or(|e| e.hash != text_hash) {
+            stale.push((key.clone(), text_hash, text));
+        }
+        current.push(key);
+    }
+    index.entries.retain(|key, _| current.contains(key));
+
+    if stale.is_empty() {
+        return Ok(0);
+    }
+    let bar = progress::bar(stale.len(), "Indexing");
+    let mut embedded = 0;
+    for batch in stale.chunks(EMBED_BATCH) {
+        if interrupt::requested() {
+            break;
+        }
+        let texts: Vec<String> = batch.iter().map(|(_, _, text)| text.clone()).collect();
+        let vectors = embed(&texts).inspect_err(|_| bar.finish_and_clear())?;
+        if vectors.len() != batch.len() {
+            bar.finish_and_clear();
+            return Err(format!("Expected {} embeddings, got {}", batch.len(), vectors.len()));
+        }
+        for ((key, hash, _), vector) in batch.iter().zip(vectors) {
+            index.entries.insert(key.clone(), IndexedEntry { hash: hash.clone(), vector });
+        }
+        embedded += batch.len();
+        bar.inc(batch.len() as u64);
+    }
+    bar.finish_and_clear();
+    Ok(embedded)
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 { 0.0 } else { dot / denominator }
+}
+
+/// The `limit` indexed entries most similar to `query`, best first, with their similarity
+pub fn nearest(index: &Index, query: &[f32], limit: usize) -> Vec<(String, f32)> {
+    let mut scored: Vec<(String, f32)> =
+        index.entries.iter().map(|(path, entry)| (path.clone(), cosine(query, &entry.vector))).collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.truncate(limit);
+    scored
+}
+
+/// Local embedding model, downloaded to the user cache directory on first use
+pub struct Model {
+    inner: fastembed::TextEmbedding,
+}
+
+impl Model {
+    pub fn load() -> Result<Self, String> {
+        let cache = dirs::cache_dir()
+            .ok_or("Could not determine the cache directory")?
+            .join("file-journal")
+            .join("models");
+        let options = fastembed::TextInitOptions::new(fastembed::EmbeddingModel::AllMiniLML6V2)
+            .with_cache_dir(cache)
+            .with_show_download_progress(true);
+        let inner = fastembed::TextEmbedding::try_new(options)
+            .map_err(|e| format!("Failed to load the embedding model: {}", e))?;
+        Ok(Model { inner })
+    }
+
+    pub fn embed(&mut self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        self.inner.embed(texts, None).map_err(|e| format!("Failed to compute embeddings: {}", e))
+    }
+}
+
+/// Refresh the journal's index and return the entries nearest to `query`
+pub fn search(journal_path: &Path, query: &str, limit: usize) -> Result<Vec<(PathBuf, f32)>, String> {
+    let mut model = Model::load()?;
+    // Held from loading the index until it's saved, so concurrent searches don't overwrite each other
+    let guard = lock::acquire(&journal_path.join(INDEX_FILE))?;
+    let mut index = load(journal_path);
+    let entries = find_all_entries(journal_path)?;
+    if update(&mut index, journal_path, &entries, |texts| model.embed(texts))? > 0 {
+        save(journal_path, &index)?;
+    }
+    drop(guard);
+    if interrupt::requested() {
+        return Err("Interrupted".to_string());
+    }
+
+    let query = model.embed(&[query.to_string()])?.pop().ok_or("The model returned no embedding for the query")?;
+    Ok(nearest(&index, &query, limit)
+        .into_iter()
+        .map(|(path, score)| (journal_path.join(path), score))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Stand-in for the model: counts of a few words
+    fn embed(texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        Ok(texts
+            .iter()
+            .map(|t| ["tired", "hike", "work"].iter().map(|w| t.matches(w).count() as f32).collect())
+            .collect())
+    }
+
+    #[test]
+    fn test_update_embeds_only_changed_entries() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        let first = month.join("16-080000-monday.md");
+        let second = month.join("17-080000-hike.md");
+        fs::write(&first, "# Monday\n\nSo tired after work, tired of work\n").unwrap();
+        fs::write(&second, "# Hike\n\nA long hike\n").unwrap();
+        let entries = vec![first.clone(), second.clone()];
+
+        let mut index = Index::default();
+        assert_eq!(update(&mut index, dir.path(), &entries, embed).unwrap(), 2);
+        assert_eq!(update(&mut index, dir.path(), &entries, embed).unwrap(), 0);
+
+        fs::write(&second, "# Hike\n\nA long hike, then tired\n").unwrap();
+        assert_eq!(update(&mut index, dir.path(), &entries, embed).unwrap(), 1);
+        assert_eq!(update(&mut index, dir.path(), &entries[..1], embed).unwrap(), 0);
+        assert_eq!(index.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_nearest_ranks_by_similarity() {
+        let mut index = Index::default();
+        for (path, vector) in [("a.md", vec![1.0, 0.0]), ("b.md", vec![0.6, 0.8]), ("c.md", vec![0.0, 1.0])] {
+            index.entries.insert(path.to_string(), IndexedEntry { hash: String::new(), vector });
+        }
+        let found = nearest(&index, &[0.0, 2.0], 2);
+        assert_eq!(found.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>(), vec!["c.md", "b.md"]);
+        assert!((found[0].1 - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,828 @@
+//! `file-journal serve`: the JSON-RPC journal API over HTTP, plus Prometheus `/metrics`.
+//!
+//! Routes: `POST /rpc` (a JSON-RPC request body), `GET`/`PUT /entries/{YYYY/MM/file}`
+//! (raw Markdown with ETags), `POST /quick` (a form-encoded `text` for today's entry),
+//! `POST /clip` (JSON `url`, `html` and optional `title` for a bookmark entry, with the
+//! `network` feature), `GET /ws` (live entry events), `GET /metrics`, `GET /health`, and
+//! `POST /graphql` with the `graphql` feature.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response, Server};
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+use crate::daemon::{self, Daemon};
+use crate::watch::Watcher;
+
+/// Requests larger than this are refused
+const MAX_BODY: u64 = 1024 * 1024;
+
+/// Length of a rate limiting window
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// How often the journal is checked for new or edited entries
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Idle time after which WebSocket clients are pinged
+const WS_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Live-update clients, each fed JSON events through a channel
+type Subscribers = Arc<Mutex<Vec<Sender<String>>>>;
+
+/// `[serve]` section of the config
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ServeConfig {
+    /// Clients allowed to use the API; without any, the API is open to anyone who can reach it
+    #[serde(default)]
+    pub tokens: Vec<ApiToken>,
+    /// Default requests per minute per client
+    pub rate_limit: Option<u32>,
+    /// Origins allowed to call the API from a browser ("*" for any)
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
+    /// Path prefix the API is mounted under behind a reverse proxy, e.g. "/journal"
+    pub base_path: Option<String>,
+    /// Honor X-Forwarded-For/X-Forwarded-Prefix (only behind a proxy that sets them)
+    #[serde(default)]
+    pub trust_proxy: bool,
+}
+
+/// A client credential, sent as `Authorization: Bearer <token>`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    /// Shown in the request log
+    pub name: String,
+    pub token: String,
+    /// "read" (list, read, metrics) and/or "write" (create, append)
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// Requests per minute for this client, overriding `[serve] rate_limit`
+    pub rate_limit: Option<u32>,
+}
+
+impl ApiToken {
+    fn allows(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+}
+
+/// `POST /clip` body, as a web clipper extension sends it
+#[cfg(feature = "network")]
+#[derive(Deserialize)]
+struct Clip {
+    url: String,
+    /// The selected part of the page
+    #[serde(default)]
+    html: String,
+    title: Option<String>,
+}
+
+/// Compare without short-circuiting so response times don't leak how much of a token matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// The token matching an `Authorization` header value
+pub fn authenticate<'a>(tokens: &'a [ApiToken], header: Option<&str>) -> Option<&'a ApiToken> {
+    let presented = header?.strip_prefix("Bearer ")?.trim();
+    tokens.iter().find(|t| constant_time_eq(t.token.as_bytes(), presented.as_bytes()))
+}
+
+/// A new random token for `serve --pair`
+pub fn mint_token() -> Result<String, String> {
+    let mut bytes = [0u8; 24];
+    getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate a token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// The URL other devices on the network reach a server listening on `listen` at. When it
+/// listens on every interface, that's the address of the one the default route goes out of.
+pub fn lan_url(listen: &str, base_path: Option<&str>) -> Result<String, String> {
+    let addr = listen
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| format!("Invalid listen address '{}'", listen))?;
+    let ip = if addr.ip().is_loopback() {
+        return Err(format!(
+            "{} is only reachable from this machine; listen on the network to pair, e.g. --listen 0.0.0.0:{}",
+            listen,
+            addr.port()
+        ));
+    } else if addr.ip().is_unspecified() {
+        // Connecting a UDP socket picks the outgoing interface without sending anything
+        let (bind, probe): (IpAddr, SocketAddr) = if addr.is_ipv4() {
+            ([0, 0, 0, 0].into(), ([192, 0, 2, 1], 9).into())
+        } else {
+            ([0u16; 8].into(), ([0x2001, 0xdb8, 0, 0, 0, 0, 0, 1], 9).into())
+        };
+        UdpSocket::bind((bind, 0))
+            .and_then(|socket| socket.connect(probe).and_then(|_| socket.local_addr()))
+            .map(|local| local.ip())
+            .map_err(|e| format!("Could not find this machine's network address ({}); pass it as --listen", e))?
+    } else {
+        addr.ip()
+    };
+    let base = base_path.unwrap_or_default().trim_end_matches('/');
+    Ok(format!("http://{}{}", SocketAddr::new(ip, addr.port()), base))
+}
+
+/// `code` as text for the terminal, two module rows per line, dark on light whatever the
+/// terminal's colours, with a four-module quiet zone
+pub fn render_qr(code: &qrcodegen::QrCode) -> String {
+    const QUIET: i32 = 4;
+    let mut text = String::new();
+    for y in (-QUIET..code.size() + QUIET).step_by(2) {
+        text.push_str("\x1b[30;47m");
+        for x in -QUIET..code.size() + QUIET {
+            // Modules outside the code read as light
+            text.push(match (code.get_module(x, y), code.get_module(x, y + 1)) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            });
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+/// Fixed-window request counter per client
+#[derive(Default)]
+pub struct RateLimiter {
+    windows: HashMap<String, (Instant, u32)>,
+}
+
+impl RateLimiter {
+    /// Count a request from `client`; false once it has used up `limit` in the current window
+    pub fn allow(&mut self, client: &str, limit: u32, now: Instant) -> bool {
+        let window = self.windows.entry(client.to_string()).or_insert((now, 0));
+        if now.duration_since(window.0) >= RATE_WINDOW {
+            *window = (now, 0);
+        }
+        window.1 += 1;
+        window.1 <= limit
+    }
+}
+
+struct State {
+    config: ServeConfig,
+    limiter: RateLimiter,
+    metrics_only: bool,
+    subscribers: Subscribers,
+    #[cfg(feature = "graphql")]
+    graphql: crate::graphql::JournalSchema,
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name, value).expect("header names and values are ASCII")
+}
+
+fn respond(request: Request, status: u16, content_type: &str, body: String, headers: &[Header]) -> u16 {
+    let mut response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(header("Content-Type", content_type));
+    for extra in headers {
+        response = response.with_header(extra.clone());
+    }
+    if let Err(e) = request.respond(response) {
+        eprintln!("Warning: failed to send response: {}", e);
+    }
+    status
+}
+
+fn rpc_error(code: i64, message: &str) -> String {
+    serde_json::json!({ "jsonrpc": "2.0", "id": null, "error": { "code": code, "message": message } }).to_string()
+}
+
+/// `path` relative to the mount point, or None if it is outside it
+pub fn strip_base<'a>(path: &'a str, base: Option<&str>) -> Option<&'a str> {
+    let base = base.unwrap_or_default().trim_end_matches('/');
+    if base.is_empty() {
+        return Some(path);
+    }
+    match path.strip_prefix(base)? {
+        "" => Some("/"),
+        rest if rest.starts_with('/') => Some(rest),
+        _ => None,
+    }
+}
+
+/// The path to route on: `base_path` is stripped first, then the prefix a trusted proxy reports
+/// in `X-Forwarded-Prefix` if the URL still has it. A URL without `base_path` is only accepted
+/// when the proxy reports that it stripped `base_path` itself.
+pub fn route_path<'a>(url: &'a str, base_path: Option<&str>, forwarded_prefix: Option<&str>) -> Option<&'a str> {
+    let same = |base: &str, prefix: &str| base.trim_end_matches('/') == prefix.trim_end_matches('/');
+    let path = match strip_base(url, base_path) {
+        Some(path) => path,
+        None if base_path.zip(forwarded_prefix).is_some_and(|(base, prefix)| same(base, prefix)) => url,
+        None => return None,
+    };
+    Some(strip_base(path, forwarded_prefix).unwrap_or(path))
+}
+
+/// Address of the client: the first `X-Forwarded-For` hop behind a trusted proxy, else the peer
+pub fn client_ip(peer: &str, forwarded_for: Option<&str>, trust_proxy: bool) -> String {
+    forwarded_for
+        .filter(|_| trust_proxy)
+        .and_then(|f| f.split(',').next())
+        .map(|ip| ip.trim().to_string())
+        .filter(|ip| !ip.is_empty())
+        .unwrap_or_else(|| peer.to_string())
+}
+
+/// `%XX` escapes in part of a URL decoded; a `%` not followed by two hex digits is kept
+pub fn percent_decode(part: &str) -> String {
+    let bytes = part.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|hex| std::str::from_utf8(hex).ok());
+        match (bytes[i], hex.and_then(|hex| u8::from_str_radix(hex, 16).ok())) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+            (byte, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Value of `name` in a query string or `application/x-www-form-urlencoded` body, decoded
+pub fn form_value(encoded: &str, name: &str) -> Option<String> {
+    let decode = |part: &str| percent_decode(&part.replace('+', " "));
+    encoded.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (decode(key) == name).then(|| decode(value))
+    })
+}
+
+/// A request body of at most `MAX_BODY` bytes, or the status and message to answer with
+pub fn read_body(reader: impl Read) -> Result<String, (u16, String)> {
+    let mut body = String::new();
+    match reader.take(MAX_BODY + 1).read_to_string(&mut body) {
+        Err(e) => Err((400, format!("Invalid request body: {}\n", e))),
+        Ok(read) if read as u64 > MAX_BODY => Err((413, format!("Request body is larger than {} bytes\n", MAX_BODY))),
+        Ok(_) => Ok(body),
+    }
+}
+
+/// The `Access-Control-Allow-Origin` value for a request's `Origin`, if it is allowed
+pub fn allowed_origin(origins: &[String], origin: Option<&str>) -> Option<String> {
+    let origin = origin?;
+    if origins.iter().any(|o| o == "*") {
+        return Some("*".to_string());
+    }
+    origins
+        .iter()
+        .find(|o| o.trim_end_matches('/') == origin)
+        .map(|_| origin.to_string())
+}
+
+/// Whether a `Host` header names this machine (`localhost`, `127.0.0.1`, `[::1]`, with or without a port)
+pub fn is_loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.rsplit_once(':').map(|(name, _)| name).unwrap_or(host),
+    };
+    name.eq_ignore_ascii_case("localhost")
+        || name.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Without tokens, only requests from this machine's own tools or from `cors_origins` get in:
+/// any web page could otherwise send a form or `text/plain` POST (which skip the CORS
+/// preflight) to the loopback server, or reach it through a DNS name rebound to 127.0.0.1
+pub fn check_anonymous(config: &ServeConfig, host: Option<&str>, origin: Option<&str>) -> Result<(), String> {
+    if !config.tokens.is_empty() {
+        return Ok(());
+    }
+    if let Some(host) = host
+        && !is_loopback_host(host)
+    {
+        return Err(format!("Host {} is not this machine", host));
+    }
+    if let Some(origin) = origin
+        && allowed_origin(&config.cors_origins, Some(origin)).is_none()
+    {
+        return Err(format!("Origin {} is not in [serve] cors_origins", origin));
+    }
+    Ok(())
+}
+
+fn handle(daemon: &Mutex<Daemon>, state: &mut State, mut request: Request) {
+    let lock = || daemon.lock().unwrap_or_else(|p| p.into_inner());
+    let request_header = |name: &'static str| {
+        request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv(name))
+            .map(|h| h.value.as_str().to_string())
+    };
+    let websocket_key = request_header("Sec-WebSocket-Key");
+    let if_match = request_header("If-Match");
+    let origin = request_header("Origin");
+    let host = request_header("Host");
+    let content_type = request_header("Content-Type");
+    let forwarded_for = request_header("X-Forwarded-For");
+    let forwarded_prefix = request_header("X-Forwarded-Prefix").filter(|_| state.config.trust_proxy);
+
+    let method = request.method().clone();
+    let url = request.url().split('?').next().unwrap_or_default().to_string();
+    let peer = request.remote_addr().map(|a| a.ip().to_string()).unwrap_or_default();
+    let remote = client_ip(&peer, forwarded_for.as_deref(), state.config.trust_proxy);
+
+    let mut headers = Vec::new();
+    if let Some(allowed) = allowed_origin(&state.config.cors_origins, origin.as_deref()) {
+        headers.push(header("Access-Control-Allow-Origin", &allowed));
+        headers.push(header("Vary", "Origin"));
+        headers.push(header("Access-Control-Expose-Headers", "ETag"));
+    }
+
+    let path = route_path(&url, state.config.base_path.as_deref(), forwarded_prefix.as_deref())
+        .unwrap_or_default()
+        .to_string();
+    // Browsers can't set headers on WebSocket connections, so /ws also takes ?access_token=,
+    // and phone shortcuts that only build a URL send ?token= to /quick
+    let authorization = request_header("Authorization").or_else(|| {
+        let query = request.url().split_once('?')?.1;
+        let token = form_value(query, "access_token").or_else(|| form_value(query, "token").filter(|_| path == "/quick"))?;
+        Some(format!("Bearer {}", token))
+    });
+    let routed = matches!(
+        (&method, path.as_str()),
+        (Method::Get, "/metrics") | (Method::Get, "/health")
+    ) || (path == "/rpc" && !state.metrics_only && method == Method::Post)
+        || (cfg!(feature = "graphql")
+            && path == "/graphql"
+            && !state.metrics_only
+            && method == Method::Post)
+        || ((path == "/quick" || (cfg!(feature = "network") && path == "/clip"))
+            && !state.metrics_only
+            && method == Method::Post)
+        || (path == "/ws" && !state.metrics_only && method == Method::Get)
+        || (path.starts_with("/entries/")
+            && !state.metrics_only
+            && matches!(method, Method::Get | Method::Put));
+    if method == Method::Options {
+        // CORS preflight, whatever the path: browsers send it without credentials
+        headers.push(header("Access-Control-Allow-Methods", "GET, POST, PUT, OPTIONS"));
+        headers.push(header("Access-Control-Allow-Headers", "Authorization, Content-Type, If-Match"));
+        headers.push(header("Access-Control-Max-Age", "600"));
+        respond(request, 204, "text/plain", String::new(), &headers);
+        return;
+    }
+    if !routed {
+        let status = respond(request, 404, "text/plain", "Not found\n".to_string(), &headers);
+        log(&remote, "-", &method, &url, "", status);
+        return;
+    }
+    if path == "/health" {
+        respond(request, 200, "text/plain", "ok\n".to_string(), &headers);
+        return;
+    }
+
+    if let Err(e) = check_anonymous(&state.config, host.as_deref(), origin.as_deref()) {
+        let status = respond(request, 403, "text/plain", e + "\n", &headers);
+        log(&remote, "-", &method, &url, "", status);
+        return;
+    }
+
+    // Authenticate when tokens are configured; otherwise every client is anonymous with full access
+    let client = if state.config.tokens.is_empty() {
+        None
+    } else {
+        match authenticate(&state.config.tokens, authorization.as_deref()) {
+            Some(token) => Some(token.clone()),
+            None => {
+                headers.push(header("WWW-Authenticate", "Bearer"));
+                let status = respond(request, 401, "text/plain", "Unauthorized\n".to_string(), &headers);
+                log(&remote, "-", &method, &url, "", status);
+                return;
+            }
+        }
+    };
+    let client_name = client.as_ref().map(|c| c.name.clone()).unwrap_or_else(|| remote.clone());
+    let allows = |scope: &str| client.as_ref().is_none_or(|c| c.allows(scope));
+
+    let limit = client.as_ref().and_then(|c| c.rate_limit).or(state.config.rate_limit);
+    if let Some(limit) = limit
+        && !state.limiter.allow(&client_name, limit, Instant::now())
+    {
+        headers.push(header("Retry-After", &RATE_WINDOW.as_secs().to_string()));
+        let status = respond(request, 429, "text/plain", "Too many requests\n".to_string(), &headers);
+        log(&remote, &client_name, &method, &url, "", status);
+        return;
+    }
+
+    if path == "/metrics" {
+        let status = if allows("read") {
+            let body = lock().render_metrics();
+            respond(request, 200, "text/plain; version=0.0.4", body, &headers)
+        } else {
+            respond(request, 403, "text/plain", "Token lacks the read scope\n".to_string(), &headers)
+        };
+        log(&remote, &client_name, &method, &url, "", status);
+        return;
+    }
+
+    if path == "/ws" {
+        let status = match websocket_key {
+            _ if !allows("read") => {
+                respond(request, 403, "text/plain", "Token lacks the read scope\n".to_string(), &headers)
+            }
+            Some(key) => {
+                subscribe(request, &key, &state.subscribers);
+                101
+            }
+            None => respond(request, 400, "text/plain", "Expected a WebSocket upgrade\n".to_string(), &headers),
+        };
+        log(&remote, &client_name, &method, &url, "", status);
+        return;
+    }
+
+    if let Some(id) = path.strip_prefix("/entries/") {
+        // Generated clients encode the id's slashes as %2F
+        let id = percent_decode(id);
+        let scope = if method == Method::Put { "write" } else { "read" };
+        let status = if !allows(scope) {
+            let message = format!("Token lacks the {} scope\n", scope);
+            respond(request, 403, "text/plain", message, &headers)
+        } else if method == Method::Put {
+            match read_body(request.as_reader()) {
+                Err((status, message)) => respond(request, status, "text/plain", message, &headers),
+                Ok(body) => match lock().put_entry(&id, &body, if_match.as_deref()) {
+                    Ok(etag) => {
+                        headers.push(header("ETag", &etag));
+                        respond(request, 204, "text/plain", String::new(), &headers)
+                    }
+                    Err((status, message)) => respond(request, status, "text/plain", message + "\n", &headers),
+                },
+            }
+        } else {
+            match lock().get_entry(&id) {
+                Ok((content, etag)) => {
+                    headers.push(header("ETag", &etag));
+                    respond(request, 200, "text/markdown; charset=utf-8", content, &headers)
+                }
+                Err((status, message)) => respond(request, status, "text/plain", message + "\n", &headers),
+            }
+        };
+        log(&remote, &client_name, &method, &url, "", status);
+        return;
+    }
+
+    let body = match read_body(request.as_reader()) {
+        Ok(body) => body,
+        Err((status, message)) => {
+            let status = respond(request, status, "text/plain", message, &headers);
+            log(&remote, &client_name, &method, &url, "", status);
+            return;
+        }
+    };
+
+    if path == "/quick" {
+        let status = if !allows("write") {
+            respond(request, 403, "text/plain", "Token lacks the write scope\n".to_string(), &headers)
+        } else {
+            match form_value(&body, "text").filter(|text| !text.trim().is_empty()) {
+                None => respond(request, 400, "text/plain", "Missing text\n".to_string(), &headers),
+                Some(text) => {
+                    let mut daemon = lock();
+                    match daemon.append(&text) {
+                        Ok(entry) => {
+                            let entry = entry.strip_prefix(daemon.journal_path()).unwrap_or(&entry).display().to_string();
+                            respond(request, 200, "text/plain", format!("Added to {}\n", entry), &headers)
+                        }
+                        Err(e) => respond(request, 500, "text/plain", e + "\n", &headers),
+                    }
+                }
+            }
+        };
+        log(&remote, &client_name, &method, &url, "", status);
+        return;
+    }
+
+    #[cfg(feature = "network")]
+    if path == "/clip" {
+        let clip = serde_json::from_str::<Clip>(&body);
+        let status = match clip {
+            _ if !allows("write") => {
+                respond(request, 403, "text/plain", "Token lacks the write scope\n".to_string(), &headers)
+            }
+            Err(e) => respond(request, 400, "text/plain", format!("Invalid clip: {}\n", e), &headers),
+            Ok(clip) if clip.url.trim().is_empty() => {
+                respond(request, 400, "text/plain", "Missing url\n".to_string(), &headers)
+            }
+            Ok(clip) => match lock().clip(clip.url.trim(), clip.title.as_deref(), &clip.html) {
+                Ok(entry) => {
+                    let body = serde_json::json!({ "path": entry }).to_string();
+                    respond(request, 201, "application/json", body, &headers)
+                }
+                Err(e) => respond(request, 500, "text/plain", e + "\n", &headers),
+            },
+        };
+        log(&remote, &client_name, &method, &url, "", status);
+        return;
+    }
+
+    // Queries only read the journal; there are no mutations
+    #[cfg(feature = "graphql")]
+    if path == "/graphql" {
+        let status = if allows("read") {
+            lock().metrics.queries += 1;
+            let response = crate::graphql::execute(&state.graphql, &body);
+            respond(request, 200, "application/json", response.to_string(), &headers)
+        } else {
+            respond(request, 403, "text/plain", "Token lacks the read scope\n".to_string(), &headers)
+        };
+        log(&remote, &client_name, &method, &url, "", status);
+        return;
+    }
+
+    // Browsers only send a JSON content type cross-origin after a CORS preflight
+    let json = content_type
+        .as_deref()
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("application/json"));
+    if !json {
+        let message = rpc_error(-32600, "Expected Content-Type: application/json");
+        let status = respond(request, 415, "application/json", message, &headers);
+        log(&remote, &client_name, &method, &url, "", status);
+        return;
+    }
+
+    let rpc_method = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| v["method"].as_str().map(str::to_string))
+        .unwrap_or_default();
+
+    let scope = if daemon::is_write_method(&rpc_method) { "write" } else { "read" };
+    let status = if allows(scope) {
+        let response = lock().handle(&body);
+        respond(request, 200, "application/json", response.to_string(), &headers)
+    } else {
+        let message = format!("Token lacks the {} scope", scope);
+        respond(request, 403, "application/json", rpc_error(-32001, &message), &headers)
+    };
+    log(&remote, &client_name, &method, &url, &rpc_method, status);
+}
+
+/// Complete the WebSocket handshake and stream events to the client on its own thread
+fn subscribe(request: Request, key: &str, subscribers: &Subscribers) {
+    let response = Response::empty(101)
+        .with_header(header("Upgrade", "websocket"))
+        .with_header(header("Connection", "Upgrade"))
+        .with_header(header(
+            "Sec-WebSocket-Accept",
+            &tungstenite::handshake::derive_accept_key(key.as_bytes()),
+        ));
+    let stream = request.upgrade("websocket", response);
+
+    let (sender, receiver) = mpsc::channel::<String>();
+    subscribers.lock().unwrap_or_else(|p| p.into_inner()).push(sender);
+    std::thread::spawn(move || {
+        let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+        loop {
+            let message = match receiver.recv_timeout(WS_PING_INTERVAL) {
+                Ok(event) => Message::text(event),
+                // Pinging notices clients that went away without closing
+                Err(RecvTimeoutError::Timeout) => Message::Ping(Vec::new().into()),
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            if socket.send(message).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Poll the journal and push each change to every live-update client
+fn watch_journal(journal_path: std::path::PathBuf, subscribers: Subscribers) {
+    let mut watcher = Watcher::new(journal_path);
+    loop {
+        std::thread::sleep(WATCH_INTERVAL);
+        for change in watcher.poll() {
+            let event = change.to_json().to_string();
+            // Dropping a sender whose client is gone ends that client's thread
+            subscribers
+                .lock()
+                .unwrap_or_else(|p| p.into_inner())
+                .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        }
+    }
+}
+
+/// One line per request: time, remote address, client, request and status
+fn log(remote: &str, client: &str, method: &Method, path: &str, rpc_method: &str, status: u16) {
+    println!(
+        "{} {} {} {} {} {} {}",
+        chrono::Local::now().format("%Y-%m-%dT%H:%M:%S"),
+        remote,
+        client,
+        method,
+        path,
+        if rpc_method.is_empty() { "-" } else { rpc_method },
+        status
+    );
+}
+
+/// Whether `addr` only accepts connections from this machine
+pub fn is_loopback(addr: &str) -> bool {
+    use std::net::ToSocketAddrs;
+    addr.to_socket_addrs().is_ok_and(|mut addrs| addrs.all(|addr| addr.ip().is_loopback()))
+}
+
+/// Without tokens every client can write, so only this machine may connect
+pub fn check_access(addr: &str, config: &ServeConfig) -> Result<(), String> {
+    if config.tokens.is_empty() && !is_loopback(addr) {
+        return Err(format!(
+            "Refusing to listen on {} without [serve] tokens, since anyone who can reach it could write to \
+             the journal; add a token (or run serve --pair) or listen on 127.0.0.1",
+            addr
+        ));
+    }
+    Ok(())
+}
+
+/// Serve HTTP on `addr` until the process is stopped. With `metrics_only`, only
+/// `/metrics` and `/health` are exposed (used alongside the unix socket daemon).
+pub fn serve(daemon: Arc<Mutex<Daemon>>, addr: &str, config: ServeConfig, metrics_only: bool) -> Result<(), String> {
+    if !metrics_only {
+        check_access(addr, &config)?;
+    }
+    let server = Server::http(addr).map_err(|e| format!("Failed to listen on {}: {}", addr, e))?;
+    if config.tokens.is_empty() && !metrics_only {
+        eprintln!("Warning: no [serve] tokens configured; anyone on this machine has full access");
+    }
+
+    let journal_path = daemon.lock().unwrap_or_else(|p| p.into_inner()).journal_path().to_path_buf();
+    let subscribers = Subscribers::default();
+    if !metrics_only {
+        let journal_path = journal_path.clone();
+        let subscribers = Arc::clone(&subscribers);
+        std::thread::spawn(move || watch_journal(journal_path, subscribers));
+    }
+
+    let mut state = State {
+        config,
+        limiter: RateLimiter::default(),
+        metrics_only,
+        subscribers,
+        #[cfg(feature = "graphql")]
+        graphql: crate::graphql::schema(journal_path),
+    };
+    for request in server.incoming_requests() {
+        handle(&daemon, &mut state, request);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(name: &str, scopes: &[&str]) -> ApiToken {
+        ApiToken {
+            name: name.to_string(),
+            token: format!("{}-secret", name),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            rate_limit: None,
+        }
+    }
+
+    #[test]
+    fn test_authenticate_and_scopes() {
+        let tokens = vec![token("phone", &["read", "write"]), token("dashboard", &["read"])];
+
+        let dashboard = authenticate(&tokens, Some("Bearer dashboard-secret")).unwrap();
+        assert_eq!(dashboard.name, "dashboard");
+        assert!(dashboard.allows("read") && !dashboard.allows("write"));
+        assert!(authenticate(&tokens, Some("Bearer dashboard-secre")).is_none());
+        assert!(authenticate(&tokens, Some("phone-secret")).is_none());
+        assert!(authenticate(&tokens, None).is_none());
+    }
+
+    #[test]
+    fn test_pairing() {
+        let token = mint_token().unwrap();
+        assert_eq!(token.len(), 48);
+        assert_ne!(token, mint_token().unwrap());
+
+        assert_eq!(lan_url("192.168.1.23:8787", Some("/journal/")).unwrap(), "http://192.168.1.23:8787/journal");
+        assert_eq!(lan_url("[fd00::5]:8787", None).unwrap(), "http://[fd00::5]:8787");
+        assert!(lan_url("127.0.0.1:8787", None).unwrap_err().contains("0.0.0.0:8787"));
+
+        let url = format!("http://192.168.1.23:8787/quick?token={}", token);
+        let code = qrcodegen::QrCode::encode_binary(url.as_bytes(), qrcodegen::QrCodeEcc::Medium).unwrap();
+        assert_eq!(code.size(), 41);
+        // 41 modules and the quiet zone, two rows a line
+        let rendered = render_qr(&code);
+        assert_eq!(rendered.lines().count(), 25);
+        assert!(rendered.lines().all(|line| line.chars().filter(|c| " ▀▄█".contains(*c)).count() == 49));
+    }
+
+    #[test]
+    fn test_strip_base() {
+        assert_eq!(strip_base("/rpc", None), Some("/rpc"));
+        assert_eq!(strip_base("/journal/rpc", Some("/journal/")), Some("/rpc"));
+        assert_eq!(strip_base("/journal", Some("/journal")), Some("/"));
+        assert_eq!(strip_base("/journalx/rpc", Some("/journal")), None);
+        assert_eq!(strip_base("/rpc", Some("/journal")), None);
+    }
+
+    #[test]
+    fn test_is_loopback() {
+        assert!(is_loopback("127.0.0.1:8787"));
+        assert!(is_loopback("[::1]:8787"));
+        assert!(!is_loopback("0.0.0.0:8787"));
+        assert!(!is_loopback("192.168.1.20:8787"));
+        assert!(!is_loopback("not an address"));
+    }
+
+    #[test]
+    fn test_check_anonymous() {
+        assert!(is_loopback_host("localhost:8787"));
+        assert!(is_loopback_host("127.0.0.1"));
+        assert!(is_loopback_host("[::1]:8787"));
+        assert!(!is_loopback_host("rebound.evil.test:8787"));
+
+        let origins = vec!["https://dash.home.test".to_string()];
+        let mut config = ServeConfig { cors_origins: origins, ..ServeConfig::default() };
+        assert!(check_anonymous(&config, Some("127.0.0.1:8787"), None).is_ok());
+        assert!(check_anonymous(&config, Some("localhost:8787"), Some("https://dash.home.test")).is_ok());
+        assert!(check_anonymous(&config, Some("localhost:8787"), Some("https://evil.example")).is_err());
+        assert!(check_anonymous(&config, Some("rebound.evil.test:8787"), None).is_err());
+
+        // Tokens keep browsers out on their own
+        config.tokens.push(token("phone", &["write"]));
+        assert!(check_anonymous(&config, Some("journal.home.test"), Some("https://evil.example")).is_ok());
+    }
+
+    #[test]
+    fn test_route_path() {
+        assert_eq!(route_path("/journal/rpc", Some("/journal"), None), Some("/rpc"));
+        assert_eq!(route_path("/rpc", Some("/journal"), None), None);
+        // The proxy stripped base_path itself and says so
+        assert_eq!(route_path("/rpc", Some("/journal"), Some("/journal/")), Some("/rpc"));
+        // A forwarded prefix can't get around base_path
+        assert_eq!(route_path("/rpc", Some("/journal"), Some("/other")), None);
+        // The proxy forwarded its prefix on the URL, on top of base_path
+        assert_eq!(route_path("/journal/app/rpc", Some("/journal"), Some("/app")), Some("/rpc"));
+        assert_eq!(route_path("/app/rpc", None, Some("/app")), Some("/rpc"));
+        assert_eq!(route_path("/rpc", None, Some("/app")), Some("/rpc"));
+    }
+
+    #[test]
+    fn test_client_ip_and_origin() {
+        assert_eq!(client_ip("10.0.0.1", Some("203.0.113.7, 10.0.0.1"), true), "203.0.113.7");
+        assert_eq!(client_ip("10.0.0.1", Some("203.0.113.7"), false), "10.0.0.1");
+
+        let origins = vec!["https://dash.home.test/".to_string()];
+        assert_eq!(allowed_origin(&origins, Some("https://dash.home.test")).as_deref(), Some("https://dash.home.test"));
+        assert_eq!(allowed_origin(&origins, Some("https://evil.test")), None);
+        assert_eq!(allowed_origin(&["*".to_string()], Some("https://x.test")).as_deref(), Some("*"));
+    }
+
+    #[test]
+    fn test_form_value() {
+        let body = "token=abc&text=Bought+milk+%26+eggs%20%E2%9C%93&empty=";
+        assert_eq!(form_value(body, "text").as_deref(), Some("Bought milk & eggs ✓"));
+        assert_eq!(form_value(body, "token").as_deref(), Some("abc"));
+        assert_eq!(form_value(body, "empty").as_deref(), Some(""));
+        assert_eq!(form_value("text=100%", "text").as_deref(), Some("100%"));
+        assert_eq!(form_value(body, "missing"), None);
+
+        assert_eq!(percent_decode("2026%2F02%2F17-081503-caf%C3%A9%20%26+co.md"), "2026/02/17-081503-café &+co.md");
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+
+    #[test]
+    fn test_read_body() {
+        let full = "x".repeat(MAX_BODY as usize);
+        assert_eq!(read_body(full.as_bytes()).unwrap().len(), full.len());
+        let over = full + "y";
+        assert_eq!(read_body(over.as_bytes()).unwrap_err().0, 413);
+        assert_eq!(read_body(&[0xff, 0xfe][..]).unwrap_err().0, 400);
+    }
+
+    #[test]
+    fn test_rate_limiter_resets_each_window() {
+        let mut limiter = RateLimiter::default();
+        let start = Instant::now();
+        assert!(limiter.allow("phone", 2, start));
+        assert!(limiter.allow("phone", 2, start));
+        assert!(!limiter.allow("phone", 2, start));
+        assert!(limiter.allow("dashboard", 2, start));
+        assert!(limiter.allow("phone", 2, start + RATE_WINDOW));
+    }
+}
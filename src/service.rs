@@ -0,0 +1,195 @@
+//! Background service definitions: systemd user units on Linux, launchd agents on macOS.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Long-running commands that can be installed as services; `watch` and `remind` join these
+/// once they exist as commands
+pub fn available_modes() -> Vec<&'static str> {
+    let mut modes = vec!["daemon", "serve"];
+    if cfg!(feature = "network") {
+        modes.extend(["telegram-bridge", "matrix-bridge"]);
+    }
+    modes
+}
+
+pub fn validate_mode(mode: &str) -> Result<(), String> {
+    if available_modes().contains(&mode) {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{}' cannot run as a service (available: {})",
+            mode,
+            available_modes().join(", ")
+        ))
+    }
+}
+
+fn systemd_name(mode: &str) -> String {
+    format!("file-journal-{}.service", mode)
+}
+
+fn launchd_label(mode: &str) -> String {
+    format!("com.file-journal.{}", mode)
+}
+
+/// Where the unit/plist for `mode` is installed on this platform
+pub fn unit_path(mode: &str) -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Could not determine the home directory")?;
+    if cfg!(target_os = "macos") {
+        Ok(home.join("Library/LaunchAgents").join(format!("{}.plist", launchd_label(mode))))
+    } else {
+        Ok(home.join(".config/systemd/user").join(systemd_name(mode)))
+    }
+}
+
+/// An `ExecStart=` argument: quoted when needed, and with `%` and `$` doubled so systemd
+/// doesn't expand them as specifiers or environment variables
+fn systemd_quote(arg: &str) -> String {
+    let arg = arg.replace('%', "%%").replace('$', "$$");
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "/._-=:".contains(c)) {
+        arg
+    } else {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/// systemd user unit running `exe args...`
+pub fn systemd_unit(mode: &str, exe: &Path, args: &[String]) -> String {
+    let command: Vec<String> = std::iter::once(exe.to_string_lossy().to_string())
+        .chain(args.iter().cloned())
+        .map(|a| systemd_quote(&a))
+        .collect();
+    format!(
+        "[Unit]\nDescription=file-journal {}\n\n[Service]\nExecStart={}\nRestart=on-failure\nRestartSec=10\n\n[Install]\nWantedBy=default.target\n",
+        mode,
+        command.join(" ")
+    )
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// launchd agent plist running `exe args...`, kept alive and logging to /tmp
+pub fn launchd_plist(mode: &str, exe: &Path, args: &[String]) -> String {
+    let arguments: String = std::iter::once(exe.to_string_lossy().to_string())
+        .chain(args.iter().cloned())
+        .map(|a| format!("        <string>{}</string>\n", xml_escape(&a)))
+        .collect();
+    let label = launchd_label(mode);
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <true/>
+    <key>StandardErrorPath</key>
+    <string>/tmp/{label}.log</string>
+</dict>
+</plist>
+"#
+    )
+}
+
+/// Unit/plist contents for `mode` on this platform
+pub fn render(mode: &str, exe: &Path, args: &[String]) -> String {
+    if cfg!(target_os = "macos") {
+        launchd_plist(mode, exe, args)
+    } else {
+        systemd_unit(mode, exe, args)
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(if stderr.is_empty() { stdout } else { stderr })
+    }
+}
+
+/// Write the unit for `mode`, then enable and start it
+pub fn install(mode: &str, contents: &str) -> Result<PathBuf, String> {
+    let path = unit_path(mode)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    if cfg!(target_os = "macos") {
+        let plist = path.to_string_lossy();
+        // Reload if an older version is already loaded
+        let _ = run("launchctl", &["unload", &plist]);
+        run("launchctl", &["load", "-w", &plist])?;
+    } else {
+        run("systemctl", &["--user", "daemon-reload"])?;
+        run("systemctl", &["--user", "enable", "--now", &systemd_name(mode)])?;
+    }
+    Ok(path)
+}
+
+/// One-line state of the service for `mode`: not installed, or what the service manager reports
+pub fn status(mode: &str) -> Result<String, String> {
+    if !unit_path(mode)?.exists() {
+        return Ok("not installed".to_string());
+    }
+    if cfg!(target_os = "macos") {
+        Ok(match run("launchctl", &["list", &launchd_label(mode)]) {
+            Ok(listing) if listing.contains("\"PID\"") => "running".to_string(),
+            Ok(_) => "loaded, not running".to_string(),
+            Err(_) => "installed, not loaded".to_string(),
+        })
+    } else {
+        // is-active exits non-zero for anything but "active" but still prints the state
+        Ok(match run("systemctl", &["--user", "is-active", &systemd_name(mode)]) {
+            Ok(state) | Err(state) => state,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_systemd_unit_quotes_arguments() {
+        let args = vec!["daemon".to_string(), "--path".to_string(), "/home/t/My Journal".to_string()];
+        let unit = systemd_unit("daemon", Path::new("/usr/bin/file-journal"), &args);
+        assert!(unit.contains("ExecStart=/usr/bin/file-journal daemon --path \"/home/t/My Journal\"\n"));
+        assert!(unit.contains("WantedBy=default.target"));
+
+        let args = vec!["serve".to_string(), "--path".to_string(), "/srv/100%/$HOME".to_string()];
+        let unit = systemd_unit("serve", Path::new("/usr/bin/file-journal"), &args);
+        assert!(unit.contains("ExecStart=/usr/bin/file-journal serve --path \"/srv/100%%/$$HOME\"\n"));
+    }
+
+    #[test]
+    fn test_launchd_plist_lists_arguments() {
+        let args = vec!["daemon".to_string()];
+        let plist = launchd_plist("daemon", Path::new("/opt/file-journal"), &args);
+        assert!(plist.contains("<string>com.file-journal.daemon</string>"));
+        assert!(plist.contains("        <string>/opt/file-journal</string>\n        <string>daemon</string>\n"));
+    }
+
+    #[test]
+    fn test_validate_mode() {
+        assert!(validate_mode("daemon").is_ok());
+        assert!(validate_mode("new").is_err());
+    }
+}
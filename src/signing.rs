@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Namespace used for `ssh-keygen -Y` signatures
+const SSH_NAMESPACE: &str = "file-journal";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureFormat {
+    /// ssh-ed25519 keys via `ssh-keygen -Y sign/verify`
+    #[default]
+    Ssh,
+    /// minisign keys via the `minisign` binary
+    Minisign,
+}
+
+/// `[signing]` section of the config
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SigningConfig {
+    /// Which tool/key type to use
+    #[serde(default)]
+    pub format: SignatureFormat,
+    /// Private key used for signing
+    pub key: Option<PathBuf>,
+    /// minisign public key, or ssh allowed_signers file, used for verification
+    pub public_key: Option<PathBuf>,
+    /// Principal matched against the allowed_signers file (ssh only)
+    pub identity: Option<String>,
+}
+
+/// Where the detached signature for an entry lives
+pub fn signature_path(format: SignatureFormat, entry: &Path) -> PathBuf {
+    let extension = match format {
+        SignatureFormat::Ssh => "sig",
+        SignatureFormat::Minisign => "minisig",
+    };
+    let mut name = entry.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+fn path_arg(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// Build the program and arguments used to sign an entry
+pub fn sign_command(config: &SigningConfig, entry: &Path) -> Result<(String, Vec<String>), String> {
+    let key = config.key.as_deref().ok_or("No signing key configured ([signing] key)")?;
+
+    Ok(match config.format {
+        SignatureFormat::Ssh => (
+            "ssh-keygen".to_string(),
+            vec![
+                "-Y".into(),
+                "sign".into(),
+                "-f".into(),
+                path_arg(key),
+                "-n".into(),
+                SSH_NAMESPACE.into(),
+                path_arg(entry),
+            ],
+        ),
+        SignatureFormat::Minisign => (
+            "minisign".to_string(),
+            vec!["-S".into(), "-s".into(), path_arg(key), "-m".into(), path_arg(entry)],
+        ),
+    })
+}
+
+/// Build the program and arguments used to verify an entry's signature.
+/// For ssh the entry content must be fed on stdin.
+pub fn verify_command(config: &SigningConfig, entry: &Path) -> Result<(String, Vec<String>), String> {
+    let public_key = config
+        .public_key
+        .as_deref()
+        .ok_or("No verification key configured ([signing] public_key)")?;
+    let signature = signature_path(config.format, entry);
+
+    Ok(match config.format {
+        SignatureFormat::Ssh => {
+            let identity = config
+                .identity
+                .as_deref()
+                .ok_or("No signer identity configured ([signing] identity)")?;
+            (
+                "ssh-keygen".to_string(),
+                vec![
+                    "-Y".into(),
+                    "verify".into(),
+                    "-f".into(),
+                    path_arg(public_key),
+                    "-I".into(),
+                    identity.into(),
+                    "-n".into(),
+                    SSH_NAMESPACE.into(),
+                    "-s".into(),
+                    path_arg(&signature),
+                ],
+            )
+        }
+        SignatureFormat::Minisign => (
+            "minisign".to_string(),
+            vec![
+                "-V".into(),
+                "-p".into(),
+                path_arg(public_key),
+                "-m".into(),
+                path_arg(entry),
+                "-x".into(),
+                path_arg(&signature),
+            ],
+        ),
+    })
+}
+
+/// Sign an entry, writing a detached signature next to it
+pub fn sign_entry(config: &SigningConfig, entry: &Path) -> Result<PathBuf, String> {
+    if !entry.is_file() {
+        return Err(format!("{} is not a regular file", entry.display()));
+    }
+
+    let signature = signature_path(config.format, entry);
+    // Both tools refuse to overwrite (or prompt), so replace stale signatures explicitly
+    if signature.exists() {
+        std::fs::remove_file(&signature)
+            .map_err(|e| format!("Failed to remove old signature {}: {}", signature.display(), e))?;
+    }
+
+    let (program, args) = sign_command(config, entry)?;
+    let status = Command::new(&program)
+        .args(&args)
+        .status()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !status.success() {
+        return Err(format!("{} failed to sign {}", program, entry.display()));
+    }
+
+    Ok(signature)
+}
+
+/// Verify an entry against its detached signature
+pub fn verify_entry(config: &SigningConfig, entry: &Path) -> Result<(), String> {
+    let signature = signature_path(config.format, entry);
+    if !signature.exists() {
+        return Err(format!("no signature at {}", signature.display()));
+    }
+
+    let (program, args) = verify_command(config, entry)?;
+    let mut command = Command::new(&program);
+    command.args(&args).stdout(Stdio::null()).stderr(Stdio::piped());
+    if config.format == SignatureFormat::Ssh {
+        let file = std::fs::File::open(entry)
+            .map_err(|e| format!("Failed to open {}: {}", entry.display(), e))?;
+        command.stdin(file);
+    }
+
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_path_per_format() {
+        let entry = Path::new("/j/2026/02/17-081503-note.md");
+        assert_eq!(
+            signature_path(SignatureFormat::Ssh, entry),
+            PathBuf::from("/j/2026/02/17-081503-note.md.sig")
+        );
+        assert_eq!(
+            signature_path(SignatureFormat::Minisign, entry),
+            PathBuf::from("/j/2026/02/17-081503-note.md.minisig")
+        );
+    }
+
+    #[test]
+    fn test_verify_command_requires_identity_for_ssh() {
+        let config = SigningConfig {
+            format: SignatureFormat::Ssh,
+            key: None,
+            public_key: Some(PathBuf::from("allowed_signers")),
+            identity: None,
+        };
+        assert!(verify_command(&config, Path::new("a.md")).is_err());
+
+        let config = SigningConfig {
+            identity: Some("me@example.com".to_string()),
+            ..config
+        };
+        let (program, args) = verify_command(&config, Path::new("a.md")).unwrap();
+        assert_eq!(program, "ssh-keygen");
+        assert!(args.contains(&"a.md.sig".to_string()));
+        assert!(args.contains(&"me@example.com".to_string()));
+    }
+}
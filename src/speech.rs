@@ -0,0 +1,73 @@
+//! `read-aloud`: speak entries through a text-to-speech command.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::i18n::Language;
+use crate::markdown;
+
+/// `[speech]` section of the config
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SpeechConfig {
+    /// Shell command that reads text on stdin and speaks it, e.g. "espeak-ng --stdin -s 150"
+    /// or "piper --model en_US-amy-medium.onnx --output-raw | aplay -r 22050 -f S16_LE -t raw -"
+    pub command: Option<String>,
+}
+
+/// Speech command used when none is configured
+pub fn default_command() -> &'static str {
+    if cfg!(target_os = "macos") { "say" } else { "espeak --stdin" }
+}
+
+/// What is read out for an entry body: its date, then its text without Markdown.
+/// The template's `Date:` line is dropped since the date is already spoken.
+pub fn script(date: Option<chrono::NaiveDate>, body: &str, language: Language) -> String {
+    let body: Vec<&str> = body.lines().filter(|line| !line.starts_with("Date: ")).collect();
+    let text = markdown::to_plain_text(&body.join("\n"));
+    match date {
+        Some(date) => format!("{}.\n{}", language.long_date(date), text),
+        None => text,
+    }
+}
+
+/// Run `command` through the shell with `text` on stdin, waiting until it has finished speaking
+pub fn speak(command: &str, text: &str) -> Result<(), String> {
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    let mut child = Command::new(shell)
+        .args([flag, command])
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run '{}': {}", command, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("Failed to send text to '{}': {}", command, e))?;
+    }
+    let status = child.wait().map_err(|e| format!("Failed to run '{}': {}", command, e))?;
+    if status.success() { Ok(()) } else { Err(format!("'{}' exited with {}", command, status)) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_starts_with_date() {
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 2, 16);
+        let body = "# Hike\n\nDate: 2026-02-16 09:00\n\nUp **Mount Tam**\n";
+        assert_eq!(script(date, body, Language::English), "Monday, 16 February 2026.\nHike.\nUp Mount Tam.");
+        assert_eq!(script(date, "Rest", Language::French), "lundi 16 février 2026.\nRest.");
+        assert_eq!(script(None, "Rest", Language::English), "Rest.");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_speak_pipes_text_to_command() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let out = dir.path().join("spoken.txt");
+        speak(&format!("cat > '{}'", out.display()), "Hello there.").unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "Hello there.");
+        assert!(speak("exit 3", "x").is_err());
+    }
+}
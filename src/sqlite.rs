@@ -0,0 +1,98 @@
+//! Single-file SQLite storage backend (requires the `sqlite` feature).
+
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::storage::Storage;
+
+pub struct SqliteStorage {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let conn = Connection::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS entries (
+                path TEXT PRIMARY KEY,
+                content BLOB NOT NULL,
+                version INTEGER NOT NULL
+            );",
+        )
+        .map_err(|e| format!("Failed to initialize {}: {}", path.display(), e))?;
+        Ok(SqliteStorage { conn: Mutex::new(conn) })
+    }
+
+    fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn list(&self) -> Result<Vec<(String, String)>, String> {
+        let conn = self.conn();
+        let mut statement = conn
+            .prepare("SELECT path, version FROM entries ORDER BY path")
+            .map_err(|e| format!("Failed to list entries: {}", e))?;
+        let rows = statement
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?.to_string())))
+            .map_err(|e| format!("Failed to list entries: {}", e))?;
+        rows.collect::<Result<_, _>>().map_err(|e| format!("Failed to list entries: {}", e))
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, String> {
+        self.conn()
+            .query_row("SELECT content FROM entries WHERE path = ?1", params![path], |row| row.get(0))
+            .optional()
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?
+            .ok_or_else(|| format!("Entry {} not found", path))
+    }
+
+    fn write(&self, path: &str, content: &[u8]) -> Result<(), String> {
+        self.conn()
+            .execute(
+                "INSERT INTO entries (path, content, version) VALUES (?1, ?2, 1)
+                 ON CONFLICT(path) DO UPDATE SET content = excluded.content, version = version + 1",
+                params![path, content],
+            )
+            .map(|_| ())
+            .map_err(|e| format!("Failed to write {}: {}", path, e))
+    }
+
+    fn delete(&self, path: &str) -> Result<(), String> {
+        self.conn()
+            .execute("DELETE FROM entries WHERE path = ?1", params![path])
+            .map(|_| ())
+            .map_err(|e| format!("Failed to delete {}: {}", path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_bumps_version() {
+        let dir = TempDir::new().unwrap();
+        let storage = SqliteStorage::open(&dir.path().join("journal.db")).unwrap();
+
+        storage.write("2026/02/17-081503-a.md", b"# A\n").unwrap();
+        storage.write("2026/02/17-081503-a.md", b"# A\nmore\n").unwrap();
+        storage.write("2026/01/05-100000-b.md", b"# B\n").unwrap();
+
+        assert_eq!(
+            storage.list().unwrap(),
+            vec![
+                ("2026/01/05-100000-b.md".to_string(), "1".to_string()),
+                ("2026/02/17-081503-a.md".to_string(), "2".to_string()),
+            ]
+        );
+        assert_eq!(storage.read("2026/02/17-081503-a.md").unwrap(), b"# A\nmore\n");
+        assert!(storage.read("2026/03/missing.md").is_err());
+
+        storage.delete("2026/02/17-081503-a.md").unwrap();
+        assert_eq!(storage.list().unwrap(), vec![("2026/01/05-100000-b.md".to_string(), "1".to_string())]);
+    }
+}
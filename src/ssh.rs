@@ -0,0 +1,179 @@
+//! A journal on another machine reached over SSH (`user@host:journal/`), for `sync --remote`.
+//!
+//! Everything runs through plain `ssh` with POSIX shell commands on the remote side, so the
+//! other machine needs nothing installed but a shell; keys, ports and connection sharing
+//! come from the user's `~/.ssh/config`.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::is_entry_file;
+use crate::storage::Storage;
+
+pub struct SshStorage {
+    program: String,
+    host: String,
+    /// Journal folder on the remote, relative to the login's home unless absolute
+    dir: String,
+}
+
+/// `user@host:journal/` -> (`user@host`, `journal`)
+pub fn parse_remote(remote: &str) -> Result<(String, String), String> {
+    let (host, dir) = remote
+        .split_once(':')
+        .filter(|(host, _)| !host.is_empty())
+        .ok_or_else(|| format!("Invalid remote '{}': expected [user@]host:path", remote))?;
+    let dir = dir.strip_prefix("~/").unwrap_or(dir).trim_end_matches('/');
+    Ok((host.to_string(), if dir.is_empty() { ".".to_string() } else { dir.to_string() }))
+}
+
+/// Quote for a POSIX shell
+fn quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+impl SshStorage {
+    pub fn new(remote: &str) -> Result<SshStorage, String> {
+        SshStorage::with_program("ssh", remote)
+    }
+
+    /// Reach the remote through `program` instead of `ssh`; it's called as `program -- host script`,
+    /// so a host like `-oProxyCommand=...` can't pass for an option
+    pub fn with_program(program: &str, remote: &str) -> Result<SshStorage, String> {
+        let (host, dir) = parse_remote(remote)?;
+        Ok(SshStorage { program: program.to_string(), host, dir })
+    }
+
+    fn path(&self, path: &str) -> String {
+        quote(&format!("{}/{}", self.dir, path))
+    }
+
+    fn run(&self, script: &str, input: Option<&[u8]>) -> Result<Vec<u8>, String> {
+        let mut child = Command::new(&self.program)
+            .arg("--")
+            .arg(&self.host)
+            .arg(script)
+            .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run {}: {}", self.program, e))?;
+        if let Some(input) = input
+            && let Some(mut stdin) = child.stdin.take()
+        {
+            stdin.write_all(input).map_err(|e| format!("Failed to send to {}: {}", self.host, e))?;
+        }
+        let output = child.wait_with_output().map_err(|e| format!("Failed to run {}: {}", self.program, e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("{} on {} failed: {}", self.program, self.host, stderr.trim()));
+        }
+        Ok(output.stdout)
+    }
+}
+
+impl Storage for SshStorage {
+    /// Versions are `cksum`'s CRC and size, so they change with the content. A missing journal
+    /// folder is an error rather than an empty journal, so sync never reads it as everything deleted
+    fn list(&self) -> Result<Vec<(String, String)>, String> {
+        let script = format!(
+            "cd {} || exit 1; for f in [0-9][0-9][0-9][0-9]/[0-9][0-9]/*; do [ -f \"$f\" ] && cksum \"$f\"; done; exit 0",
+            quote(&self.dir)
+        );
+        let output = self.run(&script, None)?;
+        let mut entries: Vec<(String, String)> = String::from_utf8_lossy(&output)
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, ' ');
+                let (crc, size, path) = (parts.next()?, parts.next()?, parts.next()?);
+                let name = path.rsplit('/').next()?;
+                is_entry_file(name).then(|| (path.to_string(), format!("{}-{}", crc, size)))
+            })
+            .collect();
+        entries.sort();
+        Ok(entries)
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, String> {
+        self.run(&format!("cat -- {}", self.path(path)), None)
+    }
+
+    /// Through a temporary file, so a dropped connection never leaves half an entry
+    fn write(&self, path: &str, content: &[u8]) -> Result<(), String> {
+        let folder = path.rsplit_once('/').map(|(folder, _)| folder).unwrap_or(".");
+        let tmp = self.path(&format!("{}.tmp", path));
+        let script = format!(
+            "mkdir -p {} && cat > {} && mv -f {} {}",
+            self.path(folder),
+            tmp,
+            tmp,
+            self.path(path)
+        );
+        self.run(&script, Some(content)).map(|_| ())
+    }
+
+    fn delete(&self, path: &str) -> Result<(), String> {
+        self.run(&format!("rm -f -- {}", self.path(path)), None).map(|_| ())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::storage::{self, FsStorage};
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_remote() {
+        assert_eq!(parse_remote("t@pi:journal/").unwrap(), ("t@pi".to_string(), "journal".to_string()));
+        assert_eq!(parse_remote("pi:~/notes").unwrap(), ("pi".to_string(), "notes".to_string()));
+        assert_eq!(parse_remote("pi:").unwrap(), ("pi".to_string(), ".".to_string()));
+        assert!(parse_remote("journal/").is_err());
+    }
+
+    #[test]
+    fn test_sync_over_ssh() {
+        // Stands in for ssh: runs the script locally, from the "remote home", once options have ended
+        let home = TempDir::new().unwrap();
+        let fake = home.path().join("fake-ssh");
+        let script = format!("#!/bin/sh\n[ \"$1\" = -- ] && cd '{}' && exec sh -c \"$3\"\n", home.path().display());
+        fs::write(&fake, script).unwrap();
+        fs::set_permissions(&fake, fs::Permissions::from_mode(0o755)).unwrap();
+        let remote = SshStorage::with_program(fake.to_str().unwrap(), "me@pi:journal/").unwrap();
+        assert!(remote.list().is_err());
+        fs::create_dir(home.path().join("journal")).unwrap();
+        assert!(remote.list().unwrap().is_empty());
+
+        let local_dir = TempDir::new().unwrap();
+        let local = FsStorage::new(local_dir.path());
+        local.write("2026/02/17-081503-it's here.md", b"# Local\n").unwrap();
+        remote.write("2026/02/18-090000-away.md", b"# Remote\n").unwrap();
+        fs::write(home.path().join("journal/2026/02/notes.txt"), "not an entry").unwrap();
+
+        let report = storage::sync_with_index(&remote, &local, &storage::index_name("me@pi:journal/")).unwrap();
+        assert_eq!(report.pulled, ["2026/02/18-090000-away.md"]);
+        assert_eq!(report.pushed, ["2026/02/17-081503-it's here.md"]);
+        assert_eq!(fs::read_to_string(home.path().join("journal/2026/02/17-081503-it's here.md")).unwrap(), "# Local\n");
+        assert_eq!(local.read("2026/02/18-090000-away.md").unwrap(), b"# Remote\n");
+        assert!(local_dir.path().join(".sync-index-me_pi_journal_.json").exists());
+
+        // An edit on the remote only comes back down
+        remote.write("2026/02/18-090000-away.md", b"# Remote\n\nmore\n").unwrap();
+        let report = storage::sync_with_index(&remote, &local, &storage::index_name("me@pi:journal/")).unwrap();
+        assert_eq!((report.pulled.len(), report.pushed.len()), (1, 0));
+
+        // `delete` moves the entry to the trash, and the next sync deletes it on the remote
+        crate::trash::move_to_trash(local_dir.path(), &local_dir.path().join("2026/02/18-090000-away.md")).unwrap();
+        let report = storage::sync_with_index(&remote, &local, &storage::index_name("me@pi:journal/")).unwrap();
+        assert_eq!(report.pushed_deletions, ["2026/02/18-090000-away.md"]);
+        assert!(report.pushed.is_empty());
+        assert!(!home.path().join("journal/2026/02/18-090000-away.md").exists());
+
+        // With the remote folder moved away, sync fails instead of deleting the entries here
+        fs::rename(home.path().join("journal"), home.path().join("moved")).unwrap();
+        assert!(storage::sync_with_index(&remote, &local, &storage::index_name("me@pi:journal/")).is_err());
+        assert!(local_dir.path().join("2026/02/17-081503-it's here.md").exists());
+    }
+}
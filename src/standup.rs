@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use crate::markdown;
+
+/// `[standup]` section of the config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandupConfig {
+    /// Section headings pulled from entries, in output order
+    #[serde(default = "default_headings")]
+    pub headings: Vec<String>,
+}
+
+impl Default for StandupConfig {
+    fn default() -> Self {
+        StandupConfig {
+            headings: default_headings(),
+        }
+    }
+}
+
+fn default_headings() -> Vec<String> {
+    vec!["Done".to_string(), "Today".to_string(), "Blockers".to_string()]
+}
+
+/// Collect each configured section across entry bodies (oldest first)
+pub fn collect_sections(headings: &[String], bodies: &[String]) -> Vec<(String, Vec<String>)> {
+    headings
+        .iter()
+        .map(|heading| {
+            let items = bodies
+                .iter()
+                .filter_map(|body| markdown::find_section(body, heading))
+                .flat_map(|content| {
+                    content
+                        .lines()
+                        .map(str::trim_end)
+                        .filter(|line| !line.trim().is_empty())
+                        .map(str::to_string)
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            (heading.clone(), items)
+        })
+        .collect()
+}
+
+/// Turn a markdown list line into plain item text
+fn item_text(line: &str) -> &str {
+    let trimmed = line.trim_start();
+    trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .or_else(|| trimmed.strip_prefix("+ "))
+        .unwrap_or(trimmed)
+}
+
+/// Render the standup as plain markdown, or Slack mrkdwn when `slack` is set
+pub fn render(sections: &[(String, Vec<String>)], slack: bool) -> String {
+    let mut out = String::new();
+    for (heading, items) in sections {
+        if slack {
+            out.push_str(&format!("*{}*\n", heading));
+        } else {
+            out.push_str(&format!("{}:\n", heading));
+        }
+
+        if items.is_empty() {
+            out.push_str(if slack { "• none\n" } else { "- none\n" });
+        }
+        for item in items {
+            // Keep nested list indentation readable in both flavours
+            let indent = item.len() - item.trim_start().len();
+            let bullet = if slack { "•" } else { "-" };
+            out.push_str(&format!("{}{} {}\n", " ".repeat(indent), bullet, item_text(item)));
+        }
+        out.push('\n');
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<(String, Vec<String>)> {
+        let yesterday = "# Mon\n\n## Done\n- fixed login\n## Blockers\n".to_string();
+        let today = "# Tue\n\n## Today\n- review PRs\n  - the big one\n".to_string();
+        collect_sections(&default_headings(), &[yesterday, today])
+    }
+
+    #[test]
+    fn test_collect_sections_across_entries() {
+        let sections = sample();
+        assert_eq!(sections[0], ("Done".to_string(), vec!["- fixed login".to_string()]));
+        assert_eq!(sections[1].1.len(), 2);
+        assert!(sections[2].1.is_empty());
+    }
+
+    #[test]
+    fn test_render_plain_and_slack() {
+        let plain = render(&sample(), false);
+        assert_eq!(
+            plain,
+            "Done:\n- fixed login\n\nToday:\n- review PRs\n  - the big one\n\nBlockers:\n- none"
+        );
+
+        let slack = render(&sample(), true);
+        assert!(slack.starts_with("*Done*\n• fixed login"));
+        assert!(slack.contains("  • the big one"));
+        assert!(slack.ends_with("*Blockers*\n• none"));
+    }
+}
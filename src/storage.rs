@@ -0,0 +1,377 @@
+//! Storage backends for journal entries and syncing a backend into a local working copy.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{is_entry_file, lock, trash};
+
+/// Sync state kept in the local working copy
+pub const SYNC_INDEX: &str = ".sync-index.json";
+
+/// Where entries live. Paths are relative to the journal root (`YYYY/MM/<file>`).
+pub trait Storage {
+    /// All entries with a version string that changes whenever the entry does
+    fn list(&self) -> Result<Vec<(String, String)>, String>;
+    fn read(&self, path: &str) -> Result<Vec<u8>, String>;
+    fn write(&self, path: &str, content: &[u8]) -> Result<(), String>;
+    fn delete(&self, path: &str) -> Result<(), String>;
+}
+
+/// A backend that can be shared with the end-of-command sync
+pub type Backend = Box<dyn Storage + Send + Sync>;
+
+/// The `YYYY/MM/<file>` directory layout on a local filesystem
+pub struct FsStorage {
+    root: PathBuf,
+}
+
+impl FsStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FsStorage { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+fn numeric_dirs(dir: &Path, len: usize) -> Vec<String> {
+    let Ok(read) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    read.flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name.len() == len && name.chars().all(|c| c.is_ascii_digit()))
+        .collect()
+}
+
+impl Storage for FsStorage {
+    fn list(&self) -> Result<Vec<(String, String)>, String> {
+        let mut entries = Vec::new();
+        for year in numeric_dirs(&self.root, 4) {
+            for month in numeric_dirs(&self.root.join(&year), 2) {
+                let dir = self.root.join(&year).join(&month);
+                let read = fs::read_dir(&dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+                for file in read.flatten() {
+                    let name = file.file_name().to_string_lossy().to_string();
+                    if !is_entry_file(&name) {
+                        continue;
+                    }
+                    let meta = file.metadata().map_err(|e| format!("Failed to stat {}: {}", name, e))?;
+                    let modified = meta
+                        .modified()
+                        .ok()
+                        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                        .map(|d| d.as_nanos())
+                        .unwrap_or_default();
+                    entries.push((format!("{}/{}/{}", year, month, name), format!("{}-{}", meta.len(), modified)));
+                }
+            }
+        }
+        entries.sort();
+        Ok(entries)
+    }
+
+    fn read(&self, path: &str) -> Result<Vec<u8>, String> {
+        let full = self.root.join(path);
+        fs::read(&full).map_err(|e| format!("Failed to read {}: {}", full.display(), e))
+    }
+
+    fn write(&self, path: &str, content: &[u8]) -> Result<(), String> {
+        let full = self.root.join(path);
+        if let Some(parent) = full.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::write(&full, content).map_err(|e| format!("Failed to write {}: {}", full.display(), e))
+    }
+
+    fn delete(&self, path: &str) -> Result<(), String> {
+        let full = self.root.join(path);
+        fs::remove_file(&full).map_err(|e| format!("Failed to delete {}: {}", full.display(), e))
+    }
+}
+
+/// A backend location as a file name: `dav://host/path` -> `host_path`
+fn sanitize_location(location: &str) -> String {
+    location
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(location)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// Local working copy for a backend: `~/.cache/file-journal/<sanitized location>`
+pub fn cache_dir(location: &str) -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("file-journal")
+        .join(sanitize_location(location))
+}
+
+/// Sync state for one of several remotes synced with the same local journal
+pub fn index_name(location: &str) -> String {
+    format!(".sync-index-{}.json", sanitize_location(location))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    /// Remote version at the last sync
+    version: String,
+    /// Content hash at the last sync
+    hash: String,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    /// Entries downloaded into the local copy
+    pub pulled: Vec<String>,
+    /// Entries uploaded to the backend
+    pub pushed: Vec<String>,
+    /// Entries changed on both sides; the remote copy was saved next to the local one
+    pub conflicts: Vec<String>,
+    /// Entries moved to the local copy's trash because they were deleted in the backend
+    pub pulled_deletions: Vec<String>,
+    /// Entries deleted from the backend because they were deleted locally
+    pub pushed_deletions: Vec<String>,
+}
+
+fn hash(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// `17-081503-notes.md` -> `17-081503-notes-conflict.md`
+pub fn conflict_path(path: &str) -> String {
+    match path.find(".md") {
+        Some(i) => format!("{}-conflict{}", &path[..i], &path[i..]),
+        None => format!("{}-conflict", path),
+    }
+}
+
+/// Two-way sync between a backend and a local working copy.
+///
+/// New or changed remote entries are downloaded, new or changed local entries are
+/// uploaded. When both sides changed, the local copy wins and the remote one is kept
+/// as `<name>-conflict.md`. An entry the last sync saw that is now gone from one side is
+/// deleted on the other too, unless it was changed there since; then the change wins and
+/// the entry comes back. Local deletions go through the trash, so `undo` brings them back.
+/// A side that lists no entries at all while the last sync saw some is taken for a missing
+/// or unmounted folder, and the sync stops rather than delete everything on the other.
+pub fn sync(remote: &dyn Storage, local: &FsStorage) -> Result<SyncReport, String> {
+    sync_with_index(remote, local, SYNC_INDEX)
+}
+
+/// `sync`, keeping its state in `index` at the local root
+pub fn sync_with_index(remote: &dyn Storage, local: &FsStorage, index: &str) -> Result<SyncReport, String> {
+    let index_path = local.root().join(index);
+    let _lock = lock::acquire(&index_path)?;
+    let mut index: BTreeMap<String, IndexEntry> = fs::read_to_string(&index_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+
+    let mut report = SyncReport::default();
+    let mut remote_entries: BTreeMap<String, String> = remote.list()?.into_iter().collect();
+    let local_entries: BTreeSet<String> = local.list()?.into_iter().map(|(path, _)| path).collect();
+    if !index.is_empty() && (remote_entries.is_empty() || local_entries.is_empty()) {
+        let (empty, other) = match remote_entries.is_empty() {
+            true => ("remote", "here"),
+            false => ("local journal", "on the remote"),
+        };
+        return Err(format!(
+            "The {} has no entries, though {} were synced before; not deleting them {} \
+             (remove {} to sync from scratch)",
+            empty,
+            index.len(),
+            other,
+            index_path.display()
+        ));
+    }
+    let mut to_push = Vec::new();
+    let mut operation = trash::Operation::new("sync");
+
+    // Entries the last sync saw that one side no longer has were deleted there
+    let known: Vec<(String, IndexEntry)> = index.iter().map(|(path, entry)| (path.clone(), entry.clone())).collect();
+    for (path, known) in known {
+        match (remote_entries.get(&path), local_entries.contains(&path)) {
+            (None, true) => {
+                if local.read(&path).is_ok_and(|content| hash(&content) == known.hash) {
+                    operation.trash(local.root(), &local.root().join(&path))?;
+                    report.pulled_deletions.push(path.clone());
+                    index.remove(&path);
+                }
+            }
+            (Some(version), false) => {
+                if *version == known.version {
+                    remote.delete(&path)?;
+                    remote_entries.remove(&path);
+                    report.pushed_deletions.push(path.clone());
+                    index.remove(&path);
+                }
+            }
+            (None, false) => {
+                index.remove(&path);
+            }
+            (Some(_), true) => {}
+        }
+    }
+
+    for (path, version) in &remote_entries {
+        let known = index.get(path);
+        let local_hash = local.read(path).ok().map(|c| hash(&c));
+        let local_changed = match (&local_hash, known) {
+            (Some(h), Some(k)) => *h != k.hash,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+
+        if known.is_some_and(|k| k.version == *version) {
+            if local_changed {
+                to_push.push(path.clone());
+            }
+            continue;
+        }
+
+        let content = remote.read(path)?;
+        let remote_hash = hash(&content);
+        if local_changed && local_hash.as_deref() != Some(remote_hash.as_str()) {
+            local.write(&conflict_path(path), &content)?;
+            report.conflicts.push(path.clone());
+            to_push.push(path.clone());
+            continue;
+        }
+        if local_hash.as_deref() != Some(remote_hash.as_str()) {
+            local.write(path, &content)?;
+            report.pulled.push(path.clone());
+        }
+        index.insert(path.clone(), IndexEntry { version: version.clone(), hash: remote_hash });
+    }
+
+    for (path, _) in local.list()? {
+        if !remote_entries.contains_key(&path) {
+            to_push.push(path);
+        }
+    }
+
+    for path in &to_push {
+        let content = local.read(path)?;
+        remote.write(path, &content)?;
+        index.insert(path.clone(), IndexEntry { version: String::new(), hash: hash(&content) });
+    }
+    report.pushed = to_push;
+
+    if !report.pushed.is_empty() {
+        // Record the versions the backend assigned to the uploads
+        for (path, version) in remote.list()? {
+            if let Some(entry) = index.get_mut(&path)
+                && entry.version.is_empty()
+            {
+                entry.version = version;
+            }
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&index).map_err(|e| format!("Failed to encode sync index: {}", e))?;
+    let tmp = index_path.with_extension("json.tmp");
+    fs::write(&tmp, json)
+        .and_then(|_| fs::rename(&tmp, &index_path))
+        .map_err(|e| format!("Failed to write {}: {}", index_path.display(), e))?;
+    trash::record(local.root(), operation)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sync_pulls_pushes_and_keeps_conflicts() {
+        let remote_dir = TempDir::new().unwrap();
+        let local_dir = TempDir::new().unwrap();
+        let remote = FsStorage::new(remote_dir.path());
+        let local = FsStorage::new(local_dir.path());
+
+        remote.write("2026/02/17-081503-a.md", b"# A\n").unwrap();
+        local.write("2026/02/18-090000-b.md", b"# B\n").unwrap();
+
+        let report = sync(&remote, &local).unwrap();
+        assert_eq!((report.pulled.len(), report.pushed.len()), (1, 1));
+        assert_eq!(local.read("2026/02/17-081503-a.md").unwrap(), b"# A\n");
+        assert_eq!(remote.read("2026/02/18-090000-b.md").unwrap(), b"# B\n");
+
+        // Nothing changed: nothing moves
+        let report = sync(&remote, &local).unwrap();
+        assert_eq!((report.pulled.len(), report.pushed.len()), (0, 0));
+
+        // Both sides edit the same entry
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        remote.write("2026/02/17-081503-a.md", b"# A\nremote\n").unwrap();
+        local.write("2026/02/17-081503-a.md", b"# A\nlocal\n").unwrap();
+        let report = sync(&remote, &local).unwrap();
+        assert_eq!(report.conflicts, vec!["2026/02/17-081503-a.md".to_string()]);
+        assert_eq!(remote.read("2026/02/17-081503-a.md").unwrap(), b"# A\nlocal\n");
+        assert_eq!(local.read("2026/02/17-081503-a-conflict.md").unwrap(), b"# A\nremote\n");
+    }
+
+    #[test]
+    fn test_sync_propagates_deletions() {
+        let remote_dir = TempDir::new().unwrap();
+        let local_dir = TempDir::new().unwrap();
+        let remote = FsStorage::new(remote_dir.path());
+        let local = FsStorage::new(local_dir.path());
+        for name in ["a", "b", "c", "d"] {
+            remote.write(&format!("2026/02/17-081503-{}.md", name), b"# entry\n").unwrap();
+        }
+        sync(&remote, &local).unwrap();
+
+        // a deleted locally, b deleted remotely, c deleted locally but edited remotely,
+        // d deleted remotely but edited locally
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        local.delete("2026/02/17-081503-a.md").unwrap();
+        remote.delete("2026/02/17-081503-b.md").unwrap();
+        local.delete("2026/02/17-081503-c.md").unwrap();
+        remote.write("2026/02/17-081503-c.md", b"# entry\nremote edit\n").unwrap();
+        remote.delete("2026/02/17-081503-d.md").unwrap();
+        local.write("2026/02/17-081503-d.md", b"# entry\nlocal edit\n").unwrap();
+
+        let report = sync(&remote, &local).unwrap();
+        assert_eq!(report.pushed_deletions, ["2026/02/17-081503-a.md"]);
+        assert_eq!(report.pulled_deletions, ["2026/02/17-081503-b.md"]);
+        let names = |storage: &FsStorage| storage.list().unwrap().into_iter().map(|(path, _)| path).collect::<Vec<_>>();
+        assert_eq!(names(&remote), ["2026/02/17-081503-c.md", "2026/02/17-081503-d.md"]);
+        assert_eq!(names(&local), names(&remote));
+        assert_eq!(local.read("2026/02/17-081503-c.md").unwrap(), b"# entry\nremote edit\n");
+        assert_eq!(remote.read("2026/02/17-081503-d.md").unwrap(), b"# entry\nlocal edit\n");
+        assert!(local_dir.path().join(".trash/2026/02/17-081503-b.md").exists());
+
+        // Nothing left to do
+        let report = sync(&remote, &local).unwrap();
+        assert!(report.pulled_deletions.is_empty() && report.pushed_deletions.is_empty());
+        assert!(report.pulled.is_empty() && report.pushed.is_empty());
+
+        // Undoing the sync brings b back, and the next sync uploads it again
+        assert_eq!(trash::undo(local_dir.path()).unwrap().unwrap().command, "sync");
+        let report = sync(&remote, &local).unwrap();
+        assert_eq!(report.pushed, ["2026/02/17-081503-b.md"]);
+    }
+
+    #[test]
+    fn test_sync_refuses_to_delete_everything() {
+        let remote_dir = TempDir::new().unwrap();
+        let local_dir = TempDir::new().unwrap();
+        let local = FsStorage::new(local_dir.path());
+        FsStorage::new(remote_dir.path()).write("2026/02/17-081503-a.md", b"# A\n").unwrap();
+        sync(&FsStorage::new(remote_dir.path()), &local).unwrap();
+
+        // The remote folder went missing
+        let gone = FsStorage::new(remote_dir.path().join("moved"));
+        assert!(sync(&gone, &local).unwrap_err().contains("remote has no entries"));
+        assert_eq!(local.read("2026/02/17-081503-a.md").unwrap(), b"# A\n");
+    }
+}
@@ -0,0 +1,140 @@
+//! Monthly summaries written by a language model (requires the `network` feature).
+//!
+//! Providers: any OpenAI-compatible chat completions endpoint, or a local Ollama.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Used when `[summarize] prompt` is not set. `{month}` and `{entries}` are filled in.
+pub const DEFAULT_PROMPT: &str = "Below are my journal entries for {month}. Write a short summary of the month \
+in Markdown: the main themes, what got done, and anything worth remembering. Write in the first person and \
+don't invent anything that isn't in the entries.\n\n{entries}";
+
+/// `[summarize]` section of the config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummarizeConfig {
+    /// "openai" (any OpenAI-compatible API) or "ollama"
+    pub provider: String,
+    pub model: String,
+    /// API base URL (default: https://api.openai.com/v1 or http://localhost:11434)
+    pub url: Option<String>,
+    /// Bearer token, sent to any provider (with openai, OPENAI_API_KEY is used when unset)
+    pub api_key: Option<String>,
+    /// Prompt template with `{month}` and `{entries}` placeholders
+    pub prompt: Option<String>,
+}
+
+impl SummarizeConfig {
+    /// The bearer token to send: `api_key`, or for OpenAI-compatible APIs OPENAI_API_KEY, which
+    /// never goes to another provider
+    pub fn api_key(&self) -> Option<String> {
+        self.api_key
+            .clone()
+            .or_else(|| std::env::var("OPENAI_API_KEY").ok().filter(|_| self.provider == "openai"))
+    }
+}
+
+/// Fill in the prompt template
+pub fn render_prompt(template: &str, month: &str, entries: &str) -> String {
+    template.replace("{month}", month).replace("{entries}", entries)
+}
+
+/// Endpoint and JSON body of a chat request for `prompt`
+pub fn request(config: &SummarizeConfig, prompt: &str) -> Result<(String, Value), String> {
+    let messages = json!([{ "role": "user", "content": prompt }]);
+    match config.provider.as_str() {
+        "openai" => {
+            let base = config.url.as_deref().unwrap_or("https://api.openai.com/v1").trim_end_matches('/');
+            Ok((format!("{}/chat/completions", base), json!({ "model": config.model, "messages": messages })))
+        }
+        "ollama" => {
+            let base = config.url.as_deref().unwrap_or("http://localhost:11434").trim_end_matches('/');
+            Ok((
+                format!("{}/api/chat", base),
+                json!({ "model": config.model, "messages": messages, "stream": false }),
+            ))
+        }
+        other => Err(format!("Unknown summarize provider '{}' (expected openai or ollama)", other)),
+    }
+}
+
+/// The generated text in a provider's response
+pub fn parse_reply(provider: &str, response: &Value) -> Result<String, String> {
+    let content = match provider {
+        "ollama" => &response["message"]["content"],
+        _ => &response["choices"][0]["message"]["content"],
+    };
+    content
+        .as_str()
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .ok_or_else(|| format!("No summary in the {} response: {}", provider, response))
+}
+
+/// Send `prompt` to the configured provider and return its reply
+pub fn complete(config: &SummarizeConfig, prompt: &str) -> Result<String, String> {
+    let (url, body) = request(config, prompt)?;
+    let mut request = ureq::post(&url).header("User-Agent", "file-journal");
+    if let Some(key) = config.api_key() {
+        request = request.header("Authorization", &format!("Bearer {}", key));
+    }
+    let response = request
+        .send_json(&body)
+        .map_err(|e| format!("Request to {} failed: {}", url, e))?
+        .body_mut()
+        .read_json::<Value>()
+        .map_err(|e| format!("Invalid response from {}: {}", url, e))?;
+    parse_reply(&config.provider, &response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(provider: &str) -> SummarizeConfig {
+        SummarizeConfig {
+            provider: provider.to_string(),
+            model: "llama3.1".to_string(),
+            url: None,
+            api_key: None,
+            prompt: None,
+        }
+    }
+
+    #[test]
+    fn test_request_per_provider() {
+        let (url, body) = request(&config("ollama"), "hi").unwrap();
+        assert_eq!(url, "http://localhost:11434/api/chat");
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["messages"][0]["content"], "hi");
+
+        let mut openai = config("openai");
+        openai.url = Some("http://localhost:8080/v1/".to_string());
+        assert_eq!(request(&openai, "hi").unwrap().0, "http://localhost:8080/v1/chat/completions");
+        assert!(request(&config("claude"), "hi").is_err());
+    }
+
+    #[test]
+    fn test_api_key_only_from_env_for_openai() {
+        assert_eq!(config("ollama").api_key(), None);
+        let mut proxied = config("ollama");
+        proxied.api_key = Some("proxy-secret".to_string());
+        assert_eq!(proxied.api_key().as_deref(), Some("proxy-secret"));
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        let openai = json!({ "choices": [{ "message": { "role": "assistant", "content": " A calm month.\n" } }] });
+        assert_eq!(parse_reply("openai", &openai).unwrap(), "A calm month.");
+        let ollama = json!({ "message": { "role": "assistant", "content": "Busy." }, "done": true });
+        assert_eq!(parse_reply("ollama", &ollama).unwrap(), "Busy.");
+        assert!(parse_reply("ollama", &openai).is_err());
+    }
+
+    #[test]
+    fn test_render_prompt() {
+        let prompt = render_prompt(DEFAULT_PROMPT, "February 2026", "## 2026-02-16 Plan\n\nhello");
+        assert!(prompt.starts_with("Below are my journal entries for February 2026."));
+        assert!(prompt.ends_with("## 2026-02-16 Plan\n\nhello"));
+    }
+}
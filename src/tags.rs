@@ -0,0 +1,179 @@
+//! Tag suggestions for `tag suggest`, and writing accepted tags into frontmatter.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::frontmatter::{self, Value};
+use crate::{find_all_entries, read_entry};
+
+/// Words too common to say anything about what an entry is about
+const STOPWORDS: &[&str] = &[
+    "a", "about", "after", "again", "all", "also", "an", "and", "any", "are", "as", "at", "be", "because", "been",
+    "before", "but", "by", "can", "could", "did", "do", "does", "done", "for", "from", "get", "got", "had", "has",
+    "have", "how", "i", "if", "in", "into", "is", "it", "its", "just", "last", "like", "me", "more", "my", "need",
+    "not", "now", "of", "on", "one", "or", "our", "out", "really", "should", "so", "some", "still", "than", "that",
+    "the", "their", "them", "then", "there", "these", "they", "this", "today", "too", "up", "us", "very", "was", "we",
+    "went", "were", "what", "when", "where", "which", "while", "who", "why", "will", "with", "would", "you", "your",
+];
+
+/// Shortest word proposed as a new tag
+const MIN_KEYWORD_LEN: usize = 4;
+
+/// How many entries use each tag
+pub fn journal_tags(journal_path: &Path) -> Result<BTreeMap<String, usize>, String> {
+    let mut counts = BTreeMap::new();
+    for entry in find_all_entries(journal_path)? {
+        let Ok(content) = read_entry(&entry) else {
+            continue;
+        };
+        for tag in frontmatter::parse(&content).0.list("tags") {
+            *counts.entry(tag).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Tag counts, most used first and alphabetical among equals
+pub fn by_count(counts: BTreeMap<String, usize>) -> Vec<(String, usize)> {
+    let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+    // Stable, so the map's alphabetical order is kept for equal counts
+    ranked.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    ranked
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '-')
+        .map(|w| w.trim_matches('-').to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Up to `limit` tags for an entry body, not counting those it already has.
+///
+/// Tags already used elsewhere in the journal come first when the body mentions
+/// them, so suggestions reuse the existing vocabulary; the rest are the body's
+/// most repeated words.
+pub fn suggest(body: &str, existing: &[String], known: &BTreeMap<String, usize>, limit: usize) -> Vec<String> {
+    let mut frequency: BTreeMap<String, usize> = BTreeMap::new();
+    for word in words(body) {
+        *frequency.entry(word).or_insert(0) += 1;
+    }
+    let is_new = |tag: &String| !existing.iter().any(|e| e.eq_ignore_ascii_case(tag));
+
+    let mut mentioned: Vec<(&String, usize, usize)> = known
+        .iter()
+        .filter(|(tag, _)| is_new(tag))
+        .filter_map(|(tag, uses)| frequency.get(&tag.to_lowercase()).map(|count| (tag, *count, *uses)))
+        .collect();
+    mentioned.sort_by_key(|(_, count, uses)| std::cmp::Reverse((*count, *uses)));
+    let mut suggestions: Vec<String> = mentioned.into_iter().map(|(tag, _, _)| tag.clone()).collect();
+
+    let mut keywords: Vec<(String, usize)> = frequency
+        .into_iter()
+        .filter(|(word, count)| {
+            *count >= 2
+                && word.chars().count() >= MIN_KEYWORD_LEN
+                && !word.chars().all(|c| c.is_ascii_digit())
+                && !STOPWORDS.contains(&word.as_str())
+        })
+        .collect();
+    // Stable sort keeps equally frequent words alphabetical
+    keywords.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    for (word, _) in keywords {
+        if is_new(&word) && !suggestions.iter().any(|s| s.eq_ignore_ascii_case(&word)) {
+            suggestions.push(word);
+        }
+    }
+
+    suggestions.truncate(limit);
+    suggestions
+}
+
+/// Add `tags` to an entry's frontmatter, keeping the ones it has
+pub fn add_tags(path: &Path, tags: &[String]) -> Result<(), String> {
+    if !path.to_string_lossy().ends_with(".md") {
+        return Err(format!("{} is compressed or archived and can't be edited", path.display()));
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let (mut fm, body) = frontmatter::parse(&content);
+    let mut all = fm.list("tags");
+    for tag in tags {
+        if !all.contains(tag) {
+            all.push(tag.clone());
+        }
+    }
+    fm.set("tags", Value::List(all));
+    fs::write(path, format!("{}{}", fm.render(), body)).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Prompt asking a language model for tags, listing the journal's existing ones to reuse
+#[cfg(feature = "network")]
+pub fn llm_prompt(body: &str, known: &BTreeMap<String, usize>, limit: usize) -> String {
+    let known: Vec<&str> = known.keys().map(String::as_str).collect();
+    format!(
+        "Suggest up to {} short lowercase tags for this journal entry. Prefer these existing tags when they fit: {}. \
+         Reply with the tags only, separated by commas.\n\n{}",
+        limit,
+        if known.is_empty() { "(none yet)".to_string() } else { known.join(", ") },
+        body
+    )
+}
+
+/// Tags from a model's comma-separated reply
+#[cfg(feature = "network")]
+pub fn parse_llm_tags(reply: &str) -> Vec<String> {
+    let mut tags: Vec<String> = reply
+        .split([',', '\n'])
+        .map(|t| t.trim().trim_start_matches(['-', '#', '*', ' ']).trim().to_lowercase().replace(' ', "-"))
+        .filter(|t| !t.is_empty() && t.len() <= 40)
+        .collect();
+    tags.dedup();
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_suggest_prefers_known_tags() {
+        let body = "# Release\n\nCut the release of the parser. The parser release notes went out; \
+                    the rollout was smooth. Paired with Sam on rust code.";
+        let known = BTreeMap::from([("rust".to_string(), 12), ("work".to_string(), 30), ("parser".to_string(), 2)]);
+        let existing = vec!["Work".to_string()];
+
+        assert_eq!(suggest(body, &existing, &known, 5), vec!["parser", "rust", "release"]);
+        assert_eq!(suggest(body, &existing, &known, 1), vec!["parser"]);
+    }
+
+    #[test]
+    fn test_journal_tags_by_count() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        fs::write(month.join("16-080000-a.md"), "---\ntags: [work, team]\n---\n\n# A\n").unwrap();
+        fs::write(month.join("17-080000-b.md"), "---\ntags: work\n---\n\n# B\n").unwrap();
+        fs::write(month.join("18-080000-c.md"), "# C\n").unwrap();
+
+        let ranked = by_count(journal_tags(dir.path()).unwrap());
+        assert_eq!(ranked, vec![("work".to_string(), 2), ("team".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_add_tags_merges_into_frontmatter() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("16-080000-release.md");
+        fs::write(&path, "---\ntags: [work]\n---\n\n# Release\n").unwrap();
+
+        add_tags(&path, &["parser".to_string(), "work".to_string()]).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "---\ntags: [work, parser]\n---\n\n# Release\n");
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn test_parse_llm_tags() {
+        assert_eq!(parse_llm_tags("Car, #maintenance,  road trip\n"), vec!["car", "maintenance", "road-trip"]);
+    }
+}
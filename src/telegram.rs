@@ -0,0 +1,177 @@
+//! Telegram bot capture bridge (requires the `network` feature).
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const API_URL: &str = "https://api.telegram.org";
+
+/// Seconds Telegram holds a getUpdates request open waiting for messages
+const POLL_TIMEOUT: u64 = 30;
+
+/// File in the journal root remembering the next update to fetch
+pub const OFFSET_FILE: &str = ".telegram-offset";
+
+/// `[telegram]` section of the config
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TelegramConfig {
+    /// Bot token from @BotFather
+    pub bot_token: Option<String>,
+    /// Chat ids allowed to write to the journal; everything else is ignored
+    #[serde(default)]
+    pub allowed_chats: Vec<i64>,
+}
+
+/// A message received by the bot
+#[derive(Debug, PartialEq)]
+pub struct Incoming {
+    pub update_id: i64,
+    /// None for updates that aren't new messages, such as edits
+    pub chat_id: Option<i64>,
+    pub date: i64,
+    pub text: Option<String>,
+    /// Largest available size of an attached photo
+    pub photo_file_id: Option<String>,
+}
+
+/// Extract messages from a getUpdates response
+pub fn parse_updates(response: &Value) -> Vec<Incoming> {
+    let Some(results) = response["result"].as_array() else {
+        return Vec::new();
+    };
+
+    results
+        .iter()
+        .filter_map(|update| {
+            let update_id = update["update_id"].as_i64()?;
+            let message = &update["message"];
+            let photo_file_id = message["photo"].as_array().and_then(|sizes| {
+                sizes
+                    .iter()
+                    .max_by_key(|size| size["width"].as_i64().unwrap_or(0) * size["height"].as_i64().unwrap_or(0))
+                    .and_then(|size| size["file_id"].as_str())
+                    .map(str::to_string)
+            });
+            let text = message["text"]
+                .as_str()
+                .or_else(|| message["caption"].as_str())
+                .map(str::to_string);
+
+            Some(Incoming {
+                update_id,
+                chat_id: message["chat"]["id"].as_i64(),
+                date: message["date"].as_i64().unwrap_or_default(),
+                text,
+                photo_file_id,
+            })
+        })
+        .collect()
+}
+
+pub struct Bot {
+    agent: ureq::Agent,
+    token: String,
+}
+
+impl Bot {
+    pub fn new(token: String) -> Self {
+        let config = ureq::Agent::config_builder()
+            .timeout_global(Some(Duration::from_secs(POLL_TIMEOUT + 15)))
+            .build();
+        Bot { agent: config.into(), token }
+    }
+
+    fn call(&self, method: &str, query: &str) -> Result<Value, String> {
+        let url = format!("{}/bot{}/{}?{}", API_URL, self.token, method, query);
+        let response: Value = self
+            .agent
+            .get(&url)
+            .call()
+            .map_err(|e| format!("Telegram {} failed: {}", method, e))?
+            .body_mut()
+            .read_json()
+            .map_err(|e| format!("Invalid Telegram response: {}", e))?;
+        if response["ok"] != true {
+            return Err(format!("Telegram {} failed: {}", method, response["description"]));
+        }
+        Ok(response)
+    }
+
+    /// Long-poll for updates starting at `offset`
+    pub fn get_updates(&self, offset: i64, wait: bool) -> Result<Vec<Incoming>, String> {
+        let timeout = if wait { POLL_TIMEOUT } else { 0 };
+        let response = self.call("getUpdates", &format!("offset={}&timeout={}", offset, timeout))?;
+        Ok(parse_updates(&response))
+    }
+
+    /// Download a file into `dir`, naming it `<stem>.<original extension>`
+    pub fn download(&self, file_id: &str, dir: &Path, stem: &str) -> Result<PathBuf, String> {
+        let response = self.call("getFile", &format!("file_id={}", file_id))?;
+        let file_path = response["result"]["file_path"]
+            .as_str()
+            .ok_or("Telegram did not return a file path")?;
+        let extension = Path::new(file_path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_string())
+            .unwrap_or_else(|| "jpg".to_string());
+
+        let url = format!("{}/file/bot{}/{}", API_URL, self.token, file_path);
+        let bytes = self
+            .agent
+            .get(&url)
+            .call()
+            .map_err(|e| format!("Failed to download {}: {}", file_path, e))?
+            .body_mut()
+            .with_config()
+            .limit(50 * 1024 * 1024)
+            .read_to_vec()
+            .map_err(|e| format!("Failed to download {}: {}", file_path, e))?;
+
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let target = dir.join(format!("{}.{}", stem, extension));
+        fs::write(&target, bytes).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        Ok(target)
+    }
+}
+
+/// Read the persisted update offset (0 if none)
+pub fn load_offset(journal_path: &Path) -> i64 {
+    fs::read_to_string(journal_path.join(OFFSET_FILE))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+pub fn save_offset(journal_path: &Path, offset: i64) -> Result<(), String> {
+    let path = journal_path.join(OFFSET_FILE);
+    fs::write(&path, offset.to_string()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_updates_text_and_photo() {
+        let response: Value = serde_json::from_str(
+            r#"{"ok": true, "result": [
+                {"update_id": 10, "message": {"chat": {"id": 42}, "date": 1700000000, "text": "hello"}},
+                {"update_id": 11, "message": {"chat": {"id": 42}, "date": 1700000060, "caption": "sunset",
+                  "photo": [{"file_id": "small", "width": 90, "height": 60},
+                            {"file_id": "big", "width": 1280, "height": 853}]}},
+                {"update_id": 12, "edited_message": {"chat": {"id": 42}}}
+            ]}"#,
+        )
+        .unwrap();
+
+        let updates = parse_updates(&response);
+        assert_eq!(updates.len(), 3);
+        assert_eq!(updates[0].text.as_deref(), Some("hello"));
+        assert_eq!(updates[1].photo_file_id.as_deref(), Some("big"));
+        assert_eq!(updates[1].text.as_deref(), Some("sunset"));
+        assert_eq!(updates[0].chat_id, Some(42));
+        assert_eq!((updates[2].chat_id, updates[2].text.as_deref()), (None, None));
+    }
+}
@@ -0,0 +1,195 @@
+//! Colours for dates, titles, tags and headings, shared by coloured CLI output and the
+//! terminal views. Set under `[theme]`: a built-in preset plus optional overrides.
+
+use serde::{Deserialize, Serialize};
+
+/// `[theme]` section of the config
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ThemeConfig {
+    /// "dark" (default) or "light"
+    pub preset: Option<String>,
+    /// Colours as `#rrggbb`, overriding the preset
+    pub date: Option<String>,
+    pub title: Option<String>,
+    pub tag: Option<String>,
+    pub heading: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    fn parse(hex: &str) -> Result<Rgb, String> {
+        let invalid = || format!("Invalid colour '{}': expected #rrggbb", hex);
+        let digits = hex.strip_prefix('#').filter(|d| d.len() == 6 && d.is_ascii()).ok_or_else(invalid)?;
+        let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| invalid());
+        Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+#[cfg(feature = "tui")]
+impl From<Rgb> for ratatui::style::Color {
+    fn from(Rgb(r, g, b): Rgb) -> Self {
+        ratatui::style::Color::Rgb(r, g, b)
+    }
+}
+
+/// What a line of an entry is, for colouring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Date,
+    Title,
+    Tag,
+    Heading,
+}
+
+/// The role of an entry line: the `# ` title, a deeper heading, the template's
+/// `Date:` line or the frontmatter `tags:` line
+pub fn line_role(line: &str) -> Option<Role> {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    if hashes > 0 && line[hashes..].starts_with(' ') {
+        return Some(if hashes == 1 { Role::Title } else { Role::Heading });
+    }
+    if line.starts_with("Date: ") {
+        Some(Role::Date)
+    } else if line.starts_with("tags:") {
+        Some(Role::Tag)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub date: Rgb,
+    pub title: Rgb,
+    pub tag: Rgb,
+    pub heading: Rgb,
+}
+
+const DARK: Theme = Theme {
+    date: Rgb(0x7a, 0xa2, 0xf7),
+    title: Rgb(0xe0, 0xaf, 0x68),
+    tag: Rgb(0x9e, 0xce, 0x6a),
+    heading: Rgb(0xbb, 0x9a, 0xf7),
+};
+
+const LIGHT: Theme = Theme {
+    date: Rgb(0x2e, 0x59, 0xa8),
+    title: Rgb(0x8f, 0x5e, 0x15),
+    tag: Rgb(0x33, 0x63, 0x5c),
+    heading: Rgb(0x78, 0x47, 0xbd),
+};
+
+impl Theme {
+    /// The configured preset with its overrides applied
+    pub fn from_config(config: Option<&ThemeConfig>) -> Result<Theme, String> {
+        let Some(config) = config else {
+            return Ok(DARK);
+        };
+        let mut theme = match config.preset.as_deref() {
+            None | Some("dark") => DARK,
+            Some("light") => LIGHT,
+            Some(other) => return Err(format!("Unknown theme preset '{}' (expected dark or light)", other)),
+        };
+        for (slot, value) in [
+            (&mut theme.date, &config.date),
+            (&mut theme.title, &config.title),
+            (&mut theme.tag, &config.tag),
+            (&mut theme.heading, &config.heading),
+        ] {
+            if let Some(hex) = value {
+                *slot = Rgb::parse(hex)?;
+            }
+        }
+        Ok(theme)
+    }
+
+    pub fn color(&self, role: Role) -> Rgb {
+        match role {
+            Role::Date => self.date,
+            Role::Title => self.title,
+            Role::Tag => self.tag,
+            Role::Heading => self.heading,
+        }
+    }
+
+    /// `text` in a role's colour for a truecolor terminal; titles and headings are bold
+    pub fn paint(&self, role: Role, text: &str) -> String {
+        let Rgb(r, g, b) = self.color(role);
+        let bold = if matches!(role, Role::Title | Role::Heading) { "1;" } else { "" };
+        format!("\x1b[{}38;2;{};{};{}m{}\x1b[0m", bold, r, g, b, text)
+    }
+
+    /// Terminal view style for a role; titles and headings are bold
+    #[cfg(feature = "tui")]
+    pub fn style(&self, role: Role) -> ratatui::style::Style {
+        let style = ratatui::style::Style::default().fg(self.color(role).into());
+        if matches!(role, Role::Title | Role::Heading) {
+            style.add_modifier(ratatui::style::Modifier::BOLD)
+        } else {
+            style
+        }
+    }
+
+    /// An entry with its title, headings, date and tags lines coloured
+    pub fn paint_entry(&self, content: &str) -> String {
+        content
+            .split('\n')
+            .map(|line| match line_role(line) {
+                Some(role) => self.paint(role, line),
+                None => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Whether to colour output: stdout is a terminal and NO_COLOR is not set
+pub fn use_color() -> bool {
+    use std::io::IsTerminal;
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_role() {
+        assert_eq!(line_role("# Standup"), Some(Role::Title));
+        assert_eq!(line_role("### Notes"), Some(Role::Heading));
+        assert_eq!(line_role("#work done"), None);
+        assert_eq!(line_role("Date: 16-02-2026"), Some(Role::Date));
+        assert_eq!(line_role("tags: [work]"), Some(Role::Tag));
+        assert_eq!(line_role("Plain text"), None);
+    }
+
+    #[test]
+    fn test_presets_and_overrides() {
+        assert_eq!(Theme::from_config(None).unwrap(), DARK);
+        let config = ThemeConfig {
+            preset: Some("light".to_string()),
+            tag: Some("#D33682".to_string()),
+            ..Default::default()
+        };
+        let theme = Theme::from_config(Some(&config)).unwrap();
+        assert_eq!(theme.date, LIGHT.date);
+        assert_eq!(theme.tag, Rgb(0xd3, 0x36, 0x82));
+
+        let bad = ThemeConfig { heading: Some("purple".to_string()), ..Default::default() };
+        assert!(Theme::from_config(Some(&bad)).is_err());
+        let unknown = ThemeConfig { preset: Some("solarized".to_string()), ..Default::default() };
+        assert!(Theme::from_config(Some(&unknown)).is_err());
+    }
+
+    #[test]
+    fn test_paint_entry() {
+        let theme = Theme::from_config(None).unwrap();
+        let painted = theme.paint_entry("# Hike\n\nDate: 16-02-2026\nUp the hill\n");
+        assert_eq!(
+            painted,
+            "\x1b[1;38;2;224;175;104m# Hike\x1b[0m\n\n\x1b[38;2;122;162;247mDate: 16-02-2026\x1b[0m\nUp the hill\n"
+        );
+    }
+}
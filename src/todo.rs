@@ -0,0 +1,87 @@
+//! `todo`: Markdown task lines (`- [ ] ...`, `- [x] ...`) gathered from entries.
+
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::entry::Entry;
+use crate::read_entry;
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Task {
+    pub path: PathBuf,
+    /// 1-based line in the entry file
+    pub line: usize,
+    pub date: Option<String>,
+    pub text: String,
+    pub done: bool,
+}
+
+/// Whether a line is a task, and if so whether it's checked and what it says
+fn parse_task(line: &str) -> Option<(bool, &str)> {
+    let rest = line.trim_start().strip_prefix(['-', '*', '+'])?.strip_prefix(' ')?;
+    let done = match rest.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    let text = rest[3..].trim();
+    (!text.is_empty()).then_some((done, text))
+}
+
+/// Task lines in `entries`, in entry and line order: open tasks, or completed ones if `done`
+pub fn collect(entries: &[PathBuf], done: bool) -> Vec<Task> {
+    let mut tasks = Vec::new();
+    for entry in entries {
+        let Ok(content) = read_entry(entry) else {
+            continue;
+        };
+        let date = Entry::parse(entry, &content).date.map(|d| d.to_string());
+        for (i, line) in content.lines().enumerate() {
+            if let Some((checked, text)) = parse_task(line)
+                && checked == done
+            {
+                tasks.push(Task { path: entry.clone(), line: i + 1, date: date.clone(), text: text.to_string(), done });
+            }
+        }
+    }
+    tasks
+}
+
+/// `path:line: text`, one task per line, so editors and terminals can jump to each
+pub fn render(tasks: &[Task]) -> String {
+    tasks.iter().map(|task| format!("{}:{}: {}\n", task.path.display(), task.line, task.text)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_task() {
+        assert_eq!(parse_task("- [ ] Call Sam"), Some((false, "Call Sam")));
+        assert_eq!(parse_task("  * [X] Ship it "), Some((true, "Ship it")));
+        assert_eq!(parse_task("- [ ]"), None);
+        assert_eq!(parse_task("- [?] Maybe"), None);
+        assert_eq!(parse_task("[ ] No bullet"), None);
+        assert_eq!(parse_task("-[ ] Tight"), None);
+    }
+
+    #[test]
+    fn test_collect() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        let entry = month.join("16-080000-plan.md");
+        fs::write(&entry, "# Plan\n\n- [ ] Book flights\n- [x] Renew passport\n- plain bullet\n  - [ ] Pack\n").unwrap();
+
+        let open = collect(std::slice::from_ref(&entry), false);
+        assert_eq!(open.len(), 2);
+        assert_eq!((open[1].line, open[1].text.as_str(), open[1].date.as_deref()), (6, "Pack", Some("2026-02-16")));
+        assert_eq!(render(&open[..1]), format!("{}:3: Book flights\n", entry.display()));
+
+        let done = collect(&[entry], true);
+        assert_eq!(done.iter().map(|t| t.text.as_str()).collect::<Vec<_>>(), ["Renew passport"]);
+    }
+}
@@ -0,0 +1,294 @@
+//! `.trash/` at the journal root: entries that `delete`, `merge`, `doctor --fix`,
+//! `conflicts --resolve` and `sync` remove or rewrite are moved (or copied) here, under
+//! the same `YYYY/MM/` path they had, instead of being lost. Each such command is logged
+//! as one operation in `.trash/log.json`, which `undo` takes back, most recent first.
+
+use chrono::SecondsFormat;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{changes, lock, record_change};
+
+pub const TRASH_DIR: &str = ".trash";
+pub const LOG_FILE: &str = "log.json";
+
+/// An entry and where its old content is kept in the trash, both relative to the journal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trashed {
+    pub path: String,
+    pub trash: String,
+}
+
+/// An entry an operation wrote, with its content hash right after
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Written {
+    pub path: String,
+    pub hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Operation {
+    pub time: String,
+    pub command: String,
+    /// Entries moved into the trash, moved back on undo
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trashed: Vec<Trashed>,
+    /// Entries rewritten in place, whose earlier content is copied back on undo
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replaced: Vec<Trashed>,
+    /// Entries left behind by the operation; the ones it created are deleted on undo
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub written: Vec<Written>,
+    /// Folders removed by the operation, created again on undo
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub folders: Vec<String>,
+}
+
+fn relative(journal_path: &Path, path: &Path) -> Result<String, String> {
+    let relative = path
+        .strip_prefix(journal_path)
+        .map_err(|_| format!("{} is outside the journal", path.display()))?;
+    Ok(relative.to_string_lossy().replace('\\', "/"))
+}
+
+impl Operation {
+    pub fn new(command: &str) -> Operation {
+        Operation {
+            time: chrono::Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+            command: command.to_string(),
+            ..Operation::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trashed.is_empty() && self.replaced.is_empty() && self.written.is_empty() && self.folders.is_empty()
+    }
+
+    /// Move `entry` to the trash as part of this operation
+    pub fn trash(&mut self, journal_path: &Path, entry: &Path) -> Result<PathBuf, String> {
+        let moved = move_to_trash(journal_path, entry)?;
+        self.trashed.push(Trashed { path: relative(journal_path, entry)?, trash: relative(journal_path, &moved)? });
+        Ok(moved)
+    }
+
+    /// Keep a copy of `entry` before the operation rewrites it
+    pub fn keep(&mut self, journal_path: &Path, entry: &Path) -> Result<(), String> {
+        let target = trash_path(journal_path, entry)?;
+        fs::copy(entry, &target).map_err(|e| format!("Failed to copy {} to the trash: {}", entry.display(), e))?;
+        self.replaced.push(Trashed { path: relative(journal_path, entry)?, trash: relative(journal_path, &target)? });
+        Ok(())
+    }
+
+    /// Note an entry the operation created or rewrote, once it's written
+    pub fn wrote(&mut self, journal_path: &Path, entry: &Path) -> Result<(), String> {
+        self.written.push(Written { path: relative(journal_path, entry)?, hash: changes::hash(entry) });
+        Ok(())
+    }
+
+    pub fn removed_folder(&mut self, journal_path: &Path, folder: &Path) -> Result<(), String> {
+        self.folders.push(relative(journal_path, folder)?);
+        Ok(())
+    }
+}
+
+/// Free path in the trash for `entry`, numbering the name if an earlier one is already there
+fn trash_path(journal_path: &Path, entry: &Path) -> Result<PathBuf, String> {
+    let relative = entry
+        .strip_prefix(journal_path)
+        .map_err(|_| format!("{} is outside the journal", entry.display()))?;
+    let mut target = journal_path.join(TRASH_DIR).join(relative);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let filename = relative.file_name().and_then(|name| name.to_str()).unwrap_or_default().to_string();
+    let mut copy = 1;
+    while target.exists() {
+        copy += 1;
+        target.set_file_name(format!("{}.{}", filename, copy));
+    }
+    Ok(target)
+}
+
+/// Move `entry` into the trash
+pub fn move_to_trash(journal_path: &Path, entry: &Path) -> Result<PathBuf, String> {
+    let target = trash_path(journal_path, entry)?;
+    fs::rename(entry, &target).map_err(|e| format!("Failed to move {} to the trash: {}", entry.display(), e))?;
+    Ok(target)
+}
+
+/// Remove the trash folders above `path` that are now empty
+fn remove_empty_parents(journal_path: &Path, path: &Path) {
+    let root = journal_path.join(TRASH_DIR);
+    for dir in path.ancestors().skip(1) {
+        if !dir.starts_with(&root) || dir == root || fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
+
+/// Delete a trashed file for good, along with the trash folders it leaves empty
+pub fn purge(journal_path: &Path, trashed: &Path) -> Result<(), String> {
+    fs::remove_file(trashed).map_err(|e| format!("Failed to delete {}: {}", trashed.display(), e))?;
+    remove_empty_parents(journal_path, trashed);
+    let _ = fs::remove_dir(journal_path.join(TRASH_DIR));
+    Ok(())
+}
+
+fn load(log_path: &Path) -> Result<Vec<Operation>, String> {
+    match fs::read_to_string(log_path) {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| format!("Failed to parse {}: {}", log_path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", log_path.display(), e)),
+    }
+}
+
+/// Write the log through a temporary file, so an interrupted save keeps the old one
+fn save(log_path: &Path, operations: &[Operation]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(operations).map_err(|e| format!("Failed to encode trash log: {}", e))?;
+    let tmp = log_path.with_extension("json.tmp");
+    fs::write(&tmp, json).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    fs::rename(&tmp, log_path).map_err(|e| format!("Failed to write {}: {}", log_path.display(), e))
+}
+
+/// Add a finished operation to the log, so `undo` can take it back
+pub fn record(journal_path: &Path, operation: Operation) -> Result<(), String> {
+    if operation.is_empty() {
+        return Ok(());
+    }
+    let dir = journal_path.join(TRASH_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let log_path = dir.join(LOG_FILE);
+    let _lock = lock::acquire(&log_path)?;
+    let mut operations = load(&log_path)?;
+    operations.push(operation);
+    save(&log_path, &operations)
+}
+
+/// Take back the most recent operation, returning it, or `None` if there's nothing to undo.
+/// Nothing is touched unless every step can be taken back: an entry changed since the
+/// operation, or a file back where a trashed one would go, stops the undo.
+pub fn undo(journal_path: &Path) -> Result<Option<Operation>, String> {
+    let log_path = journal_path.join(TRASH_DIR).join(LOG_FILE);
+    if !log_path.exists() {
+        return Ok(None);
+    }
+    let _lock = lock::acquire(&log_path)?;
+    let mut operations = load(&log_path)?;
+    let Some(operation) = operations.pop() else {
+        return Ok(None);
+    };
+
+    let written = |path: &str| operation.written.iter().any(|w| w.path == path);
+    let replaced = |path: &str| operation.replaced.iter().any(|r| r.path == path);
+    for entry in &operation.written {
+        let current = changes::hash(&journal_path.join(&entry.path));
+        if current.is_some() && current != entry.hash {
+            return Err(format!("{} changed since the {}; undoing would lose those changes", entry.path, operation.command));
+        }
+    }
+    for item in operation.trashed.iter().chain(&operation.replaced) {
+        if !journal_path.join(&item.trash).exists() {
+            return Err(format!("{} is no longer in the trash", item.trash));
+        }
+    }
+    for item in &operation.trashed {
+        if journal_path.join(&item.path).exists() && !written(&item.path) {
+            return Err(format!("{} exists again; move it away to undo the {}", item.path, operation.command));
+        }
+    }
+
+    for entry in operation.written.iter().filter(|w| !replaced(&w.path)) {
+        let path = journal_path.join(&entry.path);
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            record_change(journal_path, changes::Action::Delete, &path, None, entry.hash.clone());
+        }
+    }
+    for folder in &operation.folders {
+        let path = journal_path.join(folder);
+        fs::create_dir_all(&path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    }
+    for item in operation.replaced.iter().chain(&operation.trashed) {
+        let (path, trash) = (journal_path.join(&item.path), journal_path.join(&item.trash));
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let before = changes::hash(&path);
+        fs::rename(&trash, &path).map_err(|e| format!("Failed to restore {}: {}", path.display(), e))?;
+        remove_empty_parents(journal_path, &trash);
+        let action = if before.is_some() { changes::Action::Edit } else { changes::Action::Create };
+        record_change(journal_path, action, &path, None, before);
+    }
+
+    save(&log_path, &operations)?;
+    Ok(Some(operation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_move_to_trash() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        let entry = month.join("17-081503-note.md");
+
+        fs::write(&entry, "# first\n").unwrap();
+        let first = move_to_trash(dir.path(), &entry).unwrap();
+        assert_eq!(first, dir.path().join(".trash/2026/02/17-081503-note.md"));
+        fs::write(&entry, "# second\n").unwrap();
+        let second = move_to_trash(dir.path(), &entry).unwrap();
+        assert_eq!(second, dir.path().join(".trash/2026/02/17-081503-note.md.2"));
+        assert!(!entry.exists());
+        assert_eq!(fs::read_to_string(&first).unwrap(), "# first\n");
+
+        purge(dir.path(), &second).unwrap();
+        assert!(first.exists());
+        purge(dir.path(), &first).unwrap();
+        assert!(!dir.path().join(TRASH_DIR).exists());
+        assert!(month.exists());
+    }
+
+    #[test]
+    fn test_undo() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        let (first, second) = (month.join("17-081503-a.md"), month.join("17-120000-b.md"));
+        fs::write(&first, "# a\n").unwrap();
+        fs::write(&second, "# b\n").unwrap();
+
+        // A merge: both entries trashed, one written in place of the first
+        let mut merge = Operation::new("merge");
+        merge.trash(dir.path(), &first).unwrap();
+        merge.trash(dir.path(), &second).unwrap();
+        fs::write(&first, "# a\n\n# b\n").unwrap();
+        merge.wrote(dir.path(), &first).unwrap();
+        record(dir.path(), merge).unwrap();
+
+        // Then a rewrite of the merged entry
+        let mut fix = Operation::new("doctor --fix");
+        fix.keep(dir.path(), &first).unwrap();
+        fs::write(&first, "# fixed\n").unwrap();
+        fix.wrote(dir.path(), &first).unwrap();
+        record(dir.path(), fix).unwrap();
+
+        assert_eq!(undo(dir.path()).unwrap().unwrap().command, "doctor --fix");
+        assert_eq!(fs::read_to_string(&first).unwrap(), "# a\n\n# b\n");
+
+        // An edit made after the operation isn't thrown away
+        fs::write(&first, "# edited\n").unwrap();
+        assert!(undo(dir.path()).unwrap_err().contains("changed since the merge"));
+        fs::write(&first, "# a\n\n# b\n").unwrap();
+
+        assert_eq!(undo(dir.path()).unwrap().unwrap().command, "merge");
+        assert_eq!(fs::read_to_string(&first).unwrap(), "# a\n");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "# b\n");
+        assert!(!dir.path().join(TRASH_DIR).join("2026").exists());
+        assert_eq!(undo(dir.path()).unwrap(), None);
+    }
+}
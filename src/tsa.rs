@@ -0,0 +1,162 @@
+//! RFC 3161 timestamping of seals (requires the `network` feature).
+//!
+//! The merkle root is submitted as a SHA-256 message imprint, so the stored
+//! response can be checked independently with e.g.
+//! `openssl ts -verify -digest <root> -in <file>.tsr -CAfile <tsa-ca.pem>`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Used when `tsa_url` is not set in config
+pub const DEFAULT_TSA_URL: &str = "https://freetsa.org/tsr";
+
+/// Directory (inside the journal root) where timestamp responses are kept
+pub const TSR_DIR: &str = crate::seal::SEAL_DIR;
+
+/// DER object identifier for SHA-256 (2.16.840.1.101.3.4.2.1)
+const SHA256_OID: [u8; 9] = [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+
+fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len.to_be_bytes().into_iter().skip_while(|b| *b == 0).collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend(bytes);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+fn der_integer(bytes: &[u8]) -> Vec<u8> {
+    let mut content: Vec<u8> = bytes.iter().copied().skip_while(|b| *b == 0).collect();
+    // Keep the integer positive
+    if content.first().is_none_or(|b| *b & 0x80 != 0) {
+        content.insert(0, 0);
+    }
+    der(0x02, &content)
+}
+
+/// Build a DER-encoded TimeStampReq for a SHA-256 digest
+pub fn build_request(digest: &[u8; 32], nonce: u64) -> Vec<u8> {
+    let algorithm = der(0x30, &[der(0x06, &SHA256_OID), vec![0x05, 0x00]].concat());
+    let imprint = der(0x30, &[algorithm, der(0x04, digest)].concat());
+
+    der(
+        0x30,
+        &[
+            der_integer(&[1]),
+            imprint,
+            der_integer(&nonce.to_be_bytes()),
+            // certReq: ask the TSA to include its certificate
+            vec![0x01, 0x01, 0xff],
+        ]
+        .concat(),
+    )
+}
+
+/// Read a DER header, returning (tag, content start, content length)
+fn read_header(data: &[u8]) -> Option<(u8, usize, usize)> {
+    let tag = *data.first()?;
+    let first = *data.get(1)? as usize;
+    if first < 0x80 {
+        return Some((tag, 2, first));
+    }
+    let count = first & 0x7f;
+    let bytes = data.get(2..2 + count)?;
+    let len = bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+    Some((tag, 2 + count, len))
+}
+
+/// Extract the PKIStatus from a TimeStampResp (0 = granted, 1 = grantedWithMods)
+pub fn response_status(response: &[u8]) -> Option<u8> {
+    // TimeStampResp ::= SEQUENCE { status PKIStatusInfo, timeStampToken OPTIONAL }
+    let (tag, start, _) = read_header(response)?;
+    if tag != 0x30 {
+        return None;
+    }
+    // PKIStatusInfo ::= SEQUENCE { status INTEGER, ... }
+    let status_info = response.get(start..)?;
+    let (tag, start, _) = read_header(status_info)?;
+    if tag != 0x30 {
+        return None;
+    }
+    let status = status_info.get(start..)?;
+    let (tag, start, len) = read_header(status)?;
+    if tag != 0x02 || len != 1 {
+        return None;
+    }
+    status.get(start).copied()
+}
+
+fn decode_hex(hex: &str) -> Option<[u8; 32]> {
+    let bytes: Vec<u8> = (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+        .collect::<Option<_>>()?;
+    bytes.try_into().ok()
+}
+
+/// Request a timestamp for a seal root and store the response next to the seal log.
+/// Returns the journal-relative path of the stored `.tsr` file.
+pub fn timestamp_root(journal_path: &Path, root: &str, tsa_url: &str) -> Result<PathBuf, String> {
+    let digest = decode_hex(root).ok_or("Seal root is not a SHA-256 hex digest")?;
+    let nonce = chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64;
+    let request = build_request(&digest, nonce);
+
+    let mut response = ureq::post(tsa_url)
+        .header("Content-Type", "application/timestamp-query")
+        .send(&request[..])
+        .map_err(|e| format!("Timestamp request to {} failed: {}", tsa_url, e))?;
+    let body = response
+        .body_mut()
+        .read_to_vec()
+        .map_err(|e| format!("Failed to read timestamp response: {}", e))?;
+
+    match response_status(&body) {
+        Some(0) | Some(1) => {}
+        Some(status) => return Err(format!("TSA rejected the request (status {})", status)),
+        None => return Err("TSA returned a malformed response".to_string()),
+    }
+
+    let relative = PathBuf::from(TSR_DIR).join(format!("{}.tsr", root));
+    let target = journal_path.join(&relative);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(&target, body).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+
+    Ok(relative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_request_structure() {
+        let request = build_request(&[0xab; 32], 0x80);
+        // Outer SEQUENCE with short-form length covering the rest
+        assert_eq!(request[0], 0x30);
+        assert_eq!(request[1] as usize, request.len() - 2);
+        // version INTEGER 1
+        assert_eq!(&request[2..5], &[0x02, 0x01, 0x01]);
+        // The digest is embedded as an OCTET STRING
+        let needle = [&[0x04, 0x20][..], &[0xab; 32][..]].concat();
+        assert!(request.windows(needle.len()).any(|w| w == needle.as_slice()));
+        // A nonce with the high bit set is padded to stay positive
+        assert!(request.windows(4).any(|w| w == [0x02, 0x02, 0x00, 0x80]));
+    }
+
+    #[test]
+    fn test_response_status() {
+        let granted = der(0x30, &der(0x30, &der_integer(&[0])));
+        assert_eq!(response_status(&granted), Some(0));
+        let rejected = der(0x30, &der(0x30, &der_integer(&[2])));
+        assert_eq!(response_status(&rejected), Some(2));
+        assert_eq!(response_status(&[0x05, 0x00]), None);
+    }
+}
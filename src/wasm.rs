@@ -0,0 +1,241 @@
+//! WASI plugin modules (requires the `wasm` feature): `<name>.wasm` files in the plugins folder
+//! that add formats to `import --plugin <name>` and `export --plugin <name>`.
+//!
+//! A module is a WASI preview 1 command. It runs with one argument, `import` or `export`,
+//! reads its input from stdin and writes its result to stdout; it gets no filesystem or
+//! network access, and is stopped once it has used up its fuel.
+//! - `export`: stdin is a JSON array of `{"path", "date", "title", "content"}` objects,
+//!   and whatever the module prints is the export
+//! - `import`: stdin is the file being imported, and the module prints a JSON array of
+//!   `{"title", "note", "tags", "date"}` objects (all but the title optional), one per entry to
+//!   create. `date` is RFC 3339 or `YYYY-MM-DD[ HH:MM:SS]`; entries without one are stamped with
+//!   the time of the import, and entries that would share a second are moved a second apart in
+//!   output order, so their files never collide
+
+use chrono::{NaiveDate, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use wasmtime::{Config, Engine, Linker, Module, Store, Trap};
+use wasmtime_wasi::pipe::{MemoryInputPipe, MemoryOutputPipe};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::{I32Exit, WasiCtxBuilder};
+
+/// Most output a module may write, so a runaway plugin can't exhaust memory
+const OUTPUT_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Fuel a module may burn (roughly one unit per instruction, a few seconds of work), so a
+/// plugin stuck in a loop stops instead of hanging the command
+const FUEL_LIMIT: u64 = 10_000_000_000;
+
+/// An entry as handed to an export module
+#[derive(Debug, Serialize)]
+pub struct ExportedEntry {
+    pub path: String,
+    pub date: Option<String>,
+    pub title: Option<String>,
+    pub content: String,
+}
+
+/// An entry produced by an import module
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct ImportedEntry {
+    pub title: String,
+    #[serde(default)]
+    pub note: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// When the entry was written, if the source says
+    #[serde(default)]
+    pub date: Option<String>,
+}
+
+/// An imported `date`: RFC 3339 (converted to local time) or `YYYY-MM-DD[ HH:MM:SS]`
+fn parse_date(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&chrono::Local).naive_local());
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))
+}
+
+/// When each of `entries` is written: its `date`, or `now` without one, moved on a second at a
+/// time past any earlier entry's so that no two land on the same second
+pub fn entry_times(entries: &[ImportedEntry], now: NaiveDateTime) -> Result<Vec<NaiveDateTime>, String> {
+    let mut taken = HashSet::new();
+    entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let mut time = match &entry.date {
+                Some(date) => parse_date(date).ok_or_else(|| {
+                    format!("Entry {}: can't read date '{}': expected RFC 3339 or YYYY-MM-DD[ HH:MM:SS]", i + 1, date)
+                })?,
+                None => now,
+            };
+            time = time.with_nanosecond(0).unwrap_or(time);
+            while !taken.insert(time) {
+                time += chrono::Duration::seconds(1);
+            }
+            Ok(time)
+        })
+        .collect()
+}
+
+/// The module for plugin `name`, erroring if the plugins folder doesn't have one
+pub fn find(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let path = dir.join(format!("{}.wasm", name));
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err(format!("No plugin module {}", path.display()))
+    }
+}
+
+/// An engine whose stores meter fuel
+fn engine() -> Result<Engine, String> {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config).map_err(|e| e.to_string())
+}
+
+/// Run a compiled module in `mode` with `input` on stdin and `fuel` to burn, returning its stdout
+fn run_module(
+    engine: &Engine,
+    module: &Module,
+    name: &str,
+    mode: &str,
+    input: &[u8],
+    fuel: u64,
+) -> Result<Vec<u8>, String> {
+    let mut linker: Linker<WasiP1Ctx> = Linker::new(engine);
+    preview1::add_to_linker_sync(&mut linker, |cx| cx).map_err(|e| e.to_string())?;
+
+    let stdout = MemoryOutputPipe::new(OUTPUT_LIMIT);
+    let wasi = WasiCtxBuilder::new()
+        .stdin(MemoryInputPipe::new(input.to_vec()))
+        .stdout(stdout.clone())
+        .inherit_stderr()
+        .args(&[name, mode])
+        .build_p1();
+    let mut store = Store::new(engine, wasi);
+    store.set_fuel(fuel).map_err(|e| e.to_string())?;
+
+    let instance = linker.instantiate(&mut store, module).map_err(|e| format!("Plugin {}: {}", name, e))?;
+    let start = instance
+        .get_typed_func::<(), ()>(&mut store, "_start")
+        .map_err(|_| format!("Plugin {}: not a WASI command (no _start export)", name))?;
+    if let Err(e) = start.call(&mut store, ()) {
+        match e.downcast_ref::<I32Exit>() {
+            Some(I32Exit(0)) => {}
+            Some(I32Exit(code)) => return Err(format!("Plugin {} exited with status {}", name, code)),
+            None if e.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) => {
+                return Err(format!("Plugin {} ran out of fuel and was stopped", name));
+            }
+            None => return Err(format!("Plugin {}: {}", name, e)),
+        }
+    }
+    drop(store);
+    Ok(stdout.contents().to_vec())
+}
+
+/// Run the module at `path` in `mode` ("import" or "export")
+pub fn run(path: &Path, mode: &str, input: &[u8]) -> Result<Vec<u8>, String> {
+    let name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let engine = engine()?;
+    let module = Module::from_file(&engine, path).map_err(|e| format!("Plugin {}: {}", path.display(), e))?;
+    run_module(&engine, &module, &name, mode, input, FUEL_LIMIT)
+}
+
+/// Export `entries` through the module at `path`
+pub fn export(path: &Path, entries: &[ExportedEntry]) -> Result<Vec<u8>, String> {
+    let input = serde_json::to_vec(entries).map_err(|e| e.to_string())?;
+    run(path, "export", &input)
+}
+
+/// Turn `input` into entries through the module at `path`
+pub fn import(path: &Path, input: &[u8]) -> Result<Vec<ImportedEntry>, String> {
+    let output = run(path, "import", input)?;
+    serde_json::from_slice(&output).map_err(|e| format!("Plugin {} returned invalid entries: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Copies stdin to stdout, then exits with the status given
+    fn echo(status: i32) -> String {
+        format!(
+            r#"(module
+                (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+                (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+                (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+                (memory (export "memory") 1)
+                (func (export "_start")
+                    (i32.store (i32.const 0) (i32.const 64))
+                    (i32.store (i32.const 4) (i32.const 1024))
+                    (drop (call $fd_read (i32.const 0) (i32.const 0) (i32.const 1) (i32.const 8)))
+                    (i32.store (i32.const 4) (i32.load (i32.const 8)))
+                    (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 12)))
+                    (call $proc_exit (i32.const {}))))"#,
+            status
+        )
+    }
+
+    fn run_wat(wat: &str, input: &[u8]) -> Result<Vec<u8>, String> {
+        let engine = engine().unwrap();
+        let module = Module::new(&engine, wat).unwrap();
+        run_module(&engine, &module, "echo", "import", input, 1_000_000)
+    }
+
+    #[test]
+    fn test_run_module() {
+        let input = br#"[{"title": "From wasm", "tags": ["imported"]}]"#;
+        let output = run_wat(&echo(0), input).unwrap();
+        assert_eq!(output, input);
+        let entries: Vec<ImportedEntry> = serde_json::from_slice(&output).unwrap();
+        let tags = vec!["imported".into()];
+        assert_eq!(entries[0], ImportedEntry { title: "From wasm".into(), note: String::new(), tags, date: None });
+
+        assert_eq!(run_wat(&echo(3), b"x").unwrap_err(), "Plugin echo exited with status 3");
+        assert!(run_wat("(module)", b"").unwrap_err().contains("not a WASI command"));
+        let spin = r#"(module (memory (export "memory") 1) (func (export "_start") (loop (br 0))))"#;
+        assert_eq!(run_wat(spin, b"").unwrap_err(), "Plugin echo ran out of fuel and was stopped");
+    }
+
+    #[test]
+    fn test_entry_times() {
+        let entry = |date: Option<&str>| ImportedEntry {
+            title: "same".into(),
+            note: String::new(),
+            tags: Vec::new(),
+            date: date.map(str::to_string),
+        };
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+        let now = at("2026-02-17 08:15:03") + chrono::Duration::milliseconds(250);
+        let entries = [
+            entry(None),
+            entry(None),
+            entry(Some("2025-12-31")),
+            entry(Some("2025-12-31")),
+            entry(Some("2025-12-30 21:04:00")),
+            entry(Some("2025-12-30 21:04")),
+        ];
+        assert_eq!(
+            entry_times(&entries, now).unwrap(),
+            [
+                at("2026-02-17 08:15:03"),
+                at("2026-02-17 08:15:04"),
+                at("2025-12-31 00:00:00"),
+                at("2025-12-31 00:00:01"),
+                at("2025-12-30 21:04:00"),
+                at("2025-12-30 21:04:01"),
+            ]
+        );
+        assert!(parse_date("2025-12-31T09:30:00+01:00").is_some());
+        assert!(entry_times(&[entry(Some("31/12/2025"))], now).unwrap_err().contains("Entry 1"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Polling watcher reporting entries created or modified on disk.
+
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::{entry_date, find_all_entries};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub path: PathBuf,
+}
+
+impl Change {
+    /// Event pushed to live-update clients
+    pub fn to_json(&self) -> Value {
+        let event = match self.kind {
+            ChangeKind::Created => "created",
+            ChangeKind::Modified => "modified",
+        };
+        json!({
+            "event": event,
+            "path": self.path,
+            "date": entry_date(&self.path).map(|d| d.to_string()),
+        })
+    }
+}
+
+pub struct Watcher {
+    journal_path: PathBuf,
+    seen: HashMap<PathBuf, SystemTime>,
+}
+
+impl Watcher {
+    /// Start watching; entries that already exist are not reported
+    pub fn new(journal_path: PathBuf) -> Self {
+        let seen = scan(&journal_path);
+        Watcher { journal_path, seen }
+    }
+
+    /// Entries created or modified since the last poll
+    pub fn poll(&mut self) -> Vec<Change> {
+        let current = scan(&self.journal_path);
+        let mut changes: Vec<Change> = current
+            .iter()
+            .filter_map(|(path, modified)| {
+                let kind = match self.seen.get(path) {
+                    None => ChangeKind::Created,
+                    Some(previous) if previous != modified => ChangeKind::Modified,
+                    Some(_) => return None,
+                };
+                Some(Change { kind, path: path.clone() })
+            })
+            .collect();
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        self.seen = current;
+        changes
+    }
+}
+
+/// Modification times of every entry on disk (archived entries never change)
+fn scan(journal_path: &std::path::Path) -> HashMap<PathBuf, SystemTime> {
+    find_all_entries(journal_path)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_poll_reports_created_and_modified() {
+        let dir = TempDir::new().unwrap();
+        let month = dir.path().join("2026").join("02");
+        fs::create_dir_all(&month).unwrap();
+        fs::write(month.join("16-080000-old.md"), "# Old\n").unwrap();
+
+        let mut watcher = Watcher::new(dir.path().to_path_buf());
+        assert!(watcher.poll().is_empty());
+
+        let new = month.join("17-081503-new.md");
+        fs::write(&new, "# New\n").unwrap();
+        let changes = watcher.poll();
+        assert_eq!(changes, vec![Change { kind: ChangeKind::Created, path: new.clone() }]);
+        assert_eq!(changes[0].to_json()["date"], "2026-02-17");
+
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        fs::File::options().write(true).open(&new).unwrap().set_modified(later).unwrap();
+        assert_eq!(watcher.poll(), vec![Change { kind: ChangeKind::Modified, path: new }]);
+        assert!(watcher.poll().is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+//! Posting entry summaries to chat webhooks (requires the `network` feature).
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::markdown;
+
+/// Slack section blocks hold at most 3000 characters
+const SLACK_SECTION_LIMIT: usize = 3000;
+/// Discord messages hold at most 2000 characters
+const DISCORD_LIMIT: usize = 2000;
+
+/// `[webhooks]` section of the config
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WebhookConfig {
+    pub slack: Option<String>,
+    pub discord: Option<String>,
+}
+
+/// Entry body without the H1 title and `Date:` line that the template adds
+pub fn strip_template(body: &str) -> String {
+    body.lines()
+        .filter(|line| !line.starts_with("# ") && !line.starts_with("Date: "))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// The first `##`-level section of a body, or its opening paragraph if it has none
+pub fn first_section(body: &str) -> String {
+    if let Some(section) = markdown::sections(body).into_iter().find(|s| s.level > 1) {
+        return format!("## {}\n{}", section.heading, section.content);
+    }
+    body.split("\n\n").next().unwrap_or_default().trim().to_string()
+}
+
+/// Convert common Markdown to Slack mrkdwn
+pub fn to_mrkdwn(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let indent = &line[..line.len() - trimmed.len()];
+            let line = if let Some(heading) = trimmed.strip_prefix('#') {
+                format!("*{}*", heading.trim_start_matches('#').trim())
+            } else if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+                format!("{}• {}", indent, item)
+            } else {
+                line.to_string()
+            };
+            convert_inline(&line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `**bold**` -> `*bold*` and `[text](url)` -> `<url|text>`
+fn convert_inline(line: &str) -> String {
+    let line = line.replace("**", "*");
+    let mut out = String::new();
+    let mut rest = line.as_str();
+    while let Some(start) = rest.find('[') {
+        let Some(mid) = rest[start..].find("](").map(|i| start + i) else {
+            break;
+        };
+        let Some(end) = rest[mid..].find(')').map(|i| mid + i) else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(&format!("<{}|{}>", &rest[mid + 2..end], &rest[start + 1..mid]));
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Split text into chunks of at most `limit` characters, preferring line breaks
+fn chunk(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        if !current.is_empty() && current.chars().count() + line.chars().count() + 1 > limit {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        // A single over-long line is hard-split
+        let mut line: String = line.to_string();
+        while line.chars().count() > limit {
+            let head: String = line.chars().take(limit).collect();
+            line = line.chars().skip(limit).collect();
+            chunks.push(head);
+        }
+        current.push_str(&line);
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Slack Block Kit payload: a header and mrkdwn sections per entry
+pub fn slack_payload(entries: &[(String, String)]) -> Value {
+    let mut blocks = Vec::new();
+    for (title, body) in entries {
+        blocks.push(json!({
+            "type": "header",
+            "text": { "type": "plain_text", "text": title }
+        }));
+        for text in chunk(&to_mrkdwn(body), SLACK_SECTION_LIMIT) {
+            blocks.push(json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": text }
+            }));
+        }
+    }
+
+    let fallback: Vec<&str> = entries.iter().map(|(title, _)| title.as_str()).collect();
+    json!({ "text": fallback.join(", "), "blocks": blocks })
+}
+
+/// Discord payload: plain Markdown, truncated to the message limit
+pub fn discord_payload(entries: &[(String, String)]) -> Value {
+    let content = entries
+        .iter()
+        .map(|(title, body)| format!("## {}\n{}", title, body))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let content = if content.chars().count() > DISCORD_LIMIT {
+        let mut truncated: String = content.chars().take(DISCORD_LIMIT - 1).collect();
+        truncated.push('…');
+        truncated
+    } else {
+        content
+    };
+    json!({ "content": content })
+}
+
+/// POST a JSON payload to a webhook URL
+pub fn post(url: &str, payload: &Value) -> Result<(), String> {
+    ureq::post(url)
+        .send_json(payload)
+        .map(|_| ())
+        .map_err(|e| format!("Webhook request failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_mrkdwn() {
+        let converted = to_mrkdwn("## Done\n- shipped **v2**\n  - see [notes](https://x.test/n)");
+        assert_eq!(converted, "*Done*\n• shipped *v2*\n  • see <https://x.test/n|notes>");
+    }
+
+    #[test]
+    fn test_first_section_and_strip_template() {
+        let body = "# Day\n\nDate: 17-02-2026\n\nIntro line\n\n## Done\n- a\n## Next\n- b";
+        let stripped = strip_template(body);
+        assert!(stripped.starts_with("Intro line"));
+        assert_eq!(first_section(&stripped), "## Done\n- a");
+        assert_eq!(first_section("Just prose\n\nMore"), "Just prose");
+    }
+
+    #[test]
+    fn test_payloads_respect_limits() {
+        let long = "x".repeat(4500);
+        let slack = slack_payload(&[("Day".to_string(), long.clone())]);
+        assert_eq!(slack["blocks"].as_array().unwrap().len(), 3);
+        assert_eq!(slack["text"], "Day");
+
+        let discord = discord_payload(&[("Day".to_string(), long)]);
+        assert_eq!(discord["content"].as_str().unwrap().chars().count(), DISCORD_LIMIT);
+    }
+}